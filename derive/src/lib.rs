@@ -0,0 +1,86 @@
+//! # input_py_derive
+//!
+//! `#[derive(FromForm)]` for input_py, enable it with input_py's `derive`
+//! feature. every named field becomes a question of the form, asked in
+//! order and parsed with `FromStr`:
+//!
+//! * the prompt is the field name with spaces for underscores, or
+//!   `#[input(prompt = "...")]`
+//! * `#[input(default = "...")]` is used for an empty answer
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+#[proc_macro_derive(FromForm, attributes(input))]
+pub fn derive_from_form(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(name, "FromForm needs named fields")),
+        },
+        _ => return Err(Error::new_spanned(name, "FromForm needs a struct")),
+    };
+
+    let mut questions = Vec::new();
+    let mut values = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+        let id = ident.to_string();
+        let mut prompt = id.replace('_', " ");
+        let mut default = None;
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("input"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("prompt") {
+                    prompt = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    default = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `prompt` or `default`"))
+                }
+            })?;
+        }
+        let default = default.map(|default| quote! { .default(#default) });
+        questions.push(quote! {
+            .field(
+                #id,
+                ::input_py::Input::new(#prompt)
+                    #default
+                    .validate(::input_py::derive::check::<#ty>),
+            )
+        });
+        values.push(quote! {
+            #ident: ::input_py::derive::value::<#ty>(answers, #id)?
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::input_py::derive::FromForm for #name #ty_generics #where_clause {
+            fn form() -> ::input_py::Form {
+                ::input_py::Form::new() #(#questions)*
+            }
+
+            fn from_answers(
+                answers: &::input_py::form::Answers,
+            ) -> ::std::result::Result<Self, ::input_py::InputError> {
+                ::std::result::Result::Ok(#name { #(#values),* })
+            }
+        }
+    })
+}
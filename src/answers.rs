@@ -0,0 +1,300 @@
+//! # answers
+//!
+//! a playbook of answers by question id for unattended runs, like CI
+//! jobs and scripted installs of tools built on the crate. `play` hands
+//! one to every prompt of the process until its guard is dropped: a
+//! prompt with an answer returns it, one without returns its default or
+//! fails with `InputError::MissingAnswer`. nothing is read or written
+//!
+//! the file given in `INPUT_PY_ANSWERS` is loaded the same way and wins
+//! over the playbook, `INPUT_PY_ANSWER_<ID>` variables win over both.
+//! a `Recorder` writes such a file from an interactive run
+
+use std::borrow::Cow;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::config;
+use crate::error::InputError;
+use crate::json::{self, JsonValue};
+use crate::storage::Slot;
+use crate::toml;
+
+static PLAYBOOK: Mutex<Option<AnswerSource>> = Mutex::new(None);
+
+/// # AnswerSource
+///
+/// answers by question id, like `db.port`, from TOML, JSON or plain
+/// `key=value` lines. the first answer to an id counts
+///
+/// # usage
+/// ```
+/// use input_py::answers::AnswerSource;
+///
+/// let answers = AnswerSource::from_key_values("# install\ndb.port = 6543\nuser=admin").unwrap();
+/// assert_eq!(answers.get("db.port"), Some("6543"));
+/// assert_eq!(answers.get("user"), Some("admin"));
+/// assert_eq!(answers.get("host"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnswerSource {
+    entries: Vec<(String, String)>,
+}
+
+impl AnswerSource {
+    pub fn new() -> Self {
+        AnswerSource::default()
+    }
+
+    /// add the answer to question `id`
+    pub fn answer(mut self, id: &str, value: &str) -> Self {
+        self.entries.push((id.to_string(), value.to_string()));
+        self
+    }
+
+    /// the answers in the file at `path`: JSON for `.json`, TOML for
+    /// `.toml` and `key=value` lines otherwise
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| InputError::Io(format!("answers {}: {}", path.display(), e)))?;
+        let entries = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => parse_json(&text),
+            Some("toml") => toml::parse(&text),
+            _ => parse_key_values(&text),
+        };
+        entries
+            .map(|entries| AnswerSource { entries })
+            .map_err(|e| InputError::Io(format!("answers {}: {}", path.display(), e)))
+    }
+
+    /// `[table]` headers make dotted ids, like `port` under `[db]`
+    pub fn from_toml(text: &str) -> Result<Self, InputError> {
+        AnswerSource::parse(toml::parse(text))
+    }
+
+    /// an object of strings, numbers and bools. nested objects make
+    /// dotted ids, like `{"db": {"port": 5432}}`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::answers::AnswerSource;
+    ///
+    /// let answers = AnswerSource::from_json(r#"{"db": {"port": 5432}, "tls": true}"#).unwrap();
+    /// assert_eq!(answers.get("db.port"), Some("5432"));
+    /// assert_eq!(answers.get("tls"), Some("true"));
+    /// ```
+    pub fn from_json(text: &str) -> Result<Self, InputError> {
+        AnswerSource::parse(parse_json(text))
+    }
+
+    /// one `key=value` per line, both trimmed and the value taken as is.
+    /// empty lines and lines starting with `#` are skipped
+    pub fn from_key_values(text: &str) -> Result<Self, InputError> {
+        AnswerSource::parse(parse_key_values(text))
+    }
+
+    fn parse(entries: Result<Vec<(String, String)>, String>) -> Result<Self, InputError> {
+        entries
+            .map(|entries| AnswerSource { entries })
+            .map_err(|e| InputError::Io(format!("answers: {}", e)))
+    }
+
+    /// the answer to question `id`
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(key, _)| key == id)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// # Recorder
+///
+/// writes the answers of a session to a TOML file as they come in,
+/// under the key of each prompt, for `AnswerSource::load` to replay.
+/// attach it with `Prompter::recorder` or `Form::recorder`, clones
+/// write to the same file. an answer given again replaces the earlier
+/// one, masked form fields are never written
+///
+/// # usage
+/// ```
+/// use input_py::answers::{AnswerSource, Recorder};
+/// use input_py::io::{MockReader, MockWriter};
+/// use input_py::{Form, Input};
+///
+/// let path = std::env::temp_dir().join("input_py_doc_recording.toml");
+/// Form::new()
+///     .field("db.host", Input::new("host"))
+///     .field("db.port", Input::new("port").default("5432"))
+///     .recorder(Recorder::new(&path))
+///     .run_with_io(&mut MockReader::new(&["db1", ""]), &mut MockWriter::new())
+///     .unwrap();
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "db.host = \"db1\"\ndb.port = \"5432\"\n");
+/// assert_eq!(AnswerSource::load(&path).unwrap().get("db.port"), Some("5432"));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Recorder(Arc<Mutex<Recording>>);
+
+#[derive(Debug)]
+struct Recording {
+    file: Slot,
+    entries: Vec<(String, String)>,
+}
+
+impl Recorder {
+    /// record to the file at `path`, replaced at the first answer
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Recorder(Arc::new(Mutex::new(Recording {
+            file: Slot::File(path.as_ref().to_path_buf()),
+            entries: Vec::new(),
+        })))
+    }
+
+    /// keep `answer` under `id` and write the file
+    pub fn record(&self, id: &str, answer: &str) -> Result<(), InputError> {
+        let mut recording = self.lock();
+        match recording.entries.iter_mut().find(|(key, _)| key == id) {
+            Some((_, value)) => *value = answer.to_string(),
+            None => recording.entries.push((id.to_string(), answer.to_string())),
+        }
+        let entries: Vec<(&str, &str)> = recording
+            .entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        recording.file.write(&toml::write(&entries))
+    }
+
+    /// the recorded `(id, answer)` pairs, in the order first answered
+    pub fn entries(&self) -> Vec<(String, String)> {
+        self.lock().entries.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Recording> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// # play
+///
+/// answer every prompt of the process from `source` while the returned
+/// guard lives, see the module docs. it replaces the playbook playing
+/// before, which plays again once the guard is dropped
+///
+/// # usage
+/// ```
+/// use input_py::answers::{self, AnswerSource};
+/// use input_py::io::{MockReader, MockWriter};
+/// use input_py::{Input, InputError};
+///
+/// let playing = answers::play(AnswerSource::new().answer("db.port", "6543"));
+///
+/// let mut writer = MockWriter::new();
+/// let mut reader = MockReader::new(&[]);
+/// let port = Input::new("port").id("db.port").read_with_io(&mut reader, &mut writer);
+/// assert_eq!(port, Ok("6543".to_string()));
+/// let user = Input::new("user").default("admin").read_with_io(&mut reader, &mut writer);
+/// assert_eq!(user, Ok("admin".to_string()));
+/// let host = Input::new("host").read_with_io(&mut reader, &mut writer);
+/// assert_eq!(host, Err(InputError::MissingAnswer("host".to_string())));
+/// assert_eq!(writer.output, "");
+///
+/// // prompts are asked again
+/// drop(playing);
+/// let host = Input::new("host").read_with_io(&mut MockReader::new(&["db1"]), &mut writer);
+/// assert_eq!(host, Ok("db1".to_string()));
+/// ```
+pub fn play(source: AnswerSource) -> Playing {
+    Playing {
+        previous: playbook().replace(source),
+    }
+}
+
+/// # Playing
+///
+/// the guard of `play`, dropping it stops the playbook
+#[must_use = "the playbook stops when the guard is dropped"]
+#[derive(Debug)]
+pub struct Playing {
+    previous: Option<AnswerSource>,
+}
+
+impl Drop for Playing {
+    fn drop(&mut self) {
+        *playbook() = self.previous.take();
+    }
+}
+
+/// whether a playbook is playing
+pub fn is_playing() -> bool {
+    playbook().is_some()
+}
+
+fn playbook() -> MutexGuard<'static, Option<AnswerSource>> {
+    PLAYBOOK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// the answer to `id` from the environment or the playbook
+pub(crate) fn preset(id: &str) -> Result<Option<Cow<'static, str>>, InputError> {
+    if let Some(answer) = config::env_answer(id)? {
+        return Ok(Some(Cow::Borrowed(answer)));
+    }
+    let playbook = playbook();
+    let answer = playbook.as_ref().and_then(|playbook| playbook.get(id));
+    Ok(answer.map(|answer| Cow::Owned(answer.to_string())))
+}
+
+fn parse_key_values(text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut entries = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                entries.push((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => return Err(format!("line {}: expected key=value", i + 1)),
+        }
+    }
+    Ok(entries)
+}
+
+fn parse_json(text: &str) -> Result<Vec<(String, String)>, String> {
+    let value = json::parse(text)?;
+    let mut entries = Vec::new();
+    flatten("", &value, &mut entries)?;
+    Ok(entries)
+}
+
+/// the scalars of `value` under dotted ids starting with `prefix`
+fn flatten(
+    prefix: &str,
+    value: &JsonValue,
+    entries: &mut Vec<(String, String)>,
+) -> Result<(), String> {
+    match value {
+        JsonValue::Object(members) => {
+            for (key, value) in members {
+                let id = match prefix {
+                    "" => key.clone(),
+                    _ => format!("{}.{}", prefix, key),
+                };
+                flatten(&id, value, entries)?;
+            }
+            Ok(())
+        }
+        _ if prefix.is_empty() => Err("expected an object of answers".to_string()),
+        _ => match value.as_text() {
+            Some(text) => {
+                entries.push((prefix.to_string(), text));
+                Ok(())
+            }
+            None => Err(format!("'{}' is not a string, number or bool", prefix)),
+        },
+    }
+}
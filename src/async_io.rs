@@ -0,0 +1,283 @@
+//! # async_io
+//!
+//! async versions of `InputReader` and `OutputWriter`. the traits return
+//! boxed futures so they work as trait objects on any runtime. std readers
+//! and writers are wrapped by `AsyncBufReaderInput` and
+//! `AsyncGenericWriter`, which do the blocking calls on a thread of their
+//! own, and `MockReader` and `MockWriter` implement them for tests. with
+//! the `tokio` feature `TokioReader` and `TokioWriter` wrap tokio's own
+//! async streams
+
+use std::future::Future;
+use std::io::{self, BufRead, Write};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::io::{InputReader, MockReader, MockWriter, OutputWriter};
+
+/// future returned by the async reader and writer traits
+pub type IoFuture<'a, T> = Pin<Box<dyn Future<Output = io::Result<T>> + Send + 'a>>;
+
+/// # AsyncInputReader
+///
+/// source of input lines for async code, like `InputReader`
+pub trait AsyncInputReader: Send {
+    /// read one line into `buf` including the line ending.
+    /// resolves to the number of bytes read, 0 means EOF
+    fn read_line<'a>(&'a mut self, buf: &'a mut String) -> IoFuture<'a, usize>;
+
+    /// whether a person can answer on this reader. false unless overridden
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+/// # AsyncOutputWriter
+///
+/// destination of prompt text for async code, like `OutputWriter`
+pub trait AsyncOutputWriter: Send {
+    fn write_str<'a>(&'a mut self, s: &'a str) -> IoFuture<'a, ()>;
+    fn flush(&mut self) -> IoFuture<'_, ()>;
+}
+
+/// # AsyncBufReaderInput
+///
+/// reads lines from any `BufRead` without blocking the executor, each
+/// read runs on a thread. dropping a read before it finished loses the
+/// reader, later reads fail
+///
+/// # usage
+/// ```
+/// use input_py::async_io::{AsyncBufReaderInput, AsyncInputReader};
+///
+/// let mut reader = AsyncBufReaderInput::new("first\n".as_bytes());
+/// async {
+///     let mut line = String::new();
+///     reader.read_line(&mut line).await.unwrap();
+/// };
+/// ```
+pub struct AsyncBufReaderInput<R: BufRead + Send + 'static> {
+    inner: Option<R>,
+}
+
+impl<R: BufRead + Send + 'static> AsyncBufReaderInput<R> {
+    pub fn new(inner: R) -> Self {
+        AsyncBufReaderInput { inner: Some(inner) }
+    }
+
+    /// the reader, none after a read was dropped before it finished
+    pub fn into_inner(self) -> Option<R> {
+        self.inner
+    }
+}
+
+impl<R: BufRead + Send + 'static> AsyncInputReader for AsyncBufReaderInput<R> {
+    fn read_line<'a>(&'a mut self, buf: &'a mut String) -> IoFuture<'a, usize> {
+        Box::pin(async move {
+            let mut inner = self.inner.take().ok_or_else(lost)?;
+            let (inner, line, read) = Offload::spawn(move || {
+                let mut line = String::new();
+                let read = inner.read_line(&mut line);
+                (inner, line, read)
+            })
+            .await;
+            self.inner = Some(inner);
+            buf.push_str(&line);
+            read
+        })
+    }
+}
+
+/// # AsyncGenericWriter
+///
+/// writes to any `Write` without blocking the executor, each write runs
+/// on a thread. dropping a write before it finished loses the writer
+pub struct AsyncGenericWriter<W: Write + Send + 'static> {
+    inner: Option<W>,
+}
+
+impl<W: Write + Send + 'static> AsyncGenericWriter<W> {
+    pub fn new(inner: W) -> Self {
+        AsyncGenericWriter { inner: Some(inner) }
+    }
+
+    /// the writer, none after a write was dropped before it finished
+    pub fn into_inner(self) -> Option<W> {
+        self.inner
+    }
+
+    /// run `op` on the writer on a thread and put it back
+    async fn with_inner<F>(&mut self, op: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut W) -> io::Result<()> + Send + 'static,
+    {
+        let mut inner = self.inner.take().ok_or_else(lost)?;
+        let (inner, result) = Offload::spawn(move || {
+            let result = op(&mut inner);
+            (inner, result)
+        })
+        .await;
+        self.inner = Some(inner);
+        result
+    }
+}
+
+impl<W: Write + Send + 'static> AsyncOutputWriter for AsyncGenericWriter<W> {
+    fn write_str<'a>(&'a mut self, s: &'a str) -> IoFuture<'a, ()> {
+        let bytes = s.as_bytes().to_vec();
+        Box::pin(self.with_inner(move |inner| inner.write_all(&bytes)))
+    }
+
+    fn flush(&mut self) -> IoFuture<'_, ()> {
+        Box::pin(self.with_inner(|inner| inner.flush()))
+    }
+}
+
+/// # TokioReader
+///
+/// reads lines from a `tokio::io::AsyncBufRead` with
+/// `AsyncBufReadExt::read_line`, on the runtime itself. needs the `tokio`
+/// feature
+///
+/// # usage
+/// ```
+/// use input_py::async_io::{AsyncInputReader, TokioReader};
+///
+/// let mut reader = TokioReader::new(tokio::io::BufReader::new(tokio::io::stdin()));
+/// async {
+///     let mut line = String::new();
+///     reader.read_line(&mut line).await.unwrap();
+/// };
+/// ```
+#[cfg(feature = "tokio")]
+pub struct TokioReader<R: tokio::io::AsyncBufRead + Unpin + Send> {
+    inner: R,
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncBufRead + Unpin + Send> TokioReader<R> {
+    pub fn new(inner: R) -> Self {
+        TokioReader { inner }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncBufRead + Unpin + Send> AsyncInputReader for TokioReader<R> {
+    fn read_line<'a>(&'a mut self, buf: &'a mut String) -> IoFuture<'a, usize> {
+        use tokio::io::AsyncBufReadExt;
+
+        Box::pin(self.inner.read_line(buf))
+    }
+}
+
+/// # TokioWriter
+///
+/// writes to a `tokio::io::AsyncWrite` on the runtime itself. needs the
+/// `tokio` feature
+#[cfg(feature = "tokio")]
+pub struct TokioWriter<W: tokio::io::AsyncWrite + Unpin + Send> {
+    inner: W,
+}
+
+#[cfg(feature = "tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin + Send> TokioWriter<W> {
+    pub fn new(inner: W) -> Self {
+        TokioWriter { inner }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin + Send> AsyncOutputWriter for TokioWriter<W> {
+    fn write_str<'a>(&'a mut self, s: &'a str) -> IoFuture<'a, ()> {
+        use tokio::io::AsyncWriteExt;
+
+        Box::pin(self.inner.write_all(s.as_bytes()))
+    }
+
+    fn flush(&mut self) -> IoFuture<'_, ()> {
+        use tokio::io::AsyncWriteExt;
+
+        Box::pin(self.inner.flush())
+    }
+}
+
+impl AsyncInputReader for MockReader {
+    fn read_line<'a>(&'a mut self, buf: &'a mut String) -> IoFuture<'a, usize> {
+        Box::pin(async move { InputReader::read_line(self, buf) })
+    }
+
+    fn is_interactive(&self) -> bool {
+        InputReader::is_interactive(self)
+    }
+}
+
+impl AsyncOutputWriter for MockWriter {
+    fn write_str<'a>(&'a mut self, s: &'a str) -> IoFuture<'a, ()> {
+        Box::pin(async move { OutputWriter::write_str(self, s) })
+    }
+
+    fn flush(&mut self) -> IoFuture<'_, ()> {
+        Box::pin(async move { OutputWriter::flush(self) })
+    }
+}
+
+fn lost() -> io::Error {
+    io::Error::other("the stream was lost by a cancelled call")
+}
+
+struct Slot<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// the result of a blocking call run on a thread
+struct Offload<T> {
+    slot: Arc<Mutex<Slot<T>>>,
+}
+
+impl<T: Send + 'static> Offload<T> {
+    fn spawn<F>(call: F) -> Self
+    where
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let slot = Arc::new(Mutex::new(Slot {
+            value: None,
+            waker: None,
+        }));
+        let done = Arc::clone(&slot);
+        thread::spawn(move || {
+            let value = call();
+            let mut slot = done.lock().unwrap_or_else(|e| e.into_inner());
+            slot.value = Some(value);
+            if let Some(waker) = slot.waker.take() {
+                waker.wake();
+            }
+        });
+        Offload { slot }
+    }
+}
+
+impl<T> Future for Offload<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        match slot.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
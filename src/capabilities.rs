@@ -0,0 +1,214 @@
+//! # capabilities
+//!
+//! what each subsystem of the crate can reach outside the process, for
+//! consumers that audit their dependencies. no subsystem opens a
+//! network connection or sends telemetry: there is no keyring, DNS
+//! lookup or clipboard access. what remains is running the programs the
+//! app gives a `Driver`, and files the app names
+//!
+//! the crate's tests hold it to the promise: they check the dependency
+//! graph of every feature for networking crates and the sources for
+//! sockets. `capabilities` reports every subsystem at runtime and
+//! `degradations` what didn't work in a restricted environment, like a
+//! container without a terminal or with blocked terminal ioctls
+//!
+//! # usage
+//! ```
+//! use input_py::capabilities;
+//!
+//! for subsystem in capabilities::capabilities() {
+//!     assert!(!subsystem.reaches.network);
+//! }
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static VISIBLE_HIDDEN_INPUT: AtomicBool = AtomicBool::new(false);
+static NO_RAW_MODE: AtomicBool = AtomicBool::new(false);
+
+/// what a subsystem can reach outside the process
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Reach {
+    /// opens network connections
+    pub network: bool,
+    /// starts other programs
+    pub processes: bool,
+    /// reads or writes files the app names, or devices like `/dev/ptmx`
+    pub files: bool,
+}
+
+impl Reach {
+    /// reaches nothing outside the process
+    pub const NOTHING: Reach = Reach {
+        network: false,
+        processes: false,
+        files: false,
+    };
+
+    /// reaches what `self` or `other` does
+    pub const fn union(self, other: Reach) -> Reach {
+        Reach {
+            network: self.network || other.network,
+            processes: self.processes || other.processes,
+            files: self.files || other.files,
+        }
+    }
+}
+
+/// a part of the crate and what it can reach when used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subsystem {
+    pub name: &'static str,
+    /// the cargo feature it comes with, none for the core
+    pub feature: Option<&'static str>,
+    /// whether it is compiled into this build
+    pub enabled: bool,
+    pub reaches: Reach,
+    /// how, for the report
+    pub detail: &'static str,
+}
+
+/// every subsystem that reaches outside the process or pulls in crates,
+/// enabled or not. prompts and the other core modules reach nothing
+pub const SUBSYSTEMS: &[Subsystem] = &[
+    Subsystem {
+        name: "terminal",
+        feature: Some("terminal"),
+        enabled: cfg!(feature = "terminal"),
+        reaches: Reach::NOTHING,
+        detail: "turns the echo of stdin off with termios calls to hide passwords on unix",
+    },
+    Subsystem {
+        name: "line_editor",
+        feature: Some("line-editing"),
+        enabled: cfg!(feature = "line-editing"),
+        reaches: Reach::NOTHING,
+        detail: "switches stdin to raw mode with termios calls on unix",
+    },
+    Subsystem {
+        name: "driver",
+        feature: None,
+        enabled: true,
+        reaches: Reach {
+            processes: true,
+            ..Reach::NOTHING
+        },
+        detail: "`Driver::run` starts the program it is given",
+    },
+    Subsystem {
+        name: "testing",
+        feature: Some("pty"),
+        enabled: cfg!(all(feature = "pty", unix)),
+        reaches: Reach {
+            network: false,
+            processes: true,
+            files: true,
+        },
+        detail: "opens pseudo terminals and starts the commands it is given",
+    },
+    Subsystem {
+        name: "storage",
+        feature: None,
+        enabled: true,
+        reaches: Reach {
+            files: true,
+            ..Reach::NOTHING
+        },
+        detail:
+            "`FileStorage`, `History::load` and `Form::checkpoint` keep files at the given paths",
+    },
+    Subsystem {
+        name: "answers",
+        feature: None,
+        enabled: true,
+        reaches: Reach {
+            files: true,
+            ..Reach::NOTHING
+        },
+        detail: "reads the answers file given in `INPUT_PY_ANSWERS` or to `AnswerSource::load`",
+    },
+    Subsystem {
+        name: "form",
+        feature: None,
+        enabled: true,
+        reaches: Reach {
+            files: true,
+            ..Reach::NOTHING
+        },
+        detail: "`Form::from_toml` reads the given question file",
+    },
+    Subsystem {
+        name: "encrypted",
+        feature: Some("encryption"),
+        enabled: cfg!(feature = "encryption"),
+        reaches: Reach::NOTHING,
+        detail: "asks the OS for random salts and nonces, values go to the storage it wraps",
+    },
+];
+
+/// what the subsystems of this build can reach together
+pub const fn reach() -> Reach {
+    let mut reach = Reach::NOTHING;
+    let mut i = 0;
+    while i < SUBSYSTEMS.len() {
+        if SUBSYSTEMS[i].enabled {
+            reach = reach.union(SUBSYSTEMS[i].reaches);
+        }
+        i += 1;
+    }
+    reach
+}
+
+/// the subsystems compiled into this build that reach outside the
+/// process
+pub fn capabilities() -> Vec<Subsystem> {
+    SUBSYSTEMS.iter().filter(|s| s.enabled).copied().collect()
+}
+
+/// a terminal feature that failed in this process and what was done
+/// instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Degradation {
+    /// the echo couldn't be turned off, hidden input was read visibly
+    /// after a warning, see `config::hidden_fallback`
+    VisibleHiddenInput,
+    /// raw mode couldn't be entered, lines were read without line
+    /// editing and selects by number
+    NoRawMode,
+}
+
+/// the degradations taken so far, each once
+///
+/// # usage
+/// ```
+/// use input_py::capabilities::{self, Degradation};
+///
+/// if capabilities::degradations().contains(&Degradation::VisibleHiddenInput) {
+///     eprintln!("your password was shown on screen");
+/// }
+/// ```
+pub fn degradations() -> Vec<Degradation> {
+    [Degradation::VisibleHiddenInput, Degradation::NoRawMode]
+        .into_iter()
+        .filter(|degradation| is_degraded(*degradation))
+        .collect()
+}
+
+/// note that `degradation` was taken
+#[cfg_attr(not(all(unix, feature = "terminal")), allow(dead_code))]
+pub(crate) fn degrade(degradation: Degradation) {
+    flag(degradation).store(true, Ordering::Relaxed);
+}
+
+/// whether `degradation` was taken, so it isn't tried again
+#[cfg_attr(not(all(unix, feature = "line-editing")), allow(dead_code))]
+pub(crate) fn is_degraded(degradation: Degradation) -> bool {
+    flag(degradation).load(Ordering::Relaxed)
+}
+
+fn flag(degradation: Degradation) -> &'static AtomicBool {
+    match degradation {
+        Degradation::VisibleHiddenInput => &VISIBLE_HIDDEN_INPUT,
+        Degradation::NoRawMode => &NO_RAW_MODE,
+    }
+}
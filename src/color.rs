@@ -0,0 +1,70 @@
+//! # color
+//!
+//! `config::Color` to and from `anstyle` colors, with the `color`
+//! feature, so an app styled for clap can color its prompts alike
+
+use anstyle::AnsiColor;
+
+use crate::config::Color;
+
+impl From<Color> for AnsiColor {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black => AnsiColor::Black,
+            Color::Red => AnsiColor::Red,
+            Color::Green => AnsiColor::Green,
+            Color::Yellow => AnsiColor::Yellow,
+            Color::Blue => AnsiColor::Blue,
+            Color::Magenta => AnsiColor::Magenta,
+            Color::Cyan => AnsiColor::Cyan,
+            Color::White => AnsiColor::White,
+        }
+    }
+}
+
+impl From<Color> for anstyle::Color {
+    fn from(color: Color) -> Self {
+        anstyle::Color::Ansi(color.into())
+    }
+}
+
+/// only the 8 standard colors convert, others are given back
+///
+/// # usage
+/// ```
+/// use anstyle::AnsiColor;
+/// use input_py::config::{Color, Style};
+///
+/// let style = Style { prompt_color: Color::try_from(AnsiColor::Cyan).ok(), ..Style::PLAIN };
+/// assert_eq!(style.prompt_color, Some(Color::Cyan));
+/// assert_eq!(Color::try_from(AnsiColor::BrightCyan), Err(AnsiColor::BrightCyan));
+/// ```
+impl TryFrom<AnsiColor> for Color {
+    type Error = AnsiColor;
+
+    fn try_from(color: AnsiColor) -> Result<Self, AnsiColor> {
+        match color {
+            AnsiColor::Black => Ok(Color::Black),
+            AnsiColor::Red => Ok(Color::Red),
+            AnsiColor::Green => Ok(Color::Green),
+            AnsiColor::Yellow => Ok(Color::Yellow),
+            AnsiColor::Blue => Ok(Color::Blue),
+            AnsiColor::Magenta => Ok(Color::Magenta),
+            AnsiColor::Cyan => Ok(Color::Cyan),
+            AnsiColor::White => Ok(Color::White),
+            bright => Err(bright),
+        }
+    }
+}
+
+/// the foreground of a clap style, see `TryFrom<AnsiColor>`
+impl TryFrom<anstyle::Color> for Color {
+    type Error = anstyle::Color;
+
+    fn try_from(color: anstyle::Color) -> Result<Self, anstyle::Color> {
+        match color {
+            anstyle::Color::Ansi(ansi) => Color::try_from(ansi).map_err(anstyle::Color::Ansi),
+            other => Err(other),
+        }
+    }
+}
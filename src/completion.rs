@@ -0,0 +1,79 @@
+//! # completion
+//!
+//! Tab completion of the line being edited. a `Completer` offers the
+//! answers the text before the cursor can become, the editor fills in
+//! what they share and lists them when they go separate ways
+
+use std::fmt;
+use std::sync::Arc;
+
+/// # Completer
+///
+/// the completions of `partial`, the line up to the cursor. each one
+/// replaces it whole. closures taking the partial line and word lists
+/// work as completers
+///
+/// # usage
+/// ```
+/// use input_py::editor::{Editor, Key};
+///
+/// let mut editor = Editor::new().completer(vec!["status".to_string(), "stash".to_string()]);
+/// for c in "st".chars() {
+///     editor.handle_key(Key::Char(c).into());
+/// }
+/// editor.handle_key(Key::Tab.into());
+/// assert_eq!(editor.line(), "sta");
+/// editor.handle_key(Key::Tab.into());
+/// assert_eq!(editor.completions(), ["status", "stash"]);
+/// ```
+pub trait Completer {
+    fn complete(&self, partial: &str) -> Vec<String>;
+}
+
+impl<F> Completer for F
+where
+    F: Fn(&str) -> Vec<String>,
+{
+    fn complete(&self, partial: &str) -> Vec<String> {
+        self(partial)
+    }
+}
+
+/// the words starting with the partial line
+impl Completer for Vec<String> {
+    fn complete(&self, partial: &str) -> Vec<String> {
+        self.iter()
+            .filter(|word| word.starts_with(partial))
+            .cloned()
+            .collect()
+    }
+}
+
+/// a completer shared by clones of an `Editor`
+#[derive(Clone)]
+pub(crate) struct SharedCompleter(pub(crate) Arc<dyn Completer + Send + Sync>);
+
+impl fmt::Debug for SharedCompleter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Completer")
+    }
+}
+
+/// the longest start all `candidates` share, cut at a char boundary
+pub(crate) fn common_prefix(candidates: &[String]) -> &str {
+    let Some((first, rest)) = candidates.split_first() else {
+        return "";
+    };
+    let mut len = first.len();
+    for candidate in rest {
+        len = first[..len]
+            .char_indices()
+            .zip(candidate.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(candidate.len()), |((i, _), _)| i);
+    }
+    while !first.is_char_boundary(len) {
+        len -= 1;
+    }
+    &first[..len]
+}
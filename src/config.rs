@@ -0,0 +1,523 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use crate::answers::AnswerSource;
+use crate::editor::EditMode;
+use crate::error::InputError;
+use crate::io::OutputWriter;
+use crate::locale::Locale;
+
+static DETERMINISTIC: AtomicBool = AtomicBool::new(false);
+static STRICT_INTERACTIVE: AtomicBool = AtomicBool::new(false);
+static MAX_CONSECUTIVE_ERRORS: AtomicU32 = AtomicU32::new(50);
+static VI_MODE: AtomicBool = AtomicBool::new(false);
+static HIDDEN_STRICT: AtomicBool = AtomicBool::new(false);
+static CATCH_INTERRUPTS: AtomicBool = AtomicBool::new(false);
+static ENV: OnceLock<Env> = OnceLock::new();
+static THEME: Mutex<Theme> = Mutex::new(Theme::DEFAULT);
+
+/// `1`, `true` or `yes`: stdin is never interactive and strict interactive
+/// mode is on, so no prompt waits for a person
+pub const ENV_NONINTERACTIVE: &str = "INPUT_PY_NONINTERACTIVE";
+/// `accept`: prompts with a default return it without asking
+pub const ENV_DEFAULTS: &str = "INPUT_PY_DEFAULTS";
+/// path of a file with answers by question id, like `db.port = 5432`,
+/// see `answers::AnswerSource::load`
+pub const ENV_ANSWERS: &str = "INPUT_PY_ANSWERS";
+/// prefix of variables answering one question, the id upper cased with
+/// every other character than letters and digits replaced by `_`:
+/// `INPUT_PY_ANSWER_DB_PORT=6543` answers `db.port`
+pub const ENV_ANSWER_PREFIX: &str = "INPUT_PY_ANSWER_";
+/// set and not empty: no `Style` colors, see <https://no-color.org>
+pub const ENV_NO_COLOR: &str = "NO_COLOR";
+
+/// # deterministic
+///
+/// when enabled everything the crate records or renders is byte stable
+/// across runs and platforms: transcript entries get no timestamps and no
+/// timing dependent or randomly ordered output is produced.
+/// meant for snapshot tests. default: false
+///
+/// # usage
+/// ```
+/// use input_py::config;
+///
+/// config::deterministic(true);
+/// assert!(config::is_deterministic());
+/// ```
+pub fn deterministic(enabled: bool) {
+    DETERMINISTIC.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_deterministic() -> bool {
+    DETERMINISTIC.load(Ordering::Relaxed)
+}
+
+/// # max_consecutive_errors
+///
+/// how many invalid answers in a row a reprompting prompt accepts before
+/// it gives up with `InputError::TooManyErrors`, so a reader producing
+/// endless invalid input, like `yes | tool`, can't keep it spinning.
+/// 0 means no limit. default: 50
+///
+/// # usage
+/// ```
+/// use input_py::config;
+///
+/// config::max_consecutive_errors(3);
+/// assert_eq!(config::consecutive_error_limit(), 3);
+/// # config::max_consecutive_errors(50);
+/// ```
+pub fn max_consecutive_errors(max: u32) {
+    MAX_CONSECUTIVE_ERRORS.store(max, Ordering::Relaxed);
+}
+
+pub fn consecutive_error_limit() -> u32 {
+    MAX_CONSECUTIVE_ERRORS.load(Ordering::Relaxed)
+}
+
+/// # edit_mode
+///
+/// key bindings of every `editor::Editor` that doesn't set its own.
+/// default: `EditMode::Emacs`
+///
+/// # usage
+/// ```
+/// use input_py::config;
+/// use input_py::editor::EditMode;
+///
+/// config::edit_mode(EditMode::Vi);
+/// assert_eq!(config::default_edit_mode(), EditMode::Vi);
+/// # config::edit_mode(EditMode::Emacs);
+/// ```
+pub fn edit_mode(mode: EditMode) {
+    VI_MODE.store(mode == EditMode::Vi, Ordering::Relaxed);
+}
+
+pub fn default_edit_mode() -> EditMode {
+    match VI_MODE.load(Ordering::Relaxed) {
+        true => EditMode::Vi,
+        false => EditMode::Emacs,
+    }
+}
+
+/// what hidden input does where the terminal echo can't be turned off,
+/// like a container whose seccomp profile blocks the terminal ioctls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HiddenFallback {
+    /// write a warning and read the line visibly
+    #[default]
+    Warn,
+    /// fail with `InputError::Io` instead of showing the input
+    Error,
+}
+
+/// # hidden_fallback
+///
+/// the `HiddenFallback` of passwords and other hidden input. a fallback
+/// taken is reported by `capabilities::degradations`.
+/// default: `HiddenFallback::Warn`
+///
+/// # usage
+/// ```
+/// use input_py::config::{self, HiddenFallback};
+///
+/// config::hidden_fallback(HiddenFallback::Error);
+/// assert_eq!(config::hidden_fallback_policy(), HiddenFallback::Error);
+/// # config::hidden_fallback(HiddenFallback::Warn);
+/// ```
+pub fn hidden_fallback(fallback: HiddenFallback) {
+    HIDDEN_STRICT.store(fallback == HiddenFallback::Error, Ordering::Relaxed);
+}
+
+pub fn hidden_fallback_policy() -> HiddenFallback {
+    match HIDDEN_STRICT.load(Ordering::Relaxed) {
+        true => HiddenFallback::Error,
+        false => HiddenFallback::Warn,
+    }
+}
+
+/// # catch_interrupts
+///
+/// when enabled Ctrl+C during a stdin prompt fails it with
+/// `InputError::Interrupted`, so the caller can ask again or stop the
+/// way it wants. otherwise Ctrl+C ends the process as usual. the
+/// terminal is restored either way, also in the middle of a password
+/// or a raw mode line. default: false
+///
+/// # usage
+/// ```
+/// use input_py::config;
+///
+/// config::catch_interrupts(true);
+/// assert!(config::catches_interrupts());
+/// # config::catch_interrupts(false);
+/// ```
+pub fn catch_interrupts(enabled: bool) {
+    CATCH_INTERRUPTS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn catches_interrupts() -> bool {
+    CATCH_INTERRUPTS.load(Ordering::Relaxed)
+}
+
+/// # locale
+///
+/// how numbers and dates are shown in summaries, diffs and
+/// confirmations, and which number forms `Input::parse` and
+/// `Answers::parse` accept besides the plain one. default: `Locale::C`
+///
+/// # usage
+/// ```
+/// use input_py::config;
+/// use input_py::locale::{self, Locale};
+///
+/// config::locale(Locale::DE);
+/// assert_eq!(locale::current(), Locale::DE);
+/// # config::locale(Locale::C);
+/// ```
+pub fn locale(locale: Locale) {
+    crate::locale::set_current(locale);
+}
+
+/// # theme
+///
+/// the text around prompts, sections and errors, and their colors, for
+/// every prompt that doesn't set its own with `Input::theme` or
+/// `Select::theme`. prompts created before the call keep the theme they
+/// had. default: `Theme::DEFAULT`
+///
+/// # usage
+/// ```
+/// use input_py::config::{self, Theme};
+///
+/// config::theme(Theme { prompt_prefix: "? ", ..Theme::DEFAULT });
+/// assert_eq!(Theme::current().prompt_prefix, "? ");
+/// # config::theme(Theme::DEFAULT);
+/// ```
+pub fn theme(theme: Theme) {
+    *THEME.lock().unwrap_or_else(PoisonError::into_inner) = theme;
+}
+
+/// # strict_interactive
+///
+/// when enabled, a prompt reached while its reader is not interactive
+/// (stdin that is not a terminal) returns its default without reading,
+/// or fails with `InputError::NotInteractive` when it has none. turns CI
+/// runs that would hang on a prompt into immediate failures.
+/// readers other than stdin, like `MockReader`, count as answer sources
+/// and are never affected. default: false
+///
+/// # usage
+/// ```
+/// use input_py::config;
+///
+/// config::strict_interactive(true);
+/// assert!(config::is_strict_interactive());
+/// # config::strict_interactive(false);
+/// ```
+pub fn strict_interactive(enabled: bool) {
+    STRICT_INTERACTIVE.store(enabled, Ordering::Relaxed);
+}
+
+/// also true when `INPUT_PY_NONINTERACTIVE` is set
+pub fn is_strict_interactive() -> bool {
+    STRICT_INTERACTIVE.load(Ordering::Relaxed) || env().noninteractive
+}
+
+/// # environment
+///
+/// operators control prompting of any app built on the crate, without
+/// code changes, through `INPUT_PY_NONINTERACTIVE`, `INPUT_PY_DEFAULTS`
+/// `INPUT_PY_ANSWERS` and `INPUT_PY_ANSWER_<ID>`, see the `ENV_*`
+/// constants. an `INPUT_PY_ANSWER_<ID>` variable wins over the answers
+/// file, which wins over accepting the default, which wins over strict
+/// interactive mode. the variables are read once, at the first prompt
+///
+/// ```sh
+/// INPUT_PY_DEFAULTS=accept INPUT_PY_ANSWERS=answers.toml mytool
+/// ```
+struct Env {
+    noninteractive: bool,
+    no_color: bool,
+    accept_defaults: bool,
+    /// `INPUT_PY_ANSWER_<ID>` variables as `(ID, answer)`, none for an
+    /// answer that isn't UTF-8, reported when its question is asked
+    overrides: Vec<(String, Option<String>)>,
+    /// read error of the answers file, reported at the first prompt
+    answers: Result<AnswerSource, InputError>,
+}
+
+fn env() -> &'static Env {
+    ENV.get_or_init(|| {
+        let var = |name| std::env::var(name).unwrap_or_default();
+        let answers = match std::env::var_os(ENV_ANSWERS) {
+            Some(path) => AnswerSource::load(path),
+            None => Ok(AnswerSource::new()),
+        };
+        // vars() panics on any variable that isn't UTF-8, even unrelated ones
+        let overrides = std::env::vars_os()
+            .filter_map(|(name, value)| {
+                let id = name.to_str()?.strip_prefix(ENV_ANSWER_PREFIX)?;
+                Some((id.to_string(), value.into_string().ok()))
+            })
+            .collect();
+        Env {
+            overrides,
+            noninteractive: matches!(var(ENV_NONINTERACTIVE).as_str(), "1" | "true" | "yes"),
+            no_color: !var(ENV_NO_COLOR).is_empty(),
+            accept_defaults: var(ENV_DEFAULTS) == "accept",
+            answers,
+        }
+    })
+}
+
+pub(crate) fn env_noninteractive() -> bool {
+    env().noninteractive
+}
+
+pub(crate) fn env_accepts_defaults() -> bool {
+    env().accept_defaults
+}
+
+pub(crate) fn env_no_color() -> bool {
+    env().no_color
+}
+
+/// whether `name` is `id` in `INPUT_PY_ANSWER_<ID>` form, without allocating
+fn env_name_matches(name: &str, id: &str) -> bool {
+    let id = id.chars().map(|c| match c.is_ascii_alphanumeric() {
+        true => c.to_ascii_uppercase(),
+        false => '_',
+    });
+    name.chars().eq(id)
+}
+
+/// the answer to `id` from an `INPUT_PY_ANSWER_<ID>` variable or
+/// `INPUT_PY_ANSWERS`
+pub(crate) fn env_answer(id: &str) -> Result<Option<&'static str>, InputError> {
+    let env = env();
+    let variable = env
+        .overrides
+        .iter()
+        .find(|(name, _)| env_name_matches(name, id));
+    match variable {
+        Some((_, Some(value))) => return Ok(Some(value)),
+        Some((name, None)) => {
+            return Err(InputError::Io(format!(
+                "{}{} is not valid UTF-8",
+                ENV_ANSWER_PREFIX, name
+            )))
+        }
+        None => {}
+    }
+    match &env.answers {
+        Ok(answers) => Ok(answers.get(id)),
+        Err(e) => Err(e.clone()),
+    }
+}
+
+/// # Theme
+///
+/// the text around prompts, sections and errors, their colors and the
+/// styles of Markdown prompt bodies. every built-in prompt renders
+/// through one, `config::theme` sets it for all of them.
+/// `Theme::DEFAULT` uses no escape codes, `Theme::ANSI` styles bodies
+/// for color terminals. `colors` are used only when the writer is a
+/// terminal and `NO_COLOR` isn't set
+///
+/// # usage
+/// ```
+/// use input_py::config::Theme;
+/// use input_py::{render_prompt, Question};
+///
+/// let theme = Theme { prompt_prefix: "? ", prompt_suffix: " >", ..Theme::DEFAULT };
+/// let text = render_prompt(&Question::new("name", "name").default("guest"), &theme);
+/// assert_eq!(text, "? name [guest] >");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// written right before the prompt text
+    pub prompt_prefix: &'static str,
+    /// written right after the prompt text
+    pub prompt_suffix: &'static str,
+    /// opens the default value hint
+    pub default_open: &'static str,
+    /// closes the default value hint
+    pub default_close: &'static str,
+    /// written before messages about rejected input
+    pub error_prefix: &'static str,
+    /// written around a section title of a `Form`
+    pub section: (&'static str, &'static str),
+    /// colors of the prompt, the default hint and the errors
+    pub colors: Style,
+    /// written around `**bold**` text and headings of bodies
+    pub bold: (&'static str, &'static str),
+    /// written around `` `code` `` spans of bodies
+    pub code: (&'static str, &'static str),
+    /// replaces the `-` or `*` of list items in bodies
+    pub bullet: &'static str,
+    /// written around added fields of a `diff::ChangeSummary`
+    pub added: (&'static str, &'static str),
+    /// written around changed fields of a `diff::ChangeSummary`
+    pub changed: (&'static str, &'static str),
+    /// written around disabled `Select` items
+    pub disabled: (&'static str, &'static str),
+    /// written around warnings, like the `86/80` counter of an answer
+    /// over `Input::max_len`
+    pub warning: (&'static str, &'static str),
+}
+
+impl Theme {
+    pub const DEFAULT: Theme = Theme {
+        prompt_prefix: "",
+        prompt_suffix: format::PROMPT_SUFFIX,
+        default_open: format::DEFAULT_OPEN,
+        default_close: format::DEFAULT_CLOSE,
+        error_prefix: format::ERROR_PREFIX,
+        section: (format::SECTION_OPEN, format::SECTION_CLOSE),
+        colors: Style::PLAIN,
+        bold: ("", ""),
+        code: ("", ""),
+        bullet: "• ",
+        added: ("", ""),
+        changed: ("", ""),
+        disabled: ("", ""),
+        warning: ("", ""),
+    };
+
+    /// `DEFAULT` with bold and colors for ANSI terminals
+    pub const ANSI: Theme = Theme {
+        bold: ("\x1b[1m", "\x1b[22m"),
+        code: ("\x1b[36m", "\x1b[39m"),
+        added: ("\x1b[32m", "\x1b[39m"),
+        changed: ("\x1b[33m", "\x1b[39m"),
+        disabled: ("\x1b[2m", "\x1b[22m"),
+        warning: ("\x1b[33m", "\x1b[39m"),
+        ..Theme::DEFAULT
+    };
+
+    /// the theme set with `config::theme`
+    pub fn current() -> Theme {
+        *THEME.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// the theme to write to `writer` with, without colors unless it
+    /// shows them
+    pub(crate) fn for_writer(&self, writer: &dyn OutputWriter) -> Theme {
+        match writer.is_terminal() && !env_no_color() {
+            true => *self,
+            false => Theme {
+                colors: Style::PLAIN,
+                ..*self
+            },
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::DEFAULT
+    }
+}
+
+/// # Style
+///
+/// colors of a prompt, its `[default]` hint and the errors about
+/// rejected answers, the `colors` of a `Theme`. used only when the
+/// writer is a terminal and `NO_COLOR` isn't set, otherwise the prompt
+/// is plain text
+///
+/// # usage
+/// ```
+/// use input_py::config::{Color, Style};
+/// use input_py::io::{MockReader, MockWriter};
+/// use input_py::Input;
+///
+/// let style = Style { prompt_color: Some(Color::Cyan), ..Style::PLAIN };
+/// let mut writer = MockWriter::new().terminal(true);
+/// Input::new("name")
+///     .style(style)
+///     .read_with_io(&mut MockReader::new(&["ann"]), &mut writer)
+///     .unwrap();
+/// // "name" in cyan, unless NO_COLOR is set
+/// assert!(writer.output.ends_with("name\x1b[39m:") || writer.output == "name:");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub prompt_color: Option<Color>,
+    pub default_color: Option<Color>,
+    pub error_color: Option<Color>,
+}
+
+impl Style {
+    /// no colors at all
+    pub const PLAIN: Style = Style {
+        prompt_color: None,
+        default_color: None,
+        error_color: None,
+    };
+}
+
+/// the 8 standard terminal colors, which terminal themes adjust
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    /// the escape code switching to the color
+    pub fn code(&self) -> &'static str {
+        match self {
+            Color::Black => "\x1b[30m",
+            Color::Red => "\x1b[31m",
+            Color::Green => "\x1b[32m",
+            Color::Yellow => "\x1b[33m",
+            Color::Blue => "\x1b[34m",
+            Color::Magenta => "\x1b[35m",
+            Color::Cyan => "\x1b[36m",
+            Color::White => "\x1b[37m",
+        }
+    }
+}
+
+/// the escape code back to the terminal's own color
+pub(crate) const COLOR_RESET: &str = "\x1b[39m";
+
+/// append `text` to `buf` in `color`, if any
+pub(crate) fn paint_into(buf: &mut String, text: &str, color: Option<Color>) {
+    match color {
+        Some(color) => {
+            buf.push_str(color.code());
+            buf.push_str(text);
+            buf.push_str(COLOR_RESET);
+        }
+        None => buf.push_str(text),
+    }
+}
+
+/// # format
+///
+/// the text of `Theme::DEFAULT`. prompts render through the `Theme`,
+/// set `config::theme` to change it
+pub mod format {
+    /// written right after the prompt text
+    pub const PROMPT_SUFFIX: &str = ":";
+    /// opens the default value hint, like `name [guest]:`
+    pub const DEFAULT_OPEN: &str = " [";
+    /// closes the default value hint
+    pub const DEFAULT_CLOSE: &str = "]";
+    /// written before messages about rejected input
+    pub const ERROR_PREFIX: &str = "error: ";
+    /// written before a section title, like `— Network —`
+    pub const SECTION_OPEN: &str = "— ";
+    /// written after a section title
+    pub const SECTION_CLOSE: &str = " —";
+}
@@ -0,0 +1,343 @@
+use crate::config::Theme;
+use crate::editor::{Key, KeyEvent};
+use crate::error::InputError;
+use crate::input::Input;
+use crate::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::model::{Answer, Provenance};
+use crate::prompter::Prompt;
+use crate::widget::{Event, Role, StyledWriter, Widget};
+use crate::{check_interactive, read_answer, render_prompt_into, Retries};
+
+/// a line with just Esc, the terminal's way to back out
+const ESC: &str = "\u{1b}";
+
+/// answer of a `Confirm3`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confirmation {
+    Yes,
+    No,
+    /// stop the whole flow, not just this step
+    Abort,
+}
+
+impl Confirmation {
+    /// true for yes, false for no, `InputError::Aborted` for abort, so
+    /// `?` ends the flow
+    pub fn or_abort(self) -> Result<bool, InputError> {
+        match self {
+            Confirmation::Yes => Ok(true),
+            Confirmation::No => Ok(false),
+            Confirmation::Abort => Err(InputError::Aborted),
+        }
+    }
+
+    fn key(self) -> char {
+        match self {
+            Confirmation::Yes => 'y',
+            Confirmation::No => 'n',
+            Confirmation::Abort => 'a',
+        }
+    }
+}
+
+/// # Confirm
+///
+/// a yes/no question, created by `Input::confirm`. `y`, `yes`, `n` and
+/// `no` answer in any case, anything else asks again. the default, from
+/// `Input::default` or `Confirm::default`, is shown in capitals
+///
+/// # usage
+/// ```
+/// use input_py::Input;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut writer = MockWriter::new();
+/// let overwrite = Input::new("overwrite?")
+///     .confirm()
+///     .default(true)
+///     .read_with_io(&mut MockReader::new(&["maybe", "NO"]), &mut writer);
+/// assert_eq!(overwrite, Ok(false));
+/// assert_eq!(
+///     writer.output,
+///     "overwrite? [Y/n]:error: 'maybe' is not y or n\noverwrite? [Y/n]:"
+/// );
+/// ```
+pub struct Confirm {
+    input: Input,
+}
+
+impl Confirm {
+    pub(crate) fn new(input: Input) -> Self {
+        Confirm { input }
+    }
+
+    /// answer of an empty line
+    pub fn default(mut self, default: bool) -> Self {
+        self.input.default = Some(yes_no_key(default).to_string());
+        self
+    }
+
+    /// ask on stdin
+    pub fn read(self) -> Result<bool, InputError> {
+        self.read_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    pub fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<bool, InputError> {
+        let id = self
+            .input
+            .id
+            .clone()
+            .unwrap_or_else(|| self.input.prompt.clone());
+        self.ask(&id, reader, writer).map(|(answer, _)| answer)
+    }
+
+    /// the answer as the `Answer` to question `id`, `y` or `n`, the
+    /// default when an empty line chose it, and how many times it was
+    /// asked
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    /// use input_py::model::Provenance;
+    ///
+    /// let mut reader = MockReader::new(&["maybe", "yes"]);
+    /// let deploy = Input::new("deploy?")
+    ///     .confirm()
+    ///     .read_answer_with_io("deploy", &mut reader, &mut MockWriter::new())
+    ///     .unwrap();
+    /// assert_eq!((deploy.value.as_str(), deploy.provenance, deploy.attempts), ("y", Provenance::Entered, 2));
+    /// ```
+    pub fn read_answer_with_io(
+        self,
+        id: &str,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Answer, InputError> {
+        self.ask(id, reader, writer).map(|(_, answer)| answer)
+    }
+
+    /// the answer to question `id` with its `Answer`
+    fn ask(
+        self,
+        id: &str,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<(bool, Answer), InputError> {
+        let answered = |value: bool, provenance, attempts| {
+            let answer = Answer::new(id, yes_no_key(value), provenance);
+            (value, answer.attempts(attempts))
+        };
+        let default = self.parsed_default()?;
+        let mut prompt = String::new();
+        let theme = self.input.theme.for_writer(writer);
+        render_prompt_into(&mut prompt, &self.input.prompt, Some(keys(default)), &theme);
+        let key = default.map(yes_no_key);
+        if check_interactive(&prompt, key, reader)?.is_some() {
+            if let Some(default) = default {
+                return Ok(answered(default, Provenance::Default, 1));
+            }
+        }
+        if self.input.flush_pending {
+            reader.discard_pending()?;
+        }
+
+        let mut retries =
+            Retries::with_max_attempts(self.input.max_attempts).theme(self.input.theme);
+        loop {
+            let answer = read_answer(&prompt, reader, writer)?.ok_or(InputError::Eof)?;
+            let attempts = retries.attempts();
+            match (yes_no(&answer), default) {
+                (Some(answer), _) => return Ok(answered(answer, Provenance::Entered, attempts)),
+                (None, Some(default)) if answer.is_empty() => {
+                    return Ok(answered(default, Provenance::Default, attempts))
+                }
+                _ => retries.reject(writer, &format!("'{}' is not y or n", answer))?,
+            }
+        }
+    }
+
+    /// this question as a widget, see `widget`. `y` and `n` answer at
+    /// once, Enter takes the default
+    pub fn into_widget(self) -> Result<ConfirmWidget, InputError> {
+        Ok(ConfirmWidget {
+            default: self.parsed_default()?,
+            prompt: self.input.prompt,
+        })
+    }
+
+    fn parsed_default(&self) -> Result<Option<bool>, InputError> {
+        match self.input.default.as_deref().map(yes_no) {
+            Some(None) => Err(InputError::MissingDefault(self.input.prompt.clone())),
+            default => Ok(default.flatten()),
+        }
+    }
+}
+
+/// a `Confirm` driven by key events, made by `Confirm::into_widget`
+pub struct ConfirmWidget {
+    prompt: String,
+    default: Option<bool>,
+}
+
+impl Widget for ConfirmWidget {
+    type Output = bool;
+
+    fn handle_key(&mut self, key: KeyEvent) -> Event<bool> {
+        match (key.key, key.ctrl) {
+            (Key::Char('c'), true) | (Key::Esc, _) => Event::Cancelled,
+            (_, true) => Event::Continue,
+            (Key::Char(c), _) => match yes_no(&c.to_string()) {
+                Some(answer) => Event::Done(answer),
+                None => Event::Continue,
+            },
+            (Key::Enter, _) => self.default.map_or(Event::Continue, Event::Done),
+            _ => Event::Continue,
+        }
+    }
+
+    fn render(&self, out: &mut dyn StyledWriter) {
+        out.write(&self.prompt, Role::Prompt);
+        out.write(" ", Role::Text);
+        out.write(&format!("[{}]", keys(self.default)), Role::Hint);
+        out.write(": ", Role::Text);
+        out.set_cursor();
+    }
+}
+
+impl Prompt for Confirm {
+    type Output = bool;
+
+    fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<bool, InputError> {
+        Confirm::read_with_io(self, reader, writer)
+    }
+}
+
+/// `answer` as yes or no, in any case
+fn yes_no(answer: &str) -> Option<bool> {
+    match answer.to_lowercase().as_str() {
+        "y" | "yes" => Some(true),
+        "n" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// the keys shown after the question, the default in capitals
+fn keys(default: Option<bool>) -> &'static str {
+    match default {
+        Some(true) => "Y/n",
+        Some(false) => "y/N",
+        None => "y/n",
+    }
+}
+
+fn yes_no_key(answer: bool) -> &'static str {
+    match answer {
+        true => "y",
+        false => "n",
+    }
+}
+
+/// # Confirm3
+///
+/// a yes/no question that can also abort, so wizards can tell "skip this
+/// step" from "stop everything". `y`, `n` and `a` or the words answer,
+/// as does a line with just Esc for abort. the default is shown in
+/// capitals
+///
+/// # usage
+/// ```
+/// use input_py::{Confirm3, Confirmation};
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut writer = MockWriter::new();
+/// let answer = Confirm3::new("install extras?")
+///     .default(Confirmation::No)
+///     .read_with_io(&mut MockReader::new(&["a"]), &mut writer);
+/// assert_eq!(answer, Ok(Confirmation::Abort));
+/// assert_eq!(writer.output, "install extras? [y/N/a]:");
+/// assert!(answer.unwrap().or_abort().is_err());
+/// ```
+pub struct Confirm3 {
+    prompt: String,
+    default: Option<Confirmation>,
+}
+
+impl Confirm3 {
+    pub fn new(prompt: &str) -> Self {
+        Confirm3 {
+            prompt: prompt.to_string(),
+            default: None,
+        }
+    }
+
+    /// answer of an empty line
+    pub fn default(mut self, default: Confirmation) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// ask on stdin
+    pub fn read(self) -> Result<Confirmation, InputError> {
+        self.read_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    pub fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Confirmation, InputError> {
+        let keys: Vec<String> = [Confirmation::Yes, Confirmation::No, Confirmation::Abort]
+            .into_iter()
+            .map(|answer| match Some(answer) == self.default {
+                true => answer.key().to_ascii_uppercase().to_string(),
+                false => answer.key().to_string(),
+            })
+            .collect();
+        let mut prompt = String::new();
+        let theme = Theme::current().for_writer(writer);
+        render_prompt_into(&mut prompt, &self.prompt, Some(&keys.join("/")), &theme);
+        let default = self.default.map(|default| default.key().to_string());
+        if check_interactive(&prompt, default.as_deref(), reader)?.is_some() {
+            if let Some(default) = self.default {
+                return Ok(default);
+            }
+        }
+
+        let mut retries = Retries::new();
+        loop {
+            let answer = read_answer(&prompt, reader, writer)?.ok_or(InputError::Eof)?;
+            let answer = match (answer.to_lowercase().as_str(), self.default) {
+                ("", Some(default)) => default,
+                ("y" | "yes", _) => Confirmation::Yes,
+                ("n" | "no", _) => Confirmation::No,
+                ("a" | "abort" | ESC, _) => Confirmation::Abort,
+                _ => {
+                    retries.reject(writer, &format!("'{}' is not y, n or a", answer))?;
+                    continue;
+                }
+            };
+            return Ok(answer);
+        }
+    }
+}
+
+impl Prompt for Confirm3 {
+    type Output = Confirmation;
+
+    fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Confirmation, InputError> {
+        Confirm3::read_with_io(self, reader, writer)
+    }
+}
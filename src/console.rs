@@ -0,0 +1,72 @@
+//! # console
+//!
+//! the windows console behind `StdinReader`, with the `terminal` feature
+
+use std::ffi::c_void;
+use std::io;
+use std::time::{Duration, Instant};
+
+const STD_INPUT_HANDLE: u32 = -10i32 as u32;
+const WAIT_OBJECT_0: u32 = 0;
+const WAIT_TIMEOUT: u32 = 0x102;
+const KEY_EVENT: u16 = 1;
+
+/// the start of an INPUT_RECORD, enough to tell a key press
+#[repr(C)]
+struct InputRecord {
+    event_type: u16,
+    key_down: i32,
+    rest: [u8; 12],
+}
+
+extern "system" {
+    fn GetStdHandle(which: u32) -> *mut c_void;
+    fn WaitForSingleObject(handle: *mut c_void, millis: u32) -> u32;
+    fn PeekConsoleInputW(
+        handle: *mut c_void,
+        records: *mut InputRecord,
+        len: u32,
+        read: *mut u32,
+    ) -> i32;
+    fn ReadConsoleInputW(
+        handle: *mut c_void,
+        records: *mut InputRecord,
+        len: u32,
+        read: *mut u32,
+    ) -> i32;
+}
+
+/// waits up to `timeout` for a key press. focus and mouse events also
+/// wake the console handle, they are dropped and the wait goes on
+pub(crate) fn wait_key(timeout: Duration) -> io::Result<bool> {
+    let deadline = Instant::now() + timeout;
+    // SAFETY: the handle is the process stdin, records are written
+    // into a local of the right size
+    unsafe {
+        let handle = GetStdHandle(STD_INPUT_HANDLE);
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            let millis = left.as_millis().min(u32::MAX as u128 - 1) as u32;
+            match WaitForSingleObject(handle, millis) {
+                WAIT_OBJECT_0 => {}
+                WAIT_TIMEOUT => return Ok(false),
+                _ => return Err(io::Error::last_os_error()),
+            }
+            let mut record = InputRecord {
+                event_type: 0,
+                key_down: 0,
+                rest: [0; 12],
+            };
+            let mut read = 0;
+            if PeekConsoleInputW(handle, &mut record, 1, &mut read) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if read == 0 || (record.event_type == KEY_EVENT && record.key_down != 0) {
+                return Ok(true);
+            }
+            if ReadConsoleInputW(handle, &mut record, 1, &mut read) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+    }
+}
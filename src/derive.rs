@@ -0,0 +1,77 @@
+//! # derive
+//!
+//! structs filled in by a `Form`, with the `derive` feature.
+//! `#[derive(FromForm)]` asks one question per field, see
+//! `input_py_derive` for the attributes
+//!
+//! # usage
+//! ```
+//! use input_py::derive::FromForm;
+//! use input_py::io::{MockReader, MockWriter};
+//!
+//! #[derive(FromForm)]
+//! struct Database {
+//!     host: String,
+//!     #[input(prompt = "port number", default = "5432")]
+//!     port: u16,
+//! }
+//!
+//! let mut reader = MockReader::new(&["db.local", "http", ""]);
+//! let mut writer = MockWriter::new();
+//! let db = Database::ask_with_io(&mut reader, &mut writer).unwrap();
+//! assert_eq!((db.host.as_str(), db.port), ("db.local", 5432));
+//! assert!(writer.output.contains("error: invalid value 'http': expected integer\n"));
+//! ```
+
+use std::str::FromStr;
+
+pub use input_py_derive::FromForm;
+
+use crate::error::InputError;
+use crate::form::{Answers, Form};
+use crate::io::{InputReader, OutputWriter};
+use crate::locale;
+use crate::prompter::expected;
+
+/// # FromForm
+///
+/// a value built from the answers of its `form`, usually derived
+pub trait FromForm: Sized {
+    /// the form asking for every field
+    fn form() -> Form;
+
+    /// the value from the answers of `form`
+    fn from_answers(answers: &Answers) -> Result<Self, InputError>;
+
+    /// ask on stdin and stdout
+    fn ask() -> Result<Self, InputError> {
+        Self::from_answers(&Self::form().run()?)
+    }
+
+    fn ask_with_io(
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Self, InputError> {
+        Self::from_answers(&Self::form().run_with_io(reader, writer)?)
+    }
+}
+
+/// rejects answers that don't parse as `T`, for the derived forms
+#[doc(hidden)]
+pub fn check<T: FromStr>(answer: &str) -> Result<(), String> {
+    locale::parse::<T>(answer)
+        .map(|_| ())
+        .map_err(|_| format!("invalid value '{}': {}", answer, expected::<T>()))
+}
+
+/// the answer of `id` as `T`, for the derived forms
+#[doc(hidden)]
+pub fn value<T: FromStr>(answers: &Answers, id: &str) -> Result<T, InputError> {
+    let answer = answers
+        .get(id)
+        .ok_or_else(|| InputError::MissingAnswer(id.to_string()))?;
+    locale::parse(answer).map_err(|_| InputError::InvalidAnswer {
+        answer: answer.to_string(),
+        msg: expected::<T>(),
+    })
+}
@@ -0,0 +1,177 @@
+//! # diff
+//!
+//! the last step of an interactive config editor: show what the answers
+//! of a form change in the existing config and ask before applying them
+
+use crate::config::Theme;
+use crate::error::InputError;
+use crate::form::{ask_yes, Answers};
+use crate::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::locale::{self, Locale};
+
+/// how an answer relates to the existing value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Changed,
+    Unchanged,
+}
+
+/// one answered field compared with the existing config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub id: String,
+    /// the existing value, `None` for added fields
+    pub old: Option<String>,
+    pub new: String,
+}
+
+impl Change {
+    pub fn kind(&self) -> ChangeKind {
+        match &self.old {
+            None => ChangeKind::Added,
+            Some(old) if *old != self.new => ChangeKind::Changed,
+            Some(_) => ChangeKind::Unchanged,
+        }
+    }
+}
+
+/// # ChangeSummary
+///
+/// the answers of a form compared field by field with the existing
+/// values. every answer is listed in order, `+` marks added fields, `~`
+/// changed ones with the old value. existing values without an answer
+/// are not listed, the caller keeps them
+///
+/// # usage
+/// ```
+/// use input_py::Answers;
+/// use input_py::diff::ChangeSummary;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut answers = Answers::new();
+/// answers.insert("host", "db");
+/// answers.insert("port", "5433");
+/// answers.insert("name", "app");
+/// let existing = [("host", "db"), ("port", "5432")];
+///
+/// let mut writer = MockWriter::new();
+/// let apply = ChangeSummary::new(existing, &answers)
+///     .confirm_with_io(&mut MockReader::new(&["y"]), &mut writer);
+/// assert_eq!(apply, Ok(true));
+/// assert_eq!(
+///     writer.output,
+///     "  host = db\n~ port = 5432 → 5433\n+ name = app\napply these changes? [Y/n]:"
+/// );
+/// ```
+pub struct ChangeSummary {
+    changes: Vec<Change>,
+    theme: Theme,
+    locale: Locale,
+}
+
+impl ChangeSummary {
+    pub fn new<I, K, V>(existing: I, answers: &Answers) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let existing: Vec<(K, V)> = existing.into_iter().collect();
+        let changes = answers
+            .iter()
+            .map(|(id, value)| Change {
+                id: id.to_string(),
+                old: existing
+                    .iter()
+                    .find(|(k, _)| k.as_ref() == id)
+                    .map(|(_, v)| v.as_ref().to_string()),
+                new: value.to_string(),
+            })
+            .collect();
+        ChangeSummary {
+            changes,
+            theme: Theme::current(),
+            locale: locale::current(),
+        }
+    }
+
+    /// colors of added and changed fields. default: the `config::theme`
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// how numbers and dates are shown. default: the `config::locale`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Answers;
+    /// use input_py::diff::ChangeSummary;
+    /// use input_py::locale::Locale;
+    ///
+    /// let mut answers = Answers::new();
+    /// answers.insert("budget", "12500.75");
+    /// let summary = ChangeSummary::new([("budget", "9000")], &answers).locale(Locale::DE);
+    /// assert_eq!(summary.render(), "~ budget = 9.000 → 12.500,75\n");
+    /// ```
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// whether any answer adds or changes a value
+    pub fn has_changes(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.kind() != ChangeKind::Unchanged)
+    }
+
+    /// the summary, one line per answer
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for change in &self.changes {
+            let new = self.locale.display(&change.new);
+            match (change.kind(), &change.old) {
+                (ChangeKind::Added, _) => out.push_str(&format!(
+                    "{}+ {} = {}{}\n",
+                    self.theme.added.0, change.id, new, self.theme.added.1
+                )),
+                (ChangeKind::Changed, Some(old)) => out.push_str(&format!(
+                    "{}~ {} = {} → {}{}\n",
+                    self.theme.changed.0,
+                    change.id,
+                    self.locale.display(old),
+                    new,
+                    self.theme.changed.1
+                )),
+                _ => out.push_str(&format!("  {} = {}\n", change.id, new)),
+            }
+        }
+        out
+    }
+
+    /// show the summary on stdout and ask whether to apply it
+    pub fn confirm(self) -> Result<bool, InputError> {
+        self.confirm_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    /// true when the changes should be applied. without changes nothing
+    /// is asked and the result is false
+    pub fn confirm_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<bool, InputError> {
+        writer.write_str(&self.render())?;
+        if !self.has_changes() {
+            writer.write_str("no changes\n")?;
+            return Ok(false);
+        }
+        ask_yes("apply these changes? [Y/n]", reader, writer)
+    }
+}
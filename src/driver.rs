@@ -0,0 +1,267 @@
+//! # driver
+//!
+//! runs another program and answers its prompts from a script, like
+//! `expect`. useful to test CLIs built on this crate end to end
+//!
+//! # usage
+//! ```no_run
+//! use std::time::Duration;
+//! use input_py::driver::Driver;
+//!
+//! let run = Driver::new("my-cli")
+//!     .arg("init")
+//!     .expect("name", "alice")
+//!     .expect("port [8080]", "")
+//!     .timeout(Duration::from_secs(5))
+//!     .run()
+//!     .unwrap();
+//! assert!(run.status.success());
+//! println!("{}", run.transcript);
+//! ```
+
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// error of a scripted run
+#[derive(Debug)]
+pub enum DriverError {
+    /// starting or talking to the program failed
+    Io(io::Error),
+    /// `expected` didn't appear in time, `output` is everything read so far
+    Timeout { expected: String, output: String },
+    /// the program closed its output before `expected` appeared
+    Exited { expected: String, output: String },
+}
+
+impl fmt::Display for DriverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DriverError::Io(err) => write!(f, "io error: {}", err),
+            DriverError::Timeout { expected, .. } => {
+                write!(f, "timed out waiting for '{}'", expected)
+            }
+            DriverError::Exited { expected, .. } => {
+                write!(f, "program exited before '{}'", expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DriverError {}
+
+impl From<io::Error> for DriverError {
+    fn from(err: io::Error) -> Self {
+        DriverError::Io(err)
+    }
+}
+
+/// result of a finished scripted run
+#[derive(Debug)]
+pub struct Run {
+    /// everything the program wrote, with the answers sent to it
+    pub transcript: String,
+    pub status: ExitStatus,
+}
+
+/// the spawned program with its input and output ends
+type Spawned = (Child, Box<dyn Write>, Box<dyn Read + Send>);
+
+struct Step {
+    expected: String,
+    answer: String,
+}
+
+/// # Driver
+///
+/// runs a program and answers its prompts. every `expect` waits until
+/// its text appears in the output after the previous match, then sends
+/// the answer followed by a newline
+pub struct Driver {
+    command: Command,
+    steps: Vec<Step>,
+    timeout: Duration,
+    #[cfg(all(feature = "pty", unix))]
+    pty: bool,
+}
+
+impl Driver {
+    pub fn new(program: &str) -> Self {
+        Driver {
+            command: Command::new(program),
+            steps: Vec::new(),
+            timeout: Duration::from_secs(10),
+            #[cfg(all(feature = "pty", unix))]
+            pty: false,
+        }
+    }
+
+    pub fn arg(mut self, arg: &str) -> Self {
+        self.command.arg(arg);
+        self
+    }
+
+    pub fn env(mut self, key: &str, value: &str) -> Self {
+        self.command.env(key, value);
+        self
+    }
+
+    /// wait for `expected` in the output, then answer with `answer`
+    pub fn expect(mut self, expected: &str, answer: &str) -> Self {
+        self.steps.push(Step {
+            expected: expected.to_string(),
+            answer: answer.to_string(),
+        });
+        self
+    }
+
+    /// how long to wait for each expected text and for the exit, default: 10s
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// attach the program to a pseudo terminal instead of pipes, so it
+    /// sees a TTY. the terminal echoes the answers into the transcript
+    #[cfg(all(feature = "pty", unix))]
+    pub fn pty(mut self, pty: bool) -> Self {
+        self.pty = pty;
+        self
+    }
+
+    pub fn run(mut self) -> Result<Run, DriverError> {
+        let (mut child, mut input, output) = self.spawn()?;
+        let echoes = self.echoes();
+        let chunks = read_chunks(output);
+        let mut transcript = String::new();
+        let mut searched = 0;
+
+        for step in &self.steps {
+            let deadline = Instant::now() + self.timeout;
+            loop {
+                if let Some(pos) = transcript[searched..].find(&step.expected) {
+                    searched += pos + step.expected.len();
+                    break;
+                }
+                let wait = deadline.saturating_duration_since(Instant::now());
+                match chunks.recv_timeout(wait) {
+                    Ok(chunk) => transcript.push_str(&chunk),
+                    Err(err) => {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        let expected = step.expected.clone();
+                        let output = transcript;
+                        return Err(match err {
+                            RecvTimeoutError::Timeout => DriverError::Timeout { expected, output },
+                            RecvTimeoutError::Disconnected => {
+                                DriverError::Exited { expected, output }
+                            }
+                        });
+                    }
+                }
+            }
+            input.write_all(format!("{}\n", step.answer).as_bytes())?;
+            input.flush()?;
+            if !echoes {
+                transcript.push_str(&step.answer);
+                transcript.push('\n');
+                searched = transcript.len();
+            }
+        }
+        drop(input);
+
+        let status = wait_with_timeout(&mut child, self.timeout)?;
+        while let Ok(chunk) = chunks.recv_timeout(Duration::from_millis(50)) {
+            transcript.push_str(&chunk);
+        }
+        Ok(Run { transcript, status })
+    }
+
+    #[cfg(all(feature = "pty", unix))]
+    fn echoes(&self) -> bool {
+        self.pty
+    }
+
+    #[cfg(not(all(feature = "pty", unix)))]
+    fn echoes(&self) -> bool {
+        false
+    }
+
+    fn spawn(&mut self) -> io::Result<Spawned> {
+        #[cfg(all(feature = "pty", unix))]
+        if self.pty {
+            let pair = crate::pty::PtyPair::open()?;
+            self.command
+                .stdin(Stdio::from(pair.slave.try_clone()?))
+                .stdout(Stdio::from(pair.slave.try_clone()?))
+                .stderr(Stdio::from(pair.slave));
+            let child = self.command.spawn()?;
+            // close the parent's copies so reading the master ends with the program
+            self.command
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+            let output = pair.master.try_clone()?;
+            return Ok((child, Box::new(pair.master), Box::new(output)));
+        }
+
+        self.command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit());
+        let mut child = self.command.spawn()?;
+        let input = child
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::other("no stdin"))?;
+        let output = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::other("no stdout"))?;
+        Ok((child, Box::new(input), Box::new(output)))
+    }
+}
+
+/// forwards everything read from `output` as text, the channel closes on EOF
+fn read_chunks(mut output: Box<dyn Read + Send>) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        let mut pending = Vec::new();
+        // a pseudo terminal reports an error instead of EOF once the program exits
+        while let Ok(n) = output.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            pending.extend_from_slice(&buf[..n]);
+            let valid = match std::str::from_utf8(&pending) {
+                Ok(text) => text.len(),
+                Err(err) if err.error_len().is_none() => err.valid_up_to(),
+                Err(_) => pending.len(),
+            };
+            let chunk = String::from_utf8_lossy(&pending[..valid]).into_owned();
+            pending.drain(..valid);
+            if sender.send(chunk).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> io::Result<ExitStatus> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            child.kill()?;
+            return child.wait();
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
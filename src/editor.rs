@@ -0,0 +1,1172 @@
+//! # editor
+//!
+//! the line editing layer: key events, the actions they are bound to and
+//! an `Editor` applying them to a line. it doesn't touch the terminal, a
+//! backend feeds it keys and draws the line with the cursor
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::mem;
+use std::ops::Range;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::completion::{self, Completer, SharedCompleter};
+use crate::config;
+use crate::highlight::{Highlighter, SharedHighlighter, StyledLine};
+use crate::history::History;
+
+/// kills a `KillRing` keeps, like readline
+const KILL_RING_SIZE: usize = 10;
+
+/// a key of the keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    Esc,
+    /// a function key, `F(2)` is F2
+    F(u8),
+    /// a click or wheel turn, reported by terminals with mouse reporting
+    /// on, see `Select::mouse`
+    Mouse(Mouse),
+}
+
+/// # Mouse
+///
+/// what the mouse did where. rows and columns count from 0: the
+/// `KeyDecoder` reports them on the terminal, widgets get them relative
+/// to their first line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Mouse {
+    pub kind: MouseKind,
+    pub row: u16,
+    pub column: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseKind {
+    /// the left button was pressed
+    Click,
+    ScrollUp,
+    ScrollDown,
+}
+
+/// a key press with its modifiers. letters pressed with Ctrl are stored
+/// in lower case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+    pub key: Key,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl KeyEvent {
+    pub fn new(key: Key) -> Self {
+        KeyEvent {
+            key,
+            ctrl: false,
+            alt: false,
+        }
+    }
+
+    /// `c` with Ctrl, like `KeyEvent::ctrl('a')` for Ctrl+A
+    pub fn ctrl(c: char) -> Self {
+        KeyEvent {
+            ctrl: true,
+            ..KeyEvent::new(Key::Char(c.to_ascii_lowercase()))
+        }
+    }
+
+    /// `c` with Alt, like `KeyEvent::alt('b')` for Alt+B
+    pub fn alt(c: char) -> Self {
+        KeyEvent {
+            alt: true,
+            ..KeyEvent::new(Key::Char(c))
+        }
+    }
+}
+
+impl From<Key> for KeyEvent {
+    fn from(key: Key) -> Self {
+        KeyEvent::new(key)
+    }
+}
+
+/// what a key does to the line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// finish the line
+    Accept,
+    /// give up on the line, like Ctrl+C
+    Interrupt,
+    /// clear the screen and draw the prompt again at the top
+    ClearScreen,
+    /// stop the program like Ctrl+Z in a shell, the line is kept
+    Suspend,
+    /// end of input on an empty line, `DeleteForward` otherwise
+    EofOrDeleteForward,
+    MoveLeft,
+    MoveRight,
+    /// to the start of the word before the cursor
+    MoveWordLeft,
+    /// to the end of the word after the cursor
+    MoveWordRight,
+    /// to the start of the next word, vi's `w`
+    MoveNextWord,
+    MoveHome,
+    MoveEnd,
+    DeleteBack,
+    DeleteForward,
+    /// cut from the cursor to the end of the line into the kill ring
+    KillToEnd,
+    /// cut from the start of the line to the cursor into the kill ring
+    KillToStart,
+    /// cut back to the previous whitespace into the kill ring
+    KillWordBack,
+    /// search the history backwards, as it is typed. see
+    /// `Editor::search_prompt`
+    ReverseSearch,
+    /// show the history entry before the one shown
+    HistoryPrev,
+    /// show the history entry after the one shown, after the newest the
+    /// line from before browsing
+    HistoryNext,
+    /// complete the line up to the cursor with the `Completer`, see
+    /// `Editor::completions`
+    Complete,
+    /// insert the latest kill
+    Yank,
+    /// right after `Yank`, replace the yanked text with the kill before
+    YankPop,
+    /// leave vi's insert mode
+    ViCommandMode,
+    /// vi's `i`
+    ViInsert,
+    /// vi's `a`
+    ViAppend,
+    /// vi's `I`
+    ViInsertAtHome,
+    /// vi's `A`
+    ViAppendAtEnd,
+    /// run application code, see `Action::custom`
+    Custom(CustomAction),
+}
+
+impl Action {
+    /// an action running `f` on the editor. `f` returns the outcome of
+    /// the key and may run other actions with `Editor::run`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::editor::{Action, Editor, Key, Outcome};
+    ///
+    /// let mut editor = Editor::new().bind(
+    ///     Key::F(2).into(),
+    ///     Action::custom(|editor| {
+    ///         editor.insert_str("2024-05-01");
+    ///         Outcome::Continue
+    ///     }),
+    /// );
+    /// editor.handle_key(Key::F(2).into());
+    /// assert_eq!(editor.line(), "2024-05-01");
+    /// ```
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn(&mut Editor) -> Outcome + Send + Sync + 'static,
+    {
+        Action::Custom(CustomAction(Arc::new(f)))
+    }
+}
+
+/// the code of an `Action::Custom`. equal only to its clones
+#[derive(Clone)]
+pub struct CustomAction(Arc<dyn Fn(&mut Editor) -> Outcome + Send + Sync>);
+
+impl fmt::Debug for CustomAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CustomAction")
+    }
+}
+
+impl PartialEq for CustomAction {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CustomAction {}
+
+/// # Keymap
+///
+/// the key dispatch table of an editing mode. keys that aren't bound
+/// insert their character when the keymap inserts, like emacs mode and
+/// vi's insert mode do, and are ignored otherwise
+///
+/// # usage
+/// ```
+/// use input_py::editor::{Action, KeyEvent, Keymap};
+///
+/// let mut keymap = Keymap::emacs();
+/// assert_eq!(keymap.get(KeyEvent::ctrl('a')), Some(&Action::MoveHome));
+/// keymap.bind(KeyEvent::ctrl('t'), Action::MoveEnd);
+/// assert_eq!(keymap.get(KeyEvent::ctrl('t')), Some(&Action::MoveEnd));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    bindings: HashMap<KeyEvent, Action>,
+    inserts: bool,
+}
+
+impl Keymap {
+    /// readline's default bindings
+    pub fn emacs() -> Self {
+        let mut keymap = Keymap::inserting();
+        keymap.bind_common();
+        for (key, action) in [
+            (KeyEvent::ctrl('a'), Action::MoveHome),
+            (KeyEvent::ctrl('e'), Action::MoveEnd),
+            (KeyEvent::ctrl('b'), Action::MoveLeft),
+            (KeyEvent::ctrl('f'), Action::MoveRight),
+            (KeyEvent::alt('b'), Action::MoveWordLeft),
+            (KeyEvent::alt('f'), Action::MoveWordRight),
+            (with_ctrl(Key::Left), Action::MoveWordLeft),
+            (with_ctrl(Key::Right), Action::MoveWordRight),
+            (with_alt(Key::Backspace), Action::KillWordBack),
+            (KeyEvent::ctrl('h'), Action::DeleteBack),
+            (KeyEvent::ctrl('k'), Action::KillToEnd),
+            (KeyEvent::ctrl('y'), Action::Yank),
+            (KeyEvent::alt('y'), Action::YankPop),
+            (KeyEvent::ctrl('l'), Action::ClearScreen),
+            (KeyEvent::ctrl('p'), Action::HistoryPrev),
+            (KeyEvent::ctrl('n'), Action::HistoryNext),
+            (Key::Tab.into(), Action::Complete),
+        ] {
+            keymap.bind(key, action);
+        }
+        keymap
+    }
+
+    /// vi's insert mode, Esc switches to the command mode
+    pub fn vi_insert() -> Self {
+        let mut keymap = Keymap::inserting();
+        keymap.bind_common();
+        keymap.bind(Key::Esc.into(), Action::ViCommandMode);
+        keymap.bind(Key::Tab.into(), Action::Complete);
+        keymap
+    }
+
+    /// vi's command mode
+    pub fn vi_command() -> Self {
+        let mut keymap = Keymap::default();
+        keymap.bind_common();
+        for (c, action) in [
+            ('h', Action::MoveLeft),
+            ('l', Action::MoveRight),
+            ('0', Action::MoveHome),
+            ('$', Action::MoveEnd),
+            ('b', Action::MoveWordLeft),
+            ('w', Action::MoveNextWord),
+            ('x', Action::DeleteForward),
+            ('X', Action::DeleteBack),
+            ('D', Action::KillToEnd),
+            ('P', Action::Yank),
+            ('k', Action::HistoryPrev),
+            ('j', Action::HistoryNext),
+            ('i', Action::ViInsert),
+            ('a', Action::ViAppend),
+            ('I', Action::ViInsertAtHome),
+            ('A', Action::ViAppendAtEnd),
+        ] {
+            keymap.bind(Key::Char(c).into(), action);
+        }
+        keymap.bind(Key::Backspace.into(), Action::MoveLeft);
+        keymap
+    }
+
+    /// bind `key` to `action`, returns what it was bound to before
+    pub fn bind(&mut self, key: KeyEvent, action: Action) -> Option<Action> {
+        self.bindings.insert(key, action)
+    }
+
+    /// remove the binding of `key`, returns what it was bound to
+    pub fn unbind(&mut self, key: KeyEvent) -> Option<Action> {
+        self.bindings.remove(&key)
+    }
+
+    pub fn get(&self, key: KeyEvent) -> Option<&Action> {
+        self.bindings.get(&key)
+    }
+
+    /// every binding, in no particular order
+    pub fn bindings(&self) -> impl Iterator<Item = (&KeyEvent, &Action)> {
+        self.bindings.iter()
+    }
+
+    fn inserting() -> Self {
+        Keymap {
+            bindings: HashMap::new(),
+            inserts: true,
+        }
+    }
+
+    /// keys every mode shares
+    fn bind_common(&mut self) {
+        for (key, action) in [
+            (Key::Enter.into(), Action::Accept),
+            (KeyEvent::ctrl('c'), Action::Interrupt),
+            (KeyEvent::ctrl('d'), Action::EofOrDeleteForward),
+            (Key::Left.into(), Action::MoveLeft),
+            (Key::Right.into(), Action::MoveRight),
+            (Key::Home.into(), Action::MoveHome),
+            (Key::End.into(), Action::MoveEnd),
+            (Key::Backspace.into(), Action::DeleteBack),
+            (Key::Delete.into(), Action::DeleteForward),
+            (Key::Up.into(), Action::HistoryPrev),
+            (Key::Down.into(), Action::HistoryNext),
+            (KeyEvent::ctrl('u'), Action::KillToStart),
+            (KeyEvent::ctrl('w'), Action::KillWordBack),
+            (KeyEvent::ctrl('r'), Action::ReverseSearch),
+            (KeyEvent::ctrl('z'), Action::Suspend),
+        ] {
+            self.bind(key, action);
+        }
+    }
+}
+
+/// # KillRing
+///
+/// the text cut by kill actions, newest first, for yanking it back.
+/// clones share the ring, so editors given the same one, like those of a
+/// `Prompter` session, cut and paste between prompts. kills right after
+/// each other join into one entry, as in readline
+///
+/// # usage
+/// ```
+/// use input_py::editor::{Editor, Key, KeyEvent, KillRing};
+///
+/// let ring = KillRing::new();
+/// let mut first = Editor::new().kill_ring(ring.clone());
+/// first.set_line("cargo build --release");
+/// first.handle_key(KeyEvent::ctrl('w'));
+/// first.handle_key(KeyEvent::ctrl('w'));
+/// assert_eq!(ring.entries(), vec!["build --release".to_string()]);
+///
+/// let mut second = Editor::new().kill_ring(ring);
+/// second.set_line("cargo ");
+/// second.handle_key(KeyEvent::ctrl('y'));
+/// assert_eq!(second.line(), "cargo build --release");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct KillRing(Arc<Mutex<Kills>>);
+
+#[derive(Debug, Default)]
+struct Kills {
+    entries: VecDeque<String>,
+    /// entry the last yank inserted
+    yanked: usize,
+}
+
+impl KillRing {
+    pub fn new() -> Self {
+        KillRing::default()
+    }
+
+    /// add `text` as the newest kill
+    pub fn push(&self, text: &str) {
+        let mut kills = self.lock();
+        kills.entries.push_front(text.to_string());
+        kills.entries.truncate(KILL_RING_SIZE);
+    }
+
+    /// the kills, newest first
+    pub fn entries(&self) -> Vec<String> {
+        self.lock().entries.iter().cloned().collect()
+    }
+
+    /// the newest kill
+    pub fn yank(&self) -> Option<String> {
+        let mut kills = self.lock();
+        kills.yanked = 0;
+        kills.entries.front().cloned()
+    }
+
+    /// the kill before the one yanked last, wrapping around
+    fn rotate(&self) -> Option<String> {
+        let mut kills = self.lock();
+        if kills.entries.is_empty() {
+            return None;
+        }
+        kills.yanked = (kills.yanked + 1) % kills.entries.len();
+        kills.entries.get(kills.yanked).cloned()
+    }
+
+    /// join `text` to the newest kill, before it when `before`
+    fn extend(&self, text: &str, before: bool) {
+        let mut kills = self.lock();
+        match kills.entries.front_mut() {
+            Some(newest) if before => newest.insert_str(0, text),
+            Some(newest) => newest.push_str(text),
+            None => kills.entries.push_front(text.to_string()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Kills> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// what the previous action did, for joining kills and `YankPop`
+#[derive(Debug, Clone, Default)]
+enum LastEdit {
+    #[default]
+    Other,
+    Kill,
+    /// the chars of the line the yank inserted
+    Yank(Range<usize>),
+}
+
+/// the history entry shown by `HistoryPrev` and `HistoryNext`
+#[derive(Debug, Clone)]
+struct Browse {
+    index: usize,
+    /// the line before browsing, back after the newest entry
+    draft: String,
+}
+
+/// a running `Action::ReverseSearch`
+#[derive(Debug, Clone)]
+struct Search {
+    query: String,
+    /// history index of the entry shown
+    found: Option<usize>,
+    failed: bool,
+    /// line and cursor from before the search, for Ctrl+G
+    saved: (String, usize),
+}
+
+/// key bindings of an `Editor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditMode {
+    Emacs,
+    /// modal, starting in insert mode
+    Vi,
+}
+
+/// the keymaps of an `Editor`. emacs mode only uses `Insert`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeymapId {
+    Insert,
+    Command,
+}
+
+/// what became of the line after a key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// still editing
+    Continue,
+    /// the finished line, the editor is empty again
+    Accept(String),
+    /// end of input on an empty line
+    Eof,
+    /// the line was given up with Ctrl+C
+    Interrupted,
+    /// still editing, the screen should be cleared before the prompt
+    /// is drawn again
+    ClearScreen,
+    /// still editing, the backend should stop the program until it is
+    /// continued and then draw the prompt again
+    Suspend,
+}
+
+/// # Editor
+///
+/// a line being edited. keys are looked up in the keymap of the current
+/// mode and applied to the line. the mode is `config::default_edit_mode`
+/// unless set with `mode`
+///
+/// # usage
+/// ```
+/// use input_py::editor::{EditMode, Editor, Key, KeyEvent, Outcome};
+///
+/// let mut editor = Editor::new().mode(EditMode::Emacs);
+/// for c in "world".chars() {
+///     editor.handle_key(Key::Char(c).into());
+/// }
+/// editor.handle_key(KeyEvent::ctrl('a'));
+/// for c in "hello ".chars() {
+///     editor.handle_key(Key::Char(c).into());
+/// }
+/// assert_eq!(editor.line(), "hello world");
+/// assert_eq!(editor.handle_key(Key::Enter.into()), Outcome::Accept("hello world".to_string()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Editor {
+    line: String,
+    /// byte offset into `line`, on a char boundary
+    cursor: usize,
+    mode: EditMode,
+    active: KeymapId,
+    insert: Keymap,
+    command: Keymap,
+    kill_ring: KillRing,
+    last: LastEdit,
+    history: History,
+    browse: Option<Browse>,
+    search: Option<Search>,
+    highlighter: Option<SharedHighlighter>,
+    completer: Option<SharedCompleter>,
+    /// candidates the last `Complete` couldn't choose between
+    completions: Vec<String>,
+    /// longest line in chars, see `max_len`
+    max_len: Option<usize>,
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Editor::new()
+    }
+}
+
+impl Editor {
+    pub fn new() -> Self {
+        Editor {
+            line: String::new(),
+            cursor: 0,
+            mode: EditMode::Emacs,
+            active: KeymapId::Insert,
+            insert: Keymap::emacs(),
+            command: Keymap::default(),
+            kill_ring: KillRing::new(),
+            last: LastEdit::Other,
+            history: History::new(),
+            browse: None,
+            search: None,
+            highlighter: None,
+            completer: None,
+            completions: Vec::new(),
+            max_len: None,
+        }
+        .mode(config::default_edit_mode())
+    }
+
+    /// earlier lines, oldest first, for Up and Down and searching with
+    /// Ctrl+R. accepted lines are added. default: none
+    pub fn history<I, S>(mut self, entries: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.history = entries.into_iter().collect();
+        self
+    }
+
+    /// browse and add to `history`, shared with its other clones so the
+    /// accepted lines can be saved
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::editor::{Editor, Key, Outcome};
+    /// use input_py::history::History;
+    ///
+    /// let history: History = ["ls", "cargo test"].into_iter().collect();
+    /// let mut editor = Editor::new().shared_history(history.clone());
+    /// editor.handle_key(Key::Up.into());
+    /// editor.handle_key(Key::Up.into());
+    /// assert_eq!(editor.handle_key(Key::Enter.into()), Outcome::Accept("ls".to_string()));
+    /// assert_eq!(history.entries(), ["ls", "cargo test", "ls"]);
+    /// ```
+    pub fn shared_history(mut self, history: History) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// swap in another history, for the line editor of stdin
+    #[cfg(feature = "line-editing")]
+    pub(crate) fn replace_history(&mut self, history: History) -> History {
+        self.browse = None;
+        mem::replace(&mut self.history, history)
+    }
+
+    /// style the line with `highlighter` whenever it is drawn, see
+    /// `styled_line`. default: plain
+    pub fn highlighter<H>(mut self, highlighter: H) -> Self
+    where
+        H: Highlighter + Send + Sync + 'static,
+    {
+        self.highlighter = Some(SharedHighlighter(Arc::new(highlighter)));
+        self
+    }
+
+    /// complete the line with `completer` on Tab. default: none, Tab
+    /// does nothing
+    pub fn completer<C>(mut self, completer: C) -> Self
+    where
+        C: Completer + Send + Sync + 'static,
+    {
+        self.completer = Some(SharedCompleter(Arc::new(completer)));
+        self
+    }
+
+    /// swap in another completer, for the line editor of stdin
+    #[cfg(feature = "line-editing")]
+    pub(crate) fn replace_completer(
+        &mut self,
+        completer: Option<SharedCompleter>,
+    ) -> Option<SharedCompleter> {
+        mem::replace(&mut self.completer, completer)
+    }
+
+    /// refuse chars past `max_len` chars, yanks are cut to fit. the line
+    /// is drawn with a counter like `12/20` after it. default: no limit
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::editor::{Editor, Key};
+    /// use input_py::widget::{TextCanvas, Widget};
+    ///
+    /// let mut editor = Editor::new().max_len(3);
+    /// for c in "abcd".chars() {
+    ///     editor.handle_key(Key::Char(c).into());
+    /// }
+    /// assert_eq!(editor.line(), "abc");
+    /// let mut canvas = TextCanvas::new();
+    /// editor.render(&mut canvas);
+    /// assert_eq!(canvas.lines(), ["abc 3/3"]);
+    /// ```
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// swap in another limit, for the line editor of stdin
+    #[cfg(feature = "line-editing")]
+    pub(crate) fn replace_max_len(&mut self, max_len: Option<usize>) -> Option<usize> {
+        mem::replace(&mut self.max_len, max_len)
+    }
+
+    /// the limit of `max_len`
+    pub fn limit(&self) -> Option<usize> {
+        self.max_len
+    }
+
+    /// cut and paste through `ring`. default: a ring of its own
+    pub fn kill_ring(mut self, ring: KillRing) -> Self {
+        self.kill_ring = ring;
+        self
+    }
+
+    /// switch to the default keymaps of `mode`, dropping custom bindings.
+    /// set it before binding keys
+    pub fn mode(mut self, mode: EditMode) -> Self {
+        (self.insert, self.command) = match mode {
+            EditMode::Emacs => (Keymap::emacs(), Keymap::default()),
+            EditMode::Vi => (Keymap::vi_insert(), Keymap::vi_command()),
+        };
+        self.mode = mode;
+        self.active = KeymapId::Insert;
+        self
+    }
+
+    pub fn edit_mode(&self) -> EditMode {
+        self.mode
+    }
+
+    pub fn keymap(&self, id: KeymapId) -> &Keymap {
+        match id {
+            KeymapId::Insert => &self.insert,
+            KeymapId::Command => &self.command,
+        }
+    }
+
+    /// bind `key` to `action` in every keymap of the mode, so it works
+    /// in both of vi's modes. set the mode first
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::editor::{Action, Editor, KeyEvent, Outcome};
+    ///
+    /// let mut editor = Editor::new().bind(KeyEvent::ctrl('l'), Action::ClearScreen);
+    /// assert_eq!(editor.handle_key(KeyEvent::ctrl('l')), Outcome::ClearScreen);
+    /// ```
+    pub fn bind(mut self, key: KeyEvent, action: Action) -> Self {
+        if self.mode == EditMode::Vi {
+            self.command.bind(key, action.clone());
+        }
+        self.insert.bind(key, action);
+        self
+    }
+
+    /// the dispatch table `id`, for custom bindings
+    pub fn keymap_mut(&mut self, id: KeymapId) -> &mut Keymap {
+        match id {
+            KeymapId::Insert => &mut self.insert,
+            KeymapId::Command => &mut self.command,
+        }
+    }
+
+    /// the keymap keys are looked up in now
+    pub fn active_keymap(&self) -> KeymapId {
+        self.active
+    }
+
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// byte offset of the cursor in `line`
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// replace the line, the cursor goes to its end
+    pub fn set_line(&mut self, line: &str) {
+        self.line = line.to_string();
+        self.cursor = line.len();
+        self.clamp_cursor();
+    }
+
+    /// the line as it is drawn, styled by the highlighter. plain when
+    /// there is none or its spans don't hold the line
+    pub fn styled_line(&self) -> StyledLine {
+        let Some(SharedHighlighter(highlighter)) = &self.highlighter else {
+            return StyledLine::from(self.line.as_str());
+        };
+        let styled = highlighter.highlight(&self.line);
+        match styled.text() == self.line {
+            true => styled,
+            false => StyledLine::from(self.line.as_str()),
+        }
+    }
+
+    /// byte offset of the bracket matching the one just before the
+    /// cursor, for the backend to flash it like readline's
+    /// `blink-matching-paren`. none without a bracket there or a match
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::editor::{Editor, Key};
+    ///
+    /// let mut editor = Editor::new();
+    /// for c in "f(a[1])".chars() {
+    ///     editor.handle_key(Key::Char(c).into());
+    /// }
+    /// assert_eq!(editor.matching_bracket(), Some(1));
+    /// ```
+    pub fn matching_bracket(&self) -> Option<usize> {
+        const PAIRS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+        let (at, c) = self.line[..self.cursor].char_indices().next_back()?;
+        let mut depth = 0usize;
+        if let Some((open, close)) = PAIRS.iter().find(|(_, close)| *close == c) {
+            for (i, c) in self.line[..at].char_indices().rev() {
+                match c {
+                    c if c == *close => depth += 1,
+                    c if c == *open && depth == 0 => return Some(i),
+                    c if c == *open => depth -= 1,
+                    _ => {}
+                }
+            }
+        } else if let Some((open, close)) = PAIRS.iter().find(|(open, _)| *open == c) {
+            for (i, c) in self.line[self.cursor..].char_indices() {
+                match c {
+                    c if c == *open => depth += 1,
+                    c if c == *close && depth == 0 => return Some(self.cursor + i),
+                    c if c == *close => depth -= 1,
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    /// insert `text` at the cursor
+    pub fn insert_str(&mut self, text: &str) {
+        self.line.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+
+    /// shown before the prompt in vi mode like readline does: `(ins)` or
+    /// `(cmd)`. empty in emacs mode
+    pub fn indicator(&self) -> &'static str {
+        match (self.mode, self.active) {
+            (EditMode::Emacs, _) => "",
+            (EditMode::Vi, KeymapId::Insert) => "(ins)",
+            (EditMode::Vi, KeymapId::Command) => "(cmd)",
+        }
+    }
+
+    /// the completions Tab couldn't add anything from, for the backend
+    /// to list below the line. cleared by the next key
+    pub fn completions(&self) -> &[String] {
+        &self.completions
+    }
+
+    /// apply `key` to the line
+    pub fn handle_key(&mut self, key: KeyEvent) -> Outcome {
+        self.completions.clear();
+        if self.search.is_some() {
+            return self.handle_search_key(key);
+        }
+        let keymap = self.keymap(self.active);
+        let action = match (keymap.get(key), key) {
+            (Some(action), _) => action.clone(),
+            (
+                None,
+                KeyEvent {
+                    key: Key::Char(c),
+                    ctrl: false,
+                    alt: false,
+                },
+            ) if keymap.inserts => {
+                if self.room() == Some(0) {
+                    return Outcome::Continue;
+                }
+                self.line.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+                self.last = LastEdit::Other;
+                return Outcome::Continue;
+            }
+            (None, _) => return Outcome::Continue,
+        };
+        self.run(action)
+    }
+
+    /// apply `action` as if a key bound to it was pressed
+    pub fn run(&mut self, action: Action) -> Outcome {
+        let last = mem::take(&mut self.last);
+        let outcome = self.apply(action, last);
+        self.clamp_cursor();
+        outcome
+    }
+
+    fn apply(&mut self, action: Action, last: LastEdit) -> Outcome {
+        match action {
+            Action::Custom(custom) => return (custom.0)(self),
+            Action::ClearScreen => return Outcome::ClearScreen,
+            Action::Suspend => return Outcome::Suspend,
+            Action::Accept => {
+                self.cursor = 0;
+                self.active = KeymapId::Insert;
+                self.browse = None;
+                let line = mem::take(&mut self.line);
+                self.history.push(&line);
+                return Outcome::Accept(line);
+            }
+            Action::ReverseSearch => {
+                self.search = Some(Search {
+                    query: String::new(),
+                    found: None,
+                    failed: false,
+                    saved: (self.line.clone(), self.cursor),
+                });
+            }
+            Action::HistoryPrev => {
+                let index = self.browse.as_ref().map_or(self.history.len(), |b| b.index);
+                if let Some(entry) = index.checked_sub(1).and_then(|i| self.history.get(i)) {
+                    let draft = match self.browse.take() {
+                        Some(browse) => browse.draft,
+                        None => self.line.clone(),
+                    };
+                    self.browse = Some(Browse {
+                        index: index - 1,
+                        draft,
+                    });
+                    self.show(entry);
+                }
+            }
+            Action::HistoryNext => {
+                if let Some(browse) = self.browse.take() {
+                    match self.history.get(browse.index + 1) {
+                        Some(entry) => {
+                            self.browse = Some(Browse {
+                                index: browse.index + 1,
+                                ..browse
+                            });
+                            self.show(entry);
+                        }
+                        None => self.show(browse.draft),
+                    }
+                }
+            }
+            Action::Complete => {
+                let Some(SharedCompleter(completer)) = &self.completer else {
+                    return Outcome::Continue;
+                };
+                let partial = &self.line[..self.cursor];
+                let candidates = completer.complete(partial);
+                let completed = match candidates.as_slice() {
+                    [] => return Outcome::Continue,
+                    [only] => only.as_str(),
+                    _ => completion::common_prefix(&candidates),
+                };
+                if candidates.len() > 1 && completed.len() <= partial.len() {
+                    self.completions = candidates;
+                    return Outcome::Continue;
+                }
+                let completed = completed.to_string();
+                self.line.replace_range(..self.cursor, &completed);
+                self.cursor = completed.len();
+            }
+            Action::Interrupt => {
+                self.browse = None;
+                self.line.clear();
+                self.cursor = 0;
+                self.active = KeymapId::Insert;
+                return Outcome::Interrupted;
+            }
+            Action::EofOrDeleteForward if self.line.is_empty() => return Outcome::Eof,
+            Action::EofOrDeleteForward | Action::DeleteForward => {
+                let end = self.next_boundary();
+                self.line.drain(self.cursor..end);
+            }
+            Action::MoveLeft => self.cursor = self.prev_boundary(),
+            Action::MoveRight => self.cursor = self.next_boundary(),
+            Action::MoveWordLeft => self.cursor = self.word_start(is_word),
+            Action::MoveWordRight => {
+                let rest = &self.line[self.cursor..];
+                let word = rest.find(is_word).unwrap_or(rest.len());
+                let end = rest[word..]
+                    .find(|c| !is_word(c))
+                    .unwrap_or(rest.len() - word);
+                self.cursor += word + end;
+            }
+            Action::MoveNextWord => {
+                let rest = &self.line[self.cursor..];
+                let end = rest.find(|c| !is_word(c)).unwrap_or(rest.len());
+                let next = rest[end..].find(is_word).unwrap_or(rest.len() - end);
+                self.cursor += end + next;
+            }
+            Action::MoveHome => self.cursor = 0,
+            Action::MoveEnd => self.cursor = self.line.len(),
+            Action::DeleteBack => {
+                let start = self.prev_boundary();
+                self.line.drain(start..self.cursor);
+                self.cursor = start;
+            }
+            Action::KillToEnd => {
+                let text = self.line.split_off(self.cursor);
+                self.kill(&text, false, last);
+            }
+            Action::KillToStart => {
+                let text: String = self.line.drain(..self.cursor).collect();
+                self.cursor = 0;
+                self.kill(&text, true, last);
+            }
+            Action::KillWordBack => {
+                let start = self.word_start(|c| !c.is_whitespace());
+                let text: String = self.line.drain(start..self.cursor).collect();
+                self.cursor = start;
+                self.kill(&text, true, last);
+            }
+            Action::Yank => {
+                if let Some(text) = self.kill_ring.yank() {
+                    self.yank(&text, self.cursor..self.cursor);
+                }
+            }
+            Action::YankPop => match last {
+                LastEdit::Yank(yanked) => match self.kill_ring.rotate() {
+                    Some(text) => self.yank(&text, yanked),
+                    None => self.last = LastEdit::Yank(yanked),
+                },
+                _ => self.last = last,
+            },
+            Action::ViCommandMode if self.mode == EditMode::Vi => {
+                self.active = KeymapId::Command;
+                self.cursor = self.prev_boundary();
+            }
+            Action::ViCommandMode => {}
+            Action::ViInsert => self.active = KeymapId::Insert,
+            Action::ViAppend => {
+                self.cursor = self.next_boundary();
+                self.active = KeymapId::Insert;
+            }
+            Action::ViInsertAtHome => {
+                self.cursor = 0;
+                self.active = KeymapId::Insert;
+            }
+            Action::ViAppendAtEnd => {
+                self.cursor = self.line.len();
+                self.active = KeymapId::Insert;
+            }
+        }
+        Outcome::Continue
+    }
+
+    /// the mini prompt shown instead of the prompt while searching, like
+    /// ``(reverse-i-search)`cargo': ``. the line is the entry found
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::editor::{Editor, Key, KeyEvent, Outcome};
+    ///
+    /// let mut editor = Editor::new().history(["cargo build", "ls", "cargo test"]);
+    /// editor.handle_key(KeyEvent::ctrl('r'));
+    /// for c in "car".chars() {
+    ///     editor.handle_key(Key::Char(c).into());
+    /// }
+    /// assert_eq!(editor.search_prompt().as_deref(), Some("(reverse-i-search)`car': "));
+    /// assert_eq!(editor.line(), "cargo test");
+    /// editor.handle_key(KeyEvent::ctrl('r'));
+    /// assert_eq!(editor.handle_key(Key::Enter.into()), Outcome::Accept("cargo build".to_string()));
+    /// ```
+    pub fn search_prompt(&self) -> Option<String> {
+        let search = self.search.as_ref()?;
+        let failed = match search.failed {
+            true => "failed ",
+            false => "",
+        };
+        Some(format!("({}reverse-i-search)`{}': ", failed, search.query))
+    }
+
+    /// keys while searching: typing extends the query, Ctrl+R finds an
+    /// older entry, Ctrl+G goes back to the line from before, Esc keeps
+    /// the entry. other keys keep it and are handled as usual
+    fn handle_search_key(&mut self, key: KeyEvent) -> Outcome {
+        let Some(search) = &mut self.search else {
+            return Outcome::Continue;
+        };
+        match (key.key, key.ctrl, key.alt) {
+            (Key::Char(c), false, false) => {
+                search.query.push(c);
+                let start = search.found.map_or(self.history.len(), |i| i + 1);
+                self.search_before(start);
+            }
+            (Key::Backspace, _, _) => {
+                search.query.pop();
+                let (line, cursor) = search.saved.clone();
+                (self.line, self.cursor) = (line, cursor);
+                search.found = None;
+                self.search_before(self.history.len());
+            }
+            (Key::Char('r'), true, false) => {
+                let start = search.found.unwrap_or(self.history.len());
+                self.search_before(start);
+            }
+            (Key::Char('g'), true, false) => {
+                (self.line, self.cursor) = search.saved.clone();
+                self.search = None;
+            }
+            (Key::Esc, _, _) => self.search = None,
+            _ => {
+                self.search = None;
+                return self.handle_key(key);
+            }
+        }
+        self.clamp_cursor();
+        Outcome::Continue
+    }
+
+    /// show the newest history entry before `start` containing the query
+    fn search_before(&mut self, start: usize) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        if search.query.is_empty() {
+            search.failed = false;
+            return;
+        }
+        let query = search.query.as_str();
+        let entries = self.history.entries();
+        let found = entries[..start.min(entries.len())]
+            .iter()
+            .rposition(|entry| entry.contains(query));
+        search.failed = found.is_none();
+        if let Some(index) = found {
+            let entry = &entries[index];
+            self.cursor = entry.find(query).unwrap_or(0);
+            self.line = entry.clone();
+            search.found = Some(index);
+        }
+    }
+
+    /// put a history entry on the line, the cursor at its end
+    fn show(&mut self, entry: String) {
+        self.cursor = entry.len();
+        self.line = entry;
+    }
+
+    /// put `text`, just cut, into the kill ring
+    fn kill(&mut self, text: &str, before: bool, last: LastEdit) {
+        match last {
+            LastEdit::Kill => self.kill_ring.extend(text, before),
+            _ if text.is_empty() => return,
+            _ => self.kill_ring.push(text),
+        }
+        self.last = LastEdit::Kill;
+    }
+
+    /// replace `range` of the line with `text` and put the cursor after
+    /// it. as much of `text` as `max_len` leaves room for
+    fn yank(&mut self, text: &str, range: Range<usize>) {
+        let start = range.start;
+        let replaced = self.line[range.clone()].chars().count();
+        let text: String = match self.room() {
+            Some(room) => text.chars().take(room + replaced).collect(),
+            None => text.to_string(),
+        };
+        self.line.replace_range(range, &text);
+        self.cursor = start + text.len();
+        self.last = LastEdit::Yank(start..self.cursor);
+    }
+
+    /// chars the line can still take under `max_len`
+    fn room(&self) -> Option<usize> {
+        let len = self.line.chars().count();
+        self.max_len.map(|max_len| max_len.saturating_sub(len))
+    }
+
+    fn prev_boundary(&self) -> usize {
+        self.line[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.line[self.cursor..]
+            .chars()
+            .next()
+            .map_or(self.cursor, |c| self.cursor + c.len_utf8())
+    }
+
+    /// start of the run of `in_word` chars before the cursor, skipping
+    /// the others right before it
+    fn word_start(&self, in_word: fn(char) -> bool) -> usize {
+        let before = &self.line[..self.cursor];
+        let end = before.trim_end_matches(|c| !in_word(c)).len();
+        before[..end].trim_end_matches(in_word).len()
+    }
+
+    /// vi's command mode keeps the cursor on a char, not after the last
+    fn clamp_cursor(&mut self) {
+        if self.active == KeymapId::Command && self.cursor == self.line.len() {
+            self.cursor = self.prev_boundary();
+        }
+    }
+}
+
+fn with_ctrl(key: Key) -> KeyEvent {
+    KeyEvent {
+        ctrl: true,
+        ..key.into()
+    }
+}
+
+fn with_alt(key: Key) -> KeyEvent {
+    KeyEvent {
+        alt: true,
+        ..key.into()
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
@@ -0,0 +1,108 @@
+//! # encodings
+//!
+//! prompts on streams in a legacy encoding, like Shift_JIS from an old
+//! serial console or windows-1252 from a pipe, with the `encodings`
+//! feature. the encodings are `encoding_rs`'s, re-exported as `Encoding`
+
+use std::io::{self, BufRead, Write};
+
+pub use encoding_rs::Encoding;
+
+use crate::io::{InputReader, OutputWriter};
+
+/// # DecodingReader
+///
+/// reads lines of `encoding` from any `BufRead` as UTF-8. bytes that
+/// aren't valid in the encoding turn into U+FFFD
+///
+/// # Panics
+///
+/// `new` panics for encodings that aren't ASCII compatible, like UTF-16,
+/// whose lines don't end in a `\n` byte
+///
+/// # usage
+/// ```
+/// use input_py::encodings::{DecodingReader, Encoding};
+/// use input_py::io::MockWriter;
+/// use input_py::read_input_with_io;
+///
+/// let latin1 = Encoding::for_label(b"windows-1252").unwrap();
+/// let mut reader = DecodingReader::new(&b"Gr\xfc\xdfe\n"[..], latin1);
+/// let answer = read_input_with_io("greeting", None, &mut reader, &mut MockWriter::new());
+/// assert_eq!(answer, Ok("Grüße".to_string()));
+/// ```
+pub struct DecodingReader<R: BufRead> {
+    inner: R,
+    encoding: &'static Encoding,
+}
+
+impl<R: BufRead> DecodingReader<R> {
+    pub fn new(inner: R, encoding: &'static Encoding) -> Self {
+        assert!(
+            encoding.is_ascii_compatible(),
+            "{} is not ASCII compatible",
+            encoding.name()
+        );
+        DecodingReader { inner, encoding }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: BufRead> InputReader for DecodingReader<R> {
+    /// the number of bytes read, 0 on EOF
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        let mut line = Vec::new();
+        let read = self.inner.read_until(b'\n', &mut line)?;
+        let (text, _) = self.encoding.decode_without_bom_handling(&line);
+        buf.push_str(&text);
+        Ok(read)
+    }
+}
+
+/// # EncodingWriter
+///
+/// writes prompts to any `Write` in `encoding`. characters the encoding
+/// lacks are written as HTML numeric character references, like `&#8364;`
+///
+/// # usage
+/// ```
+/// use input_py::encodings::{EncodingWriter, Encoding};
+/// use input_py::io::MockReader;
+/// use input_py::read_input_with_io;
+///
+/// let shift_jis = Encoding::for_label(b"shift_jis").unwrap();
+/// let mut writer = EncodingWriter::new(Vec::new(), shift_jis);
+/// read_input_with_io("名前", None, &mut MockReader::new(&["x"]), &mut writer).unwrap();
+/// assert_eq!(writer.into_inner(), b"\x96\xbc\x91\x4f:");
+/// ```
+pub struct EncodingWriter<W: Write> {
+    inner: W,
+    encoding: &'static Encoding,
+}
+
+impl<W: Write> EncodingWriter<W> {
+    pub fn new(inner: W, encoding: &'static Encoding) -> Self {
+        EncodingWriter {
+            inner,
+            encoding: encoding.output_encoding(),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> OutputWriter for EncodingWriter<W> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        let (bytes, _, _) = self.encoding.encode(s);
+        self.inner.write_all(&bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
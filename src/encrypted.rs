@@ -0,0 +1,152 @@
+//! # encrypted
+//!
+//! a `Storage` that encrypts every value with ChaCha20-Poly1305 before
+//! it reaches the storage underneath, so a token kept with
+//! `Input::remember` is never written as plain text. the key comes
+//! from a master passphrase, stretched with Argon2, or is given whole,
+//! like a secret the app keeps in the OS keyring
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use crate::error::InputError;
+use crate::storage::Storage;
+
+/// key the passphrase salt is kept under, hidden from `list`
+const SALT_KEY: &str = ".salt";
+
+/// start of every encrypted value, names the format
+const PREFIX: &str = "enc1:";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// # EncryptedStorage
+///
+/// encrypts the values of `inner`, keys stay readable. each value gets
+/// a fresh nonce and is bound to its key, so values moved between keys
+/// fail to decrypt. a wrong passphrase or key shows at the first `get`
+///
+/// # usage
+/// ```
+/// use input_py::encrypted::EncryptedStorage;
+/// use input_py::storage::{MemoryStorage, Storage};
+///
+/// let storage = EncryptedStorage::with_passphrase(MemoryStorage::new(), "hunter2").unwrap();
+/// storage.put("token", "tok_abcdef").unwrap();
+/// assert_eq!(storage.get("token").unwrap().as_deref(), Some("tok_abcdef"));
+/// assert_eq!(storage.list().unwrap(), ["token"]);
+/// assert!(!storage.inner().get("token").unwrap().unwrap().contains("tok_"));
+/// ```
+pub struct EncryptedStorage<S: Storage> {
+    inner: S,
+    cipher: ChaCha20Poly1305,
+}
+
+impl<S: Storage> EncryptedStorage<S> {
+    /// encrypt with a key derived from `passphrase`. the salt is made
+    /// at the first use and kept in `inner`, so the same passphrase
+    /// opens the storage again
+    pub fn with_passphrase(inner: S, passphrase: &str) -> Result<Self, InputError> {
+        let salt = match inner.get(SALT_KEY)? {
+            Some(salt) => decode_hex(&salt)
+                .filter(|salt| salt.len() == SALT_LEN)
+                .ok_or_else(|| InputError::Io("encrypted storage: damaged salt".to_string()))?,
+            None => {
+                let mut salt = vec![0; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                inner.put(SALT_KEY, &encode_hex(&salt))?;
+                salt
+            }
+        };
+        let mut key = [0; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| InputError::Io(format!("encrypted storage: {}", e)))?;
+        Ok(EncryptedStorage::with_key(inner, key))
+    }
+
+    /// encrypt with `key` as is, 32 random bytes the app keeps
+    /// elsewhere, like an OS keyring entry
+    pub fn with_key(inner: S, key: [u8; 32]) -> Self {
+        EncryptedStorage {
+            inner,
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key)),
+        }
+    }
+
+    /// the storage the encrypted values are kept in
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    fn payload<'a>(key: &'a str, msg: &'a [u8]) -> Payload<'a, 'a> {
+        Payload {
+            msg,
+            aad: key.as_bytes(),
+        }
+    }
+}
+
+impl<S: Storage> Storage for EncryptedStorage<S> {
+    fn get(&self, key: &str) -> Result<Option<String>, InputError> {
+        let Some(value) = self.inner.get(key)? else {
+            return Ok(None);
+        };
+        let unreadable = || {
+            InputError::Io(format!(
+                "encrypted storage: can't decrypt '{}', wrong passphrase or damaged value",
+                key
+            ))
+        };
+        let bytes = value
+            .strip_prefix(PREFIX)
+            .and_then(decode_hex)
+            .filter(|bytes| bytes.len() >= NONCE_LEN)
+            .ok_or_else(unreadable)?;
+        let (nonce, sealed) = bytes.split_at(NONCE_LEN);
+        let plain = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), Self::payload(key, sealed))
+            .map_err(|_| unreadable())?;
+        String::from_utf8(plain).map(Some).map_err(|_| unreadable())
+    }
+
+    fn put(&self, key: &str, value: &str) -> Result<(), InputError> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let sealed = self
+            .cipher
+            .encrypt(&nonce, Self::payload(key, value.as_bytes()))
+            .map_err(|_| InputError::Io(format!("encrypted storage: can't encrypt '{}'", key)))?;
+        let mut bytes = nonce.to_vec();
+        bytes.extend_from_slice(&sealed);
+        self.inner
+            .put(key, &format!("{}{}", PREFIX, encode_hex(&bytes)))
+    }
+
+    fn list(&self) -> Result<Vec<String>, InputError> {
+        let mut keys = self.inner.list()?;
+        keys.retain(|key| key != SALT_KEY);
+        Ok(keys)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), InputError> {
+        self.inner.delete(key)
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
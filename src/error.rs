@@ -0,0 +1,112 @@
+use std::fmt;
+use std::io;
+
+/// # InputError
+///
+/// error returned by the prompt functions and builders
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError {
+    /// reading from the input or writing the prompt failed
+    Io(String),
+    /// the reader reached end of input before an answer was given
+    Eof,
+    /// defaults were accepted for a question that has none, carries its id
+    MissingDefault(String),
+    /// the session deadline passed before the prompt was reached
+    Timeout,
+    /// a prompt without default was reached while stdin is not a terminal,
+    /// see `config::strict_interactive`. carries the prompt
+    NotInteractive(String),
+    /// a prompt template is invalid or misses a value, see `template`
+    Template(String),
+    /// the reader kept giving invalid answers, carries how many in a row.
+    /// see `config::max_consecutive_errors`
+    TooManyErrors(u32),
+    /// an answer could not be parsed, see `Prompter::parse`. `text` is
+    /// the answer, shortened for display, and `line` the number of the
+    /// line it was read on, none when it wasn't read
+    Parse {
+        line: Option<usize>,
+        text: String,
+        msg: String,
+    },
+    /// a `Select` has no items to choose from, carries its prompt
+    NoItems(String),
+    /// the user chose to stop the flow, see `Confirmation::or_abort`
+    Aborted,
+    /// an answer could not be converted to the requested type, carries
+    /// the answer. see `input_parse`
+    ParseError(String),
+    /// an answer given without asking, like an `INPUT_PY_ANSWER_<ID>`
+    /// variable or a playbook entry, failed the constraints of its prompt.
+    /// carries the answer and why
+    InvalidAnswer { answer: String, msg: String },
+    /// a prompt rejected as many answers as `Input::max_attempts` allows
+    AttemptsExceeded { attempts: u32 },
+    /// the two entries of a `PasswordConfirm` kept differing, carries how
+    /// many pairs were entered
+    MismatchError { attempts: u32 },
+    /// no answer came within `Input::timeout` and there is no default
+    TimedOut,
+    /// a prompt without default has no answer in the playbook, see
+    /// `answers::play`. carries the question id
+    MissingAnswer(String),
+    /// Ctrl+C was pressed during the prompt, see
+    /// `config::catch_interrupts`. readers report it as
+    /// `io::ErrorKind::Interrupted`
+    Interrupted,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Io(msg) => write!(f, "io error: {}", msg),
+            InputError::Eof => write!(f, "unexpected end of input"),
+            InputError::MissingDefault(id) => write!(f, "no default value for '{}'", id),
+            InputError::Timeout => write!(f, "session deadline passed"),
+            InputError::NotInteractive(prompt) => write!(
+                f,
+                "'{}' needs an answer but stdin is not a terminal; \
+                 give the prompt a default, pipe the answers in with \
+                 strict interactive mode off, or run in a terminal",
+                prompt
+            ),
+            InputError::Template(msg) => write!(f, "template error: {}", msg),
+            InputError::TooManyErrors(count) => {
+                write!(f, "gave up after {} invalid answers in a row", count)
+            }
+            InputError::Parse {
+                line: Some(line),
+                text,
+                msg,
+            } => write!(f, "parse error: {} at line {}: '{}'", msg, line, text),
+            InputError::Parse { text, msg, .. } => write!(f, "parse error: {}: '{}'", msg, text),
+            InputError::NoItems(prompt) => write!(f, "no items to choose from for '{}'", prompt),
+            InputError::Aborted => write!(f, "aborted"),
+            InputError::ParseError(answer) => write!(f, "could not parse '{}'", answer),
+            InputError::InvalidAnswer { answer, msg } => {
+                write!(f, "invalid answer '{}': {}", answer, msg)
+            }
+            InputError::AttemptsExceeded { attempts } => {
+                write!(f, "no valid answer after {} attempts", attempts)
+            }
+            InputError::MismatchError { attempts } => {
+                write!(f, "the entries didn't match in {} attempts", attempts)
+            }
+            InputError::TimedOut => write!(f, "no answer in time"),
+            InputError::MissingAnswer(id) => write!(f, "no answer for '{}' in the playbook", id),
+            InputError::Interrupted => write!(f, "interrupted"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+impl From<io::Error> for InputError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::Interrupted => InputError::Interrupted,
+            _ => InputError::Io(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,481 @@
+//! the expressions of form files, like
+//! `answers.tls == true && answers.env != "dev"`. they read earlier
+//! answers and, in a `validate` check, the `value` being answered.
+//! nothing else is reachable and evaluating never fails: a comparison
+//! of values that don't compare is false
+//!
+//! from loosest to tightest: `c ? a : b`, `||`, `&&`, `== != < <= > >=`,
+//! `+ -`, `!` and `-`. the functions are `len(x)` and
+//! `matches(x, "regex")`
+
+use crate::form::Answers;
+use crate::regex::Regex;
+
+/// nesting deeper than this is refused, so a hostile file can't
+/// overflow the stack
+const MAX_DEPTH: usize = 64;
+
+/// a parsed expression
+#[derive(Debug, Clone)]
+pub(crate) struct Expr(Node);
+
+#[derive(Debug, Clone)]
+enum Node {
+    Literal(Val),
+    Answer(String),
+    Value,
+    Not(Box<Node>),
+    Neg(Box<Node>),
+    Binary(Op, Box<Node>, Box<Node>),
+    If(Box<Node>, Box<Node>, Box<Node>),
+    Len(Box<Node>),
+    Matches(Box<Node>, Regex),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+}
+
+/// what an expression evaluates to. answers are strings until compared
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Val {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    /// a missing answer, or arithmetic on something else than numbers
+    Null,
+}
+
+impl Expr {
+    pub(crate) fn parse(text: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            at: 0,
+            depth: 0,
+        };
+        let node = parser.ternary()?;
+        match parser.tokens.get(parser.at) {
+            None => Ok(Expr(node)),
+            Some(token) => Err(format!("unexpected {} in '{}'", token, text)),
+        }
+    }
+
+    /// the value after `answers`, with `value` as the answer being checked
+    pub(crate) fn eval(&self, answers: &Answers, value: Option<&str>) -> Val {
+        self.0.eval(answers, value)
+    }
+
+    /// whether the expression holds after `answers`
+    pub(crate) fn holds(&self, answers: &Answers, value: Option<&str>) -> bool {
+        self.eval(answers, value).truthy()
+    }
+
+    /// the ids of the answers read
+    pub(crate) fn answer_ids(&self) -> Vec<&str> {
+        let mut ids = Vec::new();
+        self.0.visit(&mut |node| {
+            if let Node::Answer(id) = node {
+                ids.push(id.as_str());
+            }
+        });
+        ids
+    }
+
+    /// whether `value` is read
+    pub(crate) fn uses_value(&self) -> bool {
+        let mut uses = false;
+        self.0
+            .visit(&mut |node| uses |= matches!(node, Node::Value));
+        uses
+    }
+}
+
+impl Node {
+    fn eval(&self, answers: &Answers, value: Option<&str>) -> Val {
+        let eval = |node: &Node| node.eval(answers, value);
+        match self {
+            Node::Literal(val) => val.clone(),
+            Node::Answer(id) => answers.get(id).map_or(Val::Null, Val::from),
+            Node::Value => value.map_or(Val::Null, Val::from),
+            Node::Not(node) => Val::Bool(!eval(node).truthy()),
+            Node::Neg(node) => eval(node).number().map_or(Val::Null, |n| Val::Num(-n)),
+            Node::Binary(op, a, b) => binary(*op, eval(a), eval(b)),
+            Node::If(cond, then, otherwise) => match eval(cond).truthy() {
+                true => eval(then),
+                false => eval(otherwise),
+            },
+            Node::Len(node) => match eval(node) {
+                Val::Null => Val::Num(0.0),
+                val => Val::Num(val.to_string().chars().count() as f64),
+            },
+            Node::Matches(node, regex) => match eval(node) {
+                Val::Null => Val::Bool(false),
+                // too long to tell is no answer, like other failures
+                val => regex
+                    .is_match(&val.to_string())
+                    .map_or(Val::Null, Val::Bool),
+            },
+        }
+    }
+
+    fn visit<'a>(&'a self, f: &mut dyn FnMut(&'a Node)) {
+        f(self);
+        match self {
+            Node::Literal(_) | Node::Answer(_) | Node::Value => {}
+            Node::Not(node) | Node::Neg(node) | Node::Len(node) | Node::Matches(node, _) => {
+                node.visit(f)
+            }
+            Node::Binary(_, a, b) => {
+                a.visit(f);
+                b.visit(f);
+            }
+            Node::If(cond, then, otherwise) => {
+                cond.visit(f);
+                then.visit(f);
+                otherwise.visit(f);
+            }
+        }
+    }
+}
+
+fn binary(op: Op, a: Val, b: Val) -> Val {
+    match op {
+        Op::Or => Val::Bool(a.truthy() || b.truthy()),
+        Op::And => Val::Bool(a.truthy() && b.truthy()),
+        Op::Eq => Val::Bool(a.equals(&b)),
+        Op::Ne => Val::Bool(!a.equals(&b)),
+        Op::Lt | Op::Le | Op::Gt | Op::Ge => {
+            let Some(ordering) = a.compare(&b) else {
+                return Val::Bool(false);
+            };
+            Val::Bool(match op {
+                Op::Lt => ordering.is_lt(),
+                Op::Le => ordering.is_le(),
+                Op::Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            })
+        }
+        // numbers add up, anything else but null joins as text
+        Op::Add => match (a.number(), b.number(), &a, &b) {
+            (Some(x), Some(y), _, _) => Val::Num(x + y),
+            (_, _, Val::Null, _) | (_, _, _, Val::Null) => Val::Null,
+            _ => Val::Str(format!("{}{}", a, b)),
+        },
+        Op::Sub => match (a.number(), b.number()) {
+            (Some(a), Some(b)) => Val::Num(a - b),
+            _ => Val::Null,
+        },
+    }
+}
+
+impl Val {
+    /// `y`, `yes` and `true` answers, non-zero numbers and true
+    pub(crate) fn truthy(&self) -> bool {
+        match self {
+            Val::Str(s) => truthy(s) == Some(true),
+            Val::Num(n) => *n != 0.0,
+            Val::Bool(b) => *b,
+            Val::Null => false,
+        }
+    }
+
+    fn number(&self) -> Option<f64> {
+        match self {
+            Val::Str(s) => s.trim().parse().ok(),
+            Val::Num(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// an answer equals a bool or number it reads as
+    fn equals(&self, other: &Val) -> bool {
+        match (self, other) {
+            (Val::Null, Val::Null) => true,
+            (Val::Null, _) | (_, Val::Null) => false,
+            (Val::Bool(a), b) | (b, Val::Bool(a)) => match b {
+                Val::Bool(b) => a == b,
+                Val::Str(s) => truthy(s) == Some(*a),
+                _ => false,
+            },
+            (Val::Num(_), _) | (_, Val::Num(_)) => {
+                self.number().is_some_and(|a| other.number() == Some(a))
+            }
+            (Val::Str(a), Val::Str(b)) => a == b,
+        }
+    }
+
+    /// numbers by value, strings in order
+    fn compare(&self, other: &Val) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (Val::Str(a), Val::Str(b)) => match (self.number(), other.number()) {
+                (Some(a), Some(b)) => a.partial_cmp(&b),
+                _ => Some(a.cmp(b)),
+            },
+            _ => self.number()?.partial_cmp(&other.number()?),
+        }
+    }
+}
+
+impl From<&str> for Val {
+    fn from(answer: &str) -> Self {
+        Val::Str(answer.to_string())
+    }
+}
+
+/// the value as an answer: whole numbers without `.0`, null as nothing
+impl std::fmt::Display for Val {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Val::Str(s) => f.write_str(s),
+            Val::Num(n) if n.fract() == 0.0 && n.abs() < 1e15 => write!(f, "{}", *n as i64),
+            Val::Num(n) => write!(f, "{}", n),
+            Val::Bool(b) => write!(f, "{}", b),
+            Val::Null => Ok(()),
+        }
+    }
+}
+
+/// `y`, `yes` and `true` or `n`, `no` and `false`, in any case
+pub(crate) fn truthy(answer: &str) -> Option<bool> {
+    match answer.to_lowercase().as_str() {
+        "y" | "yes" | "true" => Some(true),
+        "n" | "no" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Str(String),
+    Name(String),
+    Sym(&'static str),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Num(n) => write!(f, "{}", n),
+            Token::Str(s) => write!(f, "{:?}", s),
+            Token::Name(name) => write!(f, "'{}'", name),
+            Token::Sym(sym) => write!(f, "'{}'", sym),
+        }
+    }
+}
+
+const SYMBOLS: &[&str] = &[
+    "&&", "||", "==", "!=", "<=", ">=", "<", ">", "!", "+", "-", "?", ":", "(", ")", ",",
+];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if let Some(sym) = SYMBOLS.iter().find(|sym| rest.starts_with(**sym)) {
+            tokens.push(Token::Sym(sym));
+            rest = &rest[sym.len()..];
+        } else if c == '"' || c == '\'' {
+            let (s, after) =
+                string(&rest[1..], c).ok_or_else(|| format!("unclosed string in '{}'", text))?;
+            tokens.push(Token::Str(s));
+            rest = after;
+        } else if c.is_ascii_digit() {
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(rest.len());
+            let n = rest[..end]
+                .parse()
+                .map_err(|_| format!("invalid number '{}'", &rest[..end]))?;
+            tokens.push(Token::Num(n));
+            rest = &rest[end..];
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_' && c != '.')
+                .unwrap_or(rest.len());
+            tokens.push(Token::Name(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else {
+            return Err(format!("unexpected '{}' in '{}'", c, text));
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+/// the string up to the closing `quote`, `\` escapes the next char
+fn string(text: &str, quote: char) -> Option<(String, &str)> {
+    let mut out = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => out.push(chars.next()?.1),
+            c if c == quote => return Some((out, &text[i + 1..])),
+            c => out.push(c),
+        }
+    }
+    None
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    at: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn eat(&mut self, sym: &str) -> bool {
+        let found = matches!(self.tokens.get(self.at), Some(Token::Sym(s)) if *s == sym);
+        if found {
+            self.at += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, sym: &str) -> Result<(), String> {
+        match self.eat(sym) {
+            true => Ok(()),
+            false => Err(match self.tokens.get(self.at) {
+                Some(token) => format!("expected '{}' before {}", sym, token),
+                None => format!("expected '{}' at the end", sym),
+            }),
+        }
+    }
+
+    fn ternary(&mut self) -> Result<Node, String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err("expression nested too deep".to_string());
+        }
+        let cond = self.binary(0)?;
+        let node = match self.eat("?") {
+            true => {
+                let then = self.ternary()?;
+                self.expect(":")?;
+                let otherwise = self.ternary()?;
+                Node::If(Box::new(cond), Box::new(then), Box::new(otherwise))
+            }
+            false => cond,
+        };
+        self.depth -= 1;
+        Ok(node)
+    }
+
+    /// operators of `LEVELS[level]` and tighter
+    fn binary(&mut self, level: usize) -> Result<Node, String> {
+        const LEVELS: &[&[(&str, Op)]] = &[
+            &[("||", Op::Or)],
+            &[("&&", Op::And)],
+            &[
+                ("==", Op::Eq),
+                ("!=", Op::Ne),
+                ("<=", Op::Le),
+                (">=", Op::Ge),
+                ("<", Op::Lt),
+                (">", Op::Gt),
+            ],
+            &[("+", Op::Add), ("-", Op::Sub)],
+        ];
+        let Some(ops) = LEVELS.get(level) else {
+            return self.unary();
+        };
+        let mut node = self.binary(level + 1)?;
+        let mut compared = false;
+        while let Some((_, op)) = ops.iter().find(|(sym, _)| self.eat(sym)) {
+            // `a < b < c` doesn't mean what it reads like
+            if compared {
+                return Err("comparisons can't be chained, use &&".to_string());
+            }
+            compared = level == 2;
+            let right = self.binary(level + 1)?;
+            node = Node::Binary(*op, Box::new(node), Box::new(right));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node, String> {
+        if self.eat("!") {
+            return Ok(Node::Not(Box::new(self.nested(Parser::unary)?)));
+        }
+        if self.eat("-") {
+            return Ok(Node::Neg(Box::new(self.nested(Parser::unary)?)));
+        }
+        self.primary()
+    }
+
+    /// `parse` one level deeper
+    fn nested(&mut self, parse: fn(&mut Parser) -> Result<Node, String>) -> Result<Node, String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err("expression nested too deep".to_string());
+        }
+        let node = parse(self);
+        self.depth -= 1;
+        node
+    }
+
+    fn primary(&mut self) -> Result<Node, String> {
+        let token = self
+            .tokens
+            .get(self.at)
+            .cloned()
+            .ok_or("expression ends too early")?;
+        self.at += 1;
+        match token {
+            Token::Num(n) => Ok(Node::Literal(Val::Num(n))),
+            Token::Str(s) => Ok(Node::Literal(Val::Str(s))),
+            Token::Sym("(") => {
+                let node = self.ternary()?;
+                self.expect(")")?;
+                Ok(node)
+            }
+            Token::Name(name) => self.name(&name),
+            token => Err(format!("unexpected {}", token)),
+        }
+    }
+
+    fn name(&mut self, name: &str) -> Result<Node, String> {
+        match name {
+            "true" => return Ok(Node::Literal(Val::Bool(true))),
+            "false" => return Ok(Node::Literal(Val::Bool(false))),
+            "null" => return Ok(Node::Literal(Val::Null)),
+            "value" => return Ok(Node::Value),
+            "len" | "matches" => {}
+            name => {
+                return match name.strip_prefix("answers.") {
+                    Some(id) if !id.is_empty() && !id.ends_with('.') => {
+                        Ok(Node::Answer(id.to_string()))
+                    }
+                    _ => Err(format!(
+                        "unknown name '{}', answers are read like answers.{}",
+                        name, name
+                    )),
+                }
+            }
+        }
+        self.expect("(")?;
+        let arg = self.ternary()?;
+        let node = match name {
+            "len" => Node::Len(Box::new(arg)),
+            _ => {
+                self.expect(",")?;
+                let Some(Token::Str(pattern)) = self.tokens.get(self.at).cloned() else {
+                    return Err("matches takes a \"pattern\" string".to_string());
+                };
+                self.at += 1;
+                Node::Matches(Box::new(arg), Regex::new(&pattern)?)
+            }
+        };
+        self.expect(")")?;
+        Ok(node)
+    }
+}
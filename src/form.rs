@@ -0,0 +1,692 @@
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::answers::{self, Recorder};
+use crate::config::{self, Theme};
+use crate::error::InputError;
+use crate::form_file;
+use crate::frontend::{self, Frontend};
+use crate::input::Input;
+use crate::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::locale;
+use crate::model::{Answer, Provenance};
+use crate::storage::{Slot, Storage};
+use crate::toml;
+use crate::{check_interactive, read_answer, render_prompt_into, write_error, Retries};
+
+/// answer that drops the previous answer of a `Form` run and asks that
+/// field again
+pub const UNDO: &str = ":undo";
+
+/// # Answers
+///
+/// answers collected by a `Form`, keyed by field id in asking order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    values: Vec<Answer>,
+}
+
+impl Answers {
+    pub fn new() -> Self {
+        Answers::default()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self.answer(id).map(|answer| answer.value.as_str())
+    }
+
+    /// the full `Answer` of `id`, with its provenance
+    pub fn answer(&self, id: &str) -> Option<&Answer> {
+        self.values.iter().find(|answer| answer.id == id)
+    }
+
+    /// answer of `id` parsed as `T`
+    pub fn parse<T>(&self, id: &str) -> Result<T, String>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self
+            .get(id)
+            .ok_or_else(|| format!("no answer for '{}'", id))?;
+        locale::parse(value).map_err(|e| format!("invalid {} '{}': {}", id, value, e))
+    }
+
+    /// set the entered answer of `id`, replacing an earlier one
+    pub fn insert(&mut self, id: &str, value: &str) {
+        self.record(Answer::new(id, value, Provenance::Entered));
+    }
+
+    /// store `answer`, replacing an earlier one with the same id
+    pub fn record(&mut self, answer: Answer) {
+        match self.values.iter_mut().find(|old| old.id == answer.id) {
+            Some(old) => *old = answer,
+            None => self.values.push(answer),
+        }
+    }
+
+    /// drop the answer of `id`
+    pub fn remove(&mut self, id: &str) -> Option<Answer> {
+        let at = self.values.iter().position(|answer| answer.id == id)?;
+        Some(self.values.remove(at))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values
+            .iter()
+            .map(|answer| (answer.id.as_str(), answer.value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+struct Field {
+    id: String,
+    input: Input,
+}
+
+struct Section {
+    title: Option<String>,
+    fields: Vec<Field>,
+}
+
+/// # Form
+///
+/// asks several questions in order and collects the answers by id.
+/// questions can be grouped into titled sections. answering `:undo`
+/// drops the previous answer and asks that field again
+///
+/// # usage
+/// ```
+/// use input_py::{Form, Input};
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut reader = MockReader::new(&["", "db.local", ""]);
+/// let mut writer = MockWriter::new();
+/// let answers = Form::new()
+///     .field("name", Input::new("name").default("app"))
+///     .section("Network")
+///     .field("host", Input::new("host"))
+///     .field("port", Input::new("port").default("5432"))
+///     .run_with_io(&mut reader, &mut writer)
+///     .unwrap();
+/// assert_eq!(answers.get("host"), Some("db.local"));
+/// assert_eq!(answers.parse::<u16>("port"), Ok(5432));
+/// ```
+pub struct Form {
+    sections: Vec<Section>,
+    skip_sections: bool,
+    accept_defaults_prompt: bool,
+    review: bool,
+    type_ahead: bool,
+    checkpoint: Option<Slot>,
+    profile: Option<Slot>,
+    recorder: Option<Recorder>,
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Form::new()
+    }
+}
+
+impl Form {
+    pub fn new() -> Self {
+        Form {
+            sections: vec![Section {
+                title: None,
+                fields: Vec::new(),
+            }],
+            skip_sections: false,
+            accept_defaults_prompt: false,
+            review: false,
+            type_ahead: true,
+            checkpoint: None,
+            profile: None,
+            recorder: None,
+        }
+    }
+
+    /// the questions defined in the TOML file at `path`, one `[id]` table
+    /// each, asked in file order. a table can set `prompt` (default: the
+    /// id), `type` (text, integer, number, bool or secret, which is read
+    /// hidden and shown masked), `default`, `choices`, a regex `pattern`
+    /// with its `pattern_hint`, `max_len` and the `section` it belongs
+    /// to. expressions on earlier answers
+    /// like `answers.tls == true && answers.env != "dev"` can set a
+    /// `condition` for asking, a `default_from` and a `validate` check of
+    /// the `value` answered with its `validate_error`. errors name the
+    /// file and the line or question
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Form;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let path = std::env::temp_dir().join("input_py_form_doc.toml");
+    /// std::fs::write(&path, "[env]\nchoices = [\"dev\", \"prod\"]\n\n\
+    ///     [replicas]\ntype = \"integer\"\ncondition = 'answers.env == \"prod\"'\n\
+    ///     default_from = 'answers.env == \"prod\" ? 3 : 1'\n").unwrap();
+    /// let answers = Form::from_toml(&path)
+    ///     .unwrap()
+    ///     .run_with_io(&mut MockReader::new(&["prod", "three", ""]), &mut MockWriter::new())
+    ///     .unwrap();
+    /// assert_eq!(answers.parse::<u8>("replicas"), Ok(3));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn from_toml(path: impl AsRef<Path>) -> Result<Form, InputError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        form_file::parse(&text)
+            .map_err(|e| InputError::Io(format!("form {}: {}", path.display(), e)))
+    }
+
+    /// start a new section, following fields belong to it
+    pub fn section(mut self, title: &str) -> Self {
+        self.sections.push(Section {
+            title: Some(title.to_string()),
+            fields: Vec::new(),
+        });
+        self
+    }
+
+    /// add a question whose answer is stored under `id`
+    pub fn field(mut self, id: &str, input: Input) -> Self {
+        let field = Field {
+            id: id.to_string(),
+            input,
+        };
+        if let Some(section) = self.sections.last_mut() {
+            section.fields.push(field);
+        }
+        self
+    }
+
+    /// before a section whose fields all have defaults, ask whether to
+    /// accept all of them at once. default: false
+    pub fn skip_sections(mut self, skip: bool) -> Self {
+        self.skip_sections = skip;
+        self
+    }
+
+    /// first ask whether to use the defaults for everything. if confirmed
+    /// every field is answered with its default without asking, fields
+    /// without a default fail with `InputError::MissingDefault`.
+    /// default: false
+    pub fn accept_defaults_prompt(mut self, ask: bool) -> Self {
+        self.accept_defaults_prompt = ask;
+        self
+    }
+
+    /// list every field with its default and ask for the numbers of the
+    /// fields to change. only those and fields without a default are asked,
+    /// the others keep their default. default: false
+    pub fn review(mut self, review: bool) -> Self {
+        self.review = review;
+        self
+    }
+
+    /// answers typed before their prompt appeared are used in order, so
+    /// a fast typist or a paste can fill several fields at once. a field
+    /// built with `Input::flush_pending(true)` still drops them, which
+    /// suits confirmations. with false every field drops them.
+    /// default: true
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::{Form, Input};
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut reader = MockReader::new(&["y"]).typed_ahead(&["db", "5432", ""]);
+    /// let answers = Form::new()
+    ///     .field("host", Input::new("host"))
+    ///     .field("port", Input::new("port"))
+    ///     .field("apply", Input::new("apply? [y/N]").flush_pending(true))
+    ///     .run_with_io(&mut reader, &mut MockWriter::new())
+    ///     .unwrap();
+    /// assert_eq!(answers.get("port"), Some("5432"));
+    /// assert_eq!(answers.get("apply"), Some("y"));
+    /// ```
+    pub fn type_ahead(mut self, allow: bool) -> Self {
+        self.type_ahead = allow;
+        self
+    }
+
+    /// save the answers to `path` after every answer and skip the fields
+    /// already saved there, so an interrupted run resumes where it
+    /// stopped. the file is removed when the form completes. masked
+    /// fields are never saved and are asked again. review mode and
+    /// accepted defaults don't use the checkpoint
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::{Form, Input};
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let path = std::env::temp_dir().join("input_py_checkpoint_doc.toml");
+    /// // left behind by a run interrupted after the host
+    /// std::fs::write(&path, "host = \"db\"\n").unwrap();
+    /// let answers = Form::new()
+    ///     .field("host", Input::new("host"))
+    ///     .field("port", Input::new("port"))
+    ///     .checkpoint(&path)
+    ///     .run_with_io(&mut MockReader::new(&["5432"]), &mut MockWriter::new())
+    ///     .unwrap();
+    /// assert_eq!(answers.get("host"), Some("db"));
+    /// assert_eq!(answers.get("port"), Some("5432"));
+    /// assert!(!path.exists());
+    /// ```
+    pub fn checkpoint(mut self, path: impl AsRef<Path>) -> Self {
+        self.checkpoint = Some(Slot::File(path.as_ref().to_path_buf()));
+        self
+    }
+
+    /// `checkpoint` under `key` of `storage` instead of a file
+    pub fn checkpoint_in(mut self, storage: Arc<dyn Storage>, key: &str) -> Self {
+        self.checkpoint = Some(Slot::Key(storage, key.to_string()));
+        self
+    }
+
+    /// answers kept under `key` of `storage`, like `profile.prod`, are
+    /// the defaults of their fields, and a completed run keeps its
+    /// answers there for the next one. masked fields are never kept
+    ///
+    /// # usage
+    /// ```
+    /// use std::sync::Arc;
+    /// use input_py::{Form, Input};
+    /// use input_py::io::{MockReader, MockWriter};
+    /// use input_py::storage::{MemoryStorage, Storage};
+    ///
+    /// let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+    /// let form = || {
+    ///     Form::new()
+    ///         .field("host", Input::new("host").default("localhost"))
+    ///         .profile(storage.clone(), "profile.prod")
+    /// };
+    /// form().run_with_io(&mut MockReader::new(&["db.prod"]), &mut MockWriter::new()).unwrap();
+    ///
+    /// let mut writer = MockWriter::new();
+    /// let answers = form().run_with_io(&mut MockReader::new(&[""]), &mut writer).unwrap();
+    /// assert_eq!(writer.output, "host [db.prod]:");
+    /// assert_eq!(answers.get("host"), Some("db.prod"));
+    /// ```
+    pub fn profile(mut self, storage: Arc<dyn Storage>, key: &str) -> Self {
+        self.profile = Some(Slot::Key(storage, key.to_string()));
+        self
+    }
+
+    /// write the answers of a completed run to `recorder`, keyed by
+    /// field id, see `Recorder`
+    pub fn recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// run the form on stdin
+    pub fn run(self) -> Result<Answers, InputError> {
+        self.run_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    pub fn run_with_io(
+        mut self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Answers, InputError> {
+        self.load_profile()?;
+        let answers = self.ask_all(reader, writer)?;
+        self.keep(self.profile.as_ref(), &answers)?;
+        self.record(&answers)?;
+        Ok(answers)
+    }
+
+    fn ask_all(
+        &self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Answers, InputError> {
+        let mut answers = Answers::new();
+        if self.accept_defaults_prompt
+            && ask_yes("Use defaults for everything? [Y/n]", reader, writer)?
+        {
+            for field in self.sections.iter().flat_map(|s| &s.fields) {
+                if !field.input.applies(&answers) {
+                    continue;
+                }
+                let default = field.input.default_with(&answers);
+                let answer = default_answer(&field.id, default.as_deref())?
+                    .ok_or_else(|| InputError::MissingDefault(field.id.clone()))?;
+                answers.record(answer);
+            }
+            return Ok(answers);
+        }
+
+        if self.review {
+            return self.run_review(reader, writer);
+        }
+
+        let restored = self.restore()?;
+        // fields answered in this run, in order, for undo
+        let mut asked: Vec<&Field> = Vec::new();
+        for section in &self.sections {
+            let mut fields = Vec::with_capacity(section.fields.len());
+            for field in &section.fields {
+                match restored.answer(&field.id) {
+                    Some(answer) => answers.record(answer.clone()),
+                    None => fields.push(field),
+                }
+            }
+            if fields.is_empty() {
+                continue;
+            }
+            if let Some(title) = &section.title {
+                let (open, close) = Theme::current().section;
+                writer.write_str(&format!("{}{}{}\n", open, title, close))?;
+            }
+
+            if self.skip_sections {
+                if let Some(defaults) = with_defaults(&fields, &answers)? {
+                    if ask_yes("accept all defaults in this section? [Y/n]", reader, writer)? {
+                        answers = defaults;
+                        self.save(&answers)?;
+                        continue;
+                    }
+                }
+            }
+
+            // fields still to ask, the next one last
+            let mut pending: Vec<&Field> = fields.into_iter().rev().collect();
+            while let Some(field) = pending.pop() {
+                if !field.input.applies(&answers) {
+                    continue;
+                }
+                if !self.type_ahead {
+                    reader.discard_pending()?;
+                }
+                let Some(answer) = field
+                    .input
+                    .ask_as(&field.id, &answers, true, reader, writer)?
+                else {
+                    pending.push(field);
+                    match asked.pop() {
+                        Some(previous) => {
+                            answers.remove(&previous.id);
+                            self.save(&answers)?;
+                            pending.push(previous);
+                        }
+                        None => write_error(writer, "nothing to undo")?,
+                    }
+                    continue;
+                };
+                answers.record(answer);
+                self.save(&answers)?;
+                asked.push(field);
+            }
+        }
+        self.finish()?;
+        Ok(answers)
+    }
+}
+
+impl Form {
+    /// run the form on another `Frontend`, like `JsonFrontend` or a GUI.
+    /// every field is asked in order, section skipping and review mode
+    /// are terminal features and not used
+    pub fn run_with_frontend(mut self, frontend: &mut dyn Frontend) -> Result<Answers, InputError> {
+        self.load_profile()?;
+        let restored = self.restore()?;
+        let mut answers = Answers::new();
+        for field in self.sections.iter().flat_map(|s| &s.fields) {
+            if let Some(answer) = restored.answer(&field.id) {
+                answers.record(answer.clone());
+                continue;
+            }
+            if !field.input.applies(&answers) {
+                continue;
+            }
+            let question = field.input.question_with(&field.id, &answers)?;
+            answers.record(frontend::ask(frontend, &question)?);
+            self.save(&answers)?;
+        }
+        self.finish()?;
+        self.keep(self.profile.as_ref(), &answers)?;
+        self.record(&answers)?;
+        Ok(answers)
+    }
+
+    /// make the answers of the profile the defaults of their fields
+    fn load_profile(&mut self) -> Result<(), InputError> {
+        let Some(slot) = &self.profile else {
+            return Ok(());
+        };
+        let answers = read_answers("profile", slot)?;
+        for field in self.sections.iter_mut().flat_map(|s| &mut s.fields) {
+            if let Some(value) = answers.get(&field.id) {
+                field.input.default = Some(value.to_string());
+                field.input.default_from = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// answers saved by an interrupted run, empty without checkpoint
+    fn restore(&self) -> Result<Answers, InputError> {
+        match &self.checkpoint {
+            Some(slot) => read_answers("checkpoint", slot),
+            None => Ok(Answers::new()),
+        }
+    }
+
+    /// write the unmasked `answers` to the checkpoint, replacing it whole
+    fn save(&self, answers: &Answers) -> Result<(), InputError> {
+        self.keep(self.checkpoint.as_ref(), answers)
+    }
+
+    /// write the unmasked `answers` to `slot`, if any, replacing it whole
+    fn keep(&self, slot: Option<&Slot>, answers: &Answers) -> Result<(), InputError> {
+        let Some(slot) = slot else {
+            return Ok(());
+        };
+        slot.write(&toml::write(&self.unmasked(answers)))
+    }
+
+    /// write the unmasked `answers` to the recorder, if any
+    fn record(&self, answers: &Answers) -> Result<(), InputError> {
+        let Some(recorder) = &self.recorder else {
+            return Ok(());
+        };
+        for (id, value) in self.unmasked(answers) {
+            recorder.record(id, value)?;
+        }
+        Ok(())
+    }
+
+    /// `(id, answer)` of the fields in `answers` that aren't masked
+    fn unmasked<'a>(&'a self, answers: &'a Answers) -> Vec<(&'a str, &'a str)> {
+        self.sections
+            .iter()
+            .flat_map(|s| &s.fields)
+            .filter(|field| field.input.mask.is_none())
+            .filter_map(|field| {
+                answers
+                    .get(&field.id)
+                    .map(|value| (field.id.as_str(), value))
+            })
+            .collect()
+    }
+
+    /// remove the checkpoint of a completed run
+    fn finish(&self) -> Result<(), InputError> {
+        match &self.checkpoint {
+            Some(slot) => slot.delete(),
+            None => Ok(()),
+        }
+    }
+
+    fn run_review(
+        &self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Answers, InputError> {
+        let fields: Vec<&Field> = self.sections.iter().flat_map(|s| &s.fields).collect();
+        // defaults derived from earlier defaults, the real ones follow the answers
+        let mut preview = Answers::new();
+        let mut list = Vec::with_capacity(fields.len());
+        // indexes into `fields` of the listed numbers
+        let mut listed = Vec::with_capacity(fields.len());
+        for (i, field) in fields.iter().enumerate() {
+            if !field.input.applies(&preview) {
+                continue;
+            }
+            let default = field.input.default_with(&preview);
+            let shown = match (&default, field.input.mask) {
+                (Some(default), Some(mask)) => mask.apply(default),
+                (Some(default), None) => default.clone(),
+                (None, _) => "(no default)".to_string(),
+            };
+            listed.push(i);
+            list.push(format!(
+                "  {}. {} [{}]",
+                listed.len(),
+                field.input.prompt,
+                shown
+            ));
+            if let Some(default) = default {
+                preview.insert(&field.id, &default);
+            }
+        }
+        let lines: Vec<&str> = list.iter().map(String::as_str).collect();
+        writer.write_all_lines(&lines)?;
+
+        let mut prompt = String::new();
+        let theme = Theme::current().for_writer(writer);
+        render_prompt_into(
+            &mut prompt,
+            "numbers to change (empty for none)",
+            None,
+            &theme,
+        );
+        let no_changes = config::env_accepts_defaults()
+            || check_interactive(&prompt, Some(""), reader)?.is_some();
+        let mut retries = Retries::new();
+        let selected = loop {
+            if no_changes {
+                break Vec::new();
+            }
+            let line = read_answer(&prompt, reader, writer)?.ok_or(InputError::Eof)?;
+            match parse_numbers(&line, listed.len()) {
+                Ok(numbers) => break numbers.iter().map(|n| listed[n - 1]).collect(),
+                Err(msg) => retries.reject(writer, &msg)?,
+            }
+        };
+
+        let mut answers = Answers::new();
+        for (i, field) in fields.iter().enumerate() {
+            if !field.input.applies(&answers) {
+                continue;
+            }
+            let default = field.input.default_with(&answers);
+            let answer = match default_answer(&field.id, default.as_deref())? {
+                Some(answer) if !selected.contains(&i) => answer,
+                _ => {
+                    if !self.type_ahead {
+                        reader.discard_pending()?;
+                    }
+                    let answer = field
+                        .input
+                        .ask_as(&field.id, &answers, false, reader, writer)?;
+                    answer.expect("undo is off")
+                }
+            };
+            answers.record(answer);
+        }
+        Ok(answers)
+    }
+}
+
+/// the answers kept in `slot`, empty when there are none. errors name
+/// it as `what`
+fn read_answers(what: &str, slot: &Slot) -> Result<Answers, InputError> {
+    let mut answers = Answers::new();
+    let Some(text) = slot.read()? else {
+        return Ok(answers);
+    };
+    let entries =
+        toml::parse(&text).map_err(|e| InputError::Io(format!("{} {}: {}", what, slot, e)))?;
+    for (id, value) in entries {
+        answers.insert(&id, &value);
+    }
+    Ok(answers)
+}
+
+/// `answers` plus the defaults of `fields` in order, `None` when a
+/// field has no default
+fn with_defaults(fields: &[&Field], answers: &Answers) -> Result<Option<Answers>, InputError> {
+    let mut answers = answers.clone();
+    for field in fields {
+        if !field.input.applies(&answers) {
+            continue;
+        }
+        let default = field.input.default_with(&answers);
+        match default_answer(&field.id, default.as_deref())? {
+            Some(answer) => answers.record(answer),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(answers))
+}
+
+/// the answer to `id` from the environment or playbook, else `default`
+fn default_answer(id: &str, default: Option<&str>) -> Result<Option<Answer>, InputError> {
+    if let Some(value) = answers::preset(id)? {
+        return Ok(Some(Answer::new(id, &value, Provenance::Entered)));
+    }
+    Ok(default.map(|default| Answer::new(id, default, Provenance::Default)))
+}
+
+/// 1-based numbers separated by commas or spaces
+fn parse_numbers(line: &str, count: usize) -> Result<Vec<usize>, String> {
+    line.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| match s.parse::<usize>() {
+            Ok(n) if (1..=count).contains(&n) => Ok(n),
+            _ => Err(format!("'{}' is not a number between 1 and {}", s, count)),
+        })
+        .collect()
+}
+
+/// yes/no question where an empty answer means yes
+pub(crate) fn ask_yes(
+    question: &str,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+) -> Result<bool, InputError> {
+    let mut prompt = String::new();
+    render_prompt_into(
+        &mut prompt,
+        question,
+        None,
+        &Theme::current().for_writer(writer),
+    );
+    if config::env_accepts_defaults() || check_interactive(&prompt, Some("y"), reader)?.is_some() {
+        return Ok(true);
+    }
+    let mut retries = Retries::new();
+    loop {
+        let answer = read_answer(&prompt, reader, writer)?.ok_or(InputError::Eof)?;
+        match answer.to_lowercase().as_str() {
+            "" | "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => retries.reject(writer, &format!("'{}' is not y or n", answer))?,
+        }
+    }
+}
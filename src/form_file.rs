@@ -0,0 +1,191 @@
+//! questions of a `Form` read from a TOML file, see `Form::from_toml`.
+//! each `[id]` table is a question, asked in file order
+
+use crate::expr::{self, Expr, Val};
+use crate::form::Form;
+use crate::input::Input;
+use crate::model::MaskStyle;
+use crate::regex::Regex;
+use crate::toml::{self, Value};
+
+/// the keys a question table may have
+const KEYS: &[&str] = &[
+    "prompt",
+    "type",
+    "default",
+    "default_from",
+    "choices",
+    "pattern",
+    "pattern_hint",
+    "max_len",
+    "condition",
+    "validate",
+    "validate_error",
+    "section",
+];
+
+/// the form described by `text`, errors name the line or the question
+pub(crate) fn parse(text: &str) -> Result<Form, String> {
+    // (id, keys) in file order
+    let mut questions: Vec<(String, Vec<(String, Value)>)> = Vec::new();
+    for (key, value) in toml::parse_values(text)? {
+        if value == Value::Table {
+            if !questions.iter().any(|(known, _)| *known == key) {
+                questions.push((key, Vec::new()));
+            }
+            continue;
+        }
+        let Some((id, key)) = key.rsplit_once('.') else {
+            return Err(format!("'{}' is outside of a [question] table", key));
+        };
+        if !KEYS.contains(&key) {
+            return Err(format!("question '{}': unknown key '{}'", id, key));
+        }
+        let entry = (key.to_string(), value);
+        match questions.iter_mut().find(|(known, _)| known == id) {
+            Some((_, keys)) => keys.push(entry),
+            None => questions.push((id.to_string(), vec![entry])),
+        }
+    }
+
+    let ids: Vec<&str> = questions.iter().map(|(id, _)| id.as_str()).collect();
+    let mut form = Form::new();
+    let mut section = None;
+    for (id, keys) in &questions {
+        let at = |msg: String| format!("question '{}': {}", id, msg);
+        let title = scalar(keys, "section").map_err(at)?;
+        if let Some(title) = title.filter(|title| section.as_deref() != Some(*title)) {
+            form = form.section(title);
+            section = Some(title.to_string());
+        }
+        let input = question(id, keys, &ids).map_err(at)?;
+        form = form.field(id, input);
+    }
+    Ok(form)
+}
+
+/// the question `id` defined by `keys`, in a form of the questions `ids`
+fn question(id: &str, keys: &[(String, Value)], ids: &[&str]) -> Result<Input, String> {
+    let prompt = scalar(keys, "prompt")?.unwrap_or(id);
+    let mut input = Input::new(prompt);
+    if let Some(default) = scalar(keys, "default")? {
+        input = input.default(default);
+    }
+    match scalar(keys, "type")?.unwrap_or("text") {
+        "text" => {}
+        "secret" => input = input.hidden(true).mask_display(MaskStyle::Full),
+        "integer" => {
+            input = input.validate(|answer| match answer.parse::<i64>() {
+                Ok(_) => Ok(()),
+                Err(_) => Err(format!("'{}' is not an integer", answer)),
+            })
+        }
+        "number" => {
+            input = input.validate(|answer| match answer.parse::<f64>() {
+                Ok(_) => Ok(()),
+                Err(_) => Err(format!("'{}' is not a number", answer)),
+            })
+        }
+        "bool" => {
+            input = input.validate(|answer| match expr::truthy(answer) {
+                Some(_) => Ok(()),
+                None => Err(format!("'{}' is not y or n", answer)),
+            })
+        }
+        other => {
+            return Err(format!(
+                "unknown type '{}', expected text, secret, integer, number or bool",
+                other
+            ))
+        }
+    }
+    match keys.iter().find(|(key, _)| key == "choices") {
+        Some((_, Value::Array(choices))) => input = input.choices(choices),
+        Some(_) => return Err("choices must be an array".to_string()),
+        None => {}
+    }
+    if let Some(pattern) = scalar(keys, "pattern")? {
+        let regex = Regex::new(pattern)?;
+        let hint = match scalar(keys, "pattern_hint")? {
+            Some(hint) => hint.to_string(),
+            None => format!("like {}", pattern),
+        };
+        input = input.try_pattern(&hint, move |answer| regex.is_match(answer));
+    }
+    if let Some(max_len) = scalar(keys, "max_len")? {
+        let max_len = max_len
+            .parse()
+            .map_err(|_| format!("max_len '{}' is not a number", max_len))?;
+        input = input.max_len(max_len);
+    }
+    if let Some(condition) = expression(keys, "condition", id, ids)? {
+        input = input.when(move |answers| condition.holds(answers, None));
+    }
+    if let Some(default) = expression(keys, "default_from", id, ids)? {
+        input = input.default_from(move |answers| match default.eval(answers, None) {
+            Val::Null => None,
+            value => Some(value.to_string()),
+        });
+    }
+    if let Some(check) = scalar(keys, "validate")? {
+        let error = scalar(keys, "validate_error")?.map(str::to_string);
+        let text = check.to_string();
+        let check = parse_expression(check, id, ids, true)?;
+        input =
+            input.validate_from(
+                move |answer, answers| match check.holds(answers, Some(answer)) {
+                    true => Ok(()),
+                    false => Err(match &error {
+                        Some(error) => error.clone(),
+                        None => format!("'{}' fails {}", answer, text),
+                    }),
+                },
+            );
+    }
+    Ok(input)
+}
+
+/// the expression under `key` of question `id`
+fn expression(
+    keys: &[(String, Value)],
+    key: &str,
+    id: &str,
+    ids: &[&str],
+) -> Result<Option<Expr>, String> {
+    match scalar(keys, key)? {
+        Some(text) => parse_expression(text, id, ids, false)
+            .map(Some)
+            .map_err(|e| format!("{}: {}", key, e)),
+        None => Ok(None),
+    }
+}
+
+/// `text` as an expression of question `id` reading the answers of
+/// other questions, and `value` when it `checks` the answer
+fn parse_expression(text: &str, id: &str, ids: &[&str], checks: bool) -> Result<Expr, String> {
+    let expr = Expr::parse(text)?;
+    for read in expr.answer_ids() {
+        if read == id {
+            return Err(format!("'{}' reads its own answer, use value", text));
+        }
+        if !ids.contains(&read) {
+            return Err(format!(
+                "'{}' reads answers.{}, which isn't a question",
+                text, read
+            ));
+        }
+    }
+    if expr.uses_value() && !checks {
+        return Err(format!("'{}' reads value, which only validate knows", text));
+    }
+    Ok(expr)
+}
+
+/// the value of `key` unless it is missing, an array is an error
+fn scalar<'a>(keys: &'a [(String, Value)], key: &str) -> Result<Option<&'a str>, String> {
+    match keys.iter().find(|(k, _)| k == key) {
+        Some((_, Value::Scalar(value))) => Ok(Some(value)),
+        Some(_) => Err(format!("{} can't be an array", key)),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,242 @@
+use crate::config::Theme;
+use crate::error::InputError;
+use crate::io::{InputReader, OutputWriter};
+use crate::json::{self, JsonValue};
+use crate::markdown;
+use crate::model::Provenance;
+pub use crate::model::{Answer, Question};
+use crate::{preset_answer, process_input, render_masked_prompt_into, write_error, Retries};
+
+/// # Frontend
+///
+/// shows a `Question` to the user and returns the raw answer. defaults
+/// and validation are applied by the crate, so a frontend only has to
+/// display text and collect it, like a native dialog or a web form
+///
+/// # usage
+/// ```
+/// use input_py::frontend::{self, Frontend, Question};
+/// use input_py::InputError;
+///
+/// struct Dialog;
+///
+/// impl Frontend for Dialog {
+///     fn ask(&mut self, question: &Question) -> Result<String, InputError> {
+///         // show a dialog for `question.prompt` here
+///         Ok(String::new())
+///     }
+///
+///     fn report_error(&mut self, _question: &Question, _message: &str) -> Result<(), InputError> {
+///         Ok(())
+///     }
+/// }
+///
+/// let port = frontend::ask(&mut Dialog, &Question::new("port", "port").default("5432")).unwrap();
+/// assert_eq!(port.value, "5432");
+/// ```
+pub trait Frontend {
+    /// ask `question` and return the answer as entered, an empty answer
+    /// means none was given
+    fn ask(&mut self, question: &Question) -> Result<String, InputError>;
+
+    /// tell the user the answer to `question` was rejected
+    fn report_error(&mut self, question: &Question, message: &str) -> Result<(), InputError>;
+}
+
+/// ask `question` on `frontend` and apply its default to an empty answer.
+/// answers breaking the constraints are reported and asked again
+pub fn ask(frontend: &mut dyn Frontend, question: &Question) -> Result<Answer, InputError> {
+    let mut retries = Retries::new();
+    loop {
+        let value = frontend.ask(question)?;
+        let answer = match &question.default {
+            Some(default) if value.is_empty() => {
+                Answer::new(&question.id, default, Provenance::Default)
+            }
+            _ => Answer::new(&question.id, &value, Provenance::Entered),
+        };
+        match question.constraints.check(&answer.value) {
+            Ok(()) => return Ok(answer.attempts(retries.attempts())),
+            Err(msg) => {
+                let msg = question.hide_in(&msg, &answer.value);
+                frontend.report_error(question, &msg)?;
+                retries.count()?;
+            }
+        }
+    }
+}
+
+/// # TerminalFrontend
+///
+/// the human text frontend used by `Input` and `Form`, as a `Frontend`.
+/// `QuestionKind::Secret` questions are read with the echo off
+pub struct TerminalFrontend<R: InputReader, W: OutputWriter> {
+    reader: R,
+    writer: W,
+}
+
+impl<R: InputReader, W: OutputWriter> TerminalFrontend<R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        TerminalFrontend { reader, writer }
+    }
+
+    pub fn into_inner(self) -> (R, W) {
+        (self.reader, self.writer)
+    }
+}
+
+impl<R: InputReader, W: OutputWriter> Frontend for TerminalFrontend<R, W> {
+    fn ask(&mut self, question: &Question) -> Result<String, InputError> {
+        let default = question.default.as_deref();
+        match preset_answer(&question.id, &question.prompt, default, &self.reader)? {
+            // an empty answer resolves to the default
+            Some(answer) if Some(&*answer) == default => return Ok(String::new()),
+            Some(answer) => return Ok(answer.to_string()),
+            None => {}
+        }
+        let mut text = match &question.body {
+            Some(body) => markdown::render(body, &Theme::current()),
+            None => String::new(),
+        };
+        let prompt = question.constraints.with_hint(&question.prompt);
+        let theme = Theme::current().for_writer(&self.writer);
+        let default = question.default.as_deref();
+        render_masked_prompt_into(&mut text, &prompt, default, question.mask, &theme);
+        self.writer.write_str(&text)?;
+        self.writer.flush()?;
+
+        let mut buf = String::new();
+        let read = match question.is_secret() {
+            true => self.reader.read_hidden(&mut buf)?,
+            false => self.reader.read_line(&mut buf)?,
+        };
+        if read == 0 {
+            return Err(InputError::Eof);
+        }
+        Ok(process_input(&buf))
+    }
+
+    fn report_error(&mut self, _question: &Question, message: &str) -> Result<(), InputError> {
+        write_error(&mut self.writer, message)
+    }
+}
+
+/// # JsonFrontend
+///
+/// asks questions over a line based JSON protocol instead of human text,
+/// so GUIs and editor extensions can drive wizards over a pipe.
+///
+/// every question is written as one line
+///
+/// `{"type":"question","id":"port","kind":"text","prompt":"port","default":"5432"}`
+///
+/// with a `"hint"` like `"[1-64]"` when the question has constraints
+///
+/// and answered by one line, either a JSON string or an object like
+///
+/// `{"id":"port","value":"6543"}`
+///
+/// an empty or `null` value selects the default. answers that can't be
+/// used are reported with `{"type":"error","id":"port","message":"..."}`
+/// and read again
+///
+/// # usage
+/// ```
+/// use input_py::frontend::{self, JsonFrontend, Question};
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let reader = MockReader::new(&[r#"{"id":"port","value":"6543"}"#]);
+/// let mut frontend = JsonFrontend::new(reader, MockWriter::new());
+/// let port = frontend::ask(&mut frontend, &Question::new("port", "port").default("5432")).unwrap();
+/// assert_eq!(port.value, "6543");
+/// ```
+pub struct JsonFrontend<R: InputReader, W: OutputWriter> {
+    reader: R,
+    writer: W,
+}
+
+impl<R: InputReader, W: OutputWriter> JsonFrontend<R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        JsonFrontend { reader, writer }
+    }
+
+    pub fn into_inner(self) -> (R, W) {
+        (self.reader, self.writer)
+    }
+
+    fn write_error(&mut self, id: &str, msg: &str) -> Result<(), InputError> {
+        let line = format!(
+            "{{\"type\":\"error\",\"id\":{},\"message\":{}}}\n",
+            json::quote(id),
+            json::quote(msg)
+        );
+        self.writer.write_str(&line)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl<R: InputReader, W: OutputWriter> Frontend for JsonFrontend<R, W> {
+    fn ask(&mut self, question: &Question) -> Result<String, InputError> {
+        let default = match &question.default {
+            Some(default) => json::quote(default),
+            None => "null".to_string(),
+        };
+        let hint = match question.constraints.hint() {
+            Some(hint) => format!(",\"hint\":{}", json::quote(&hint)),
+            None => String::new(),
+        };
+        let line = format!(
+            "{{\"type\":\"question\",\"id\":{},\"kind\":{},\"prompt\":{},\"default\":{}{}}}\n",
+            json::quote(&question.id),
+            json::quote(question.kind.name()),
+            json::quote(&question.prompt),
+            default,
+            hint
+        );
+        self.writer.write_str(&line)?;
+        self.writer.flush()?;
+
+        let mut retries = Retries::new();
+        loop {
+            let mut buf = String::new();
+            if self.reader.read_line(&mut buf)? == 0 {
+                return Err(InputError::Eof);
+            }
+            match parse_answer(buf.trim(), question) {
+                Ok(value) => return Ok(value.unwrap_or_default()),
+                Err(msg) => {
+                    self.write_error(&question.id, &msg)?;
+                    retries.count()?;
+                }
+            }
+        }
+    }
+
+    fn report_error(&mut self, question: &Question, message: &str) -> Result<(), InputError> {
+        self.write_error(&question.id, message)
+    }
+}
+
+/// the answer value of one protocol line, `None` for `null`
+fn parse_answer(line: &str, question: &Question) -> Result<Option<String>, String> {
+    let value = json::parse(line).map_err(|e| format!("invalid JSON: {}", e))?;
+    let value = match &value {
+        JsonValue::Object(_) => {
+            if let Some(id) = value.get("id") {
+                if id.as_text().as_deref() != Some(question.id.as_str()) {
+                    return Err(format!("expected an answer for '{}'", question.id));
+                }
+            }
+            value.get("value").unwrap_or(&JsonValue::Null)
+        }
+        other => other,
+    };
+    match value {
+        JsonValue::Null => Ok(None),
+        other => other
+            .as_text()
+            .map(Some)
+            .ok_or_else(|| "value must be a string, number or bool".to_string()),
+    }
+}
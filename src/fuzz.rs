@@ -0,0 +1,181 @@
+//! # fuzz
+//!
+//! panic free entry points into the input processing, for fuzzers and
+//! property tests. with the `fuzz` feature `InputGen` produces arbitrary
+//! raw lines biased towards the inputs that tend to break trimming and
+//! parsing: CRLF endings, unicode whitespace, invalid UTF-8 and BOMs.
+//! with the `proptest` feature `raw_input` and `number_input` are the
+//! same inputs as proptest strategies, which shrink failing cases
+//!
+//! # invariants
+//!
+//! for every `raw` input, `process_input_checked(raw)`
+//!
+//! * never panics
+//! * has no leading or trailing whitespace, so no `\r` or `\n` at the ends
+//! * is unchanged when processed again
+//! * is a substring of the UTF-8 lossy decoding of `raw`
+//!
+//! `check_invariants` verifies all of them for one input
+
+use std::str::FromStr;
+
+use crate::process_input;
+
+/// `process_input` for arbitrary bytes, invalid UTF-8 is replaced with U+FFFD
+pub fn process_input_checked(raw: &[u8]) -> String {
+    process_input(&String::from_utf8_lossy(raw))
+}
+
+/// processes `raw` like a prompt answer and parses it as `T`
+pub fn parse_checked<T: FromStr>(raw: &[u8]) -> Option<T> {
+    process_input_checked(raw).parse().ok()
+}
+
+/// checks the documented invariants for `raw`, the error names the broken one
+pub fn check_invariants(raw: &[u8]) -> Result<(), String> {
+    let decoded = String::from_utf8_lossy(raw);
+    let processed = process_input_checked(raw);
+    if processed.trim() != processed {
+        return Err(format!("surrounding whitespace left in {:?}", processed));
+    }
+    if process_input(&processed) != processed {
+        return Err(format!("processing {:?} again changed it", processed));
+    }
+    if !decoded.contains(processed.as_str()) {
+        return Err(format!(
+            "{:?} is not part of the input {:?}",
+            processed, decoded
+        ));
+    }
+    Ok(())
+}
+
+/// # InputGen
+///
+/// endless deterministic generator of raw input lines
+///
+/// # usage
+/// ```
+/// use input_py::fuzz::{check_invariants, InputGen};
+///
+/// for raw in InputGen::new(42).take(1000) {
+///     check_invariants(&raw).unwrap();
+/// }
+/// ```
+#[cfg(feature = "fuzz")]
+pub struct InputGen {
+    state: u64,
+}
+
+#[cfg(any(feature = "fuzz", feature = "proptest"))]
+const FRAGMENTS: &[&[u8]] = &[
+    b"\r\n",
+    b"\n",
+    b"\r",
+    b" ",
+    b"\t",
+    b"42",
+    b"-7",
+    b"3.14",
+    b"yes",
+    b"abc",
+    b",",
+    "\u{feff}".as_bytes(),
+    "\u{a0}".as_bytes(),
+    "\u{3000}".as_bytes(),
+    "日本語".as_bytes(),
+    "é".as_bytes(),
+    b"\xff",
+    b"\xe6\x97",
+    b"\0",
+    b"\x1b[A",
+];
+
+#[cfg(feature = "fuzz")]
+impl InputGen {
+    pub fn new(seed: u64) -> Self {
+        InputGen {
+            state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        // xorshift64*
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+#[cfg(feature = "fuzz")]
+impl Iterator for InputGen {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Vec<u8>> {
+        let parts = self.next_u64() % 8;
+        let mut raw = Vec::new();
+        for _ in 0..parts {
+            let pick = self.next_u64();
+            if pick.is_multiple_of(4) {
+                raw.push((pick >> 8) as u8);
+            } else {
+                raw.extend_from_slice(FRAGMENTS[(pick >> 8) as usize % FRAGMENTS.len()]);
+            }
+        }
+        Some(raw)
+    }
+}
+
+/// raw input lines like `InputGen` makes, as a proptest strategy
+///
+/// # usage
+/// ```
+/// use input_py::fuzz::{check_invariants, raw_input};
+/// use proptest::test_runner::TestRunner;
+///
+/// TestRunner::default()
+///     .run(&raw_input(), |raw| {
+///         check_invariants(&raw).unwrap();
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+#[cfg(feature = "proptest")]
+pub fn raw_input() -> impl proptest::strategy::Strategy<Value = Vec<u8>> {
+    use proptest::prelude::*;
+
+    let part = prop_oneof![
+        1 => any::<u8>().prop_map(|byte| vec![byte]),
+        3 => proptest::sample::select(FRAGMENTS).prop_map(<[u8]>::to_vec),
+    ];
+    proptest::collection::vec(part, 0..8).prop_map(|parts| parts.concat())
+}
+
+/// a number with the whitespace and line endings a prompt has to strip
+/// around it, and the number it has to parse to
+///
+/// # usage
+/// ```
+/// use input_py::fuzz::{number_input, parse_checked};
+/// use proptest::test_runner::TestRunner;
+///
+/// TestRunner::default()
+///     .run(&number_input(), |(number, raw)| {
+///         assert_eq!(parse_checked::<i64>(&raw), Some(number));
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+#[cfg(feature = "proptest")]
+pub fn number_input() -> impl proptest::strategy::Strategy<Value = (i64, Vec<u8>)> {
+    use proptest::prelude::*;
+
+    let padding = proptest::sample::select(&["", " ", "\t", "\u{a0}", "\u{3000}"][..]);
+    let end = proptest::sample::select(&["", "\n", "\r\n", "\r"][..]);
+    (any::<i64>(), padding.clone(), padding, end).prop_map(|(number, before, after, end)| {
+        let raw = format!("{}{}{}{}", before, number, after, end);
+        (number, raw.into_bytes())
+    })
+}
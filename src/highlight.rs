@@ -0,0 +1,122 @@
+//! # highlight
+//!
+//! coloring the line being edited. a `Highlighter` splits the line into
+//! styled spans each time the editor draws it, so a REPL can color
+//! commands, strings and mistakes while they are typed
+
+use std::fmt;
+use std::sync::Arc;
+
+/// written around a span, like `Theme::warning`. `("", "")` is plain
+pub type Style = (&'static str, &'static str);
+
+/// # StyledLine
+///
+/// a line cut into spans with a style each
+///
+/// # usage
+/// ```
+/// use input_py::highlight::StyledLine;
+///
+/// let mut line = StyledLine::new();
+/// line.push("echo", ("\x1b[32m", "\x1b[39m"));
+/// line.plain(" hi");
+/// assert_eq!(line.text(), "echo hi");
+/// assert_eq!(line.render(), "\x1b[32mecho\x1b[39m hi");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StyledLine {
+    spans: Vec<(String, Style)>,
+}
+
+impl StyledLine {
+    pub fn new() -> Self {
+        StyledLine::default()
+    }
+
+    /// append `text` written in `style`
+    pub fn push(&mut self, text: &str, style: Style) {
+        if !text.is_empty() {
+            self.spans.push((text.to_string(), style));
+        }
+    }
+
+    /// append `text` without a style
+    pub fn plain(&mut self, text: &str) {
+        self.push(text, ("", ""));
+    }
+
+    pub fn spans(&self) -> &[(String, Style)] {
+        &self.spans
+    }
+
+    /// the text without the styles
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|(text, _)| text.as_str()).collect()
+    }
+
+    /// the text with each span between its style, ready to be written
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (text, (open, close)) in &self.spans {
+            out.push_str(open);
+            out.push_str(text);
+            out.push_str(close);
+        }
+        out
+    }
+}
+
+impl From<&str> for StyledLine {
+    fn from(text: &str) -> Self {
+        let mut line = StyledLine::new();
+        line.plain(text);
+        line
+    }
+}
+
+/// # Highlighter
+///
+/// styles the line being edited, called on every redraw. the spans must
+/// hold the line unchanged, or it is drawn plain so the cursor stays in
+/// place. closures taking the line work as highlighters
+///
+/// # usage
+/// ```
+/// use input_py::editor::{Editor, Key};
+/// use input_py::highlight::StyledLine;
+///
+/// let mut editor = Editor::new().highlighter(|line: &str| {
+///     let mut styled = StyledLine::new();
+///     let (command, rest) = line.split_at(line.find(' ').unwrap_or(line.len()));
+///     styled.push(command, ("\x1b[1m", "\x1b[22m"));
+///     styled.plain(rest);
+///     styled
+/// });
+/// for c in "ls -l".chars() {
+///     editor.handle_key(Key::Char(c).into());
+/// }
+/// assert_eq!(editor.styled_line().render(), "\x1b[1mls\x1b[22m -l");
+/// ```
+pub trait Highlighter {
+    fn highlight(&self, line: &str) -> StyledLine;
+}
+
+impl<F> Highlighter for F
+where
+    F: Fn(&str) -> StyledLine,
+{
+    fn highlight(&self, line: &str) -> StyledLine {
+        self(line)
+    }
+}
+
+/// a highlighter shared by clones of an `Editor`
+#[derive(Clone)]
+pub(crate) struct SharedHighlighter(pub(crate) Arc<dyn Highlighter + Send + Sync>);
+
+impl fmt::Debug for SharedHighlighter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Highlighter")
+    }
+}
@@ -0,0 +1,210 @@
+//! # history
+//!
+//! answers kept between prompts and runs. an `Editor` given a `History`
+//! browses it with Up and Down and searches it with Ctrl+R, and
+//! `Input::history` adds each answer to it
+
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::error::InputError;
+use crate::storage::{Slot, Storage};
+
+/// entries kept unless `History::limit` says otherwise
+const DEFAULT_LIMIT: usize = 1000;
+
+/// # History
+///
+/// earlier answers, oldest first. clones share the entries like a
+/// `KillRing`, so the history given to an editor or an `Input` is the one
+/// saved afterwards. an answer equal to the newest entry or empty isn't
+/// added again
+///
+/// # usage
+/// ```
+/// use input_py::history::History;
+/// use input_py::io::{MockReader, MockWriter};
+/// use input_py::Input;
+///
+/// let path = std::env::temp_dir().join("input_py_doc_history");
+/// let mut history = History::load(&path).unwrap();
+/// history.clear();
+/// Input::new("host")
+///     .history(&mut history)
+///     .read_with_io(&mut MockReader::new(&["db1"]), &mut MockWriter::new())
+///     .unwrap();
+/// history.save().unwrap();
+/// assert_eq!(History::load(&path).unwrap().entries(), ["db1"]);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct History(Arc<Mutex<Entries>>);
+
+#[derive(Debug, Default)]
+struct Entries {
+    lines: Vec<String>,
+    /// at most this many lines are kept, none is the default
+    limit: Option<usize>,
+    /// where `load` read from and `save` writes to
+    slot: Option<Slot>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        History::default()
+    }
+
+    /// the history saved at `path`, empty when there is no file yet.
+    /// `save` writes back to it
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        History::read(Slot::File(path.as_ref().to_path_buf()))
+    }
+
+    /// the history kept under `key` of `storage`, empty when there is
+    /// nothing yet. `save` writes back to it
+    ///
+    /// # usage
+    /// ```
+    /// use std::sync::Arc;
+    /// use input_py::history::History;
+    /// use input_py::storage::{MemoryStorage, Storage};
+    ///
+    /// let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+    /// let history = History::load_from(storage.clone(), "history.host").unwrap();
+    /// history.push("db1");
+    /// history.save().unwrap();
+    /// assert_eq!(storage.get("history.host").unwrap().as_deref(), Some("db1\n"));
+    /// ```
+    pub fn load_from(storage: Arc<dyn Storage>, key: &str) -> Result<Self, InputError> {
+        History::read(Slot::Key(storage, key.to_string()))
+    }
+
+    fn read(slot: Slot) -> Result<Self, InputError> {
+        let history = History::new();
+        {
+            let mut entries = history.lock();
+            if let Some(text) = slot.read()? {
+                entries.lines = text.lines().map(unescape).collect();
+            }
+            entries.slot = Some(slot);
+            entries.trim();
+        }
+        Ok(history)
+    }
+
+    /// write the entries to where `load` or `load_from` read them,
+    /// replacing them whole
+    pub fn save(&self) -> Result<(), InputError> {
+        let slot = self.lock().slot.clone();
+        match slot {
+            Some(slot) => slot.write(&self.text()),
+            None => Err(InputError::Io(
+                "the history wasn't loaded from a file, use save_to".to_string(),
+            )),
+        }
+    }
+
+    /// write the entries to `path`, one per line. line breaks in an
+    /// entry are written as `\n`
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), InputError> {
+        Slot::File(path.as_ref().to_path_buf()).write(&self.text())
+    }
+
+    /// the entries as saved, one per line
+    fn text(&self) -> String {
+        let mut text = String::new();
+        for line in &self.lock().lines {
+            text.push_str(&escape(line));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// keep at most the `limit` newest entries. default: 1000
+    pub fn limit(self, limit: usize) -> Self {
+        {
+            let mut entries = self.lock();
+            entries.limit = Some(limit);
+            entries.trim();
+        }
+        self
+    }
+
+    /// add `line` as the newest entry
+    pub fn push(&self, line: &str) {
+        let mut entries = self.lock();
+        if line.is_empty() || entries.lines.last().map(String::as_str) == Some(line) {
+            return;
+        }
+        entries.lines.push(line.to_string());
+        entries.trim();
+    }
+
+    /// the entries, oldest first
+    pub fn entries(&self) -> Vec<String> {
+        self.lock().lines.clone()
+    }
+
+    /// the entry `index` places from the oldest
+    pub fn get(&self, index: usize) -> Option<String> {
+        self.lock().lines.get(index).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().lines.is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.lock().lines.clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for History {
+    fn from_iter<I: IntoIterator<Item = S>>(entries: I) -> Self {
+        let history = History::new();
+        for entry in entries {
+            history.push(&entry.into());
+        }
+        history
+    }
+}
+
+impl Entries {
+    fn trim(&mut self) {
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+        if self.lines.len() > limit {
+            self.lines.drain(..self.lines.len() - limit);
+        }
+    }
+}
+
+fn escape(line: &str) -> String {
+    line.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                out.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                out.push('\\');
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,1210 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::answers::{self, Recorder};
+use crate::completion::{Completer, SharedCompleter};
+use crate::config::{Style, Theme};
+use crate::confirm::Confirm;
+use crate::error::InputError;
+use crate::form::{Answers, UNDO};
+use crate::frontend::{self, Frontend};
+use crate::history::History;
+use crate::io::{HiddenReader, InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::list::ListInput;
+use crate::locale;
+use crate::markdown;
+use crate::model::{Answer, Constraints, MaskStyle, Provenance, Question, QuestionKind};
+use crate::prompter::{expected, Prompt};
+use crate::range::RangeInput;
+use crate::storage::{Slot, Storage};
+use crate::template;
+use crate::{
+    ask_line, preset_answer, process_input_in_place, read_answer_until, render_masked_prompt_into,
+    write_caret, Retries,
+};
+
+/// # Input
+///
+/// builder for a single prompt
+///
+/// # usage
+/// ```
+/// use input_py::Input;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut reader = MockReader::new(&[""]);
+/// let mut writer = MockWriter::new();
+/// let host = Input::new("host")
+///     .default("localhost")
+///     .read_with_io(&mut reader, &mut writer);
+/// assert_eq!(host, Ok("localhost".to_string()));
+/// ```
+pub struct Input {
+    pub(crate) prompt: String,
+    pub(crate) default: Option<String>,
+    pub(crate) default_from: Option<DefaultFrom>,
+    pub(crate) when: Option<Condition>,
+    pub(crate) id: Option<String>,
+    pub(crate) mask: Option<MaskStyle>,
+    pub(crate) hidden: bool,
+    pub(crate) body: Option<String>,
+    pub(crate) theme: Theme,
+    pub(crate) vars: Option<Vec<(String, String)>>,
+    pub(crate) flush_pending: bool,
+    pub(crate) constraints: Constraints,
+    pub(crate) pattern: Option<Pattern>,
+    pub(crate) length_policy: LengthPolicy,
+    pub(crate) validator: Option<Validator>,
+    pub(crate) max_attempts: Option<u32>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) non_interactive: Option<NonInteractive>,
+    pub(crate) history: Option<History>,
+    pub(crate) remembered: Option<Slot>,
+    pub(crate) recorder: Option<Recorder>,
+    pub(crate) completer: Option<SharedCompleter>,
+}
+
+type DefaultFrom = Box<dyn Fn(&Answers) -> Option<String>>;
+
+type Condition = Box<dyn Fn(&Answers) -> bool>;
+
+/// whether an answer matches, or why it couldn't be told
+type Pattern = Box<dyn Fn(&str) -> Result<bool, String>>;
+
+type Validator = Box<dyn Fn(&str, &Answers) -> Result<(), String>>;
+
+/// what to do with an answer longer than `Input::max_len`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthPolicy {
+    /// report it and ask again
+    Reject,
+    /// keep the first `max_len` characters
+    Truncate,
+}
+
+/// what a prompt does when its reader is not a terminal, like stdin
+/// piped from a file or `/dev/null`. see `Input::non_interactive_behavior`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonInteractive {
+    /// return the default without writing the prompt, fail with
+    /// `InputError::NotInteractive` without one
+    UseDefault,
+    /// fail with `InputError::NotInteractive`, default or not
+    Error,
+    /// read the answer from the pipe without writing the prompt
+    ReadSilently,
+}
+
+impl Input {
+    pub fn new(prompt: &str) -> Self {
+        Input {
+            prompt: prompt.to_string(),
+            default: None,
+            default_from: None,
+            when: None,
+            id: None,
+            mask: None,
+            hidden: false,
+            body: None,
+            theme: Theme::current(),
+            vars: None,
+            flush_pending: false,
+            constraints: Constraints::default(),
+            pattern: None,
+            length_policy: LengthPolicy::Reject,
+            validator: None,
+            max_attempts: None,
+            timeout: None,
+            non_interactive: None,
+            history: None,
+            remembered: None,
+            recorder: None,
+            completer: None,
+        }
+    }
+
+    /// default derived from the earlier answers of a `Form`, evaluated
+    /// when the question is reached. when it returns `None` the `default`
+    /// value applies. outside a form it sees no answers
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::{Form, Input};
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut reader = MockReader::new(&["db.local", ""]);
+    /// let answers = Form::new()
+    ///     .field("host", Input::new("host"))
+    ///     .field("url", Input::new("url").default_from(|answers| {
+    ///         answers.get("host").map(|host| format!("{}:5432", host))
+    ///     }))
+    ///     .run_with_io(&mut reader, &mut MockWriter::new())
+    ///     .unwrap();
+    /// assert_eq!(answers.get("url"), Some("db.local:5432"));
+    /// ```
+    pub fn default_from<F>(mut self, default: F) -> Self
+    where
+        F: Fn(&Answers) -> Option<String> + 'static,
+    {
+        self.default_from = Some(Box::new(default));
+        self
+    }
+
+    /// ask the question in a `Form` only when `condition` holds for the
+    /// earlier answers. a skipped field has no answer. outside a form it
+    /// is always asked
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::{Form, Input};
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut reader = MockReader::new(&["n", "db"]);
+    /// let answers = Form::new()
+    ///     .field("tls", Input::new("tls? [y/N]"))
+    ///     .field("cert", Input::new("cert").when(|answers| answers.get("tls") == Some("y")))
+    ///     .field("host", Input::new("host"))
+    ///     .run_with_io(&mut reader, &mut MockWriter::new())
+    ///     .unwrap();
+    /// assert_eq!(answers.get("cert"), None);
+    /// assert_eq!(answers.get("host"), Some("db"));
+    /// ```
+    pub fn when<F>(mut self, condition: F) -> Self
+    where
+        F: Fn(&Answers) -> bool + 'static,
+    {
+        self.when = Some(Box::new(condition));
+        self
+    }
+
+    /// whether a `Form` asks this question after `answers`
+    pub(crate) fn applies(&self, answers: &Answers) -> bool {
+        self.when.as_ref().is_none_or(|when| when(answers))
+    }
+
+    /// explanation shown before the question line, written in minimal
+    /// Markdown and styled by the theme, see `markdown::render`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut writer = MockWriter::new();
+    /// Input::new("region")
+    ///     .body("Where the **data** lives:\n- `eu`\n- `us`")
+    ///     .read_with_io(&mut MockReader::new(&["eu"]), &mut writer)
+    ///     .unwrap();
+    /// assert_eq!(writer.output, "Where the data lives:\n• eu\n• us\nregion:");
+    /// ```
+    pub fn body(mut self, markdown: &str) -> Self {
+        self.body = Some(markdown.to_string());
+        self
+    }
+
+    /// the text around the prompt and errors, their colors and the
+    /// styles of the body. default: the `config::theme`
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// colors of the prompt, the `[default]` hint and the errors, on a
+    /// terminal without `NO_COLOR`. sets the `colors` of the theme
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::config::{Color, Style};
+    /// use input_py::io::{MockReader, MockWriter};
+    /// use input_py::Input;
+    ///
+    /// let style = Style {
+    ///     default_color: Some(Color::Green),
+    ///     error_color: Some(Color::Red),
+    ///     ..Style::PLAIN
+    /// };
+    /// let mut writer = MockWriter::new();
+    /// Input::new("port")
+    ///     .default("80")
+    ///     .style(style)
+    ///     .parse_with_io::<u16>(&mut MockReader::new(&[""]), &mut writer)
+    ///     .unwrap();
+    /// // not a terminal
+    /// assert_eq!(writer.output, "port [80]:");
+    /// ```
+    pub fn style(mut self, style: Style) -> Self {
+        self.theme.colors = style;
+        self
+    }
+
+    /// values for the `{key}` placeholders of the prompt and body,
+    /// filled in when asked. a missing value fails with
+    /// `InputError::Template`, see `template::render`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut writer = MockWriter::new();
+    /// Input::new("Deploy {app} to {env}?")
+    ///     .vars(&[("app", "web"), ("env", "prod")])
+    ///     .read_with_io(&mut MockReader::new(&["yes"]), &mut writer)
+    ///     .unwrap();
+    /// assert_eq!(writer.output, "Deploy web to prod?:");
+    /// ```
+    pub fn vars(mut self, values: &[(&str, &str)]) -> Self {
+        let values = values.iter().map(|(k, v)| (k.to_string(), v.to_string()));
+        self.vars = Some(values.collect());
+        self
+    }
+
+    /// throw away input typed before the prompt appeared, so a stray
+    /// newline can't answer a destructive confirmation. only a terminal
+    /// is drained, piped answers are kept. default: false
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut reader = MockReader::new(&["yes"]).typed_ahead(&[""]);
+    /// let answer = Input::new("drop the database? [y/N]")
+    ///     .flush_pending(true)
+    ///     .read_with_io(&mut reader, &mut MockWriter::new());
+    /// assert_eq!(answer, Ok("yes".to_string()));
+    /// ```
+    pub fn flush_pending(mut self, flush: bool) -> Self {
+        self.flush_pending = flush;
+        self
+    }
+
+    /// show only part of the answer in confirmations, summaries,
+    /// transcripts, errors and the default hint, like the last 4
+    /// characters of a token. the question is a `QuestionKind::Secret`, which frontends
+    /// read hidden
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::model::MaskStyle;
+    ///
+    /// let token = Input::new("api token").mask_display(MaskStyle::Last(4));
+    /// let question = token.question("token");
+    /// assert!(question.is_secret());
+    /// assert_eq!(question.display("tok_abcdef"), "******cdef");
+    /// ```
+    pub fn mask_display(mut self, mask: MaskStyle) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// read the answer with the terminal echo off, like a password. see
+    /// `InputReader::read_hidden`. the question is a
+    /// `QuestionKind::Secret`. default: false
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::io::{MockReader, MockWriter, TeeReader};
+    /// use input_py::Input;
+    ///
+    /// let mut reader = TeeReader::new(MockReader::new(&["tok_abcdef"]), MockWriter::new());
+    /// let token = Input::new("token").hidden(true).read_with_io(&mut reader, &mut MockWriter::new());
+    /// assert_eq!(token, Ok("tok_abcdef".to_string()));
+    /// assert_eq!(reader.into_inner().1.output, "(hidden)\n");
+    /// ```
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// stable id like `db.port`, the key of the answer in answers files,
+    /// recordings, `INPUT_PY_ANSWER_DB_PORT` overrides and transcripts.
+    /// default: the prompt text. a `Form` uses its field ids instead
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// machine readable key like `db_port`, separate from the prompt
+    /// text, which is shown as before. the same as `id`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::answers::Recorder;
+    /// use input_py::io::{MockReader, MockWriter};
+    /// use input_py::Input;
+    ///
+    /// let path = std::env::temp_dir().join("input_py_doc_key.toml");
+    /// let recorder = Recorder::new(&path);
+    /// let mut writer = MockWriter::new();
+    /// Input::new("Database port")
+    ///     .key("db_port")
+    ///     .recorder(recorder.clone())
+    ///     .read_with_io(&mut MockReader::new(&["6543"]), &mut writer)
+    ///     .unwrap();
+    /// assert_eq!(writer.output, "Database port:");
+    /// assert_eq!(recorder.entries(), [("db_port".to_string(), "6543".to_string())]);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn key(self, key: &str) -> Self {
+        self.id(key)
+    }
+
+    /// write the answer to `recorder` under the key, see `Recorder`
+    pub fn recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// the only accepted answers, listed after the prompt like
+    /// `color [red/green/blue]:`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut writer = MockWriter::new();
+    /// let color = Input::new("color")
+    ///     .choices(&["red", "green"])
+    ///     .read_with_io(&mut MockReader::new(&["blue", "red"]), &mut writer);
+    /// assert_eq!(color, Ok("red".to_string()));
+    /// assert_eq!(
+    ///     writer.output,
+    ///     "color [red/green]:error: 'blue' is not one of red, green\ncolor [red/green]:"
+    /// );
+    /// ```
+    pub fn choices<S: AsRef<str>>(mut self, choices: &[S]) -> Self {
+        let choices = choices.iter().map(|choice| choice.as_ref().to_string());
+        self.constraints.choices = choices.collect();
+        self
+    }
+
+    /// longest accepted answer in characters, shown as `[max 20 chars]`.
+    /// with the `line-editing` feature a terminal refuses keys past it and
+    /// shows a counter like `12/20` while typing. a longer line read
+    /// otherwise has the extra characters underlined and a counter like
+    /// `86/80` in the theme's warning style
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.constraints.max_len = Some(max_len);
+        self
+    }
+
+    /// what happens to answers over `max_len`. default: `LengthPolicy::Reject`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::input::LengthPolicy;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut writer = MockWriter::new();
+    /// let code = Input::new("code")
+    ///     .max_len(3)
+    ///     .length_policy(LengthPolicy::Truncate)
+    ///     .read_with_io(&mut MockReader::new(&["abcde"]).interactive(true), &mut writer);
+    /// assert_eq!(code, Ok("abc".to_string()));
+    /// assert_eq!(writer.output, "code [max 3 chars]:  abcde\n     ^^ 5/3, cut\n");
+    /// ```
+    pub fn length_policy(mut self, policy: LengthPolicy) -> Self {
+        self.length_policy = policy;
+        self
+    }
+
+    /// accept only answers passing `check`, which `description` explains
+    /// in the hint and in the error, like `lowercase letters`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut writer = MockWriter::new();
+    /// let tag = Input::new("tag")
+    ///     .pattern("lowercase letters", |tag| tag.chars().all(|c| c.is_ascii_lowercase()))
+    ///     .max_len(8)
+    ///     .read_with_io(&mut MockReader::new(&["v1", "beta"]), &mut writer);
+    /// assert_eq!(tag, Ok("beta".to_string()));
+    /// assert!(writer.output.starts_with("tag [lowercase letters, max 8 chars]:"));
+    /// assert!(writer.output.contains("error: 'v1' is not lowercase letters\n"));
+    /// ```
+    pub fn pattern<F>(self, description: &str, check: F) -> Self
+    where
+        F: Fn(&str) -> bool + 'static,
+    {
+        self.try_pattern(description, move |answer| Ok(check(answer)))
+    }
+
+    /// `pattern` with a `check` that can fail, rejecting the answer with
+    /// its message
+    pub(crate) fn try_pattern<F>(mut self, description: &str, check: F) -> Self
+    where
+        F: Fn(&str) -> Result<bool, String> + 'static,
+    {
+        self.constraints.pattern = Some(description.to_string());
+        self.pattern = Some(Box::new(check));
+        self
+    }
+
+    /// check applied to the answer after the constraints. a rejected
+    /// answer is reported with the message and asked again
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut writer = MockWriter::new();
+    /// let user = Input::new("user")
+    ///     .validate(|user| match user == "root" {
+    ///         true => Err("root is not allowed".to_string()),
+    ///         false => Ok(()),
+    ///     })
+    ///     .read_with_io(&mut MockReader::new(&["root", "alice"]), &mut writer);
+    /// assert_eq!(user, Ok("alice".to_string()));
+    /// assert_eq!(writer.output, "user:error: root is not allowed\nuser:");
+    /// ```
+    pub fn validate<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str) -> Result<(), String> + 'static,
+    {
+        self.validator = Some(Box::new(move |answer, _| validator(answer)));
+        self
+    }
+
+    /// `validate` with the earlier answers of a `Form`, replacing it.
+    /// outside a form it sees no answers
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::{Form, Input};
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut reader = MockReader::new(&["5", "2", "8"]);
+    /// let answers = Form::new()
+    ///     .field("min", Input::new("min"))
+    ///     .field("max", Input::new("max").validate_from(|max, answers| {
+    ///         let min: u32 = answers.parse("min")?;
+    ///         match max.parse::<u32>() {
+    ///             Ok(max) if max > min => Ok(()),
+    ///             _ => Err(format!("'{}' is not above {}", max, min)),
+    ///         }
+    ///     }))
+    ///     .run_with_io(&mut reader, &mut MockWriter::new())
+    ///     .unwrap();
+    /// assert_eq!(answers.get("max"), Some("8"));
+    /// ```
+    pub fn validate_from<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str, &Answers) -> Result<(), String> + 'static,
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// what to do instead of writing the prompt into nowhere when the
+    /// reader is not interactive, see `is_interactive`. answers from the
+    /// environment still win. default: the `config::strict_interactive`
+    /// setting
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::input::NonInteractive;
+    /// use input_py::io::{MockReader, MockWriter};
+    /// use input_py::Input;
+    ///
+    /// let mut writer = MockWriter::new();
+    /// let port = Input::new("port")
+    ///     .default("5432")
+    ///     .non_interactive_behavior(NonInteractive::UseDefault)
+    ///     .read_with_io(&mut MockReader::new(&["6543"]), &mut writer);
+    /// assert_eq!(port, Ok("5432".to_string()));
+    /// let host = Input::new("host")
+    ///     .non_interactive_behavior(NonInteractive::ReadSilently)
+    ///     .read_with_io(&mut MockReader::new(&["db1"]), &mut writer);
+    /// assert_eq!(host, Ok("db1".to_string()));
+    /// assert_eq!(writer.output, "");
+    /// ```
+    pub fn non_interactive_behavior(mut self, behavior: NonInteractive) -> Self {
+        self.non_interactive = Some(behavior);
+        self
+    }
+
+    /// stop waiting after `timeout`: the default is returned, or
+    /// `InputError::TimedOut` without one. the time covers answers asked
+    /// again. applies to `read` and `parse` on terminals and readers that
+    /// can wait, see `InputReader::wait_readable`. once a windows user
+    /// starts typing the line is waited for
+    ///
+    /// # usage
+    /// ```
+    /// use std::time::Duration;
+    /// use input_py::{Input, InputError};
+    /// use input_py::io::{BlockingBridgeReader, MockWriter};
+    ///
+    /// let (mut reader, _sender) = BlockingBridgeReader::channel();
+    /// let mut writer = MockWriter::new();
+    /// let answer = Input::new("continue")
+    ///     .timeout(Duration::from_millis(20))
+    ///     .read_with_io(&mut reader, &mut writer);
+    /// assert_eq!(answer, Err(InputError::TimedOut));
+    /// assert_eq!(writer.output, "continue:\n");
+    /// ```
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// add the answer to `history`. with the `line-editing` feature Up
+    /// and Down browse it while typing on stdin, see `History`
+    pub fn history(mut self, history: &mut History) -> Self {
+        self.history = Some(history.clone());
+        self
+    }
+
+    /// keep the answer under `key` of `storage` and offer it as the
+    /// default the next time, ahead of `default`. a storage that can't
+    /// be read offers nothing
+    ///
+    /// # usage
+    /// ```
+    /// use std::sync::Arc;
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    /// use input_py::storage::{MemoryStorage, Storage};
+    ///
+    /// let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+    /// let host = || Input::new("host").default("localhost").remember(storage.clone(), "host");
+    /// host().read_with_io(&mut MockReader::new(&["db1"]), &mut MockWriter::new()).unwrap();
+    ///
+    /// let mut writer = MockWriter::new();
+    /// let answer = host().read_with_io(&mut MockReader::new(&[""]), &mut writer);
+    /// assert_eq!(answer, Ok("db1".to_string()));
+    /// assert_eq!(writer.output, "host [db1]:");
+    /// ```
+    pub fn remember(mut self, storage: Arc<dyn Storage>, key: &str) -> Self {
+        self.remembered = Some(Slot::Key(storage, key.to_string()));
+        self
+    }
+
+    /// complete the answer on Tab, with the `line-editing` feature on
+    /// stdin. see `Completer`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    ///
+    /// let branches = vec!["main".to_string(), "release".to_string()];
+    /// let input = Input::new("branch").completer(branches);
+    /// ```
+    pub fn completer<C>(mut self, completer: C) -> Self
+    where
+        C: Completer + Send + Sync + 'static,
+    {
+        self.completer = Some(SharedCompleter(Arc::new(completer)));
+        self
+    }
+
+    /// give up with `InputError::AttemptsExceeded` after `attempts`
+    /// rejected answers, so a script piping bad input fails fast.
+    /// applies to checked prompts, `parse`, `list` and `range`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::{Input, InputError};
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut reader = MockReader::new(&["a", "b", "c"]);
+    /// let n = Input::new("n")
+    ///     .max_attempts(2)
+    ///     .parse_with_io::<u8>(&mut reader, &mut MockWriter::new());
+    /// assert_eq!(n, Err(InputError::AttemptsExceeded { attempts: 2 }));
+    /// ```
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = Some(attempts.max(1));
+        self
+    }
+
+    /// value returned when the answer is empty
+    pub fn default(mut self, value: &str) -> Self {
+        self.default = Some(value.to_string());
+        self
+    }
+
+    /// read the answer from stdin
+    pub fn read(self) -> Result<String, InputError> {
+        self.read_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    pub fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<String, InputError> {
+        let id = self.id.as_deref().unwrap_or(&self.prompt);
+        let answer = self.ask_as(id, &Answers::new(), false, reader, writer)?;
+        Ok(answer.expect("undo is off").value)
+    }
+
+    /// read every line until EOF from stdin
+    pub fn read_all_lines(self) -> Result<Vec<String>, InputError> {
+        self.read_all_lines_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    /// the prompt is written once, then each line up to EOF becomes an
+    /// answer, trimmed and with the default for an empty line. for piped
+    /// batch input, so nothing is validated or asked again
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut writer = MockWriter::new();
+    /// let hosts = Input::new("hosts")
+    ///     .default("localhost")
+    ///     .read_all_lines_with_io(&mut MockReader::new(&[" web1 ", "", "db1"]), &mut writer);
+    /// assert_eq!(hosts, Ok(vec!["web1".to_string(), "localhost".to_string(), "db1".to_string()]));
+    /// assert_eq!(writer.output, "hosts [localhost]:");
+    /// ```
+    pub fn read_all_lines_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Vec<String>, InputError> {
+        let default = self.default_with(&Answers::new());
+        let prompt = self.interpolate(&self.prompt)?;
+        let text = self.render(&prompt, default.as_deref(), reader, writer)?;
+        writer.write_str(&text)?;
+        writer.flush()?;
+
+        let mut lines = Vec::new();
+        let mut buf = String::new();
+        while reader.read_line(&mut buf)? > 0 {
+            process_input_in_place(&mut buf);
+            match &default {
+                Some(default) if buf.is_empty() => lines.push(default.clone()),
+                _ => lines.push(buf.clone()),
+            }
+            buf.clear();
+        }
+        Ok(lines)
+    }
+
+    /// ask as question `id` after the earlier `answers`. with `undo` a
+    /// typed `form::UNDO` gives `None`, before it is checked or kept
+    pub(crate) fn ask_as(
+        &self,
+        id: &str,
+        answers: &Answers,
+        undo: bool,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Option<Answer>, InputError> {
+        if self.constraints != Constraints::default() || self.validator.is_some() {
+            let answer = self.ask_converted(id, answers, undo, reader, writer, |_| Ok(()))?;
+            return Ok(answer.map(|(_, answer)| answer));
+        }
+        let mut hidden;
+        let reader = match self.hidden {
+            true => {
+                hidden = HiddenReader(reader);
+                &mut hidden as &mut dyn InputReader
+            }
+            false => reader,
+        };
+        let deadline = self.deadline();
+        let default = self.default_with(answers);
+        let default = default.as_deref();
+        let prompt = self.interpolate(&self.prompt)?;
+        if let Some(answer) = self.unattended(id, &prompt, default, reader)? {
+            self.keep(id, &answer)?;
+            return Ok(Some(given(id, &answer, default)));
+        }
+        if let Some(answer) = preset_answer(id, &prompt, default, reader)? {
+            self.keep(id, &answer)?;
+            return Ok(Some(given(id, &answer, default)));
+        }
+        let text = self.render(&prompt, default, reader, writer)?;
+        #[cfg(feature = "line-editing")]
+        let _installed = crate::line_editor::install(
+            self.history.as_ref(),
+            self.completer.as_ref(),
+            self.constraints.max_len,
+        );
+        let answer = match ask_line(&text, None, deadline, reader, writer) {
+            Err(InputError::TimedOut) if default.is_some() => String::new(),
+            answer => answer?,
+        };
+        if undo && answer == UNDO {
+            return Ok(None);
+        }
+        let answer = match default {
+            Some(default) if answer.is_empty() => Answer::new(id, default, Provenance::Default),
+            _ => Answer::new(id, &answer, Provenance::Entered),
+        };
+        self.keep(id, &answer.value)?;
+        Ok(Some(answer))
+    }
+
+    /// ask as question `id` until an answer passes the constraints and
+    /// `convert`, which tells what is wrong otherwise. an answer given
+    /// without asking is checked once, failing with
+    /// `InputError::InvalidAnswer` or `InputError::ParseError`. `undo`
+    /// as in `ask_as`. returns the converted value with the `Answer`
+    fn ask_converted<T>(
+        &self,
+        id: &str,
+        answers: &Answers,
+        undo: bool,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+        convert: impl Fn(&str) -> Result<T, String>,
+    ) -> Result<Option<(T, Answer)>, InputError> {
+        let mut hidden;
+        let reader = match self.hidden {
+            true => {
+                hidden = HiddenReader(reader);
+                &mut hidden as &mut dyn InputReader
+            }
+            false => reader,
+        };
+        let deadline = self.deadline();
+        let default = self.default_with(answers);
+        let default = default.as_deref();
+        let prompt = self.constraints.with_hint(&self.interpolate(&self.prompt)?);
+        if let Some(answer) = self.unattended(id, &prompt, default, reader)? {
+            let value = self.settle(&answer, answers, &convert)?;
+            self.keep(id, &answer)?;
+            return Ok(Some((value, given(id, &answer, default))));
+        }
+        if let Some(answer) = preset_answer(id, &prompt, default, reader)? {
+            let value = self.settle(&answer, answers, &convert)?;
+            self.keep(id, &answer)?;
+            return Ok(Some((value, given(id, &answer, default))));
+        }
+        let text = self.render(&prompt, default, reader, writer)?;
+        #[cfg(feature = "line-editing")]
+        let _installed = crate::line_editor::install(
+            self.history.as_ref(),
+            self.completer.as_ref(),
+            self.constraints.max_len,
+        );
+
+        let mut retries = Retries::with_max_attempts(self.max_attempts).theme(self.theme);
+        loop {
+            let answer = read_answer_until(&text, deadline, reader, writer, &mut |_| Ok(()));
+            let timed_out = answer == Err(InputError::TimedOut);
+            let answer = match (answer, default) {
+                (Err(InputError::TimedOut), Some(_)) => String::new(),
+                (answer, _) => answer?.ok_or(InputError::Eof)?,
+            };
+            if undo && answer == UNDO {
+                return Ok(None);
+            }
+            let provenance = match (default, answer.is_empty()) {
+                (Some(_), true) => Provenance::Default,
+                _ => Provenance::Entered,
+            };
+            let mut answer = match default {
+                Some(default) if answer.is_empty() => default.to_string(),
+                _ => answer,
+            };
+            let interactive = reader.is_interactive();
+            let len = answer.chars().count();
+            let over = self.constraints.max_len.filter(|max_len| len > *max_len);
+            if let (Some(max_len), LengthPolicy::Truncate) = (over, self.length_policy) {
+                if interactive {
+                    let note = format!("{}, cut", self.counter(len, max_len));
+                    write_caret(writer, &answer, max_len..len, &note)?;
+                }
+                answer = answer.chars().take(max_len).collect();
+            }
+            let value = self
+                .check(&answer, answers)
+                .and_then(|()| convert(&answer).map_err(|msg| (msg, None)));
+            match value {
+                Ok(value) => {
+                    self.keep(id, &answer)?;
+                    let answer = Answer::new(id, &answer, provenance);
+                    return Ok(Some((value, answer.attempts(retries.attempts()))));
+                }
+                Err(_) if timed_out => return Err(InputError::TimedOut),
+                Err((msg, _)) if self.error_mask().is_some() => {
+                    let mask = self.error_mask().unwrap_or(MaskStyle::Full);
+                    retries.reject(writer, &mask.hide_in(&msg, &answer))?
+                }
+                Err((msg, span)) => {
+                    // the line editor offers the answer again to be fixed
+                    #[cfg(feature = "line-editing")]
+                    crate::line_editor::prefill(&answer);
+                    match span {
+                        Some(span) => {
+                            let note = match over {
+                                Some(max_len) => self.counter(len, max_len),
+                                None => String::new(),
+                            };
+                            retries.reject_at(interactive, writer, &msg, &answer, span, &note)?
+                        }
+                        None => retries.reject(writer, &msg)?,
+                    }
+                }
+            }
+        }
+    }
+
+    /// `answer`, given without asking, checked and converted
+    fn settle<T>(
+        &self,
+        answer: &str,
+        answers: &Answers,
+        convert: &impl Fn(&str) -> Result<T, String>,
+    ) -> Result<T, InputError> {
+        let mask = self.error_mask();
+        let hide = |text: &str| match mask {
+            Some(mask) => mask.hide_in(text, answer),
+            None => text.to_string(),
+        };
+        self.check(answer, answers)
+            .map_err(|(msg, _)| InputError::InvalidAnswer {
+                answer: hide(answer),
+                msg: hide(&msg),
+            })?;
+        convert(answer).map_err(|_| InputError::ParseError(hide(answer)))
+    }
+
+    /// how rejected answers are quoted in errors, masked like
+    /// `mask_display` or fully when `hidden`
+    fn error_mask(&self) -> Option<MaskStyle> {
+        self.mask.or(self.hidden.then_some(MaskStyle::Full))
+    }
+
+    /// add the answer to question `id` to the `history`, keep it for
+    /// `remember` and record it. answers given without asking are kept
+    /// too, like `Prompter` records them
+    fn keep(&self, id: &str, answer: &str) -> Result<(), InputError> {
+        if let Some(history) = &self.history {
+            history.push(answer);
+        }
+        if let Some(slot) = &self.remembered {
+            slot.write(answer)?;
+        }
+        match &self.recorder {
+            Some(recorder) => recorder.record(id, answer),
+            None => Ok(()),
+        }
+    }
+
+    /// the answer without writing the prompt when the reader is not
+    /// interactive, see `non_interactive_behavior`. `None` means go on
+    fn unattended(
+        &self,
+        id: &str,
+        prompt: &str,
+        default: Option<&str>,
+        reader: &mut dyn InputReader,
+    ) -> Result<Option<String>, InputError> {
+        let Some(behavior) = self.non_interactive.filter(|_| !reader.is_interactive()) else {
+            return Ok(None);
+        };
+        if let Some(answer) = answers::preset(id)? {
+            return Ok(Some(answer.to_string()));
+        }
+        let not_interactive = || InputError::NotInteractive(prompt.to_string());
+        match behavior {
+            NonInteractive::UseDefault => match default {
+                Some(default) => Ok(Some(default.to_string())),
+                None => Err(not_interactive()),
+            },
+            NonInteractive::Error => Err(not_interactive()),
+            NonInteractive::ReadSilently => {
+                let mut buf = String::new();
+                if reader.read_line(&mut buf)? == 0 {
+                    return match default {
+                        Some(default) => Ok(Some(default.to_string())),
+                        None => Err(InputError::Eof),
+                    };
+                }
+                process_input_in_place(&mut buf);
+                match default {
+                    Some(default) if buf.is_empty() => Ok(Some(default.to_string())),
+                    _ => Ok(Some(buf)),
+                }
+            }
+        }
+    }
+
+    /// when to stop waiting, see `timeout`
+    fn deadline(&self) -> Option<Instant> {
+        self.timeout.map(|timeout| Instant::now() + timeout)
+    }
+
+    /// `len` against `max_len` like `86/80`, in the warning style
+    fn counter(&self, len: usize, max_len: usize) -> String {
+        let (open, close) = self.theme.warning;
+        format!("{}{}/{}{}", open, len, max_len, close)
+    }
+
+    /// the body and the prompt line, ready to be written to `writer`
+    fn render(
+        &self,
+        prompt: &str,
+        default: Option<&str>,
+        reader: &mut dyn InputReader,
+        writer: &dyn OutputWriter,
+    ) -> Result<String, InputError> {
+        let mut text = match &self.body {
+            Some(body) => markdown::render(&self.interpolate(body)?, &self.theme),
+            None => String::new(),
+        };
+        let theme = self.theme.for_writer(writer);
+        render_masked_prompt_into(&mut text, prompt, default, self.mask, &theme);
+        if self.flush_pending {
+            reader.discard_pending()?;
+        }
+        Ok(text)
+    }
+
+    /// `answer` against the constraints, or why not and the chars at fault
+    fn check(&self, answer: &str, answers: &Answers) -> Result<(), (String, Option<Range<usize>>)> {
+        if let Err(msg) = self.constraints.check(answer) {
+            let len = answer.chars().count();
+            let span = match self.constraints.max_len {
+                Some(max_len) if len > max_len => Some(max_len..len),
+                _ => None,
+            };
+            return Err((msg, span));
+        }
+        if let (Some(check), Some(description)) = (&self.pattern, &self.constraints.pattern) {
+            match check(answer) {
+                Ok(true) => {}
+                Ok(false) => return Err((format!("'{}' is not {}", answer, description), None)),
+                Err(msg) => return Err((msg, None)),
+            }
+        }
+        match &self.validator {
+            Some(validator) => validator(answer, answers).map_err(|msg| (msg, None)),
+            None => Ok(()),
+        }
+    }
+
+    /// read the answer from stdin as `T`, asking again until it parses
+    ///
+    /// # usage
+    /// ```no_run
+    /// use input_py::Input;
+    ///
+    /// let port: u16 = Input::new("port").default("8080").parse().unwrap();
+    /// ```
+    pub fn parse<T: FromStr>(self) -> Result<T, InputError> {
+        self.parse_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    /// `parse` on `reader` and `writer`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut writer = MockWriter::new();
+    /// let port = Input::new("port")
+    ///     .default("8080")
+    ///     .parse_with_io::<u16>(&mut MockReader::new(&["http", " 443 "]), &mut writer);
+    /// assert_eq!(port, Ok(443));
+    /// assert_eq!(
+    ///     writer.output,
+    ///     "port [8080]:error: invalid value 'http': expected integer\nport [8080]:"
+    /// );
+    /// ```
+    pub fn parse_with_io<T: FromStr>(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<T, InputError> {
+        let id = self.id.as_deref().unwrap_or(&self.prompt);
+        let value = self.ask_converted(id, &Answers::new(), false, reader, writer, |answer| {
+            locale::parse(answer)
+                .map_err(|_| format!("invalid value '{}': {}", answer, expected::<T>()))
+        })?;
+        Ok(value.expect("undo is off").0)
+    }
+
+    /// ask on another `Frontend`, like a GUI dialog
+    pub fn read_with_frontend(self, frontend: &mut dyn Frontend) -> Result<String, InputError> {
+        let id = self.id.as_deref().unwrap_or(&self.prompt);
+        let question = self.question_with(id, &Answers::new())?;
+        frontend::ask(frontend, &question).map(|answer| answer.value)
+    }
+
+    /// the `Question` frontends are asked, identified by `id`. an invalid
+    /// template or missing value leaves the placeholders as written
+    pub fn question(&self, id: &str) -> Question {
+        let answers = Answers::new();
+        self.question_with(id, &answers)
+            .unwrap_or_else(|_| self.build_question(id, &self.prompt, self.body.clone(), &answers))
+    }
+
+    /// `question` with the default derived from `answers` and the
+    /// placeholders filled in
+    pub(crate) fn question_with(
+        &self,
+        id: &str,
+        answers: &Answers,
+    ) -> Result<Question, InputError> {
+        let body = match &self.body {
+            Some(body) => Some(self.interpolate(body)?.into_owned()),
+            None => None,
+        };
+        let prompt = self.interpolate(&self.prompt)?;
+        Ok(self.build_question(id, &prompt, body, answers))
+    }
+
+    fn build_question(
+        &self,
+        id: &str,
+        prompt: &str,
+        body: Option<String>,
+        answers: &Answers,
+    ) -> Question {
+        let mut question = Question::new(id, prompt);
+        question.default = self.default_with(answers);
+        question.constraints = self.constraints.clone();
+        question.mask = self.mask;
+        if self.hidden || self.mask.is_some() {
+            question.kind = QuestionKind::Secret;
+        }
+        question.body = body;
+        question
+    }
+
+    /// `text` with the `vars` filled in
+    fn interpolate<'a>(&self, text: &'a str) -> Result<Cow<'a, str>, InputError> {
+        match &self.vars {
+            Some(vars) => {
+                let vars: Vec<(&str, &str)> =
+                    vars.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+                template::render(text, &vars).map(Cow::Owned)
+            }
+            None => Ok(Cow::Borrowed(text)),
+        }
+    }
+
+    /// the default given the earlier `answers`
+    pub(crate) fn default_with(&self, answers: &Answers) -> Option<String> {
+        self.default_from
+            .as_ref()
+            .and_then(|default| default(answers))
+            .or_else(|| self.remembered.as_ref()?.read().ok()?)
+            .or_else(|| self.default.clone())
+    }
+
+    /// read the answer from stdin without blocking the async executor,
+    /// with tokio's `AsyncBufReadExt` under the `tokio` feature. see
+    /// `stream::AnswerFuture`
+    #[cfg(feature = "async")]
+    pub fn read_async(self) -> crate::stream::AnswerFuture {
+        #[cfg(feature = "tokio")]
+        let answer = crate::stream::AnswerFuture::stdin(self);
+        #[cfg(not(feature = "tokio"))]
+        let answer = self.read_async_with_io(StdinReader, StdoutWriter);
+        answer
+    }
+
+    #[cfg(feature = "async")]
+    pub fn read_async_with_io<R, W>(self, reader: R, writer: W) -> crate::stream::AnswerFuture
+    where
+        R: InputReader + Send + 'static,
+        W: OutputWriter + Send + 'static,
+    {
+        crate::stream::AnswerFuture::spawn(&self, reader, writer)
+    }
+
+    /// ask on an async reader and writer. like `read_async` it uses the
+    /// prompt and default and leaves out the checks
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// async fn ask(reader: &mut MockReader, writer: &mut MockWriter) -> String {
+    ///     Input::new("name").default("guest").read_with_async_io(reader, writer).await.unwrap()
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn read_with_async_io<'a>(
+        self,
+        reader: &'a mut dyn crate::async_io::AsyncInputReader,
+        writer: &'a mut dyn crate::async_io::AsyncOutputWriter,
+    ) -> impl std::future::Future<Output = Result<String, InputError>> + Send + 'a {
+        // async writers can't tell whether they are a terminal
+        let theme = Theme {
+            colors: Style::PLAIN,
+            ..self.theme
+        };
+        let (prompt, default) = crate::stream::prompt_of(&self, &theme);
+        crate::stream::ask_async(prompt, default, reader, writer)
+    }
+
+    /// ask again and again on stdin, yielding the answers asynchronously
+    #[cfg(feature = "async")]
+    pub fn into_stream(self) -> crate::stream::AnswerStream {
+        self.into_stream_with_io(StdinReader, StdoutWriter)
+    }
+
+    #[cfg(feature = "async")]
+    pub fn into_stream_with_io<R, W>(self, reader: R, writer: W) -> crate::stream::AnswerStream
+    where
+        R: InputReader + Send + 'static,
+        W: OutputWriter + Send + 'static,
+    {
+        crate::stream::AnswerStream::spawn(self, reader, writer)
+    }
+
+    /// turn this prompt into a prompt for several values of `T`.
+    /// see `ListInput`
+    pub fn list<T: FromStr>(self) -> ListInput<T> {
+        ListInput::new(self)
+    }
+
+    /// turn this prompt into a yes/no question. see `Confirm`
+    pub fn confirm(self) -> Confirm {
+        Confirm::new(self)
+    }
+
+    /// turn this prompt into a prompt for a number within `range`.
+    /// see `RangeInput`
+    pub fn range<T>(self, range: RangeInclusive<T>) -> RangeInput<T>
+    where
+        T: FromStr + PartialOrd + Display + Clone,
+    {
+        RangeInput::new(self, range)
+    }
+}
+
+impl Prompt for Input {
+    type Output = String;
+
+    fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<String, InputError> {
+        Input::read_with_io(self, reader, writer)
+    }
+}
+
+/// `answer` to question `id` given without asking, the default when it
+/// is the same
+fn given(id: &str, answer: &str, default: Option<&str>) -> Answer {
+    match default {
+        Some(default) if answer == default => Answer::new(id, answer, Provenance::Default),
+        _ => Answer::new(id, answer, Provenance::Entered),
+    }
+}
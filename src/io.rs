@@ -0,0 +1,947 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+
+/// # InputReader
+///
+/// source of input lines, like stdin
+pub trait InputReader {
+    /// read one line into `buf` including the line ending.
+    /// returns the number of bytes read, 0 means EOF
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize>;
+
+    /// whether a person can answer on this reader, see
+    /// `config::strict_interactive`. false unless overridden, readers
+    /// on a terminal report true
+    fn is_interactive(&self) -> bool {
+        false
+    }
+
+    /// throw away input typed ahead that was not read yet, without
+    /// blocking. does nothing unless overridden
+    fn discard_pending(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// `read_line` without showing the line, for passwords. readers
+    /// that don't echo read normally
+    fn read_hidden(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.read_line(buf)
+    }
+
+    /// wait up to `timeout` for a line or EOF, false when none arrived.
+    /// readers that can't wait report true and are read at once
+    fn wait_readable(&mut self, timeout: Duration) -> io::Result<bool> {
+        let _ = timeout;
+        Ok(true)
+    }
+
+    /// whether the terminal was resized or the process resumed after
+    /// Ctrl+Z since the last call, so a prompt waiting in `wait_readable`
+    /// can draw itself again. false unless overridden
+    fn needs_redraw(&mut self) -> bool {
+        false
+    }
+}
+
+/// # OutputWriter
+///
+/// destination of prompt text, like stdout
+pub trait OutputWriter {
+    fn write_str(&mut self, s: &str) -> io::Result<()>;
+    fn flush(&mut self) -> io::Result<()>;
+
+    /// whether the output shows on a terminal, which can color a
+    /// `config::Style`. default: false
+    fn is_terminal(&self) -> bool {
+        false
+    }
+
+    /// write every line followed by a newline in a single `write_str`,
+    /// so multi-line output costs one write over slow links
+    fn write_all_lines(&mut self, lines: &[&str]) -> io::Result<()> {
+        let len = lines.iter().map(|l| l.len() + 1).sum();
+        let mut text = String::with_capacity(len);
+        for line in lines {
+            text.push_str(line);
+            text.push('\n');
+        }
+        self.write_str(&text)
+    }
+}
+
+impl<W: OutputWriter + ?Sized> OutputWriter for &mut W {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        (**self).write_str(s)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (**self).flush()
+    }
+
+    fn is_terminal(&self) -> bool {
+        (**self).is_terminal()
+    }
+
+    fn write_all_lines(&mut self, lines: &[&str]) -> io::Result<()> {
+        (**self).write_all_lines(lines)
+    }
+}
+
+/// reads lines from the process stdin
+pub struct StdinReader;
+
+impl InputReader for StdinReader {
+    /// with the `line-editing` feature a terminal is read in raw mode
+    /// through the `line_editor` session, unless `TERM` is `dumb`
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        #[cfg(all(unix, feature = "line-editing"))]
+        if crate::line_editor::edits_stdin() {
+            return crate::line_editor::read_stdin(buf);
+        }
+        #[cfg(all(unix, feature = "terminal"))]
+        if io::stdin().is_terminal() {
+            return read_terminal(buf);
+        }
+        io::stdin().read_line(buf)
+    }
+
+    fn is_interactive(&self) -> bool {
+        !crate::config::env_noninteractive() && io::stdin().is_terminal()
+    }
+
+    /// flushes the terminal input queue. piped input is scripted and kept
+    fn discard_pending(&mut self) -> io::Result<()> {
+        #[cfg(all(unix, feature = "terminal"))]
+        if io::stdin().is_terminal() {
+            return crate::terminal::flush_input();
+        }
+        Ok(())
+    }
+
+    /// polls the terminal, or waits for a key on a windows console.
+    /// piped input is read at once
+    fn wait_readable(&mut self, timeout: Duration) -> io::Result<bool> {
+        // canonical mode only wakes up on a whole line
+        #[cfg(all(unix, feature = "line-editing"))]
+        if crate::line_editor::edits_stdin() {
+            if let Some(_raw) = crate::line_editor::raw_mode() {
+                return crate::terminal::poll_input(timeout);
+            }
+        }
+        #[cfg(all(unix, feature = "terminal"))]
+        if io::stdin().is_terminal() {
+            return poll_terminal(timeout);
+        }
+        #[cfg(all(windows, feature = "terminal"))]
+        if io::stdin().is_terminal() {
+            return crate::console::wait_key(timeout);
+        }
+        let _ = timeout;
+        Ok(true)
+    }
+
+    /// turns the terminal echo off while reading, the line ending the
+    /// user typed is written in its place. where the echo can't be
+    /// turned off `config::hidden_fallback` decides
+    fn read_hidden(&mut self, buf: &mut String) -> io::Result<usize> {
+        #[cfg(all(unix, feature = "terminal"))]
+        if io::stdin().is_terminal() {
+            let echo_off = match crate::terminal::EchoOff::new() {
+                Ok(echo_off) => echo_off,
+                Err(e) => return read_visibly(e, buf),
+            };
+            let read = read_terminal(buf);
+            drop(echo_off);
+            let mut stdout = io::stdout();
+            stdout.write_all(b"\n")?;
+            stdout.flush()?;
+            return read;
+        }
+        io::stdin().read_line(buf)
+    }
+
+    /// SIGWINCH and SIGCONT caught while `wait_readable` waited. windows
+    /// consoles only report resizes in raw input mode and have no Ctrl+Z,
+    /// so this stays false there
+    fn needs_redraw(&mut self) -> bool {
+        #[cfg(all(unix, feature = "terminal"))]
+        if io::stdin().is_terminal() {
+            return crate::terminal::take_redraw();
+        }
+        false
+    }
+}
+
+/// hidden input where the echo couldn't be turned off because of
+/// `error`, per `config::hidden_fallback`
+#[cfg(all(unix, feature = "terminal"))]
+fn read_visibly(error: io::Error, buf: &mut String) -> io::Result<usize> {
+    use crate::capabilities::{self, Degradation};
+    use crate::config::{self, HiddenFallback};
+
+    if config::hidden_fallback_policy() == HiddenFallback::Error {
+        return Err(io::Error::other(format!(
+            "can't hide the input, the terminal echo can't be turned off: {}",
+            error
+        )));
+    }
+    capabilities::degrade(Degradation::VisibleHiddenInput);
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\nwarning: the terminal echo can't be turned off, the input will show\n")?;
+    stdout.flush()?;
+    read_terminal(buf)
+}
+
+/// how long `read_terminal` waits for a line before it looks for a
+/// Ctrl+C that came in just before it started to wait
+#[cfg(all(unix, feature = "terminal"))]
+const INTERRUPT_POLL: Duration = Duration::from_secs(1);
+
+/// a line of the terminal in canonical mode. Ctrl+C fails the read with
+/// `io::ErrorKind::Interrupted` under `config::catch_interrupts`,
+/// otherwise it ends the process with the echo back on
+#[cfg(all(unix, feature = "terminal"))]
+pub(crate) fn read_terminal(buf: &mut String) -> io::Result<usize> {
+    let catch = crate::config::catches_interrupts();
+    let interrupts = crate::terminal::Interrupts::new(catch);
+    while catch && !crate::terminal::poll_input(INTERRUPT_POLL)? {
+        interrupted(&interrupts)?;
+    }
+    io::stdin().read_line(buf)
+}
+
+/// whether the terminal has a line within `timeout`, failing with
+/// `io::ErrorKind::Interrupted` on Ctrl+C under `config::catch_interrupts`
+#[cfg(all(unix, feature = "terminal"))]
+fn poll_terminal(timeout: Duration) -> io::Result<bool> {
+    if !crate::config::catches_interrupts() {
+        return crate::terminal::poll_input(timeout);
+    }
+    let interrupts = crate::terminal::Interrupts::new(true);
+    let ready = crate::terminal::poll_input(timeout)?;
+    interrupted(&interrupts)?;
+    Ok(ready)
+}
+
+/// ends the row of the prompt and fails if Ctrl+C was pressed
+#[cfg(all(unix, feature = "terminal"))]
+fn interrupted(interrupts: &crate::terminal::Interrupts) -> io::Result<()> {
+    if !interrupts.take() {
+        return Ok(());
+    }
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\n")?;
+    stdout.flush()?;
+    Err(io::ErrorKind::Interrupted.into())
+}
+
+/// columns of the terminal on stdout, none when it isn't one
+pub(crate) fn terminal_columns() -> Option<usize> {
+    #[cfg(all(unix, feature = "terminal"))]
+    if io::stdout().is_terminal() {
+        return crate::terminal::columns();
+    }
+    None
+}
+
+/// # BlockingBridgeReader
+///
+/// sync `InputReader` fed from another thread or an async task, for
+/// mixing sync prompt code with async input sources during a migration
+/// to the async API. `read_line` blocks until the feeding side sends a
+/// line, and reports EOF once every `LineSender` is dropped. with the
+/// `tokio` feature `from_async` feeds it from a `tokio::io::AsyncBufRead`
+///
+/// # usage
+/// ```
+/// use input_py::io::BlockingBridgeReader;
+/// use input_py::read_input_with_io;
+/// use input_py::io::MockWriter;
+///
+/// let (mut reader, sender) = BlockingBridgeReader::channel();
+/// std::thread::spawn(move || sender.send("from async"));
+/// let answer = read_input_with_io("value", None, &mut reader, &mut MockWriter::new());
+/// assert_eq!(answer, Ok("from async".to_string()));
+/// ```
+pub struct BlockingBridgeReader {
+    lines: Receiver<io::Result<String>>,
+    /// a line received by `wait_readable` and not read yet
+    waiting: Option<io::Result<String>>,
+}
+
+/// feeding side of a `BlockingBridgeReader`
+#[derive(Clone)]
+pub struct LineSender {
+    lines: Sender<io::Result<String>>,
+}
+
+impl LineSender {
+    /// send one line, a missing line ending is added.
+    /// returns false when the reader is gone
+    pub fn send(&self, line: &str) -> bool {
+        let mut line = line.to_string();
+        if !line.ends_with('\n') {
+            line.push('\n');
+        }
+        self.lines.send(Ok(line)).is_ok()
+    }
+}
+
+impl BlockingBridgeReader {
+    pub fn channel() -> (BlockingBridgeReader, LineSender) {
+        let (sender, receiver) = mpsc::channel();
+        (
+            BlockingBridgeReader {
+                lines: receiver,
+                waiting: None,
+            },
+            LineSender { lines: sender },
+        )
+    }
+
+    /// read the lines of `source` in a task spawned on `runtime`, so sync
+    /// code, in `spawn_blocking` or a thread of its own, can prompt on an
+    /// async stream. a read error is returned by the next `read_line`.
+    /// needs the `tokio` feature
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::io::{BlockingBridgeReader, MockWriter};
+    /// use input_py::read_input_with_io;
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// let answer = runtime.block_on(async {
+    ///     let source: &'static [u8] = b"from async\n";
+    ///     let mut reader = BlockingBridgeReader::from_async(source, tokio::runtime::Handle::current());
+    ///     tokio::task::spawn_blocking(move || {
+    ///         read_input_with_io("value", None, &mut reader, &mut MockWriter::new())
+    ///     })
+    ///     .await
+    ///     .unwrap()
+    /// });
+    /// assert_eq!(answer, Ok("from async".to_string()));
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn from_async<R>(source: R, runtime: tokio::runtime::Handle) -> BlockingBridgeReader
+    where
+        R: tokio::io::AsyncBufRead + Unpin + Send + 'static,
+    {
+        use tokio::io::AsyncBufReadExt;
+
+        let (reader, sender) = BlockingBridgeReader::channel();
+        runtime.spawn(async move {
+            let mut source = source;
+            loop {
+                let mut line = String::new();
+                let line = match source.read_line(&mut line).await {
+                    Ok(0) => break,
+                    Ok(_) => Ok(line),
+                    Err(err) => Err(err),
+                };
+                let failed = line.is_err();
+                // the reader is gone or the stream broke
+                if sender.lines.send(line).is_err() || failed {
+                    break;
+                }
+            }
+        });
+        reader
+    }
+}
+
+impl InputReader for BlockingBridgeReader {
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        let line = match self.waiting.take() {
+            Some(line) => Ok(line),
+            None => self.lines.recv(),
+        };
+        match line {
+            Ok(Ok(line)) => {
+                buf.push_str(&line);
+                Ok(line.len())
+            }
+            Ok(Err(err)) => Err(err),
+            Err(_) => Ok(0),
+        }
+    }
+
+    fn discard_pending(&mut self) -> io::Result<()> {
+        self.waiting = None;
+        while self.lines.try_recv().is_ok() {}
+        Ok(())
+    }
+
+    fn wait_readable(&mut self, timeout: Duration) -> io::Result<bool> {
+        if self.waiting.is_some() {
+            return Ok(true);
+        }
+        match self.lines.recv_timeout(timeout) {
+            Ok(line) => {
+                self.waiting = Some(line);
+                Ok(true)
+            }
+            Err(RecvTimeoutError::Timeout) => Ok(false),
+            Err(RecvTimeoutError::Disconnected) => Ok(true),
+        }
+    }
+}
+
+/// writes to the process stdout
+pub struct StdoutWriter;
+
+impl OutputWriter for StdoutWriter {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        #[cfg(all(unix, feature = "line-editing"))]
+        crate::line_editor::note_output(s);
+        io::stdout().write_all(s.as_bytes())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+
+    fn is_terminal(&self) -> bool {
+        io::stdout().is_terminal()
+    }
+}
+
+/// adapts any `BufRead` (files, byte slices, sockets) into an `InputReader`
+pub struct BufReaderInput<R: BufRead> {
+    inner: R,
+}
+
+impl<R: BufRead> BufReaderInput<R> {
+    pub fn new(inner: R) -> Self {
+        BufReaderInput { inner }
+    }
+}
+
+impl<R: BufRead> InputReader for BufReaderInput<R> {
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.inner.read_line(buf)
+    }
+}
+
+/// adapts any `Write` into an `OutputWriter`
+pub struct GenericWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> GenericWriter<W> {
+    pub fn new(inner: W) -> Self {
+        GenericWriter { inner }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> OutputWriter for GenericWriter<W> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.inner.write_all(s.as_bytes())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// when a `FlushPolicyWriter` flushes its inner writer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlushPolicy {
+    /// after every write
+    Always,
+    /// when a prompt is complete, the crate's normal behavior
+    #[default]
+    OnPromptEnd,
+    /// never, the inner writer decides or the caller flushes it
+    Never,
+}
+
+/// # FlushPolicyWriter
+///
+/// wraps an `OutputWriter` and flushes it according to a `FlushPolicy`,
+/// so high frequency prompting isn't forced into a flush per prompt
+///
+/// # usage
+/// ```
+/// use input_py::io::{FlushPolicy, FlushPolicyWriter, StdoutWriter};
+///
+/// let writer = FlushPolicyWriter::new(StdoutWriter, FlushPolicy::Never);
+/// ```
+pub struct FlushPolicyWriter<W: OutputWriter> {
+    inner: W,
+    policy: FlushPolicy,
+}
+
+impl<W: OutputWriter> FlushPolicyWriter<W> {
+    pub fn new(inner: W, policy: FlushPolicy) -> Self {
+        FlushPolicyWriter { inner, policy }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: OutputWriter> OutputWriter for FlushPolicyWriter<W> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.inner.write_str(s)?;
+        if self.policy == FlushPolicy::Always {
+            self.inner.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.policy {
+            FlushPolicy::Never => Ok(()),
+            FlushPolicy::Always | FlushPolicy::OnPromptEnd => self.inner.flush(),
+        }
+    }
+
+    fn write_all_lines(&mut self, lines: &[&str]) -> io::Result<()> {
+        self.inner.write_all_lines(lines)?;
+        if self.policy == FlushPolicy::Always {
+            self.inner.flush()?;
+        }
+        Ok(())
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.inner.is_terminal()
+    }
+}
+
+/// # TeeWriter
+///
+/// writes prompt output to several sinks at once, like the terminal, a
+/// logfile and a transcript, so a session is recorded without wrapping
+/// every call site. each sink flushes by its own `FlushPolicy`. a sink
+/// that fails doesn't stop the others, the first error is returned once
+/// all were tried. colors follow the first sink
+///
+/// # usage
+/// ```
+/// use input_py::io::{FlushPolicy, MockReader, MockWriter, TeeWriter};
+/// use input_py::read_input_with_io;
+///
+/// let mut terminal = MockWriter::new();
+/// let mut log = MockWriter::new();
+/// let mut writer = TeeWriter::new()
+///     .sink(&mut terminal)
+///     .sink_with(&mut log, FlushPolicy::Never);
+/// let user = read_input_with_io("user", None, &mut MockReader::new(&["admin"]), &mut writer);
+/// assert_eq!(user, Ok("admin".to_string()));
+/// drop(writer);
+/// assert_eq!(terminal.output, log.output);
+/// assert_eq!(log.flushes, 0);
+/// ```
+#[derive(Default)]
+pub struct TeeWriter<'a> {
+    sinks: Vec<Box<dyn OutputWriter + 'a>>,
+}
+
+impl<'a> TeeWriter<'a> {
+    pub fn new() -> Self {
+        TeeWriter::default()
+    }
+
+    /// add a sink flushed when a prompt is complete
+    pub fn sink(self, writer: impl OutputWriter + 'a) -> Self {
+        self.sink_with(writer, FlushPolicy::OnPromptEnd)
+    }
+
+    /// add a sink flushed according to `policy`
+    pub fn sink_with(mut self, writer: impl OutputWriter + 'a, policy: FlushPolicy) -> Self {
+        self.sinks
+            .push(Box::new(FlushPolicyWriter::new(writer, policy)));
+        self
+    }
+
+    /// run `f` on every sink, the first error after all were tried
+    fn each(
+        &mut self,
+        mut f: impl FnMut(&mut dyn OutputWriter) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let mut result = Ok(());
+        for sink in &mut self.sinks {
+            let written = f(sink.as_mut());
+            if result.is_ok() {
+                result = written;
+            }
+        }
+        result
+    }
+}
+
+impl OutputWriter for TeeWriter<'_> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.each(|sink| sink.write_str(s))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.each(|sink| sink.flush())
+    }
+
+    fn write_all_lines(&mut self, lines: &[&str]) -> io::Result<()> {
+        self.each(|sink| sink.write_all_lines(lines))
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.sinks.first().is_some_and(|sink| sink.is_terminal())
+    }
+}
+
+/// # TeeReader
+///
+/// copies every line `inner` gives to `sink` byte for byte, line ending
+/// and whitespace included, before the prompt trims it. meant for
+/// auditing and for debugging trimming or encoding issues. lines read
+/// hidden, like passwords, are copied as `(hidden)`
+///
+/// # usage
+/// ```
+/// use input_py::io::{MockReader, MockWriter, TeeReader};
+/// use input_py::password::PasswordConfirm;
+/// use input_py::read_input_with_io;
+///
+/// let mut reader = TeeReader::new(MockReader::new(&["  admin\t", "pw", "pw"]), MockWriter::new());
+/// let mut writer = MockWriter::new();
+/// let user = read_input_with_io("user", None, &mut reader, &mut writer);
+/// assert_eq!(user, Ok("admin".to_string()));
+/// PasswordConfirm::new("password", "again").read_with_io(&mut reader, &mut writer).unwrap();
+/// let (_, audit) = reader.into_inner();
+/// assert_eq!(audit.output, "  admin\t\n(hidden)\n(hidden)\n");
+/// ```
+pub struct TeeReader<R: InputReader, W: OutputWriter> {
+    inner: R,
+    sink: W,
+}
+
+impl<R: InputReader, W: OutputWriter> TeeReader<R, W> {
+    pub fn new(inner: R, sink: W) -> Self {
+        TeeReader { inner, sink }
+    }
+
+    /// the reader and the sink
+    pub fn into_inner(self) -> (R, W) {
+        (self.inner, self.sink)
+    }
+
+    /// `copy` of a line that was read, nothing at EOF
+    fn copy(&mut self, read: usize, copy: &str) -> io::Result<usize> {
+        if read > 0 {
+            self.sink.write_str(copy)?;
+            self.sink.flush()?;
+        }
+        Ok(read)
+    }
+}
+
+impl<R: InputReader, W: OutputWriter> InputReader for TeeReader<R, W> {
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        let start = buf.len();
+        let read = self.inner.read_line(buf)?;
+        self.copy(read, &buf[start..])
+    }
+
+    fn read_hidden(&mut self, buf: &mut String) -> io::Result<usize> {
+        let read = self.inner.read_hidden(buf)?;
+        self.copy(read, "(hidden)\n")
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.inner.is_interactive()
+    }
+
+    fn discard_pending(&mut self) -> io::Result<()> {
+        self.inner.discard_pending()
+    }
+
+    fn wait_readable(&mut self, timeout: Duration) -> io::Result<bool> {
+        self.inner.wait_readable(timeout)
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        self.inner.needs_redraw()
+    }
+}
+
+/// reads every line of `inner` with `read_hidden`, for `Input::hidden`
+pub(crate) struct HiddenReader<'a>(pub(crate) &'a mut dyn InputReader);
+
+impl InputReader for HiddenReader<'_> {
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.0.read_hidden(buf)
+    }
+
+    fn read_hidden(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.0.read_hidden(buf)
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.0.is_interactive()
+    }
+
+    fn discard_pending(&mut self) -> io::Result<()> {
+        self.0.discard_pending()
+    }
+
+    fn wait_readable(&mut self, timeout: Duration) -> io::Result<bool> {
+        self.0.wait_readable(timeout)
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        self.0.needs_redraw()
+    }
+}
+
+/// # EchoReader
+///
+/// line discipline for readers that don't echo, like sockets, serial
+/// lines or pinentry style pipes. every accepted line is echoed back
+/// through the shared writer like a local terminal would, backspace and
+/// DEL erase the previous character and `\r` ends a line
+///
+/// # usage
+/// ```
+/// use input_py::io::{EchoReader, MockReader, MockWriter};
+/// use input_py::read_input_with_io;
+///
+/// let (mut reader, mut writer) = EchoReader::new(MockReader::new(&["adx\u{7f}min"]), MockWriter::new());
+/// let user = read_input_with_io("user", None, &mut reader, &mut writer);
+/// assert_eq!(user, Ok("admin".to_string()));
+/// drop(reader);
+/// assert_eq!(writer.into_inner().output, "user:admin\n");
+/// ```
+pub struct EchoReader<R: InputReader, W: OutputWriter> {
+    inner: R,
+    writer: Rc<RefCell<W>>,
+    /// raw input after the last line ending, a `\r` can end a line early
+    pending: String,
+}
+
+/// writer half of an `EchoReader`, shares the writer used for echoing
+pub struct SharedWriter<W: OutputWriter> {
+    inner: Rc<RefCell<W>>,
+}
+
+impl<R: InputReader, W: OutputWriter> EchoReader<R, W> {
+    pub fn new(inner: R, writer: W) -> (EchoReader<R, W>, SharedWriter<W>) {
+        let writer = Rc::new(RefCell::new(writer));
+        let reader = EchoReader {
+            inner,
+            writer: Rc::clone(&writer),
+            pending: String::new(),
+        };
+        (reader, SharedWriter { inner: writer })
+    }
+}
+
+impl<R: InputReader, W: OutputWriter> EchoReader<R, W> {
+    /// the next edited line into `buf`, echoed in full when `shown`,
+    /// otherwise only its line ending
+    fn take_line(&mut self, buf: &mut String, shown: bool) -> io::Result<usize> {
+        if self.pending.is_empty() && self.inner.read_line(&mut self.pending)? == 0 {
+            return Ok(0);
+        }
+
+        let mut line = String::with_capacity(self.pending.len());
+        let mut rest = "";
+        for (i, c) in self.pending.char_indices() {
+            match c {
+                '\u{8}' | '\u{7f}' => {
+                    line.pop();
+                }
+                '\r' if self.pending[i + 1..].starts_with('\n') => {
+                    rest = &self.pending[i + 2..];
+                    break;
+                }
+                '\r' | '\n' => {
+                    rest = &self.pending[i + 1..];
+                    break;
+                }
+                c => line.push(c),
+            }
+        }
+        line.push('\n');
+        self.pending = rest.to_string();
+
+        let mut writer = self.writer.borrow_mut();
+        writer.write_str(if shown { &line } else { "\n" })?;
+        writer.flush()?;
+        buf.push_str(&line);
+        Ok(line.len())
+    }
+}
+
+impl<R: InputReader, W: OutputWriter> InputReader for EchoReader<R, W> {
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.take_line(buf, true)
+    }
+
+    fn read_hidden(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.take_line(buf, false)
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.inner.is_interactive()
+    }
+
+    fn discard_pending(&mut self) -> io::Result<()> {
+        self.pending.clear();
+        self.inner.discard_pending()
+    }
+
+    fn wait_readable(&mut self, timeout: Duration) -> io::Result<bool> {
+        match self.pending.is_empty() {
+            true => self.inner.wait_readable(timeout),
+            false => Ok(true),
+        }
+    }
+
+    fn needs_redraw(&mut self) -> bool {
+        self.inner.needs_redraw()
+    }
+}
+
+impl<W: OutputWriter> SharedWriter<W> {
+    /// the writer, once the `EchoReader` is dropped
+    ///
+    /// # Panics
+    /// when the `EchoReader` is still alive
+    pub fn into_inner(self) -> W {
+        match Rc::try_unwrap(self.inner) {
+            Ok(writer) => writer.into_inner(),
+            Err(_) => panic!("EchoReader still holds the writer"),
+        }
+    }
+}
+
+impl<W: OutputWriter> OutputWriter for SharedWriter<W> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.inner.borrow_mut().write_str(s)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.borrow_mut().flush()
+    }
+
+    fn write_all_lines(&mut self, lines: &[&str]) -> io::Result<()> {
+        self.inner.borrow_mut().write_all_lines(lines)
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.inner.borrow().is_terminal()
+    }
+}
+
+/// # MockReader
+///
+/// returns the given lines one by one, then EOF.
+/// useful for testing code that prompts. lines given to `typed_ahead`
+/// come first and are thrown away by `discard_pending`, like input typed
+/// before the prompt appeared. it acts like piped input unless
+/// `interactive` makes it a terminal
+///
+/// # usage
+/// ```
+/// use input_py::io::MockReader;
+/// let reader = MockReader::new(&["first", "second"]);
+/// ```
+pub struct MockReader {
+    lines: VecDeque<String>,
+    typed_ahead: VecDeque<String>,
+    interactive: bool,
+}
+
+impl MockReader {
+    pub fn new(lines: &[&str]) -> Self {
+        MockReader {
+            lines: lines.iter().map(|l| format!("{}\n", l)).collect(),
+            typed_ahead: VecDeque::new(),
+            interactive: false,
+        }
+    }
+
+    /// lines already waiting when the first prompt is shown
+    pub fn typed_ahead(mut self, lines: &[&str]) -> Self {
+        self.typed_ahead = lines.iter().map(|l| format!("{}\n", l)).collect();
+        self
+    }
+
+    /// act as a terminal a person answers on. default: false
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+}
+
+impl InputReader for MockReader {
+    fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+
+    fn discard_pending(&mut self) -> io::Result<()> {
+        self.typed_ahead.clear();
+        Ok(())
+    }
+
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        let next = match self.typed_ahead.pop_front() {
+            Some(line) => Some(line),
+            None => self.lines.pop_front(),
+        };
+        match next {
+            Some(line) => {
+                buf.push_str(&line);
+                Ok(line.len())
+            }
+            None => Ok(0),
+        }
+    }
+}
+
+/// # MockWriter
+///
+/// collects everything written into `output` and counts the flushes
+#[derive(Default)]
+pub struct MockWriter {
+    pub output: String,
+    pub flushes: usize,
+    terminal: bool,
+}
+
+impl MockWriter {
+    pub fn new() -> Self {
+        MockWriter::default()
+    }
+
+    /// act as a terminal, which shows colors. default: false
+    pub fn terminal(mut self, terminal: bool) -> Self {
+        self.terminal = terminal;
+        self
+    }
+}
+
+impl OutputWriter for MockWriter {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.output.push_str(s);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushes += 1;
+        Ok(())
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.terminal
+    }
+}
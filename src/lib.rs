@@ -1,18 +1,105 @@
-use std::io::{self, Write};
+pub mod answers;
+#[cfg(feature = "async")]
+pub mod async_io;
+pub mod capabilities;
+#[cfg(feature = "color")]
+mod color;
+pub mod completion;
+pub mod config;
+pub mod confirm;
+#[cfg(all(feature = "terminal", windows))]
+mod console;
+#[cfg(feature = "derive")]
+pub mod derive;
+pub mod diff;
+pub mod driver;
+pub mod editor;
+#[cfg(feature = "encodings")]
+pub mod encodings;
+#[cfg(feature = "encryption")]
+pub mod encrypted;
+pub mod error;
+mod expr;
+pub mod form;
+mod form_file;
+pub mod frontend;
+pub mod fuzz;
+pub mod highlight;
+pub mod history;
+pub mod input;
+pub mod io;
+mod json;
+#[cfg(feature = "line-editing")]
+pub mod line_editor;
+pub mod list;
+pub mod locale;
+pub mod map_editor;
+pub mod markdown;
+pub mod menu;
+pub mod metrics;
+pub mod model;
+pub mod multi_select;
+pub mod password;
+#[cfg(feature = "regex")]
+mod patterns;
+pub mod prompter;
+#[cfg(all(feature = "pty", unix))]
+mod pty;
+pub mod range;
+mod regex;
+pub mod render;
+pub mod screen;
+pub mod select;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod service;
+pub mod storage;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod table;
+pub mod template;
+#[cfg(all(feature = "terminal", unix))]
+mod terminal;
+#[cfg(all(feature = "pty", unix))]
+pub mod testing;
+pub mod text_area;
+mod toml;
+pub mod transcript;
+pub mod widget;
 
+pub use confirm::{Confirm, Confirm3, Confirmation};
+pub use error::InputError;
+pub use form::{Answers, Form};
+pub use input::Input;
+pub use list::{Dedup, ListInput, ListMode};
+pub use model::{Answer, Question};
+pub use multi_select::MultiSelect;
+pub use prompter::{DeadlinePolicy, Prompt, Prompter};
+pub use select::Select;
+pub use text_area::TextArea;
+
+use std::borrow::Cow;
+use std::ops::Range;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use config::Theme;
+use model::MaskStyle;
+use io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
 
 /// # input_py
 ///
 /// you can use input like python3
 ///
 /// # Args:
-/// * comment: &str - display text in terminal
-/// if set comment like "test" display like
+/// * comment: &str - display text in terminal.
+///   if set comment like "test" display like
 ///
 /// test:
 ///
 /// # Returns:
-/// Result<String, String>
+/// Result<String, String>. the answer is empty at the end of input too,
+/// `input_opt` tells the two apart
 ///
 /// # usage
 /// ```
@@ -23,14 +110,759 @@ use std::io::{self, Write};
 /// }
 /// ```
 pub fn input(comment: &str) -> Result<String, String> {
+    match read_input_with_io(comment, None, &mut StdinReader, &mut StdoutWriter) {
+        Err(InputError::Eof) => Ok(String::new()),
+        answer => answer.map_err(|e| e.to_string()),
+    }
+}
+
+/// # read_input_with_io
+///
+/// same as `input` but reads from `reader` and writes the prompt to `writer`.
+/// when the answer is empty and `default` is set, the default is returned.
+/// at EOF it fails with `InputError::Eof`, see `read_input_opt_with_io`
+///
+/// # usage
+/// ```
+/// use input_py::read_input_with_io;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut reader = MockReader::new(&[""]);
+/// let mut writer = MockWriter::new();
+/// let name = read_input_with_io("name", Some("guest"), &mut reader, &mut writer);
+/// assert_eq!(name, Ok("guest".to_string()));
+/// assert_eq!(writer.output, "name [guest]:");
+/// ```
+pub fn read_input_with_io(
+    prompt: &str,
+    default: Option<&str>,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+) -> Result<String, InputError> {
+    read_input_as(prompt, prompt, default, reader, writer)
+}
+
+/// # input_opt
+///
+/// `input` that tells the end of input apart from an empty answer:
+/// `Ok(None)` once stdin is closed, so a loop over piped input can stop
+///
+/// # usage
+/// ```no_run
+/// use input_py::input_opt;
+///
+/// while let Some(host) = input_opt("host").unwrap() {
+///     println!("pinging {}", host);
+/// }
+/// ```
+pub fn input_opt(prompt: &str) -> Result<Option<String>, InputError> {
+    read_input_opt_with_io(prompt, None, &mut StdinReader, &mut StdoutWriter)
+}
+
+/// # read_input_opt_with_io
+///
+/// same as `input_opt` but reads from `reader` and writes the prompt to
+/// `writer`. an empty line still gives `default`, EOF gives `None`
+///
+/// # usage
+/// ```
+/// use input_py::read_input_opt_with_io;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut reader = MockReader::new(&[""]);
+/// let mut writer = MockWriter::new();
+/// let first = read_input_opt_with_io("host", None, &mut reader, &mut writer);
+/// assert_eq!(first, Ok(Some(String::new())));
+/// let second = read_input_opt_with_io("host", None, &mut reader, &mut writer);
+/// assert_eq!(second, Ok(None));
+/// ```
+pub fn read_input_opt_with_io(
+    prompt: &str,
+    default: Option<&str>,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+) -> Result<Option<String>, InputError> {
+    match read_input_with_io(prompt, default, reader, writer) {
+        Err(InputError::Eof) => Ok(None),
+        answer => answer.map(Some),
+    }
+}
+
+/// # input_parse
+///
+/// `input` converted to `T`, like an integer, a float or an IP address.
+/// an answer that doesn't parse fails with `InputError::ParseError`
+///
+/// # usage
+/// ```no_run
+/// use std::net::IpAddr;
+/// use input_py::input_parse;
+///
+/// let port: u16 = input_parse("port").unwrap();
+/// let addr = input_parse::<IpAddr>("address").unwrap();
+/// ```
+pub fn input_parse<T: FromStr>(prompt: &str) -> Result<T, InputError> {
+    read_input_parse_with_io(prompt, None, &mut StdinReader, &mut StdoutWriter)
+}
+
+/// # read_input_parse_with_io
+///
+/// same as `input_parse` but reads from `reader` and writes the prompt to
+/// `writer`. an empty answer parses `default` when set
+///
+/// # usage
+/// ```
+/// use input_py::{read_input_parse_with_io, InputError};
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut reader = MockReader::new(&["", "many"]);
+/// let mut writer = MockWriter::new();
+/// let ratio = read_input_parse_with_io::<f64>("ratio", Some("0.5"), &mut reader, &mut writer);
+/// assert_eq!(ratio, Ok(0.5));
+/// let count = read_input_parse_with_io::<u32>("count", None, &mut reader, &mut writer);
+/// assert_eq!(count, Err(InputError::ParseError("many".to_string())));
+/// ```
+pub fn read_input_parse_with_io<T: FromStr>(
+    prompt: &str,
+    default: Option<&str>,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+) -> Result<T, InputError> {
+    let answer = read_input_with_io(prompt, default, reader, writer)?;
+    answer.parse().map_err(|_| InputError::ParseError(answer))
+}
+
+/// # input_async
+///
+/// `input` for async code: the line is read on a background thread, so
+/// the executor keeps running. works with any runtime. needs the `async`
+/// feature. with the `tokio` feature it is read with tokio's
+/// `AsyncBufReadExt` on the tokio runtime instead
+///
+/// # usage
+/// ```no_run
+/// use input_py::input_async;
+///
+/// async fn ask_name() -> String {
+///     input_async("name").await.unwrap()
+/// }
+/// ```
+#[cfg(feature = "async")]
+pub async fn input_async(prompt: &str) -> Result<String, InputError> {
+    Input::new(prompt).read_async().await
+}
+
+/// # confirm
+///
+/// ask a yes/no question on stdin, asking again until the answer is
+/// y, yes, n or no in any case. see `Input::confirm` for a default
+///
+/// # usage
+/// ```no_run
+/// use input_py::confirm;
+///
+/// if confirm("delete the branch?").unwrap() {
+///     // delete it
+/// }
+/// ```
+pub fn confirm(prompt: &str) -> Result<bool, InputError> {
+    Input::new(prompt).confirm().read()
+}
+
+/// # input_multiline
+///
+/// read lines from stdin until `terminator`, or an empty line when it is
+/// `None`, and return them joined with `\n`. EOF also ends the text
+///
+/// # usage
+/// ```no_run
+/// use input_py::input_multiline;
+///
+/// let description = input_multiline("description", Some("EOF")).unwrap();
+/// ```
+pub fn input_multiline(prompt: &str, terminator: Option<&str>) -> Result<String, InputError> {
+    read_multiline_with_io(prompt, terminator, &mut StdinReader, &mut StdoutWriter)
+}
+
+/// # read_multiline_with_io
+///
+/// same as `input_multiline` but reads from `reader` and writes the prompt
+/// to `writer`
+///
+/// # usage
+/// ```
+/// use input_py::read_multiline_with_io;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut reader = MockReader::new(&["first", "second", "", "next question"]);
+/// let mut writer = MockWriter::new();
+/// let text = read_multiline_with_io("notes", None, &mut reader, &mut writer);
+/// assert_eq!(text, Ok("first\nsecond".to_string()));
+/// assert_eq!(writer.output, "notes (end with an empty line):\n");
+/// ```
+pub fn read_multiline_with_io(
+    prompt: &str,
+    terminator: Option<&str>,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+) -> Result<String, InputError> {
+    TextArea::new(prompt)
+        .plain(true)
+        .terminator(terminator.unwrap_or(""))
+        .read_with_io(reader, writer)
+}
+
+/// # input_lines
+///
+/// read every line on stdin until EOF, each trimmed, like python's
+/// `sys.stdin.readlines()`. the prompt is written once. see
+/// `Input::read_all_lines` for a default for empty lines
+///
+/// # usage
+/// ```no_run
+/// use input_py::input_lines;
+///
+/// let urls = input_lines("urls").unwrap();
+/// ```
+pub fn input_lines(prompt: &str) -> Result<Vec<String>, InputError> {
+    read_lines_with_io(prompt, &mut StdinReader, &mut StdoutWriter)
+}
+
+/// # read_lines_with_io
+///
+/// same as `input_lines` but reads from `reader` and writes the prompt
+/// to `writer`
+///
+/// # usage
+/// ```
+/// use input_py::read_lines_with_io;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut writer = MockWriter::new();
+/// let lines = read_lines_with_io("ids", &mut MockReader::new(&["1 ", "", "3"]), &mut writer);
+/// assert_eq!(lines, Ok(vec!["1".to_string(), String::new(), "3".to_string()]));
+/// assert_eq!(writer.output, "ids:");
+/// ```
+pub fn read_lines_with_io(
+    prompt: &str,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+) -> Result<Vec<String>, InputError> {
+    Input::new(prompt).read_all_lines_with_io(reader, writer)
+}
+
+/// # password_confirm
+///
+/// read a new password twice with the terminal echo off, asking again
+/// until both entries match. see `password::PasswordConfirm` to change
+/// how many mismatches are allowed
+///
+/// # usage
+/// ```no_run
+/// use input_py::password_confirm;
+///
+/// let password = password_confirm("new password", "repeat password").unwrap();
+/// ```
+pub fn password_confirm(prompt: &str, confirm_prompt: &str) -> Result<String, InputError> {
+    password::PasswordConfirm::new(prompt, confirm_prompt).read()
+}
+
+/// # input_or_default_after
+///
+/// ask `prompt` with a countdown and return `default` when nothing is
+/// entered within `after`, like a boot menu that continues on its own.
+/// an answer entered in time is used, an empty one gives the default
+///
+/// # usage
+/// ```no_run
+/// use std::time::Duration;
+/// use input_py::input_or_default_after;
+///
+/// let mode = input_or_default_after("boot mode", "normal", Duration::from_secs(5));
+/// ```
+pub fn input_or_default_after(
+    prompt: &str,
+    default: &str,
+    after: Duration,
+) -> Result<String, InputError> {
+    input_or_default_after_with_io(prompt, default, after, &mut StdinReader, &mut StdoutWriter)
+}
+
+/// # input_or_default_after_with_io
+///
+/// same as `input_or_default_after` on `reader` and `writer`. the prompt
+/// shown once, and on a terminal the seconds left are redrawn in place
+/// every second without touching what was typed. a line typed halfway
+/// when the time runs out is discarded. readers that can't wait, see
+/// `InputReader::wait_readable`, are read at once
+///
+/// # usage
+/// ```
+/// use std::time::Duration;
+/// use input_py::input_or_default_after_with_io;
+/// use input_py::io::{BlockingBridgeReader, MockWriter};
+///
+/// let (mut reader, _sender) = BlockingBridgeReader::channel();
+/// let mut writer = MockWriter::new();
+/// let after = Duration::from_millis(20);
+/// let mode = input_or_default_after_with_io("boot mode", "normal", after, &mut reader, &mut writer);
+/// assert_eq!(mode, Ok("normal".to_string()));
+/// assert_eq!(writer.output, "boot mode [normal] (1s):\n");
+/// ```
+pub fn input_or_default_after_with_io(
+    prompt: &str,
+    default: &str,
+    after: Duration,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+) -> Result<String, InputError> {
+    if let Some(answer) = preset_answer(prompt, prompt, Some(default), reader)? {
+        return Ok(answer.to_string());
+    }
+    let deadline = Instant::now() + after;
+    let theme = Theme::current().for_writer(writer);
+    // the countdown goes between the default hint and the suffix
+    let hint = Theme {
+        prompt_suffix: "",
+        ..theme
+    };
+    let mut shown = String::new();
+    render_prompt_into(&mut shown, prompt, Some(default), &hint);
+    let mut width = 0;
+    let mut text = String::new();
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            // a line typed halfway would end up in the next prompt
+            reader.discard_pending()?;
+            writer.write_str("\n")?;
+            return Ok(default.to_string());
+        }
+        // whole seconds left, rounded up, and the wait until that changes
+        let secs = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+        let tick = left - Duration::from_secs(secs - 1);
+        text.clear();
+        if width == 0 {
+            width = secs.to_string().len();
+            text.push_str(&format!("{} ({}s){}", shown, secs, theme.prompt_suffix));
+        } else if writer.is_terminal() {
+            // redraw the prompt in place, padded to the same width, and put
+            // the cursor back after whatever was typed so far
+            text.push_str(&format!(
+                "\x1b7\r{} ({:>width$}s){}\x1b8",
+                shown, secs, theme.prompt_suffix
+            ));
+        }
+        if !text.is_empty() {
+            writer.write_str(&text)?;
+            writer.flush()?;
+        }
+        if reader.wait_readable(tick)? {
+            let mut buf = String::new();
+            reader.read_line(&mut buf)?;
+            process_input_in_place(&mut buf);
+            return match buf.is_empty() {
+                true => Ok(default.to_string()),
+                false => Ok(buf),
+            };
+        }
+    }
+}
+
+/// `read_input_with_io` for the question `id`
+pub(crate) fn read_input_as(
+    id: &str,
+    prompt: &str,
+    default: Option<&str>,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+) -> Result<String, InputError> {
+    if let Some(answer) = preset_answer(id, prompt, default, reader)? {
+        return Ok(answer.to_string());
+    }
+    let mut text = String::new();
+    render_prompt_into(
+        &mut text,
+        prompt,
+        default,
+        &Theme::current().for_writer(writer),
+    );
+    ask_line(&text, default, None, reader, writer)
+}
+
+/// writes the rendered `prompt` and reads the processed answer, or
+/// `default` if it is empty or none came before `deadline`
+pub(crate) fn ask_line(
+    prompt: &str,
+    default: Option<&str>,
+    deadline: Option<Instant>,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+) -> Result<String, InputError> {
+    writer.write_str(prompt)?;
+    writer.flush()?;
+    if !await_line(prompt, deadline, reader, writer, &mut |_| Ok(()))? {
+        writer.write_str("\n")?;
+        return default.map(str::to_string).ok_or(InputError::TimedOut);
+    }
+
+    let mut buf = String::new();
+    if reader.read_line(&mut buf)? == 0 {
+        return Err(InputError::Eof);
+    }
+    process_input_in_place(&mut buf);
+    match default {
+        Some(default) if buf.is_empty() => Ok(default.to_string()),
+        _ => Ok(buf),
+    }
+}
+
+/// # process_input
+///
+/// turns a raw line into the answer by trimming whitespace and the line ending
+pub fn process_input(raw: &str) -> String {
+    raw.trim().to_string()
+}
+
+/// `process_input` without allocating, trims `buf` itself
+pub(crate) fn process_input_in_place(buf: &mut String) {
+    let end = buf.trim_end().len();
+    buf.truncate(end);
+    let start = buf.len() - buf.trim_start().len();
+    buf.drain(..start);
+}
+
+/// # is_interactive
+///
+/// whether a person can answer on stdin: it is a terminal and
+/// `INPUT_PY_NONINTERACTIVE` is not set. false when stdin is a pipe or
+/// `/dev/null`, like in CI
+///
+/// # usage
+/// ```no_run
+/// use input_py::{input, is_interactive};
+///
+/// let name = match is_interactive() {
+///     true => input("name").unwrap(),
+///     false => "guest".to_string(),
+/// };
+/// ```
+pub fn is_interactive() -> bool {
+    StdinReader.is_interactive()
+}
+
+/// # render_prompt
+///
+/// the exact prompt text the crate writes for `question`, like
+/// `name [guest]:`. pure, for alternate frontends that want the same
+/// formatting
+///
+/// # usage
+/// ```
+/// use input_py::config::Theme;
+/// use input_py::{render_prompt, Question};
+///
+/// let question = Question::new("port", "port").default("5432");
+/// assert_eq!(render_prompt(&question, &Theme::DEFAULT), "port [5432]:");
+/// ```
+pub fn render_prompt(question: &Question, theme: &Theme) -> String {
+    let mut text = String::new();
+    render_masked_prompt_into(
+        &mut text,
+        &question.constraints.with_hint(&question.prompt),
+        question.default.as_deref(),
+        question.mask,
+        theme,
+    );
+    text
+}
+
+/// appends the prompt line for `prompt` and `default` in `theme` to
+/// `buf`, see `Theme::for_writer` for the colors
+pub(crate) fn render_prompt_into(
+    buf: &mut String,
+    prompt: &str,
+    default: Option<&str>,
+    theme: &Theme,
+) {
+    buf.push_str(theme.prompt_prefix);
+    config::paint_into(buf, prompt, theme.colors.prompt_color);
+    if let Some(default) = default {
+        let hint = format!("{}{}{}", theme.default_open, default, theme.default_close);
+        config::paint_into(buf, &hint, theme.colors.default_color);
+    }
+    buf.push_str(theme.prompt_suffix);
+}
+
+/// like `render_prompt_into`, with the default hint masked by `mask`.
+/// a remembered secret is offered as the default but never shown
+pub(crate) fn render_masked_prompt_into(
+    buf: &mut String,
+    prompt: &str,
+    default: Option<&str>,
+    mask: Option<MaskStyle>,
+    theme: &Theme,
+) {
+    match (default, mask) {
+        (Some(default), Some(mask)) => {
+            render_prompt_into(buf, prompt, Some(&mask.apply(default)), theme)
+        }
+        _ => render_prompt_into(buf, prompt, default, theme),
+    }
+}
+
+/// the answer to question `id` given without reading, from the
+/// `config` environment or strict interactive mode. `None` means ask
+pub(crate) fn preset_answer<'a>(
+    id: &str,
+    prompt: &str,
+    default: Option<&'a str>,
+    reader: &dyn InputReader,
+) -> Result<Option<Cow<'a, str>>, InputError> {
+    if let Some(answer) = answers::preset(id)? {
+        return Ok(Some(answer));
+    }
+    if let (true, Some(default)) = (config::env_accepts_defaults(), default) {
+        return Ok(Some(Cow::Borrowed(default)));
+    }
+    if answers::is_playing() {
+        return default
+            .map(|default| Some(Cow::Borrowed(default)))
+            .ok_or_else(|| InputError::MissingAnswer(id.to_string()));
+    }
+    Ok(check_interactive(prompt, default, reader)?.map(Cow::Borrowed))
+}
+
+/// under `config::strict_interactive`, a prompt on a non interactive
+/// reader resolves to `Some(default)` without reading, or fails when it
+/// has no default. so does any prompt while `answers::play` runs a
+/// playbook. `None` means ask as usual
+pub(crate) fn check_interactive<'a>(
+    prompt: &str,
+    default: Option<&'a str>,
+    reader: &dyn InputReader,
+) -> Result<Option<&'a str>, InputError> {
+    if answers::is_playing() {
+        return default
+            .map(Some)
+            .ok_or_else(|| InputError::MissingAnswer(prompt.to_string()));
+    }
+    if !config::is_strict_interactive() || reader.is_interactive() {
+        return Ok(None);
+    }
+    match default {
+        Some(default) => Ok(Some(default)),
+        None => Err(InputError::NotInteractive(prompt.to_string())),
+    }
+}
+
+/// writes `prompt` and reads one processed answer, `None` on EOF
+pub(crate) fn read_answer(
+    prompt: &str,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+) -> Result<Option<String>, InputError> {
+    read_answer_redrawn(prompt, reader, writer, &mut |_| Ok(()))
+}
+
+/// `read_answer` that writes the lines above the prompt again with
+/// `redraw` after a resize or Ctrl+Z, see `await_line`
+pub(crate) fn read_answer_redrawn(
+    prompt: &str,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+    redraw: &mut dyn FnMut(&mut dyn OutputWriter) -> Result<(), InputError>,
+) -> Result<Option<String>, InputError> {
+    read_answer_until(prompt, None, reader, writer, redraw)
+}
+
+/// `read_answer_redrawn` that fails with `InputError::TimedOut` when no
+/// line came before `deadline`
+pub(crate) fn read_answer_until(
+    prompt: &str,
+    deadline: Option<Instant>,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+    redraw: &mut dyn FnMut(&mut dyn OutputWriter) -> Result<(), InputError>,
+) -> Result<Option<String>, InputError> {
+    check_interactive(prompt, None, reader)?;
+    writer.write_str(prompt)?;
+    writer.flush()?;
+    if !await_line(prompt, deadline, reader, writer, redraw)? {
+        writer.write_str("\n")?;
+        return Err(InputError::TimedOut);
+    }
     let mut buf = String::new();
-    print!("{}:", comment);
-    io::stdout().flush().expect("Failed to flush stdout");
+    if reader.read_line(&mut buf)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(process_input(&buf)))
+}
+
+/// how often `await_line` looks for a signal that came in just before
+/// it started to wait
+const REDRAW_POLL: Duration = Duration::from_secs(1);
+
+/// waits until `reader` has a line, false when `deadline` passed first.
+/// a resize of the terminal meanwhile tears what is on screen and Ctrl+Z
+/// leaves the shell's output below the prompt, so `redraw` and `prompt`
+/// are written again on a fresh line. input typed ahead is discarded
+/// when the deadline passes. readers that can't wait return at once
+pub(crate) fn await_line(
+    prompt: &str,
+    deadline: Option<Instant>,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+    redraw: &mut dyn FnMut(&mut dyn OutputWriter) -> Result<(), InputError>,
+) -> Result<bool, InputError> {
+    // drops redraws noted before this prompt
+    reader.needs_redraw();
+    loop {
+        let poll = match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => REDRAW_POLL,
+        };
+        if reader.wait_readable(poll.min(REDRAW_POLL))? {
+            return Ok(true);
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            // a line typed halfway would end up in the next prompt
+            reader.discard_pending()?;
+            return Ok(false);
+        }
+        if reader.needs_redraw() {
+            writer.write_str("\n")?;
+            redraw(writer)?;
+            writer.write_str(prompt)?;
+            writer.flush()?;
+        }
+    }
+}
 
-    if io::stdin().read_line(&mut buf).is_ok() {
-        let trimmed = buf.trim().to_string();
-        Ok(trimmed)
-    } else {
-        Err("Failed to read input".to_string())
+/// counts the invalid answers in a row of a reprompt loop and gives up
+/// at `config::max_consecutive_errors` or the prompt's own limit
+pub(crate) struct Retries {
+    rejected: u32,
+    max_attempts: Option<u32>,
+    theme: Theme,
+}
+
+impl Retries {
+    pub(crate) fn new() -> Self {
+        Retries::with_max_attempts(None)
+    }
+
+    /// gives up with `InputError::AttemptsExceeded` after `max_attempts`
+    /// rejections in a row, if set
+    pub(crate) fn with_max_attempts(max_attempts: Option<u32>) -> Self {
+        Retries {
+            rejected: 0,
+            max_attempts,
+            theme: Theme::current(),
+        }
+    }
+
+    /// write the errors in `theme` instead of `config::theme`
+    pub(crate) fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// report `msg` to the user and count the rejection
+    pub(crate) fn reject(
+        &mut self,
+        writer: &mut dyn OutputWriter,
+        msg: &str,
+    ) -> Result<(), InputError> {
+        write_themed_error(writer, msg, &self.theme)?;
+        self.count()
+    }
+
+    /// `reject` and, when a person reads, show `line` with a caret
+    /// underline below its chars in `span`, see `write_caret`
+    pub(crate) fn reject_at(
+        &mut self,
+        interactive: bool,
+        writer: &mut dyn OutputWriter,
+        msg: &str,
+        line: &str,
+        span: Range<usize>,
+        note: &str,
+    ) -> Result<(), InputError> {
+        write_themed_error(writer, msg, &self.theme)?;
+        if interactive {
+            write_caret(writer, line, span, note)?;
+        }
+        self.count()
+    }
+
+    /// count a rejection reported by other means
+    pub(crate) fn count(&mut self) -> Result<(), InputError> {
+        metrics::reject();
+        self.rejected += 1;
+        if self.max_attempts.is_some_and(|max| self.rejected >= max) {
+            return Err(InputError::AttemptsExceeded {
+                attempts: self.rejected,
+            });
+        }
+        let max = config::consecutive_error_limit();
+        if max > 0 && self.rejected >= max {
+            return Err(InputError::TooManyErrors(self.rejected));
+        }
+        Ok(())
+    }
+
+    /// the attempt an answer given now is, 1 without rejections
+    pub(crate) fn attempts(&self) -> u32 {
+        self.rejected + 1
+    }
+
+    /// an answer was accepted, the next rejection starts a new row
+    pub(crate) fn accepted(&mut self) {
+        self.rejected = 0;
+    }
+}
+
+/// reports rejected input to the user
+pub(crate) fn write_error(writer: &mut dyn OutputWriter, msg: &str) -> Result<(), InputError> {
+    write_themed_error(writer, msg, &Theme::current())
+}
+
+/// `write_error` with the prefix and color of `theme`
+fn write_themed_error(
+    writer: &mut dyn OutputWriter,
+    msg: &str,
+    theme: &Theme,
+) -> Result<(), InputError> {
+    let color = theme.for_writer(writer).colors.error_color;
+    let mut line = String::new();
+    config::paint_into(&mut line, &format!("{}{}", theme.error_prefix, msg), color);
+    line.push('\n');
+    writer.write_str(&line)?;
+    Ok(())
+}
+
+/// writes `line` with a caret underline below the chars in `span`, so
+/// the user sees what to fix, followed by `note` if any
+///
+/// ```text
+///   80, http, 443
+///       ^^^^
+/// ```
+pub(crate) fn write_caret(
+    writer: &mut dyn OutputWriter,
+    line: &str,
+    span: Range<usize>,
+    note: &str,
+) -> Result<(), InputError> {
+    let start = span.start.min(line.chars().count());
+    let width = span.end.saturating_sub(start).max(1);
+    let mut text = format!("  {}\n  {}{}", line, " ".repeat(start), "^".repeat(width));
+    if !note.is_empty() {
+        text.push(' ');
+        text.push_str(note);
     }
+    text.push('\n');
+    writer.write_str(&text)?;
+    Ok(())
 }
@@ -0,0 +1,925 @@
+//! # line_editor
+//!
+//! editing stdin prompts in raw mode. with the `line-editing` feature
+//! `StdinReader` reads from a terminal through an `Editor` instead of the
+//! terminal's canonical mode, so arrows, Home/End, word moves and
+//! deletion, history and the kill ring work in every prompt. the bytes of
+//! the terminal are decoded by a `KeyDecoder` and drawn by a
+//! `LineSession`, which can also drive other byte streams.
+//!
+//! the line is drawn on the row of the prompt: lines longer than the
+//! terminal and wide chars are not laid out, and only unix terminals are
+//! edited, others read lines as before
+
+use std::collections::VecDeque;
+#[cfg(unix)]
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::str;
+use std::sync::{Mutex, PoisonError};
+#[cfg(unix)]
+use std::time::Duration;
+
+#[cfg(unix)]
+use crate::capabilities::{self, Degradation};
+use crate::completion::SharedCompleter;
+use crate::editor::{Editor, Key, KeyEvent, Mouse, MouseKind, Outcome};
+use crate::history::History;
+use crate::widget::{Event, Role, StyledWriter, TextCanvas, Widget};
+
+/// clears the screen with the cursor at the top
+const CLEAR: &str = "\u{1b}[H\u{1b}[2J";
+/// clears from the cursor to the end of the row
+const CLEAR_REST: &str = "\u{1b}[K";
+/// clears from the cursor to the end of the screen
+const CLEAR_BELOW: &str = "\u{1b}[J";
+/// asks the terminal where the cursor is, it answers `ESC [ row ; column R`
+const REPORT_CURSOR: &str = "\u{1b}[6n";
+/// reports clicks and the wheel as SGR sequences, and stops it
+#[cfg(unix)]
+const MOUSE_ON: &str = "\u{1b}[?1000h\u{1b}[?1006h";
+#[cfg(unix)]
+const MOUSE_OFF: &str = "\u{1b}[?1006l\u{1b}[?1000l";
+/// escape sequences longer than this are dropped as garbage
+const MAX_SEQUENCE: usize = 32;
+
+/// # KeyDecoder
+///
+/// turns the bytes a terminal sends in raw mode into key events. a
+/// sequence split between reads is kept until the rest arrives, and a
+/// lone Esc waits for `flush` as it starts sequences too
+///
+/// # usage
+/// ```
+/// use input_py::editor::{Key, KeyEvent};
+/// use input_py::line_editor::KeyDecoder;
+///
+/// let mut decoder = KeyDecoder::new();
+/// assert_eq!(decoder.feed(b"a\x1b[D"), [Key::Char('a').into(), Key::Left.into()]);
+/// assert_eq!(decoder.feed(b"\x01\x1b"), [KeyEvent::ctrl('a')]);
+/// assert!(decoder.is_pending());
+/// assert_eq!(decoder.flush(), [Key::Esc.into()]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct KeyDecoder {
+    pending: Vec<u8>,
+}
+
+/// what the start of the bytes holds
+enum Decoded {
+    Key(KeyEvent, usize),
+    /// bytes that aren't a key, like an unknown sequence
+    Skip(usize),
+    Incomplete,
+}
+
+impl KeyDecoder {
+    pub fn new() -> Self {
+        KeyDecoder::default()
+    }
+
+    /// the keys in `bytes` and the bytes kept from earlier calls
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<KeyEvent> {
+        self.pending.extend_from_slice(bytes);
+        let mut keys = Vec::new();
+        let mut at = 0;
+        while at < self.pending.len() {
+            match decode(&self.pending[at..]) {
+                Decoded::Key(key, len) => {
+                    keys.push(key);
+                    at += len;
+                }
+                Decoded::Skip(len) => at += len,
+                Decoded::Incomplete => break,
+            }
+        }
+        self.pending.drain(..at);
+        keys
+    }
+
+    /// whether the bytes so far end inside a sequence
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// the kept bytes as they are, for when nothing followed them in
+    /// time. a lone Esc is the Esc key
+    pub fn flush(&mut self) -> Vec<KeyEvent> {
+        let pending = std::mem::take(&mut self.pending);
+        match pending.split_first() {
+            Some((0x1b, rest)) => {
+                let mut keys = vec![Key::Esc.into()];
+                keys.extend(self.feed(rest));
+                self.pending.clear();
+                keys
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn decode(bytes: &[u8]) -> Decoded {
+    match bytes[0] {
+        0x1b => decode_escape(bytes),
+        b'\r' | b'\n' => Decoded::Key(Key::Enter.into(), 1),
+        b'\t' => Decoded::Key(Key::Tab.into(), 1),
+        0x7f | 0x08 => Decoded::Key(Key::Backspace.into(), 1),
+        b @ 0x01..=0x1a => Decoded::Key(KeyEvent::ctrl(char::from(b'a' + b - 1)), 1),
+        0x00..=0x1f => Decoded::Skip(1),
+        b => {
+            let len = match b {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => 1,
+            };
+            if bytes.len() < len {
+                return Decoded::Incomplete;
+            }
+            match str::from_utf8(&bytes[..len])
+                .ok()
+                .and_then(|s| s.chars().next())
+            {
+                Some(c) => Decoded::Key(Key::Char(c).into(), len),
+                None => Decoded::Skip(1),
+            }
+        }
+    }
+}
+
+/// Esc alone, a CSI or SS3 sequence, or a key pressed with Alt
+fn decode_escape(bytes: &[u8]) -> Decoded {
+    match bytes.get(1) {
+        None => Decoded::Incomplete,
+        Some(b'[') => decode_csi(bytes),
+        Some(b'O') => match bytes.get(2) {
+            None => Decoded::Incomplete,
+            Some(&b) => match final_key(b) {
+                Some(key) => Decoded::Key(key.into(), 3),
+                None => Decoded::Skip(3),
+            },
+        },
+        Some(0x1b) => Decoded::Key(Key::Esc.into(), 1),
+        Some(_) => match decode(&bytes[1..]) {
+            Decoded::Key(key, len) => Decoded::Key(KeyEvent { alt: true, ..key }, len + 1),
+            Decoded::Skip(len) => Decoded::Skip(len + 1),
+            Decoded::Incomplete => Decoded::Incomplete,
+        },
+    }
+}
+
+/// `ESC [ params final`, like `ESC [ 1 ; 5 C` for Ctrl+Right
+fn decode_csi(bytes: &[u8]) -> Decoded {
+    let Some(end) = bytes[2..].iter().position(|b| (0x40..=0x7e).contains(b)) else {
+        return match bytes.len() > MAX_SEQUENCE {
+            true => Decoded::Skip(bytes.len()),
+            false => Decoded::Incomplete,
+        };
+    };
+    let len = end + 3;
+    let params = str::from_utf8(&bytes[2..len - 1]).unwrap_or("");
+    if let Some(params) = params.strip_prefix('<') {
+        return match decode_mouse(params, bytes[len - 1]) {
+            Some(mouse) => Decoded::Key(Key::Mouse(mouse).into(), len),
+            None => Decoded::Skip(len),
+        };
+    }
+    let mut numbers = params.split(';').map(|n| n.parse::<u8>().unwrap_or(1));
+    let first = numbers.next().unwrap_or(1);
+    let modifiers = numbers.next().unwrap_or(1).saturating_sub(1);
+    let key = match bytes[len - 1] {
+        b'~' => match first {
+            1 | 7 => Some(Key::Home),
+            4 | 8 => Some(Key::End),
+            3 => Some(Key::Delete),
+            11..=15 => Some(Key::F(first - 10)),
+            17..=21 => Some(Key::F(first - 11)),
+            23 | 24 => Some(Key::F(first - 12)),
+            _ => None,
+        },
+        b => final_key(b),
+    };
+    match key {
+        Some(key) => Decoded::Key(
+            KeyEvent {
+                key,
+                alt: modifiers & 2 != 0,
+                ctrl: modifiers & 4 != 0,
+            },
+            len,
+        ),
+        None => Decoded::Skip(len),
+    }
+}
+
+/// an SGR mouse report, `ESC [ < button ; column ; row M`, where `m`
+/// ends a release. only left clicks and the wheel are kept
+fn decode_mouse(params: &str, end: u8) -> Option<Mouse> {
+    let mut numbers = params.split(';').map(|n| n.parse::<u16>().ok());
+    let (button, column, row) = (numbers.next()??, numbers.next()??, numbers.next()??);
+    // the modifiers are in bits 2 to 4
+    let kind = match (button & !0b11100, end) {
+        (0, b'M') => MouseKind::Click,
+        (64, _) => MouseKind::ScrollUp,
+        (65, _) => MouseKind::ScrollDown,
+        _ => return None,
+    };
+    Some(Mouse {
+        kind,
+        row: row.checked_sub(1)?,
+        column: column.checked_sub(1)?,
+    })
+}
+
+/// the key named by the last byte of a CSI or SS3 sequence
+fn final_key(b: u8) -> Option<Key> {
+    match b {
+        b'A' => Some(Key::Up),
+        b'B' => Some(Key::Down),
+        b'C' => Some(Key::Right),
+        b'D' => Some(Key::Left),
+        b'H' => Some(Key::Home),
+        b'F' => Some(Key::End),
+        b'P'..=b'S' => Some(Key::F(b - b'P' + 1)),
+        _ => None,
+    }
+}
+
+/// # LineSession
+///
+/// an `Editor` on a terminal: bytes go in, escape codes redrawing the
+/// line after the prompt come out. text typed at the end of the line is
+/// written as it is, like the terminal's echo. keys typed after an
+/// accepted line are kept for the next one, so pasted lines answer
+/// prompts in turn
+///
+/// # usage
+/// ```
+/// use input_py::editor::{Editor, Outcome};
+/// use input_py::line_editor::LineSession;
+///
+/// let mut session = LineSession::new(Editor::new());
+/// let mut out = String::new();
+/// session.begin("name: ", &mut out);
+/// assert_eq!(session.feed(b"ana\x1b[Dn", &mut out), None);
+/// assert!(out.ends_with("\rname: anna\u{1b}[K\u{1b}[1D"));
+/// assert_eq!(session.feed(b"\r", &mut out), Some(Outcome::Accept("anna".to_string())));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineSession {
+    prompt: String,
+    editor: Editor,
+    decoder: KeyDecoder,
+    ahead: VecDeque<KeyEvent>,
+    /// what is on the screen after the prompt, while the cursor is at
+    /// its end
+    drawn: Option<String>,
+    /// what the next line starts with, see `prefill`
+    prefill: Option<String>,
+}
+
+impl LineSession {
+    pub fn new(editor: Editor) -> Self {
+        LineSession {
+            prompt: String::new(),
+            editor,
+            decoder: KeyDecoder::new(),
+            ahead: VecDeque::new(),
+            drawn: None,
+            prefill: None,
+        }
+    }
+
+    /// start a line after `prompt`, which is on the screen already.
+    /// keys typed ahead are applied and may finish it right away
+    pub fn begin(&mut self, prompt: &str, out: &mut String) -> Option<Outcome> {
+        self.prompt = prompt.to_string();
+        self.drawn = Some(String::new());
+        if let Some(line) = self.prefill.take() {
+            self.editor.set_line(&line);
+        }
+        self.update(out);
+        self.run(out)
+    }
+
+    /// start the next line with `line` to edit, like a rejected answer
+    /// to fix instead of typing it again
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::editor::{Editor, Outcome};
+    /// use input_py::line_editor::LineSession;
+    ///
+    /// let mut session = LineSession::new(Editor::new());
+    /// session.prefill("anna!");
+    /// let mut out = String::new();
+    /// session.begin("name: ", &mut out);
+    /// assert_eq!(out, "anna!");
+    /// assert_eq!(session.feed(b"\x7f\r", &mut out), Some(Outcome::Accept("anna".to_string())));
+    /// ```
+    pub fn prefill(&mut self, line: &str) {
+        self.prefill = Some(line.to_string());
+    }
+
+    /// apply the keys in `bytes`, writing the redrawn line to `out`.
+    /// returns the outcome ending the line, or asking the backend to
+    /// suspend. `Continue` and `ClearScreen` are handled here
+    pub fn feed(&mut self, bytes: &[u8], out: &mut String) -> Option<Outcome> {
+        self.ahead.extend(self.decoder.feed(bytes));
+        self.run(out)
+    }
+
+    /// whether the last bytes end inside an escape sequence. a backend
+    /// waits a little for the rest and calls `flush_escape` without it
+    pub fn is_pending(&self) -> bool {
+        self.decoder.is_pending()
+    }
+
+    /// take a pending Esc as the key itself
+    pub fn flush_escape(&mut self, out: &mut String) -> Option<Outcome> {
+        self.ahead.extend(self.decoder.flush());
+        self.run(out)
+    }
+
+    /// draw the prompt and the line again, after a resize or a resume
+    pub fn redraw(&mut self, out: &mut String) {
+        self.drawn = None;
+        self.update(out);
+    }
+
+    pub fn editor(&self) -> &Editor {
+        &self.editor
+    }
+
+    pub fn editor_mut(&mut self) -> &mut Editor {
+        &mut self.editor
+    }
+
+    pub fn into_editor(self) -> Editor {
+        self.editor
+    }
+
+    /// write the completions Tab couldn't choose between under the line,
+    /// the line is drawn again below them
+    fn list_completions(&mut self, out: &mut String) {
+        out.push_str("\r\n");
+        out.push_str(&self.editor.completions().join("  "));
+        out.push_str("\r\n");
+        self.drawn = None;
+    }
+
+    /// draw the line, only the new text when it was added at the end
+    fn update(&mut self, out: &mut String) {
+        let mut line = AnsiLine::default();
+        self.editor.render(&mut line);
+        self.draw(&line.text, line.after_cursor.unwrap_or(0), out);
+    }
+
+    /// put `text` after the prompt with the cursor `after` chars before
+    /// its end
+    fn draw(&mut self, text: &str, after: usize, out: &mut String) {
+        match self.drawn.as_deref() {
+            Some(drawn) if after == 0 && text.starts_with(drawn) => {
+                out.push_str(&text[drawn.len()..]);
+            }
+            _ => {
+                out.push('\r');
+                out.push_str(&self.prompt);
+                out.push_str(text);
+                out.push_str(CLEAR_REST);
+                if after > 0 {
+                    out.push_str(&format!("\u{1b}[{}D", after));
+                }
+            }
+        }
+        self.drawn = (after == 0).then(|| text.to_string());
+    }
+
+    fn run(&mut self, out: &mut String) -> Option<Outcome> {
+        let mut changed = false;
+        while let Some(key) = self.ahead.pop_front() {
+            match self.editor.handle_key(key) {
+                Outcome::Continue if !self.editor.completions().is_empty() => {
+                    self.list_completions(out);
+                    changed = true;
+                }
+                Outcome::Continue => changed = true,
+                Outcome::ClearScreen => {
+                    out.push_str(CLEAR);
+                    self.drawn = None;
+                    changed = true;
+                }
+                Outcome::Accept(line) => {
+                    // drawn without the mode indicator or a search
+                    let mut shown = self.editor.clone();
+                    shown.set_line(&line);
+                    self.draw(&shown.styled_line().render(), 0, out);
+                    return Some(Outcome::Accept(line));
+                }
+                outcome => {
+                    if changed {
+                        self.update(out);
+                    }
+                    return Some(outcome);
+                }
+            }
+        }
+        if changed {
+            self.update(out);
+        }
+        None
+    }
+}
+
+/// a rendered line with the styles as escape codes
+#[derive(Default)]
+struct AnsiLine {
+    text: String,
+    /// chars drawn after the cursor
+    after_cursor: Option<usize>,
+}
+
+impl StyledWriter for AnsiLine {
+    fn write(&mut self, text: &str, role: Role) {
+        let (open, close) = match role {
+            Role::Styled(style) => style,
+            _ => ("", ""),
+        };
+        self.text.push_str(open);
+        self.text.push_str(text);
+        self.text.push_str(close);
+        if let Some(after) = &mut self.after_cursor {
+            *after += text.chars().count();
+        }
+    }
+
+    fn end_line(&mut self) {}
+
+    fn set_cursor(&mut self) {
+        self.after_cursor = Some(0);
+    }
+}
+
+/// # WidgetSession
+///
+/// a `Widget` on a terminal, like `LineSession` for the editor: bytes go
+/// in, the widget drawn again from the row it started on comes out. with
+/// the mouse `begin` asks the terminal where the cursor is, so clicks
+/// reach the widget relative to its first line
+///
+/// # usage
+/// ```
+/// use input_py::line_editor::WidgetSession;
+/// use input_py::widget::Event;
+/// use input_py::Select;
+///
+/// let select = Select::new("env", &["dev", "prod"]).into_widget().unwrap();
+/// let mut session = WidgetSession::new(select);
+/// let mut out = String::new();
+/// session.begin(true, &mut out);
+/// assert!(out.ends_with("env\r\n> 1. dev\r\n  2. prod\u{1b}[6n"));
+/// // the cursor is on row 12 of the terminal, the widget starts on row 10
+/// assert_eq!(session.feed(b"\x1b[12;9R", &mut out), None);
+/// // a click on row 11 counted from 0, the line of "prod"
+/// let click = session.feed(b"\x1b[<0;5;12M", &mut out);
+/// assert_eq!(click, Some(Event::Done("prod".to_string())));
+/// ```
+pub struct WidgetSession<W: Widget> {
+    widget: W,
+    decoder: KeyDecoder,
+    /// lines on the screen, the cursor is at the end of the last
+    drawn: usize,
+    /// terminal row of the first line, counted from 0, once reported
+    top: Option<u16>,
+    /// bytes that may start the cursor report, while it is awaited
+    report: Option<Vec<u8>>,
+    interrupted: bool,
+}
+
+impl<W: Widget> WidgetSession<W> {
+    pub fn new(widget: W) -> Self {
+        WidgetSession {
+            widget,
+            decoder: KeyDecoder::new(),
+            drawn: 0,
+            top: None,
+            report: None,
+            interrupted: false,
+        }
+    }
+
+    /// draw the widget from the start of the cursor's row. with `mouse`
+    /// the terminal is asked where that is, mouse reporting itself is up
+    /// to the backend
+    pub fn begin(&mut self, mouse: bool, out: &mut String) {
+        self.draw(out);
+        if mouse {
+            out.push_str(REPORT_CURSOR);
+            self.report = Some(Vec::new());
+        }
+    }
+
+    /// apply the keys in `bytes`, writing the redrawn widget to `out`.
+    /// returns the event ending the widget
+    pub fn feed(&mut self, bytes: &[u8], out: &mut String) -> Option<Event<W::Output>> {
+        let keys = match self.report.take() {
+            Some(mut held) => {
+                held.extend_from_slice(bytes);
+                let rest = self.take_report(held);
+                self.decoder.feed(&rest)
+            }
+            None => self.decoder.feed(bytes),
+        };
+        self.run(keys, out)
+    }
+
+    /// whether the last bytes end inside an escape sequence. a backend
+    /// waits a little for the rest and calls `flush_escape` without it
+    pub fn is_pending(&self) -> bool {
+        self.decoder.is_pending() || self.report.as_ref().is_some_and(|held| !held.is_empty())
+    }
+
+    /// take the pending bytes as they are, a lone Esc is the Esc key
+    pub fn flush_escape(&mut self, out: &mut String) -> Option<Event<W::Output>> {
+        let mut keys = Vec::new();
+        if let Some(held) = self.report.as_mut() {
+            keys = self.decoder.feed(&std::mem::take(held));
+        }
+        keys.extend(self.decoder.flush());
+        self.run(keys, out)
+    }
+
+    /// draw the widget again, after a resize
+    pub fn redraw(&mut self, out: &mut String) {
+        self.draw(out);
+    }
+
+    /// whether the widget was cancelled with Ctrl+C rather than Esc
+    pub fn interrupted(&self) -> bool {
+        self.interrupted
+    }
+
+    pub fn into_widget(self) -> W {
+        self.widget
+    }
+
+    fn draw(&mut self, out: &mut String) {
+        let mut canvas = TextCanvas::new();
+        self.widget.render(&mut canvas);
+        if self.drawn > 1 {
+            out.push_str(&format!("\u{1b}[{}A", self.drawn - 1));
+        }
+        out.push('\r');
+        out.push_str(CLEAR_BELOW);
+        out.push_str(&canvas.lines().join("\r\n"));
+        self.drawn = canvas.lines().len();
+    }
+
+    /// `bytes` without the cursor report. a start of it at the end is
+    /// held back for the next bytes
+    fn take_report(&mut self, mut bytes: Vec<u8>) -> Vec<u8> {
+        let mut at = 0;
+        while let Some(found) = bytes[at..].iter().position(|b| *b == 0x1b) {
+            let start = at + found;
+            match cursor_report(&bytes[start..]) {
+                Report::Row(row, len) => {
+                    // the cursor is on the last line
+                    let lines = u16::try_from(self.drawn).unwrap_or(u16::MAX);
+                    self.top = Some(row.saturating_sub(lines));
+                    bytes.drain(start..start + len);
+                    return bytes;
+                }
+                Report::Incomplete => {
+                    self.report = Some(bytes.split_off(start));
+                    return bytes;
+                }
+                Report::Not => at = start + 1,
+            }
+        }
+        self.report = Some(Vec::new());
+        bytes
+    }
+
+    fn run(&mut self, keys: Vec<KeyEvent>, out: &mut String) -> Option<Event<W::Output>> {
+        let mut changed = false;
+        for mut key in keys {
+            if let Key::Mouse(mouse) = &mut key.key {
+                // clicks before the report can't be placed
+                let Some(row) = self.top.and_then(|top| mouse.row.checked_sub(top)) else {
+                    continue;
+                };
+                mouse.row = row;
+            }
+            match self.widget.handle_key(key) {
+                Event::Continue => changed = true,
+                Event::Cancelled => {
+                    self.interrupted = key == KeyEvent::ctrl('c');
+                    return Some(Event::Cancelled);
+                }
+                done => return Some(done),
+            }
+        }
+        if changed {
+            self.draw(out);
+        }
+        None
+    }
+}
+
+/// what the start of the bytes holds while the cursor report is awaited
+enum Report {
+    /// the row, counted from 1, and the length of the report
+    Row(u16, usize),
+    Incomplete,
+    Not,
+}
+
+/// the cursor report `ESC [ row ; column R` at the start of `bytes`
+fn cursor_report(bytes: &[u8]) -> Report {
+    if bytes.get(1).is_some_and(|b| *b != b'[') {
+        return Report::Not;
+    }
+    let (mut row, mut separated, mut digits) = (0u16, false, 0);
+    for (at, b) in bytes.iter().enumerate().skip(2) {
+        match b {
+            b'0'..=b'9' => {
+                digits += 1;
+                if !separated {
+                    row = row.saturating_mul(10).saturating_add(u16::from(b - b'0'));
+                }
+            }
+            b';' if !separated && digits > 0 => (separated, digits) = (true, 0),
+            b'R' if separated && digits > 0 => return Report::Row(row, at + 1),
+            _ => return Report::Not,
+        }
+    }
+    Report::Incomplete
+}
+
+/// a `Widget` on stdin in `raw` mode with mouse reporting on, for the
+/// selects that `mouse` was turned on for. Esc aborts and Ctrl+C raises
+/// SIGINT once the terminal is restored, like in `read_stdin`
+#[cfg(unix)]
+pub(crate) fn run_widget<W: Widget>(
+    raw: crate::terminal::RawMode,
+    widget: W,
+) -> Result<W::Output, crate::error::InputError> {
+    use crate::error::InputError;
+
+    /// turns mouse reporting off again however the widget ends
+    struct Reporting;
+    impl Drop for Reporting {
+        fn drop(&mut self) {
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(MOUSE_OFF.as_bytes());
+            let _ = stdout.flush();
+        }
+    }
+
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout();
+    let mut session = WidgetSession::new(widget);
+    let mut out = String::from(MOUSE_ON);
+    let reporting = Reporting;
+    session.begin(true, &mut out);
+    loop {
+        stdout.write_all(out.as_bytes())?;
+        stdout.flush()?;
+        out.clear();
+        let pending = session.is_pending();
+        let wait = match pending {
+            true => ESCAPE_WAIT,
+            false => Duration::from_secs(60),
+        };
+        let event = if crate::terminal::poll_input(wait)? {
+            let bytes = stdin.fill_buf()?;
+            if bytes.is_empty() {
+                stdout.write_all(b"\r\n")?;
+                return Err(InputError::Eof);
+            }
+            let read = bytes.len();
+            let event = session.feed(bytes, &mut out);
+            stdin.consume(read);
+            event
+        } else if pending {
+            session.flush_escape(&mut out)
+        } else {
+            if crate::terminal::take_redraw() {
+                session.redraw(&mut out);
+            }
+            None
+        };
+        match event {
+            Some(Event::Done(value)) => {
+                stdout.write_all(format!("{}\r\n", out).as_bytes())?;
+                stdout.flush()?;
+                return Ok(value);
+            }
+            Some(Event::Cancelled) if session.interrupted() => {
+                stdout.write_all(format!("{}^C\r\n", out).as_bytes())?;
+                stdout.flush()?;
+                drop(reporting);
+                drop(raw);
+                if !crate::config::catches_interrupts() {
+                    crate::terminal::interrupt();
+                }
+                return Err(InputError::Interrupted);
+            }
+            Some(Event::Cancelled) => {
+                stdout.write_all(format!("{}\r\n", out).as_bytes())?;
+                stdout.flush()?;
+                return Err(InputError::Aborted);
+            }
+            Some(Event::Continue) | None => {}
+        }
+    }
+}
+
+/// the session of stdin, kept between prompts for the history
+static SESSION: Mutex<Option<LineSession>> = Mutex::new(None);
+/// what `StdoutWriter` wrote after its last line break, the prompt
+static PROMPT: Mutex<String> = Mutex::new(String::new());
+
+/// the editor stdin prompts are edited with, for key bindings, a
+/// highlighter or history from earlier runs. `Editor::new()` otherwise
+///
+/// # usage
+/// ```
+/// use input_py::editor::{EditMode, Editor};
+///
+/// input_py::line_editor::set_editor(Editor::new().mode(EditMode::Vi));
+/// ```
+pub fn set_editor(editor: Editor) {
+    let mut session = SESSION.lock().unwrap_or_else(PoisonError::into_inner);
+    match &mut *session {
+        Some(session) => *session.editor_mut() = editor,
+        None => *session = Some(LineSession::new(editor)),
+    }
+}
+
+/// run `f` on the session of stdin
+pub(crate) fn with_session<T>(f: impl FnOnce(&mut LineSession) -> T) -> T {
+    let mut session = SESSION.lock().unwrap_or_else(PoisonError::into_inner);
+    f(session.get_or_insert_with(|| LineSession::new(Editor::new())))
+}
+
+/// keep track of the row stdout is on, called by `StdoutWriter`
+pub(crate) fn note_output(text: &str) {
+    let mut prompt = PROMPT.lock().unwrap_or_else(PoisonError::into_inner);
+    match text.rfind(['\n', '\r']) {
+        Some(at) => *prompt = text[at + 1..].to_string(),
+        None => prompt.push_str(text),
+    }
+}
+
+/// the prompt before a line read from stdin, the row is done afterwards
+pub(crate) fn take_prompt() -> String {
+    std::mem::take(&mut *PROMPT.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// how long the rest of an escape sequence may take after the Esc
+#[cfg(unix)]
+const ESCAPE_WAIT: Duration = Duration::from_millis(50);
+
+/// whether stdin is a terminal `read_stdin` can draw on, for `StdinReader`
+#[cfg(unix)]
+pub(crate) fn edits_stdin() -> bool {
+    io::stdin().is_terminal()
+        && io::stdout().is_terminal()
+        && std::env::var("TERM").map_or(true, |term| term != "dumb")
+        && !capabilities::is_degraded(Degradation::NoRawMode)
+}
+
+/// raw mode until dropped, none where the terminal refuses it, like
+/// under a seccomp profile blocking its ioctls. lines are read in
+/// canonical mode from then on
+#[cfg(unix)]
+pub(crate) fn raw_mode() -> Option<crate::terminal::RawMode> {
+    match crate::terminal::RawMode::new() {
+        Ok(raw) => Some(raw),
+        Err(_) => {
+            capabilities::degrade(Degradation::NoRawMode);
+            None
+        }
+    }
+}
+
+/// a line of the terminal read in raw mode through the stdin session.
+/// Ctrl+C and Ctrl+Z raise their signals once the terminal is restored,
+/// Ctrl+C only fails the read under `config::catch_interrupts`
+#[cfg(unix)]
+pub(crate) fn read_stdin(buf: &mut String) -> io::Result<usize> {
+    use crate::editor::Outcome;
+
+    let prompt = take_prompt();
+    let Some(raw) = raw_mode() else {
+        return crate::io::read_terminal(buf);
+    };
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout();
+    let mut out = String::new();
+    let mut outcome = with_session(|session| session.begin(&prompt, &mut out));
+    loop {
+        match outcome.take() {
+            Some(Outcome::Accept(line)) => {
+                stdout.write_all(format!("{}\r\n", out).as_bytes())?;
+                stdout.flush()?;
+                buf.push_str(&line);
+                buf.push('\n');
+                return Ok(line.len() + 1);
+            }
+            Some(Outcome::Eof) => {
+                stdout.write_all(format!("{}\r\n", out).as_bytes())?;
+                stdout.flush()?;
+                return Ok(0);
+            }
+            Some(Outcome::Interrupted) => {
+                stdout.write_all(format!("{}^C\r\n", out).as_bytes())?;
+                stdout.flush()?;
+                drop(raw);
+                if !crate::config::catches_interrupts() {
+                    crate::terminal::interrupt();
+                }
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            Some(Outcome::Suspend) => {
+                stdout.write_all(out.as_bytes())?;
+                stdout.flush()?;
+                out.clear();
+                crate::terminal::suspend();
+            }
+            _ => {}
+        }
+        stdout.write_all(out.as_bytes())?;
+        stdout.flush()?;
+        out.clear();
+        let pending = with_session(|session| session.is_pending());
+        let wait = match pending {
+            true => ESCAPE_WAIT,
+            false => Duration::from_secs(60),
+        };
+        if !crate::terminal::poll_input(wait)? {
+            if pending {
+                outcome = with_session(|session| session.flush_escape(&mut out));
+            } else if crate::terminal::take_redraw() {
+                with_session(|session| session.redraw(&mut out));
+            }
+            continue;
+        }
+        let bytes = stdin.fill_buf()?;
+        if bytes.is_empty() {
+            return Ok(0);
+        }
+        let read = bytes.len();
+        outcome = with_session(|session| session.feed(bytes, &mut out));
+        stdin.consume(read);
+    }
+}
+
+/// start the next line of stdin with `line`, while an `install` guard
+/// lives
+pub(crate) fn prefill(line: &str) {
+    with_session(|session| session.prefill(line));
+}
+
+/// puts the history, completer and limit of the stdin editor back when
+/// dropped, a `prefill` left unused goes too
+pub(crate) struct Installed {
+    history: Option<History>,
+    completer: Option<Option<SharedCompleter>>,
+    max_len: Option<Option<usize>>,
+}
+
+/// edit stdin with the `history`, `completer` and `max_len` of an `Input`
+/// until the guard is dropped. the ones left out stay
+pub(crate) fn install(
+    history: Option<&History>,
+    completer: Option<&SharedCompleter>,
+    max_len: Option<usize>,
+) -> Installed {
+    with_session(|session| {
+        let editor = session.editor_mut();
+        Installed {
+            history: history.map(|history| editor.replace_history(history.clone())),
+            completer: completer.map(|completer| editor.replace_completer(Some(completer.clone()))),
+            max_len: max_len.map(|max_len| editor.replace_max_len(Some(max_len))),
+        }
+    })
+}
+
+impl Drop for Installed {
+    fn drop(&mut self) {
+        with_session(|session| {
+            let editor = session.editor_mut();
+            if let Some(history) = self.history.take() {
+                editor.replace_history(history);
+            }
+            if let Some(completer) = self.completer.take() {
+                editor.replace_completer(completer);
+            }
+            if let Some(max_len) = self.max_len.take() {
+                editor.replace_max_len(max_len);
+            }
+            session.prefill = None;
+        });
+    }
+}
@@ -0,0 +1,313 @@
+use std::fmt::Display;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::error::InputError;
+use crate::input::Input;
+use crate::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::prompter::Prompt;
+use crate::{read_answer, render_prompt_into, Retries};
+
+/// how the values of a list are entered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListMode {
+    /// all values on one line separated by the delimiter, like `a, b, c`
+    Delimited,
+    /// one value per line until an empty line or EOF
+    Repeated,
+}
+
+/// what to do with a value that was already entered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dedup {
+    /// keep duplicates
+    Keep,
+    /// silently drop duplicates
+    Remove,
+    /// reject duplicates and ask again
+    Reject,
+}
+
+type ItemValidator<T> = Box<dyn Fn(&T) -> Result<(), String>>;
+
+/// # ListInput
+///
+/// prompt for several values of `T`, created by `Input::list`.
+/// every value is parsed and validated on its own, invalid values
+/// are reported through the writer and asked again
+///
+/// # usage
+/// ```
+/// use input_py::{Dedup, Input};
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut reader = MockReader::new(&["80, 443, 80"]);
+/// let mut writer = MockWriter::new();
+/// let ports = Input::new("ports")
+///     .list::<u16>()
+///     .dedup(Dedup::Remove)
+///     .min(1)
+///     .read_with_io(&mut reader, &mut writer);
+/// assert_eq!(ports, Ok(vec![80, 443]));
+/// ```
+pub struct ListInput<T> {
+    input: Input,
+    mode: ListMode,
+    delimiter: char,
+    dedup: Dedup,
+    min: usize,
+    max: Option<usize>,
+    validator: Option<ItemValidator<T>>,
+}
+
+impl<T: FromStr> ListInput<T> {
+    pub(crate) fn new(input: Input) -> Self {
+        ListInput {
+            input,
+            mode: ListMode::Delimited,
+            delimiter: ',',
+            dedup: Dedup::Keep,
+            min: 0,
+            max: None,
+            validator: None,
+        }
+    }
+
+    /// default: `ListMode::Delimited`
+    pub fn mode(mut self, mode: ListMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// separator for `ListMode::Delimited`, default: `,`
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// default: `Dedup::Keep`
+    pub fn dedup(mut self, dedup: Dedup) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// minimum number of values
+    pub fn min(mut self, min: usize) -> Self {
+        self.min = min;
+        self.input.constraints.min_items = Some(min);
+        self
+    }
+
+    /// maximum number of values
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = Some(max);
+        self.input.constraints.max_items = Some(max);
+        self
+    }
+
+    /// check applied to every value after parsing
+    pub fn validate_item<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&T) -> Result<(), String> + 'static,
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+}
+
+impl<T> ListInput<T>
+where
+    T: FromStr + PartialEq,
+    T::Err: Display,
+{
+    /// read the values from stdin
+    pub fn read(self) -> Result<Vec<T>, InputError> {
+        self.read_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    pub fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Vec<T>, InputError> {
+        match self.mode {
+            ListMode::Delimited => self.read_delimited(reader, writer),
+            ListMode::Repeated => self.read_repeated(reader, writer),
+        }
+    }
+
+    fn read_delimited(
+        &self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Vec<T>, InputError> {
+        let mut retries =
+            Retries::with_max_attempts(self.input.max_attempts).theme(self.input.theme);
+        loop {
+            let prompt = self.prompt_text(None, writer);
+            let line = match read_answer(&prompt, reader, writer)? {
+                Some(line) => line,
+                None => return Err(InputError::Eof),
+            };
+            let line = match (&self.input.default, line.is_empty()) {
+                (Some(default), true) => default.clone(),
+                _ => line,
+            };
+
+            match self.collect_delimited(&line) {
+                Ok(items) => return Ok(items),
+                Err((msg, Some(span))) => {
+                    retries.reject_at(reader.is_interactive(), writer, &msg, &line, span, "")?
+                }
+                Err((msg, None)) => retries.reject(writer, &msg)?,
+            }
+        }
+    }
+
+    /// the values of `line`, or why not and the chars of the bad value
+    fn collect_delimited(&self, line: &str) -> Result<Vec<T>, (String, Option<Range<usize>>)> {
+        let mut items = Vec::new();
+        let mut offset = 0;
+        for part in line.split(self.delimiter) {
+            let start = offset + part.len() - part.trim_start().len();
+            offset += part.len() + self.delimiter.len_utf8();
+            let raw = part.trim();
+            if raw.is_empty() {
+                continue;
+            }
+            let start = line[..start].chars().count();
+            let span = start..start + raw.chars().count();
+            let item = self
+                .parse_item(raw)
+                .map_err(|msg| (msg, Some(span.clone())))?;
+            if items.contains(&item) {
+                match self.dedup {
+                    Dedup::Keep => {}
+                    Dedup::Remove => continue,
+                    Dedup::Reject => {
+                        return Err((format!("duplicate value '{}'", raw), Some(span)))
+                    }
+                }
+            }
+            items.push(item);
+        }
+        self.check_count(items.len()).map_err(|msg| (msg, None))?;
+        Ok(items)
+    }
+
+    fn read_repeated(
+        &self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Vec<T>, InputError> {
+        let mut items: Vec<T> = Vec::new();
+        let mut retries = Retries::with_max_attempts(self.input.max_attempts);
+        loop {
+            if self.max.is_some_and(|max| items.len() >= max) {
+                return Ok(items);
+            }
+
+            let prompt = self.prompt_text(Some(items.len() + 1), writer);
+            let line = match read_answer(&prompt, reader, writer)? {
+                Some(line) => line,
+                None if items.len() >= self.min => return Ok(items),
+                None => return Err(InputError::Eof),
+            };
+
+            if line.is_empty() {
+                if items.is_empty() {
+                    if let Some(default) = &self.input.default {
+                        match self.collect_delimited(default) {
+                            Ok(defaults) => return Ok(defaults),
+                            Err((msg, _)) => retries.reject(writer, &msg)?,
+                        }
+                        continue;
+                    }
+                }
+                match self.check_count(items.len()) {
+                    Ok(()) => return Ok(items),
+                    Err(msg) => retries.reject(writer, &msg)?,
+                }
+                continue;
+            }
+
+            let item = match self.parse_item(&line) {
+                Ok(item) => item,
+                Err(msg) => {
+                    retries.reject(writer, &msg)?;
+                    continue;
+                }
+            };
+            if items.contains(&item) {
+                match self.dedup {
+                    Dedup::Keep => {}
+                    Dedup::Remove => continue,
+                    Dedup::Reject => {
+                        retries.reject(writer, &format!("duplicate value '{}'", line))?;
+                        continue;
+                    }
+                }
+            }
+            retries.accepted();
+            items.push(item);
+        }
+    }
+
+    fn parse_item(&self, raw: &str) -> Result<T, String> {
+        let item = raw
+            .parse::<T>()
+            .map_err(|e| format!("invalid value '{}': {}", raw, e))?;
+        if let Some(validator) = &self.validator {
+            validator(&item).map_err(|e| format!("invalid value '{}': {}", raw, e))?;
+        }
+        Ok(item)
+    }
+
+    fn check_count(&self, count: usize) -> Result<(), String> {
+        if count < self.min {
+            return Err(format!("at least {} values required", self.min));
+        }
+        if let Some(max) = self.max {
+            if count > max {
+                return Err(format!("at most {} values allowed", max));
+            }
+        }
+        Ok(())
+    }
+
+    fn prompt_text(&self, index: Option<usize>, writer: &dyn OutputWriter) -> String {
+        let mut prompt = self.input.prompt.clone();
+        if let Some(index) = index {
+            prompt.push_str(&format!(" #{}", index));
+        }
+        let prompt = self.input.constraints.with_hint(&prompt);
+        let default = match index {
+            None | Some(1) => self.input.default.as_deref(),
+            Some(_) => None,
+        };
+        let mut text = String::new();
+        render_prompt_into(
+            &mut text,
+            &prompt,
+            default,
+            &self.input.theme.for_writer(writer),
+        );
+        text
+    }
+}
+
+impl<T> Prompt for ListInput<T>
+where
+    T: FromStr + PartialEq,
+    T::Err: Display,
+{
+    type Output = Vec<T>;
+
+    fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Vec<T>, InputError> {
+        ListInput::read_with_io(self, reader, writer)
+    }
+}
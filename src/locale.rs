@@ -0,0 +1,247 @@
+//! # locale
+//!
+//! how numbers and dates are written for the user. answers keep the
+//! plain form, `1234.5` and `2024-03-01`, which summaries, diffs and
+//! confirmations show in the configured locale, and numbers typed in
+//! the locale's form parse too. `config::locale` sets it for every
+//! prompt, `Locale::C` shows everything as entered
+
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+
+static CURRENT: Mutex<Locale> = Mutex::new(Locale::C);
+
+/// the order of day, month and year in a date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    /// `2024-03-01`
+    Ymd,
+    /// `01.03.2024`
+    Dmy,
+    /// `03/01/2024`
+    Mdy,
+}
+
+/// # Locale
+///
+/// separators and date order of a region
+///
+/// # usage
+/// ```
+/// use input_py::locale::Locale;
+///
+/// assert_eq!(Locale::DE.display("1234567.5"), "1.234.567,5");
+/// assert_eq!(Locale::EN_US.display("2024-03-01"), "03/01/2024");
+/// assert_eq!(Locale::DE.parse_number("1.234,5"), Some("1234.5".to_string()));
+/// assert_eq!(Locale::DE.display("web-1"), "web-1");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    /// between the whole and the fractional part
+    pub decimal: char,
+    /// between groups of three digits, none writes no groups
+    pub grouping: Option<char>,
+    pub date_order: DateOrder,
+    /// between day, month and year
+    pub date_separator: char,
+}
+
+impl Locale {
+    /// the plain forms, numbers and dates are shown as entered
+    pub const C: Locale = Locale {
+        decimal: '.',
+        grouping: None,
+        date_order: DateOrder::Ymd,
+        date_separator: '-',
+    };
+
+    pub const EN_US: Locale = Locale {
+        decimal: '.',
+        grouping: Some(','),
+        date_order: DateOrder::Mdy,
+        date_separator: '/',
+    };
+
+    pub const EN_GB: Locale = Locale {
+        decimal: '.',
+        grouping: Some(','),
+        date_order: DateOrder::Dmy,
+        date_separator: '/',
+    };
+
+    pub const DE: Locale = Locale {
+        decimal: ',',
+        grouping: Some('.'),
+        date_order: DateOrder::Dmy,
+        date_separator: '.',
+    };
+
+    pub const FR: Locale = Locale {
+        decimal: ',',
+        grouping: Some('\u{202f}'),
+        date_order: DateOrder::Dmy,
+        date_separator: '/',
+    };
+
+    /// `value` as shown to the user: a plain number or date in this
+    /// locale's form, anything else as it is
+    pub fn display(&self, value: &str) -> String {
+        self.format_number(value)
+            .or_else(|| self.format_date(value))
+            .unwrap_or_else(|| value.to_string())
+    }
+
+    /// a plain number like `-1234.5` in this locale's form
+    pub fn format_number(&self, number: &str) -> Option<String> {
+        let (sign, digits) = match number.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", number),
+        };
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (digits, None),
+        };
+        let all_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+        if !all_digits(whole) || !fraction.is_none_or(all_digits) {
+            return None;
+        }
+        let mut out = sign.to_string();
+        for (i, digit) in whole.chars().enumerate() {
+            if let Some(grouping) = self.grouping {
+                if i > 0 && (whole.len() - i) % 3 == 0 {
+                    out.push(grouping);
+                }
+            }
+            out.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            out.push(self.decimal);
+            out.push_str(fraction);
+        }
+        Some(out)
+    }
+
+    /// a number written in this locale's form or the plain one, as a
+    /// plain number that `str::parse` reads. groups must have three
+    /// digits
+    pub fn parse_number(&self, text: &str) -> Option<String> {
+        let text = text.trim();
+        let (sign, digits) = match text.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", text),
+        };
+        let (whole, fraction) = match digits.split_once(self.decimal) {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (digits, None),
+        };
+        let whole = match self.grouping {
+            Some(grouping) if whole.contains(grouping) => {
+                let mut groups = whole.split(grouping);
+                let first = groups.next()?;
+                if first.is_empty() || first.len() > 3 {
+                    return None;
+                }
+                let mut plain = first.to_string();
+                for group in groups {
+                    if group.len() != 3 {
+                        return None;
+                    }
+                    plain.push_str(group);
+                }
+                plain
+            }
+            _ => whole.to_string(),
+        };
+        let plain = match fraction {
+            Some(fraction) => format!("{}{}.{}", sign, whole, fraction),
+            None => format!("{}{}", sign, whole),
+        };
+        self.format_number(&plain)?;
+        Some(plain)
+    }
+
+    /// a plain date like `2024-03-01` in this locale's order
+    pub fn format_date(&self, date: &str) -> Option<String> {
+        let [year, month, day] = split_date(date, '-')?;
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 || !valid_date(year, month, day) {
+            return None;
+        }
+        let parts = match self.date_order {
+            DateOrder::Ymd => [year, month, day],
+            DateOrder::Dmy => [day, month, year],
+            DateOrder::Mdy => [month, day, year],
+        };
+        let separator = self.date_separator.to_string();
+        Some(parts.join(&separator))
+    }
+
+    /// a date in this locale's order, or the plain form, as a plain
+    /// date. day and month may have one digit
+    pub fn parse_date(&self, text: &str) -> Option<String> {
+        let text = text.trim();
+        let [year, month, day] = match split_date(text, self.date_separator) {
+            Some([a, b, c]) => match self.date_order {
+                DateOrder::Ymd => [a, b, c],
+                DateOrder::Dmy => [c, b, a],
+                DateOrder::Mdy => [c, a, b],
+            },
+            None => split_date(text, '-')?,
+        };
+        if year.len() != 4 || month.len() > 2 || day.len() > 2 || !valid_date(year, month, day) {
+            return None;
+        }
+        Some(format!("{}-{:0>2}-{:0>2}", year, month, day))
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::C
+    }
+}
+
+/// `text` as `T`, also when it is a number in the current locale's form
+pub(crate) fn parse<T: FromStr>(text: &str) -> Result<T, T::Err> {
+    text.parse::<T>().or_else(|e| {
+        current()
+            .parse_number(text)
+            .and_then(|plain| plain.parse().ok())
+            .ok_or(e)
+    })
+}
+
+/// the locale set with `config::locale`
+pub fn current() -> Locale {
+    *CURRENT.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub(crate) fn set_current(locale: Locale) {
+    *CURRENT.lock().unwrap_or_else(PoisonError::into_inner) = locale;
+}
+
+/// three runs of digits between `separator`
+fn split_date(text: &str, separator: char) -> Option<[&str; 3]> {
+    let mut parts = text.split(separator);
+    let date = [parts.next()?, parts.next()?, parts.next()?];
+    let digits = |part: &&str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    (parts.next().is_none() && date.iter().all(digits)).then_some(date)
+}
+
+fn valid_date(year: &str, month: &str, day: &str) -> bool {
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
@@ -1,5 +1,231 @@
-use input_py::input;
-fn main() {
-    let comment = "test";
-    let input_data = input(&comment);
+//! # input-demo
+//!
+//! tries every prompt type from the command line, as living documentation
+//! and a manual test bed. the answers are printed after the prompts, the
+//! exit code tells scripts how the prompt ended
+
+use std::process::ExitCode;
+
+use input_py::config::{self, Theme};
+use input_py::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use input_py::locale::{self, Locale};
+use input_py::model::MaskStyle;
+use input_py::password::PasswordConfirm;
+use input_py::{process_input, render_prompt, Form, Input, InputError, Select, TextArea};
+
+const USAGE: &str = "\
+usage: input-demo <command> [options] [items]
+
+commands:
+  text        ask for one line, numbers and dates shown in the locale
+  secret      ask for a secret, hidden while typing and shown masked
+  password    ask for a new password twice, hidden while typing
+  confirm     ask a yes/no question, the exit code is the answer
+  select      choose one of the items given after the options
+  list        ask for comma separated values
+  textarea    ask for multi-line text
+  form        run a small sectioned form
+
+options:
+  --prompt <text>       prompt text
+  --default <value>     answer used for an empty line
+  --body <markdown>     explanation shown above the prompt
+  --theme plain|ansi    styling of bodies. default: plain
+  --locale c|en-us|en-gb|de|fr
+                        how numbers and dates are shown and typed. default: c
+  --non-interactive     fail instead of waiting when stdin is not a terminal
+  -h, --help            show this help
+
+exit codes:
+  0  answered, or confirmed
+  1  declined
+  2  usage error
+  3  no answer: end of input, not interactive or too many errors
+  130  interrupted with Ctrl+C";
+
+/// answered, or confirmed
+const EXIT_OK: u8 = 0;
+/// a confirmation was declined
+const EXIT_DECLINED: u8 = 1;
+/// unknown command or option
+const EXIT_USAGE: u8 = 2;
+/// the prompt could not be answered
+const EXIT_NO_ANSWER: u8 = 3;
+/// Ctrl+C was pressed, like a shell reports SIGINT
+const EXIT_INTERRUPTED: u8 = 130;
+
+struct Options {
+    command: String,
+    prompt: Option<String>,
+    default: Option<String>,
+    body: Option<String>,
+    theme: Theme,
+    items: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::from(EXIT_OK);
+        }
+        Err(msg) => {
+            eprintln!("input-demo: {}\n\n{}", msg, USAGE);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+    config::catch_interrupts(true);
+    match run(&options) {
+        Ok(code) => ExitCode::from(code),
+        Err(InputError::Interrupted) => ExitCode::from(EXIT_INTERRUPTED),
+        Err(err) => {
+            eprintln!("input-demo: {}", err);
+            ExitCode::from(EXIT_NO_ANSWER)
+        }
+    }
+}
+
+/// the options, `None` when help was asked for
+fn parse_args(args: &[String]) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        command: String::new(),
+        prompt: None,
+        default: None,
+        body: None,
+        theme: Theme::DEFAULT,
+        items: Vec::new(),
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value", arg))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--prompt" => options.prompt = Some(value()?),
+            "--default" => options.default = Some(value()?),
+            "--body" => options.body = Some(value()?),
+            "--theme" => {
+                options.theme = match value()?.as_str() {
+                    "plain" => Theme::DEFAULT,
+                    "ansi" => Theme::ANSI,
+                    other => return Err(format!("unknown theme '{}'", other)),
+                }
+            }
+            "--locale" => config::locale(match value()?.as_str() {
+                "c" => Locale::C,
+                "en-us" => Locale::EN_US,
+                "en-gb" => Locale::EN_GB,
+                "de" => Locale::DE,
+                "fr" => Locale::FR,
+                other => return Err(format!("unknown locale '{}'", other)),
+            }),
+            "--non-interactive" => config::strict_interactive(true),
+            flag if flag.starts_with('-') => return Err(format!("unknown option '{}'", flag)),
+            command if options.command.is_empty() => options.command = command.to_string(),
+            item => options.items.push(item.to_string()),
+        }
+    }
+    match (options.command.as_str(), options.items.first()) {
+        ("", _) => Err("missing command".to_string()),
+        ("select", None) => Err("select needs items".to_string()),
+        ("select", Some(_)) | (_, None) => Ok(Some(options)),
+        (_, Some(extra)) => Err(format!("unexpected argument '{}'", extra)),
+    }
+}
+
+fn run(options: &Options) -> Result<u8, InputError> {
+    let reader = &mut StdinReader;
+    let writer = &mut StdoutWriter;
+    let prompt = |fallback: &str| options.prompt.clone().unwrap_or(fallback.to_string());
+    let input = |fallback: &str| {
+        let mut input = Input::new(&prompt(fallback)).theme(options.theme);
+        if let Some(default) = &options.default {
+            input = input.default(default);
+        }
+        if let Some(body) = &options.body {
+            input = input.body(body);
+        }
+        input
+    };
+
+    match options.command.as_str() {
+        "text" => {
+            let text = input("text").read_with_io(reader, writer)?;
+            println!("{}", locale::current().display(&text));
+        }
+        "secret" => {
+            let question = input("secret")
+                .mask_display(MaskStyle::Last(2))
+                .question("secret");
+            writer.write_str(&render_prompt(&question, &options.theme))?;
+            writer.flush()?;
+            let mut line = String::new();
+            if reader.read_hidden(&mut line)? == 0 {
+                return Err(InputError::Eof);
+            }
+            let secret = match process_input(&line) {
+                secret if secret.is_empty() => question.default.clone().unwrap_or_default(),
+                secret => secret,
+            };
+            println!("entered {}", question.display(&secret));
+        }
+        "password" => {
+            let password = PasswordConfirm::new(&prompt("new password"), "repeat password")
+                .read_with_io(reader, writer)?;
+            println!("password set, {} characters", password.chars().count());
+        }
+        "confirm" => {
+            let confirm = input("continue?").flush_pending(true).confirm();
+            let confirm = match &options.default {
+                Some(_) => confirm,
+                None => confirm.default(false),
+            };
+            if !confirm.read_with_io(reader, writer)? {
+                return Ok(EXIT_DECLINED);
+            }
+        }
+        "select" => {
+            let mut select = Select::new(&prompt("choose"), &options.items);
+            if let Some(default) = &options.default {
+                select = select.default(default);
+            }
+            println!("{}", select.read_with_io(reader, writer)?);
+        }
+        "list" => {
+            let items = input("values")
+                .list::<String>()
+                .read_with_io(reader, writer)?;
+            for item in items {
+                println!("{}", item);
+            }
+        }
+        "textarea" => println!(
+            "{}",
+            TextArea::new(&prompt("text")).read_with_io(reader, writer)?
+        ),
+        "form" => {
+            // section titles follow the global theme
+            config::theme(options.theme);
+            let answers = Form::new()
+                .field("name", Input::new(&prompt("name")).default("demo"))
+                .section("Network")
+                .field("host", Input::new("host").default("localhost"))
+                .field("port", Input::new("port").default("8080"))
+                .skip_sections(true)
+                .run_with_io(reader, writer)?;
+            for (id, value) in answers.iter() {
+                println!("{}={}", id, value);
+            }
+        }
+        other => {
+            eprintln!("input-demo: unknown command '{}'\n\n{}", other, USAGE);
+            return Ok(EXIT_USAGE);
+        }
+    }
+    Ok(EXIT_OK)
 }
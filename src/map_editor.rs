@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use crate::config::Theme;
+use crate::error::InputError;
+use crate::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::{read_answer, render_prompt_into, Retries};
+
+type ValueCheck = Box<dyn Fn(&str) -> Result<(), String>>;
+
+const USAGE: &str = "commands: set <key> <value>, del <key>, done";
+
+/// # MapEditor
+///
+/// edits a `HashMap<String, String>` with the commands
+///
+/// * `set <key> <value>` - add or change an entry
+/// * `del <key>` - remove an entry
+/// * `done` - finish editing
+///
+/// the current entries are listed before every command.
+/// changes are applied to the map only when `done` is entered
+///
+/// # usage
+/// ```
+/// use std::collections::HashMap;
+/// use input_py::map_editor::MapEditor;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut settings = HashMap::new();
+/// let mut reader = MockReader::new(&["set port 8080", "done"]);
+/// let mut writer = MockWriter::new();
+/// MapEditor::new("settings")
+///     .validate("port", |v| v.parse::<u16>().map(|_| ()).map_err(|e| e.to_string()))
+///     .edit_with_io(&mut settings, &mut reader, &mut writer)
+///     .unwrap();
+/// assert_eq!(settings["port"], "8080");
+/// ```
+pub struct MapEditor {
+    prompt: String,
+    validators: HashMap<String, ValueCheck>,
+}
+
+impl MapEditor {
+    pub fn new(prompt: &str) -> Self {
+        MapEditor {
+            prompt: prompt.to_string(),
+            validators: HashMap::new(),
+        }
+    }
+
+    /// check applied to values set for `key`
+    pub fn validate<F>(mut self, key: &str, validator: F) -> Self
+    where
+        F: Fn(&str) -> Result<(), String> + 'static,
+    {
+        self.validators.insert(key.to_string(), Box::new(validator));
+        self
+    }
+
+    /// edit `map` on stdin
+    pub fn edit(&self, map: &mut HashMap<String, String>) -> Result<(), InputError> {
+        self.edit_with_io(map, &mut StdinReader, &mut StdoutWriter)
+    }
+
+    pub fn edit_with_io(
+        &self,
+        map: &mut HashMap<String, String>,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<(), InputError> {
+        let mut entries = map.clone();
+        let mut prompt = String::new();
+        let theme = Theme::current().for_writer(writer);
+        render_prompt_into(&mut prompt, &self.prompt, None, &theme);
+        let mut retries = Retries::new();
+        loop {
+            write_entries(&entries, writer)?;
+            let line = read_answer(&prompt, reader, writer)?.ok_or(InputError::Eof)?;
+            let (command, rest) = match line.split_once(char::is_whitespace) {
+                Some((command, rest)) => (command, rest.trim()),
+                None => (line.as_str(), ""),
+            };
+
+            match command {
+                "done" if rest.is_empty() => break,
+                "set" => {
+                    let (key, value) = match rest.split_once(char::is_whitespace) {
+                        Some((key, value)) => (key, value.trim()),
+                        None => (rest, ""),
+                    };
+                    if key.is_empty() {
+                        retries.reject(writer, USAGE)?;
+                        continue;
+                    }
+                    if let Some(validator) = self.validators.get(key) {
+                        if let Err(msg) = validator(value) {
+                            let msg = format!("invalid {} '{}': {}", key, value, msg);
+                            retries.reject(writer, &msg)?;
+                            continue;
+                        }
+                    }
+                    retries.accepted();
+                    entries.insert(key.to_string(), value.to_string());
+                }
+                "del" if !rest.is_empty() => match entries.remove(rest) {
+                    Some(_) => retries.accepted(),
+                    None => retries.reject(writer, &format!("no entry '{}'", rest))?,
+                },
+                "" => {}
+                _ => retries.reject(writer, USAGE)?,
+            }
+        }
+        *map = entries;
+        Ok(())
+    }
+}
+
+fn write_entries(
+    entries: &HashMap<String, String>,
+    writer: &mut dyn OutputWriter,
+) -> Result<(), InputError> {
+    let mut keys: Vec<&String> = entries.keys().collect();
+    keys.sort();
+    let list: Vec<String> = keys
+        .iter()
+        .map(|key| format!("  {} = {}", key, entries[*key]))
+        .collect();
+    let lines: Vec<&str> = list.iter().map(String::as_str).collect();
+    writer.write_all_lines(&lines)?;
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! # markdown
+//!
+//! minimal Markdown for explanatory prompt bodies: `**bold**`,
+//! `` `code` ``, `# headings` and `-`, `*` or numbered list items.
+//! everything else is shown as written
+
+use crate::config::Theme;
+
+/// # render
+///
+/// `text` styled with `theme`, every line followed by a newline
+///
+/// # usage
+/// ```
+/// use input_py::config::Theme;
+/// use input_py::markdown;
+///
+/// let body = markdown::render("Pick a **region**:\n- `eu-west`\n- `us-east`", &Theme::DEFAULT);
+/// assert_eq!(body, "Pick a region:\n• eu-west\n• us-east\n");
+/// ```
+pub fn render(text: &str, theme: &Theme) -> String {
+    let mut out = String::with_capacity(text.len() + 16);
+    for line in text.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let (indent, rest) = line.split_at(indent);
+        out.push_str(indent);
+        if let Some(heading) = heading(rest) {
+            out.push_str(theme.bold.0);
+            render_inline(&mut out, heading, theme);
+            out.push_str(theme.bold.1);
+        } else if let Some(item) = rest.strip_prefix("- ").or_else(|| rest.strip_prefix("* ")) {
+            out.push_str(theme.bullet);
+            render_inline(&mut out, item, theme);
+        } else {
+            render_inline(&mut out, rest, theme);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    match (1..=6).contains(&level) {
+        true => text.strip_prefix(' '),
+        false => None,
+    }
+}
+
+/// bold and code spans, unmatched markers are kept as text
+fn render_inline(out: &mut String, text: &str, theme: &Theme) {
+    let mut rest = text;
+    while !rest.is_empty() {
+        let next = rest.find(['*', '_', '`']).unwrap_or(rest.len());
+        out.push_str(&rest[..next]);
+        rest = &rest[next..];
+        if rest.is_empty() {
+            break;
+        }
+
+        let marker = if rest.starts_with('`') {
+            "`"
+        } else if rest.starts_with("**") {
+            "**"
+        } else if rest.starts_with("__") {
+            "__"
+        } else {
+            ""
+        };
+        let span = match marker {
+            "" => None,
+            marker => rest[marker.len()..]
+                .find(marker)
+                .filter(|end| *end > 0)
+                .map(|end| (marker, &rest[marker.len()..marker.len() + end])),
+        };
+        match span {
+            Some(("`", code)) => {
+                out.push_str(theme.code.0);
+                out.push_str(code);
+                out.push_str(theme.code.1);
+                rest = &rest[code.len() + 2..];
+            }
+            Some((marker, bold)) => {
+                out.push_str(theme.bold.0);
+                render_inline(out, bold, theme);
+                out.push_str(theme.bold.1);
+                rest = &rest[bold.len() + 2 * marker.len()..];
+            }
+            None => {
+                let len = rest.chars().next().map_or(1, char::len_utf8);
+                out.push_str(&rest[..len]);
+                rest = &rest[len..];
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use crate::config::Theme;
+use crate::error::InputError;
+use crate::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::prompter::Prompt;
+use crate::{check_interactive, read_answer_redrawn, render_prompt_into, Retries};
+
+/// answer that picks the quit option
+const QUIT: &str = "q";
+
+/// # Menu
+///
+/// numbered options with a callback each. the answer is a number or an
+/// option's label, the callback of the chosen option runs and its result
+/// is returned. an invalid answer asks again. with `quit` the menu also
+/// lists a `q` option that leaves without running anything
+///
+/// # usage
+/// ```
+/// use input_py::menu::Menu;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut started = false;
+/// let mut writer = MockWriter::new();
+/// let ran = Menu::new()
+///     .item("Start", || {
+///         started = true;
+///         "starting"
+///     })
+///     .item("Stop", || "stopping")
+///     .quit("Quit")
+///     .run_with_io(&mut MockReader::new(&["3", "1"]), &mut writer);
+/// assert_eq!(ran, Ok(Some("starting")));
+/// assert!(started);
+/// assert_eq!(
+///     writer.output,
+///     "  1. Start\n  2. Stop\n  q. Quit\n\
+///      choose:error: '3' is not an option or a number between 1 and 2\nchoose:"
+/// );
+/// ```
+pub struct Menu<'a, T> {
+    prompt: String,
+    items: Vec<(String, Box<dyn FnMut() -> T + 'a>)>,
+    quit: Option<String>,
+}
+
+impl<'a, T> Default for Menu<'a, T> {
+    fn default() -> Self {
+        Menu::new()
+    }
+}
+
+impl<'a, T> Menu<'a, T> {
+    pub fn new() -> Self {
+        Menu {
+            prompt: "choose".to_string(),
+            items: Vec::new(),
+            quit: None,
+        }
+    }
+
+    /// text asking for the choice. default: `choose`
+    pub fn prompt(mut self, prompt: &str) -> Self {
+        self.prompt = prompt.to_string();
+        self
+    }
+
+    /// add an option running `action` when chosen
+    pub fn item<F>(mut self, label: &str, action: F) -> Self
+    where
+        F: FnMut() -> T + 'a,
+    {
+        self.items.push((label.to_string(), Box::new(action)));
+        self
+    }
+
+    /// add an option, answered with `q` or `label`, that leaves the menu
+    pub fn quit(mut self, label: &str) -> Self {
+        self.quit = Some(label.to_string());
+        self
+    }
+
+    /// ask on stdin
+    pub fn run(self) -> Result<Option<T>, InputError> {
+        self.run_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    /// the result of the chosen option's callback, none after quit
+    pub fn run_with_io(
+        mut self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Option<T>, InputError> {
+        if self.items.is_empty() {
+            return Err(InputError::NoItems(self.prompt));
+        }
+        let mut prompt = String::new();
+        render_prompt_into(
+            &mut prompt,
+            &self.prompt,
+            None,
+            &Theme::current().for_writer(writer),
+        );
+        check_interactive(&prompt, None, reader)?;
+
+        self.write_list(writer)?;
+        let mut retries = Retries::new();
+        loop {
+            let answer = read_answer_redrawn(&prompt, reader, writer, &mut |writer| {
+                self.write_list(writer)
+            })?
+            .ok_or(InputError::Eof)?;
+            match self.choose(&answer) {
+                Ok(Some(index)) => return Ok(Some((self.items[index].1)())),
+                Ok(None) => return Ok(None),
+                Err(msg) => retries.reject(writer, &msg)?,
+            }
+        }
+    }
+
+    fn write_list(&self, writer: &mut dyn OutputWriter) -> Result<(), InputError> {
+        let mut list: Vec<String> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, (label, _))| format!("  {}. {}", i + 1, label))
+            .collect();
+        if let Some(quit) = &self.quit {
+            list.push(format!("  {}. {}", QUIT, quit));
+        }
+        let list: Vec<&str> = list.iter().map(String::as_str).collect();
+        writer.write_all_lines(&list)?;
+        Ok(())
+    }
+
+    /// the option `answer` picks by label or number, none for quit
+    fn choose(&self, answer: &str) -> Result<Option<usize>, String> {
+        if let Some(quit) = &self.quit {
+            if answer == QUIT || answer == quit {
+                return Ok(None);
+            }
+        }
+        if let Some(index) = self.items.iter().position(|(label, _)| label == answer) {
+            return Ok(Some(index));
+        }
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=self.items.len()).contains(&n) => Ok(Some(n - 1)),
+            _ => Err(format!(
+                "'{}' is not an option or a number between 1 and {}",
+                answer,
+                self.items.len()
+            )),
+        }
+    }
+}
+
+impl<T> Prompt for Menu<'_, T> {
+    type Output = Option<T>;
+
+    fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Option<T>, InputError> {
+        self.run_with_io(reader, writer)
+    }
+}
@@ -0,0 +1,72 @@
+//! # metrics
+//!
+//! hooks to export how prompting goes, like prompt counts, retry rates
+//! and timeouts, to Prometheus, StatsD or any other system without this
+//! crate depending on one. plug a `Metrics` into `Prompter::metrics`
+
+use std::cell::Cell;
+
+/// answered prompts, a counter
+pub const PROMPTS: &str = "input_py_prompts";
+/// rejected answers, a counter
+pub const RETRIES: &str = "input_py_retries";
+/// prompts reached after the session deadline, a counter
+pub const TIMEOUTS: &str = "input_py_timeouts";
+/// seconds from showing a prompt to its answer, a histogram
+pub const ANSWER_SECONDS: &str = "input_py_answer_seconds";
+
+/// # Metrics
+///
+/// receives the measurements of a `Prompter`, named by the constants of
+/// this module
+///
+/// # usage
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use input_py::io::{MockReader, MockWriter};
+/// use input_py::metrics::{self, Metrics};
+/// use input_py::Prompter;
+///
+/// #[derive(Clone, Default)]
+/// struct Counts(Arc<Mutex<u64>>);
+///
+/// impl Metrics for Counts {
+///     fn counter(&mut self, name: &str, value: u64) {
+///         if name == metrics::PROMPTS {
+///             *self.0.lock().unwrap() += value;
+///         }
+///     }
+///
+///     fn histogram(&mut self, _name: &str, _value: f64) {}
+/// }
+///
+/// let counts = Counts::default();
+/// let mut prompter = Prompter::new(MockReader::new(&["a", "b"]), MockWriter::new())
+///     .metrics(counts.clone());
+/// prompter.ask("first", None).unwrap();
+/// prompter.ask("second", None).unwrap();
+/// assert_eq!(*counts.0.lock().unwrap(), 2);
+/// ```
+pub trait Metrics {
+    /// add `value` to the counter `name`
+    fn counter(&mut self, name: &str, value: u64);
+
+    /// record one observation of `value` for the histogram `name`
+    fn histogram(&mut self, name: &str, value: f64);
+}
+
+thread_local! {
+    /// answers rejected on this thread so far, see `rejected`
+    static REJECTED: Cell<u64> = const { Cell::new(0) };
+}
+
+/// count one rejected answer, called where a prompt rejects it
+pub(crate) fn reject() {
+    REJECTED.with(|rejected| rejected.set(rejected.get() + 1));
+}
+
+/// answers rejected on this thread so far, a `Prompter` counts the
+/// retries of a prompt as the difference around it
+pub(crate) fn rejected() -> u64 {
+    REJECTED.with(Cell::get)
+}
@@ -0,0 +1,286 @@
+//! # model
+//!
+//! the question and answer types shared by prompts, frontends,
+//! transcripts and serialization
+
+/// what kind of answer a `Question` expects
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum QuestionKind {
+    /// one line of text
+    #[default]
+    Text,
+    /// text that must not be shown or recorded in clear text
+    Secret,
+    /// several values, see `Input::list`
+    List,
+}
+
+impl QuestionKind {
+    /// lower case name, like `text`
+    pub fn name(&self) -> &'static str {
+        match self {
+            QuestionKind::Text => "text",
+            QuestionKind::Secret => "secret",
+            QuestionKind::List => "list",
+        }
+    }
+}
+
+/// limits an answer has to satisfy, used for hints and by frontends
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Constraints {
+    /// minimum number of values of a list
+    pub min_items: Option<usize>,
+    /// maximum number of values of a list
+    pub max_items: Option<usize>,
+    /// the only accepted answers, empty for any
+    pub choices: Vec<String>,
+    /// inclusive bounds of a number, as shown
+    pub range: Option<(String, String)>,
+    /// what the answer has to look like, like `lowercase letters`. the
+    /// check itself belongs to the prompt
+    pub pattern: Option<String>,
+    /// maximum length in characters
+    pub max_len: Option<usize>,
+}
+
+impl Constraints {
+    /// what is acceptable, shown after the prompt like
+    /// `[red/green/blue]` or `[1-64]`. `None` without constraints
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::model::Constraints;
+    ///
+    /// let constraints = Constraints {
+    ///     pattern: Some("lowercase letters".to_string()),
+    ///     max_len: Some(20),
+    ///     ..Constraints::default()
+    /// };
+    /// assert_eq!(constraints.hint().as_deref(), Some("[lowercase letters, max 20 chars]"));
+    /// ```
+    pub fn hint(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if !self.choices.is_empty() {
+            parts.push(self.choices.join("/"));
+        }
+        if let Some((min, max)) = &self.range {
+            parts.push(format!("{}-{}", min, max));
+        }
+        if let Some(pattern) = &self.pattern {
+            parts.push(pattern.clone());
+        }
+        if let Some(max_len) = self.max_len {
+            parts.push(format!("max {} chars", max_len));
+        }
+        match (self.min_items.filter(|min| *min > 0), self.max_items) {
+            (Some(min), Some(max)) if min == max => parts.push(format!("{} values", min)),
+            (Some(min), Some(max)) => parts.push(format!("{}-{} values", min, max)),
+            (Some(min), None) => parts.push(format!("at least {} values", min)),
+            (None, Some(max)) => parts.push(format!("at most {} values", max)),
+            (None, None) => {}
+        }
+        match parts.is_empty() {
+            true => None,
+            false => Some(format!("[{}]", parts.join(", "))),
+        }
+    }
+
+    /// check `answer` against the choices and the length, the
+    /// constraints a frontend can't enforce on its own
+    pub fn check(&self, answer: &str) -> Result<(), String> {
+        if !self.choices.is_empty() && !self.choices.iter().any(|choice| choice == answer) {
+            return Err(format!(
+                "'{}' is not one of {}",
+                answer,
+                self.choices.join(", ")
+            ));
+        }
+        if let Some(max_len) = self.max_len {
+            if answer.chars().count() > max_len {
+                return Err(format!(
+                    "'{}' is longer than {} characters",
+                    answer, max_len
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// `prompt` followed by the hint, if any
+    pub(crate) fn with_hint(&self, prompt: &str) -> String {
+        match self.hint() {
+            Some(hint) => format!("{} {}", prompt, hint),
+            None => prompt.to_string(),
+        }
+    }
+}
+
+/// # MaskStyle
+///
+/// how much of an answer confirmations, summaries and transcripts show
+///
+/// # usage
+/// ```
+/// use input_py::model::MaskStyle;
+///
+/// assert_eq!(MaskStyle::Last(4).apply("sk-12345678"), "*******5678");
+/// assert_eq!(MaskStyle::Full.apply("hunter2"), "********");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskStyle {
+    /// a fixed `********`, hiding the length too
+    Full,
+    /// only the last n characters
+    Last(usize),
+    /// only the first n characters
+    First(usize),
+}
+
+impl MaskStyle {
+    /// `value` as it may be shown. values no longer than the visible
+    /// part are masked completely
+    pub fn apply(&self, value: &str) -> String {
+        let len = value.chars().count();
+        let (keep, from_start) = match *self {
+            MaskStyle::Full => return "********".to_string(),
+            MaskStyle::Last(n) => (n, false),
+            MaskStyle::First(n) => (n, true),
+        };
+        if len <= keep {
+            return "*".repeat(len);
+        }
+        value
+            .chars()
+            .enumerate()
+            .map(|(i, c)| match (from_start, i < keep, i >= len - keep) {
+                (true, true, _) | (false, _, true) => c,
+                _ => '*',
+            })
+            .collect()
+    }
+
+    /// `message` with every `value` in it masked, for an error quoting a
+    /// rejected answer
+    pub(crate) fn hide_in(&self, message: &str, value: &str) -> String {
+        match value.is_empty() {
+            true => message.to_string(),
+            false => message.replace(value, &self.apply(value)),
+        }
+    }
+}
+
+/// # Question
+///
+/// what a frontend is asked to get an answer for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question {
+    /// stable id of the question, like the field id of a `Form`
+    pub id: String,
+    /// text shown to the user
+    pub prompt: String,
+    pub kind: QuestionKind,
+    /// answer used when the user gives an empty one
+    pub default: Option<String>,
+    pub constraints: Constraints,
+    /// how answers are shown, secret questions are always masked fully
+    pub mask: Option<MaskStyle>,
+    /// Markdown explanation shown before the prompt
+    pub body: Option<String>,
+}
+
+impl Question {
+    pub fn new(id: &str, prompt: &str) -> Self {
+        Question {
+            id: id.to_string(),
+            prompt: prompt.to_string(),
+            kind: QuestionKind::Text,
+            default: None,
+            constraints: Constraints::default(),
+            mask: None,
+            body: None,
+        }
+    }
+
+    pub fn default(mut self, value: &str) -> Self {
+        self.default = Some(value.to_string());
+        self
+    }
+
+    pub fn kind(mut self, kind: QuestionKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn mask_display(mut self, mask: MaskStyle) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    pub fn is_secret(&self) -> bool {
+        self.kind == QuestionKind::Secret
+    }
+
+    /// `value` as it may be shown in confirmations, summaries and
+    /// transcripts, numbers and dates in the `config::locale`
+    pub fn display(&self, value: &str) -> String {
+        match (self.is_secret(), self.mask) {
+            (_, Some(mask)) => mask.apply(value),
+            (true, None) => MaskStyle::Full.apply(value),
+            (false, None) => crate::locale::current().display(value),
+        }
+    }
+
+    /// `message` with the rejected `value` in it masked like `display`
+    /// does, for secret and masked questions
+    pub(crate) fn hide_in(&self, message: &str, value: &str) -> String {
+        match (self.is_secret(), self.mask) {
+            (_, Some(mask)) => mask.hide_in(message, value),
+            (true, None) => MaskStyle::Full.hide_in(message, value),
+            (false, None) => message.to_string(),
+        }
+    }
+}
+
+/// where the value of an `Answer` came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Provenance {
+    /// entered by the user
+    Entered,
+    /// the question's default, for an empty answer or skipped question
+    Default,
+}
+
+/// # Answer
+///
+/// the accepted answer to a `Question`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Answer {
+    /// id of the answered question
+    pub id: String,
+    pub value: String,
+    pub provenance: Provenance,
+    /// how many times the question was asked until it was accepted
+    pub attempts: u32,
+}
+
+impl Answer {
+    pub fn new(id: &str, value: &str, provenance: Provenance) -> Self {
+        Answer {
+            id: id.to_string(),
+            value: value.to_string(),
+            provenance,
+            attempts: 1,
+        }
+    }
+
+    /// how many times the question was asked, 1 unless answers were
+    /// rejected
+    pub fn attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts;
+        self
+    }
+}
@@ -0,0 +1,354 @@
+use std::ops::Range;
+
+use crate::config::Theme;
+use crate::editor::{Key, KeyEvent, MouseKind};
+use crate::error::InputError;
+use crate::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::prompter::Prompt;
+use crate::widget::{Event, Role, StyledWriter, Widget};
+use crate::{check_interactive, read_answer_redrawn, render_prompt_into, Retries};
+
+/// # MultiSelect
+///
+/// choose any number of several items. the items are listed with numbers
+/// and the answer is the numbers of the chosen ones, separated by commas
+/// or spaces, like `1,3 4`. an empty answer picks the defaults, or none.
+/// the chosen items are returned in the order of the list
+///
+/// # usage
+/// ```
+/// use input_py::MultiSelect;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut writer = MockWriter::new();
+/// let features = MultiSelect::new("features", &["tls", "metrics", "tracing"])
+///     .read_with_io(&mut MockReader::new(&["3,5", "3, 1"]).interactive(true), &mut writer);
+/// assert_eq!(features, Ok(vec!["tls".to_string(), "tracing".to_string()]));
+/// assert_eq!(
+///     writer.output,
+///     "  1. tls\n  2. metrics\n  3. tracing\n\
+///      features:error: '5' is not a number between 1 and 3\n  3,5\n    ^\nfeatures:"
+/// );
+/// ```
+pub struct MultiSelect<T = String> {
+    prompt: String,
+    values: Vec<T>,
+    display: Box<dyn Fn(&T) -> String>,
+    defaults: Vec<String>,
+    #[cfg_attr(not(all(unix, feature = "line-editing")), allow(dead_code))]
+    mouse: bool,
+}
+
+impl MultiSelect<String> {
+    pub fn new<S: AsRef<str>>(prompt: &str, items: &[S]) -> Self {
+        let items = items.iter().map(|item| item.as_ref().to_string());
+        MultiSelect::with_display(prompt, items, String::clone)
+    }
+}
+
+impl<T> MultiSelect<T> {
+    /// choose among `items`, each listed as `display` shows it
+    pub fn with_display<I, F>(prompt: &str, items: I, display: F) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        F: Fn(&T) -> String + 'static,
+    {
+        MultiSelect {
+            prompt: prompt.to_string(),
+            values: items.into_iter().collect(),
+            display: Box::new(display),
+            defaults: Vec::new(),
+            mouse: false,
+        }
+    }
+
+    /// the items shown as `labels` are chosen by an empty answer
+    pub fn defaults<S: AsRef<str>>(mut self, labels: &[S]) -> Self {
+        self.defaults = labels.iter().map(|l| l.as_ref().to_string()).collect();
+        self
+    }
+
+    /// check items with the arrows and Space or a click on a terminal,
+    /// like `Select::mouse`. off by default
+    pub fn mouse(mut self, mouse: bool) -> Self {
+        self.mouse = mouse;
+        self
+    }
+
+    /// choose from stdin
+    pub fn read(self) -> Result<Vec<T>, InputError> {
+        #[cfg(all(unix, feature = "line-editing"))]
+        if self.mouse && StdinReader.is_interactive() && crate::line_editor::edits_stdin() {
+            if let Some(raw) = crate::line_editor::raw_mode() {
+                return crate::line_editor::run_widget(raw, self.into_widget()?);
+            }
+        }
+        self.read_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    pub fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Vec<T>, InputError> {
+        let chosen = self.choose_with_io(reader, writer)?;
+        let mut values: Vec<Option<T>> = self.values.into_iter().map(Some).collect();
+        Ok(chosen
+            .into_iter()
+            .filter_map(|i| values[i].take())
+            .collect())
+    }
+
+    /// choose from stdin, returning the positions of the chosen items
+    pub fn read_indices(self) -> Result<Vec<usize>, InputError> {
+        self.read_indices_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    /// the positions of the chosen items, counted from 0
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::MultiSelect;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let chosen = MultiSelect::new("targets", &["linux", "mac", "windows"])
+    ///     .defaults(&["linux"])
+    ///     .read_indices_with_io(&mut MockReader::new(&["3 1"]), &mut MockWriter::new());
+    /// assert_eq!(chosen, Ok(vec![0, 2]));
+    /// ```
+    pub fn read_indices_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Vec<usize>, InputError> {
+        self.choose_with_io(reader, writer)
+    }
+
+    fn choose_with_io(
+        &self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Vec<usize>, InputError> {
+        let defaults = self.default_indices()?;
+        let numbers: Vec<String> = defaults.iter().map(|i| (i + 1).to_string()).collect();
+        let default = (!numbers.is_empty()).then(|| numbers.join(","));
+
+        let mut prompt = String::new();
+        let theme = Theme::current().for_writer(writer);
+        render_prompt_into(&mut prompt, &self.prompt, default.as_deref(), &theme);
+        if check_interactive(&prompt, default.as_deref(), reader)?.is_some() {
+            return Ok(defaults);
+        }
+        self.write_list(writer)?;
+        let mut retries = Retries::new();
+        loop {
+            let answer = read_answer_redrawn(&prompt, reader, writer, &mut |writer| {
+                self.write_list(writer)
+            })?
+            .ok_or(InputError::Eof)?;
+            if answer.is_empty() {
+                return Ok(defaults);
+            }
+            match self.choose(&answer) {
+                Ok(chosen) => return Ok(chosen),
+                Err((msg, span)) => {
+                    retries.reject_at(reader.is_interactive(), writer, &msg, &answer, span, "")?
+                }
+            }
+        }
+    }
+
+    /// the sorted positions of the defaults
+    fn default_indices(&self) -> Result<Vec<usize>, InputError> {
+        if self.values.is_empty() {
+            return Err(InputError::NoItems(self.prompt.clone()));
+        }
+        let mut defaults = Vec::with_capacity(self.defaults.len());
+        for label in &self.defaults {
+            match self.values.iter().position(|v| (self.display)(v) == *label) {
+                Some(index) if !defaults.contains(&index) => defaults.push(index),
+                Some(_) => {}
+                None => return Err(InputError::MissingDefault(self.prompt.clone())),
+            }
+        }
+        defaults.sort_unstable();
+        Ok(defaults)
+    }
+
+    /// this multi select as a widget, see `widget`. Up and Down or the
+    /// wheel move between the items, Space or a click toggles one, a
+    /// digit toggles that item and Enter chooses the checked ones. the
+    /// defaults start checked
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::editor::Key;
+    /// use input_py::widget::{Event, TextCanvas, Widget};
+    /// use input_py::MultiSelect;
+    ///
+    /// let mut features = MultiSelect::new("features", &["tls", "metrics"])
+    ///     .defaults(&["tls"])
+    ///     .into_widget()
+    ///     .unwrap();
+    /// features.handle_key(Key::Down.into());
+    /// features.handle_key(Key::Char(' ').into());
+    /// let mut canvas = TextCanvas::new();
+    /// features.render(&mut canvas);
+    /// assert_eq!(canvas.lines(), ["features", "  [x] 1. tls", "> [x] 2. metrics"]);
+    /// let chosen = features.handle_key(Key::Enter.into());
+    /// assert_eq!(chosen, Event::Done(vec!["tls".to_string(), "metrics".to_string()]));
+    /// ```
+    pub fn into_widget(self) -> Result<MultiSelectWidget<T>, InputError> {
+        let mut checked = vec![false; self.values.len()];
+        for index in self.default_indices()? {
+            checked[index] = true;
+        }
+        Ok(MultiSelectWidget {
+            select: Some(self),
+            active: 0,
+            checked,
+        })
+    }
+
+    fn write_list(&self, writer: &mut dyn OutputWriter) -> Result<(), InputError> {
+        let list: Vec<String> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| format!("  {}. {}", i + 1, (self.display)(value)))
+            .collect();
+        let list: Vec<&str> = list.iter().map(String::as_str).collect();
+        writer.write_all_lines(&list)?;
+        Ok(())
+    }
+
+    /// the sorted positions `answer` picks, or why not and the chars of
+    /// the bad number
+    fn choose(&self, answer: &str) -> Result<Vec<usize>, (String, Range<usize>)> {
+        let mut chosen = Vec::new();
+        let mut offset = 0;
+        for part in answer.split([',', ' ', '\t']) {
+            let start = answer[..offset].chars().count();
+            offset += part.len() + 1;
+            if part.is_empty() {
+                continue;
+            }
+            let span = start..start + part.chars().count();
+            let index = match part.parse::<usize>() {
+                Ok(n) if (1..=self.values.len()).contains(&n) => n - 1,
+                _ => {
+                    let msg = format!(
+                        "'{}' is not a number between 1 and {}",
+                        part,
+                        self.values.len()
+                    );
+                    return Err((msg, span));
+                }
+            };
+            if chosen.contains(&index) {
+                return Err((format!("duplicate value '{}'", part), span));
+            }
+            chosen.push(index);
+        }
+        chosen.sort_unstable();
+        Ok(chosen)
+    }
+}
+
+/// a `MultiSelect` driven by key events, made by `MultiSelect::into_widget`
+pub struct MultiSelectWidget<T> {
+    /// none once chosen
+    select: Option<MultiSelect<T>>,
+    /// the highlighted item
+    active: usize,
+    checked: Vec<bool>,
+}
+
+impl<T> MultiSelectWidget<T> {
+    fn toggle(&mut self, index: usize) {
+        if let Some(checked) = self.checked.get_mut(index) {
+            self.active = index;
+            *checked = !*checked;
+        }
+    }
+}
+
+impl<T> Widget for MultiSelectWidget<T> {
+    type Output = Vec<T>;
+
+    fn handle_key(&mut self, key: KeyEvent) -> Event<Vec<T>> {
+        let Some(select) = &self.select else {
+            return Event::Continue;
+        };
+        let last = select.values.len() - 1;
+        match (key.key, key.ctrl) {
+            (Key::Char('c'), true) | (Key::Esc, _) => return Event::Cancelled,
+            (Key::Enter, _) => {
+                if let Some(select) = self.select.take() {
+                    let chosen = select.values.into_iter().zip(&self.checked);
+                    let chosen = chosen.filter(|(_, checked)| **checked).map(|(v, _)| v);
+                    return Event::Done(chosen.collect());
+                }
+            }
+            (Key::Up, _) | (Key::Char('p'), true) => self.active = self.active.saturating_sub(1),
+            (Key::Down, _) | (Key::Char('n'), true) => self.active = (self.active + 1).min(last),
+            (Key::Home, _) => self.active = 0,
+            (Key::End, _) => self.active = last,
+            (Key::Char(' '), false) => self.toggle(self.active),
+            (Key::Char(c), false) => {
+                if let Some(n) = c.to_digit(10).filter(|n| *n > 0) {
+                    self.toggle(n as usize - 1);
+                }
+            }
+            (Key::Mouse(mouse), _) => match mouse.kind {
+                MouseKind::ScrollUp => self.active = self.active.saturating_sub(1),
+                MouseKind::ScrollDown => self.active = (self.active + 1).min(last),
+                // the prompt is on the first line, the items below it
+                MouseKind::Click => {
+                    if let Some(index) = (mouse.row as usize).checked_sub(1) {
+                        self.toggle(index);
+                    }
+                }
+            },
+            _ => {}
+        }
+        Event::Continue
+    }
+
+    fn render(&self, out: &mut dyn StyledWriter) {
+        let Some(select) = &self.select else {
+            return;
+        };
+        out.write(&select.prompt, Role::Prompt);
+        for (index, value) in select.values.iter().enumerate() {
+            out.end_line();
+            let check = match self.checked[index] {
+                true => 'x',
+                false => ' ',
+            };
+            let label = (select.display)(value);
+            match index == self.active {
+                true => out.write(
+                    &format!("> [{}] {}. {}", check, index + 1, label),
+                    Role::Active,
+                ),
+                false => out.write(
+                    &format!("  [{}] {}. {}", check, index + 1, label),
+                    Role::Text,
+                ),
+            }
+        }
+    }
+}
+
+impl<T> Prompt for MultiSelect<T> {
+    type Output = Vec<T>;
+
+    fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Vec<T>, InputError> {
+        MultiSelect::read_with_io(self, reader, writer)
+    }
+}
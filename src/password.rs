@@ -0,0 +1,117 @@
+use crate::config::Theme;
+use crate::error::InputError;
+use crate::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::metrics;
+use crate::prompter::Prompt;
+use crate::{check_interactive, render_prompt_into, write_error};
+
+/// mismatches `PasswordConfirm` accepts unless told otherwise
+const MAX_MISMATCHES: u32 = 3;
+
+/// # PasswordConfirm
+///
+/// reads a new password twice without showing it and asks again until
+/// both entries match. after `max_mismatches` failed pairs it gives up
+/// with `InputError::MismatchError`. the line is kept as typed, only the
+/// line ending is removed
+///
+/// # usage
+/// ```
+/// use input_py::io::{MockReader, MockWriter};
+/// use input_py::password::PasswordConfirm;
+///
+/// let mut reader = MockReader::new(&["hunter2", "hunter3", "hunter2", "hunter2"]);
+/// let mut writer = MockWriter::new();
+/// let password = PasswordConfirm::new("new password", "repeat password")
+///     .read_with_io(&mut reader, &mut writer);
+/// assert_eq!(password, Ok("hunter2".to_string()));
+/// assert_eq!(
+///     writer.output,
+///     "new password:repeat password:error: the entries don't match\n\
+///      new password:repeat password:"
+/// );
+/// ```
+pub struct PasswordConfirm {
+    prompt: String,
+    confirm_prompt: String,
+    max_mismatches: u32,
+}
+
+impl PasswordConfirm {
+    pub fn new(prompt: &str, confirm_prompt: &str) -> Self {
+        PasswordConfirm {
+            prompt: prompt.to_string(),
+            confirm_prompt: confirm_prompt.to_string(),
+            max_mismatches: MAX_MISMATCHES,
+        }
+    }
+
+    /// failed pairs before giving up, at least 1. default: 3
+    pub fn max_mismatches(mut self, max: u32) -> Self {
+        self.max_mismatches = max.max(1);
+        self
+    }
+
+    /// read from stdin with the terminal echo off
+    pub fn read(self) -> Result<String, InputError> {
+        self.read_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    pub fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<String, InputError> {
+        let theme = Theme::current().for_writer(writer);
+        let mut prompt = String::new();
+        render_prompt_into(&mut prompt, &self.prompt, None, &theme);
+        let mut confirm_prompt = String::new();
+        render_prompt_into(&mut confirm_prompt, &self.confirm_prompt, None, &theme);
+        check_interactive(&prompt, None, reader)?;
+
+        let mut mismatches = 0;
+        loop {
+            let password = read_secret(&prompt, reader, writer)?;
+            if password == read_secret(&confirm_prompt, reader, writer)? {
+                return Ok(password);
+            }
+            mismatches += 1;
+            metrics::reject();
+            write_error(writer, "the entries don't match")?;
+            if mismatches >= self.max_mismatches {
+                return Err(InputError::MismatchError {
+                    attempts: mismatches,
+                });
+            }
+        }
+    }
+}
+
+impl Prompt for PasswordConfirm {
+    type Output = String;
+
+    fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<String, InputError> {
+        PasswordConfirm::read_with_io(self, reader, writer)
+    }
+}
+
+/// one hidden line without its line ending
+fn read_secret(
+    prompt: &str,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+) -> Result<String, InputError> {
+    writer.write_str(prompt)?;
+    writer.flush()?;
+    let mut buf = String::new();
+    if reader.read_hidden(&mut buf)? == 0 {
+        return Err(InputError::Eof);
+    }
+    let len = buf.trim_end_matches(['\n', '\r']).len();
+    buf.truncate(len);
+    Ok(buf)
+}
@@ -0,0 +1,30 @@
+//! # patterns
+//!
+//! answers checked against a `regex::Regex`, with the `regex` feature
+
+use ::regex::Regex;
+
+use crate::input::Input;
+
+impl Input {
+    /// `pattern` with a regex: answers it doesn't match are rejected and
+    /// asked again. like `Regex::is_match` a match may be anywhere in the
+    /// answer, anchor it with `^...$` to match all of it
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    /// use regex::Regex;
+    ///
+    /// let mut writer = MockWriter::new();
+    /// let version = Input::new("version")
+    ///     .regex("a version like 1.2.3", Regex::new(r"^\d+\.\d+\.\d+$").unwrap())
+    ///     .read_with_io(&mut MockReader::new(&["v1", "1.2.3"]), &mut writer);
+    /// assert_eq!(version, Ok("1.2.3".to_string()));
+    /// assert!(writer.output.contains("error: 'v1' is not a version like 1.2.3\n"));
+    /// ```
+    pub fn regex(self, description: &str, regex: Regex) -> Self {
+        self.pattern(description, move |answer| regex.is_match(answer))
+    }
+}
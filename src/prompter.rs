@@ -0,0 +1,609 @@
+use std::any::type_name;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::answers::Recorder;
+use crate::config::Theme;
+use crate::editor::{Editor, KillRing};
+use crate::error::InputError;
+use crate::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::metrics::{self, Metrics};
+use crate::model::Constraints;
+use crate::{preset_answer, render_prompt_into};
+
+/// # Prompter
+///
+/// a prompting session over one reader and writer. it keeps its prompt and
+/// line buffers between prompts, so bulk input tools don't allocate per
+/// prompt
+///
+/// # allocations
+///
+/// * `read_fast` allocates nothing once its buffers and the caller's
+///   answer buffer have grown to the longest prompt and line
+/// * `ask` allocates for the returned `String` and its undo history
+///   entry, pipe mode keeps no history
+/// * the free function `read_input_with_io` allocates twice per prompt,
+///   for the prompt text and the line, plus once more when the default
+///   is returned
+///
+/// # session deadline
+///
+/// with `session_deadline` every prompt reached after the deadline fails
+/// at once with `InputError::Timeout` instead of waiting for input, or
+/// returns its default under `DeadlinePolicy::UseDefault`. a read already
+/// waiting when the deadline passes is not interrupted
+///
+/// # pipe mode
+///
+/// with `pipe_mode` nothing is written or flushed and every answer comes
+/// from the reader, environment answers and strict interactive mode are
+/// not consulted. EOF ends the session: `read_fast` returns false and
+/// `try_ask` returns `None`. wizard code becomes a batch line processor
+/// without changes at the call sites
+///
+/// # line numbers
+///
+/// the session counts the lines it reads. `parse` and `parse_error`
+/// report failures with the offending answer and, when it was read from
+/// the reader, its 1-based line number, like
+/// `parse error: expected integer at line 3124: 'abc'`
+///
+/// # undo
+///
+/// answers from `ask` and `try_ask` are kept on a history stack of the
+/// last 32. `undo` drops the latest and asks its question again
+///
+/// # metrics
+///
+/// with `metrics` the session reports answered prompts, the time they
+/// took, rejected answers of `prompt` and deadline timeouts, see the
+/// `metrics` module
+///
+/// # kill ring
+///
+/// editors from `editor` share the session's kill ring, so text cut with
+/// Ctrl+K, Ctrl+U or Ctrl+W in one prompt is yanked with Ctrl+Y in the next
+///
+/// # usage
+/// ```
+/// use input_py::Prompter;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut prompter = Prompter::new(MockReader::new(&[" 1 ", "2"]), MockWriter::new());
+/// let mut answer = String::new();
+/// let mut sum = 0;
+/// while prompter.read_fast("n", &mut answer).unwrap() {
+///     sum += answer.parse::<i32>().unwrap();
+/// }
+/// assert_eq!(sum, 3);
+/// ```
+pub struct Prompter<R: InputReader, W: OutputWriter> {
+    reader: R,
+    writer: W,
+    prompt_buf: String,
+    line_buf: String,
+    deadline: Option<Instant>,
+    deadline_policy: DeadlinePolicy,
+    pipe_mode: bool,
+    line: usize,
+    /// the last answer and the line it was read on, for `parse_error`
+    last_answer: String,
+    last_line: Option<usize>,
+    history: VecDeque<Asked>,
+    metrics: Option<Box<dyn Metrics + Send>>,
+    kill_ring: KillRing,
+    recorder: Option<Recorder>,
+}
+
+/// characters of the offending line shown in parse errors
+const SNIPPET_LEN: usize = 40;
+
+/// answers kept for `undo`
+const UNDO_DEPTH: usize = 32;
+
+/// an answered question in the undo history
+struct Asked {
+    prompt: String,
+    default: Option<String>,
+    answer: String,
+}
+
+/// # Prompt
+///
+/// a prompt type that can be asked on any reader and writer, like
+/// `Input`, `Select`, `ListInput` or `TextArea`. lets a `Prompter` ask
+/// all of them and chain them with `pipe`
+pub trait Prompt {
+    type Output;
+
+    fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Self::Output, InputError>;
+}
+
+/// # Piped
+///
+/// a chain of prompts started by `Prompter::chain`. each `pipe` builds
+/// the next prompt from the answers so far, so its items can be fetched
+/// when it is reached. after a failure the remaining steps are skipped
+/// and `finish` returns the error
+///
+/// # usage
+/// ```
+/// use input_py::{Prompter, Select};
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// fn environments(project: &str) -> Vec<String> {
+///     vec![format!("{}-dev", project), format!("{}-prod", project)]
+/// }
+///
+/// let mut prompter = Prompter::new(MockReader::new(&["shop", "2"]), MockWriter::new());
+/// let (project, env) = prompter
+///     .chain(Select::new("project", &["blog", "shop"]))
+///     .pipe(|project| Select::new("environment", &environments(project)))
+///     .finish()
+///     .unwrap();
+/// assert_eq!((project.as_str(), env.as_str()), ("shop", "shop-prod"));
+/// ```
+pub struct Piped<'a, R: InputReader, W: OutputWriter, T> {
+    prompter: &'a mut Prompter<R, W>,
+    answers: Result<T, InputError>,
+}
+
+impl<'a, R: InputReader, W: OutputWriter, T> Piped<'a, R, W, T> {
+    /// ask the prompt `next` builds from the answers so far
+    pub fn pipe<P, F>(self, next: F) -> Piped<'a, R, W, (T, P::Output)>
+    where
+        P: Prompt,
+        F: FnOnce(&T) -> P,
+    {
+        let answers = match self.answers {
+            Ok(answers) => {
+                let prompt = next(&answers);
+                self.prompter.prompt(prompt).map(|answer| (answers, answer))
+            }
+            Err(err) => Err(err),
+        };
+        Piped {
+            prompter: self.prompter,
+            answers,
+        }
+    }
+
+    /// the answers, nested as `((first, second), third)`
+    pub fn finish(self) -> Result<T, InputError> {
+        self.answers
+    }
+}
+
+/// what prompts reached after the session deadline do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeadlinePolicy {
+    /// fail with `InputError::Timeout`
+    #[default]
+    Fail,
+    /// return the default, fail with `InputError::Timeout` when there is none
+    UseDefault,
+}
+
+impl Prompter<StdinReader, StdoutWriter> {
+    /// prompter on the process stdin and stdout
+    pub fn stdio() -> Self {
+        Prompter::new(StdinReader, StdoutWriter)
+    }
+}
+
+impl<R: InputReader, W: OutputWriter> Prompter<R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        Prompter {
+            reader,
+            writer,
+            prompt_buf: String::new(),
+            line_buf: String::new(),
+            deadline: None,
+            deadline_policy: DeadlinePolicy::Fail,
+            pipe_mode: false,
+            line: 0,
+            last_answer: String::new(),
+            last_line: None,
+            history: VecDeque::new(),
+            metrics: None,
+            kill_ring: KillRing::new(),
+            recorder: None,
+        }
+    }
+
+    /// quiet batch operation, see the pipe mode section. default: false
+    pub fn pipe_mode(mut self, pipe: bool) -> Self {
+        self.pipe_mode = pipe;
+        self
+    }
+
+    /// prompts reached after `deadline` don't wait for input,
+    /// see `deadline_policy`
+    pub fn session_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// default: `DeadlinePolicy::Fail`
+    pub fn deadline_policy(mut self, policy: DeadlinePolicy) -> Self {
+        self.deadline_policy = policy;
+        self
+    }
+
+    /// report measurements to `metrics`, see the metrics section
+    pub fn metrics<M: Metrics + Send + 'static>(mut self, metrics: M) -> Self {
+        self.metrics = Some(Box::new(metrics));
+        self
+    }
+
+    /// write the answers of `ask` and `try_ask` to `recorder`, keyed by
+    /// their prompt, for replay with `answers::play`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::answers::Recorder;
+    /// use input_py::io::{MockReader, MockWriter};
+    /// use input_py::Prompter;
+    ///
+    /// let path = std::env::temp_dir().join("input_py_doc_prompter_recording.toml");
+    /// let recorder = Recorder::new(&path);
+    /// let reader = MockReader::new(&["prod", ""]);
+    /// let mut prompter = Prompter::new(reader, MockWriter::new()).recorder(recorder.clone());
+    /// prompter.ask("env", None).unwrap();
+    /// prompter.ask("region", Some("eu")).unwrap();
+    /// let entries = [("env".to_string(), "prod".to_string()), ("region".to_string(), "eu".to_string())];
+    /// assert_eq!(recorder.entries(), entries);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// a line editor for this session, see the kill ring section. Ctrl+R
+    /// searches the session's answers
+    pub fn editor(&self) -> Editor {
+        Editor::new()
+            .kill_ring(self.kill_ring.clone())
+            .history(self.history.iter().map(|asked| asked.answer.as_str()))
+    }
+
+    pub fn into_inner(self) -> (R, W) {
+        (self.reader, self.writer)
+    }
+
+    /// ask `prompt` and return the processed answer, or `default` if it is empty
+    pub fn ask(&mut self, prompt: &str, default: Option<&str>) -> Result<String, InputError> {
+        self.try_ask(prompt, default)?.ok_or(InputError::Eof)
+    }
+
+    /// `ask`, but `None` on EOF, for loops that run until the input ends
+    pub fn try_ask(
+        &mut self,
+        prompt: &str,
+        default: Option<&str>,
+    ) -> Result<Option<String>, InputError> {
+        let answer = self.answer(prompt, default)?;
+        if let Some(answer) = &answer {
+            self.remember(prompt, default, answer);
+            self.record(prompt, answer)?;
+        }
+        Ok(answer)
+    }
+
+    /// ask `prompt` on this session's reader and writer
+    pub fn prompt<P: Prompt>(&mut self, prompt: P) -> Result<P::Output, InputError> {
+        if self.deadline_passed() {
+            self.timed_out();
+            return Err(InputError::Timeout);
+        }
+        let started = self.started();
+        let rejected = metrics::rejected();
+        let answer = prompt.read_with_io(&mut self.reader, &mut self.writer);
+        let errors = metrics::rejected() - rejected;
+        if let Some(sink) = self.metrics.as_mut().filter(|_| errors > 0) {
+            sink.counter(metrics::RETRIES, errors);
+        }
+        if answer.is_ok() {
+            self.answered(started);
+        }
+        answer
+    }
+
+    /// ask `prompt` and start a chain of prompts, see `Piped`
+    pub fn chain<P: Prompt>(&mut self, prompt: P) -> Piped<'_, R, W, P::Output> {
+        let answers = self.prompt(prompt);
+        Piped {
+            prompter: self,
+            answers,
+        }
+    }
+
+    /// drop the latest answer from `ask` or `try_ask` and ask its question
+    /// again. returns the new answer, `None` when there is nothing to undo
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Prompter;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut prompter = Prompter::new(MockReader::new(&["prod", "staging"]), MockWriter::new());
+    /// assert_eq!(prompter.ask("env", None), Ok("prod".to_string()));
+    /// assert_eq!(prompter.undo(), Ok(Some("staging".to_string())));
+    /// assert_eq!(prompter.history().collect::<Vec<_>>(), [("env", "staging")]);
+    /// ```
+    pub fn undo(&mut self) -> Result<Option<String>, InputError> {
+        let Some(asked) = self.history.pop_back() else {
+            return Ok(None);
+        };
+        let answer = self
+            .answer(&asked.prompt, asked.default.as_deref())?
+            .ok_or(InputError::Eof)?;
+        self.remember(&asked.prompt, asked.default.as_deref(), &answer);
+        self.record(&asked.prompt, &answer)?;
+        Ok(Some(answer))
+    }
+
+    /// `(prompt, answer)` of the answers `undo` can drop, oldest first
+    pub fn history(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.history
+            .iter()
+            .map(|asked| (asked.prompt.as_str(), asked.answer.as_str()))
+    }
+
+    fn remember(&mut self, prompt: &str, default: Option<&str>, answer: &str) {
+        if self.pipe_mode {
+            return;
+        }
+        if self.history.len() == UNDO_DEPTH {
+            self.history.pop_front();
+        }
+        self.history.push_back(Asked {
+            prompt: prompt.to_string(),
+            default: default.map(str::to_string),
+            answer: answer.to_string(),
+        });
+    }
+
+    fn record(&self, prompt: &str, answer: &str) -> Result<(), InputError> {
+        match &self.recorder {
+            Some(recorder) => recorder.record(prompt, answer),
+            None => Ok(()),
+        }
+    }
+
+    /// the processed answer to `prompt`, `None` on EOF
+    fn answer(
+        &mut self,
+        prompt: &str,
+        default: Option<&str>,
+    ) -> Result<Option<String>, InputError> {
+        if self.deadline_passed() {
+            self.timed_out();
+            return match (self.deadline_policy, default) {
+                (DeadlinePolicy::UseDefault, Some(default)) => {
+                    self.keep_last(default, None);
+                    Ok(Some(default.to_string()))
+                }
+                _ => Err(InputError::Timeout),
+            };
+        }
+        let started = self.started();
+        if let Some(answer) = self.preset(prompt, default)? {
+            self.answered(started);
+            self.keep_last(&answer, None);
+            return Ok(Some(answer.to_string()));
+        }
+        if !self.read_line(prompt, default)? {
+            return Ok(None);
+        }
+        self.answered(started);
+        let answer = self.line_buf.trim();
+        let (answer, line) = match default {
+            Some(default) if answer.is_empty() => (default.to_string(), None),
+            _ => (answer.to_string(), Some(self.line)),
+        };
+        self.keep_last(&answer, line);
+        Ok(Some(answer))
+    }
+
+    /// ask `prompt` and write the processed answer into `answer`, reusing
+    /// its capacity. returns false on EOF
+    pub fn read_fast(&mut self, prompt: &str, answer: &mut String) -> Result<bool, InputError> {
+        answer.clear();
+        if self.deadline_passed() {
+            self.timed_out();
+            return Err(InputError::Timeout);
+        }
+        let started = self.started();
+        if let Some(preset) = self.preset(prompt, None)? {
+            self.answered(started);
+            answer.push_str(&preset);
+            self.keep_last(answer, None);
+            return Ok(true);
+        }
+        if !self.read_line(prompt, None)? {
+            return Ok(false);
+        }
+        self.answered(started);
+        answer.push_str(self.line_buf.trim());
+        self.keep_last(answer, Some(self.line));
+        Ok(true)
+    }
+
+    /// 1-based number of the last line read, 0 before the first
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// ask `prompt` and parse the answer as `T`, `None` on EOF. a failure
+    /// carries the answer and the number of the line it was read on
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Prompter;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut prompter = Prompter::new(MockReader::new(&["1", "abc"]), MockWriter::new());
+    /// assert_eq!(prompter.parse::<i32>("n"), Ok(Some(1)));
+    /// let err = prompter.parse::<i32>("n").unwrap_err();
+    /// assert_eq!(err.to_string(), "parse error: expected integer at line 2: 'abc'");
+    /// ```
+    pub fn parse<T: FromStr>(&mut self, prompt: &str) -> Result<Option<T>, InputError> {
+        let answer = match self.try_ask(prompt, None)? {
+            Some(answer) => answer,
+            None => return Ok(None),
+        };
+        match answer.parse::<T>() {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => Err(self.parse_error(&expected::<T>())),
+        }
+    }
+
+    /// `parse` for a number between the bounds of `range`, which are
+    /// shown after the prompt
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Prompter;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut prompter = Prompter::new(MockReader::new(&["8", "99"]), MockWriter::new());
+    /// assert_eq!(prompter.parse_in("threads", 1..=64), Ok(Some(8)));
+    /// let err = prompter.parse_in("threads", 1..=64).unwrap_err();
+    /// assert_eq!(err.to_string(), "parse error: expected integer between 1 and 64 at line 2: '99'");
+    /// ```
+    pub fn parse_in<T>(
+        &mut self,
+        prompt: &str,
+        range: RangeInclusive<T>,
+    ) -> Result<Option<T>, InputError>
+    where
+        T: FromStr + PartialOrd + Display,
+    {
+        let constraints = Constraints {
+            range: Some((range.start().to_string(), range.end().to_string())),
+            ..Constraints::default()
+        };
+        let prompt = constraints.with_hint(prompt);
+        match self.parse::<T>(&prompt) {
+            Ok(Some(value)) if !range.contains(&value) => {}
+            Err(InputError::Parse { .. }) => {}
+            answer => return answer,
+        }
+        let (min, max) = range.into_inner();
+        Err(self.parse_error(&format!("{} between {} and {}", expected::<T>(), min, max)))
+    }
+
+    /// `InputError::Parse` with `msg` for the last answer, for checks
+    /// done by the caller. the line number is left out for answers that
+    /// weren't read from the reader, like defaults and preset answers
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Prompter;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut prompter = Prompter::new(MockReader::new(&["", "-1"]), MockWriter::new());
+    /// prompter.ask("retries", Some("many")).unwrap();
+    /// let err = prompter.parse_error("expected a count");
+    /// assert_eq!(err.to_string(), "parse error: expected a count: 'many'");
+    /// prompter.ask("retries", Some("3")).unwrap();
+    /// let err = prompter.parse_error("expected a count");
+    /// assert_eq!(err.to_string(), "parse error: expected a count at line 2: '-1'");
+    /// ```
+    pub fn parse_error(&self, msg: &str) -> InputError {
+        let mut text: String = self.last_answer.chars().take(SNIPPET_LEN).collect();
+        if text.len() < self.last_answer.len() {
+            text.push('…');
+        }
+        InputError::Parse {
+            line: self.last_line,
+            text,
+            msg: msg.to_string(),
+        }
+    }
+
+    /// remember `answer` for `parse_error`, `line` when it was read
+    fn keep_last(&mut self, answer: &str, line: Option<usize>) {
+        self.last_answer.clear();
+        self.last_answer.push_str(answer);
+        self.last_line = line;
+    }
+
+    /// the answer given without reading, never in pipe mode
+    fn preset<'a>(
+        &self,
+        prompt: &str,
+        default: Option<&'a str>,
+    ) -> Result<Option<Cow<'a, str>>, InputError> {
+        match self.pipe_mode {
+            true => Ok(None),
+            false => preset_answer(prompt, prompt, default, &self.reader),
+        }
+    }
+
+    /// when a prompt started, only taken when metrics are reported
+    fn started(&self) -> Option<Instant> {
+        self.metrics.as_ref().map(|_| Instant::now())
+    }
+
+    fn answered(&mut self, started: Option<Instant>) {
+        if let (Some(sink), Some(started)) = (self.metrics.as_mut(), started) {
+            sink.counter(metrics::PROMPTS, 1);
+            sink.histogram(metrics::ANSWER_SECONDS, started.elapsed().as_secs_f64());
+        }
+    }
+
+    fn timed_out(&mut self) {
+        if let Some(sink) = self.metrics.as_mut() {
+            sink.counter(metrics::TIMEOUTS, 1);
+        }
+    }
+
+    fn deadline_passed(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// writes the prompt and reads the raw line into `line_buf`, false on EOF
+    fn read_line(&mut self, prompt: &str, default: Option<&str>) -> Result<bool, InputError> {
+        if !self.pipe_mode {
+            self.prompt_buf.clear();
+            let theme = Theme::current().for_writer(&self.writer);
+            render_prompt_into(&mut self.prompt_buf, prompt, default, &theme);
+            self.writer.write_str(&self.prompt_buf)?;
+            self.writer.flush()?;
+        }
+
+        self.line_buf.clear();
+        if self.reader.read_line(&mut self.line_buf)? == 0 {
+            return Ok(false);
+        }
+        self.line += 1;
+        Ok(true)
+    }
+}
+
+/// what a line should have been for `T`
+pub(crate) fn expected<T>() -> String {
+    let name = type_name::<T>();
+    let name = name.rsplit("::").next().unwrap_or(name);
+    match name {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => "expected integer".to_string(),
+        "f32" | "f64" => "expected number".to_string(),
+        "bool" => "expected true or false".to_string(),
+        "char" => "expected a single character".to_string(),
+        _ => format!("expected {}", name),
+    }
+}
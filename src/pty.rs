@@ -0,0 +1,61 @@
+//! pseudo terminal allocation through the C library, without extra crates
+
+use std::ffi::CStr;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::raw::{c_char, c_int};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::FromRawFd;
+use std::sync::Mutex;
+
+extern "C" {
+    fn posix_openpt(flags: c_int) -> c_int;
+    fn grantpt(fd: c_int) -> c_int;
+    fn unlockpt(fd: c_int) -> c_int;
+    fn ptsname(fd: c_int) -> *mut c_char;
+}
+
+const O_RDWR: c_int = 2;
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+const O_NOCTTY: c_int = 0x20000;
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+const O_NOCTTY: c_int = 0o400;
+
+/// `ptsname` returns a static buffer
+static PTSNAME_LOCK: Mutex<()> = Mutex::new(());
+
+/// both ends of a pseudo terminal, the program side is `slave`
+pub(crate) struct PtyPair {
+    pub(crate) master: File,
+    pub(crate) slave: File,
+}
+
+impl PtyPair {
+    pub(crate) fn open() -> io::Result<Self> {
+        // SAFETY: plain libc calls on a descriptor owned by `master`
+        unsafe {
+            let fd = posix_openpt(O_RDWR | O_NOCTTY);
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let master = File::from_raw_fd(fd);
+            if grantpt(fd) != 0 || unlockpt(fd) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let path = {
+                let _guard = PTSNAME_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+                let name = ptsname(fd);
+                if name.is_null() {
+                    return Err(io::Error::last_os_error());
+                }
+                CStr::from_ptr(name).to_string_lossy().into_owned()
+            };
+            let slave = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .custom_flags(O_NOCTTY)
+                .open(path)?;
+            Ok(PtyPair { master, slave })
+        }
+    }
+}
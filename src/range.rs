@@ -0,0 +1,160 @@
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::error::InputError;
+use crate::input::Input;
+use crate::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::prompter::{expected, Prompt};
+use crate::{check_interactive, read_answer, render_prompt_into, Retries};
+
+/// what to do with a number outside the range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangePolicy {
+    /// report it and ask again
+    Reject,
+    /// use the nearest bound instead
+    Clamp,
+}
+
+/// # RangeInput
+///
+/// prompt for a number between two bounds, created by `Input::range`.
+/// the bounds are shown in the prompt, an answer outside of them is
+/// rejected or clamped
+///
+/// # usage
+/// ```
+/// use input_py::Input;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut writer = MockWriter::new();
+/// let threads = Input::new("Threads")
+///     .default("8")
+///     .range(1..=64)
+///     .read_with_io(&mut MockReader::new(&["100", ""]), &mut writer);
+/// assert_eq!(threads, Ok(8));
+/// assert_eq!(
+///     writer.output,
+///     "Threads [1-64] [8]:error: '100' is not between 1 and 64\nThreads [1-64] [8]:"
+/// );
+/// ```
+pub struct RangeInput<T> {
+    input: Input,
+    min: T,
+    max: T,
+    policy: RangePolicy,
+}
+
+impl<T> RangeInput<T>
+where
+    T: FromStr + PartialOrd + Display + Clone,
+{
+    pub(crate) fn new(mut input: Input, range: RangeInclusive<T>) -> Self {
+        let (min, max) = range.into_inner();
+        input.constraints.range = Some((min.to_string(), max.to_string()));
+        RangeInput {
+            input,
+            min,
+            max,
+            policy: RangePolicy::Reject,
+        }
+    }
+
+    /// default: `RangePolicy::Reject`
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Input;
+    /// use input_py::io::{MockReader, MockWriter};
+    /// use input_py::range::RangePolicy;
+    ///
+    /// let workers = Input::new("workers")
+    ///     .range(1..=16)
+    ///     .policy(RangePolicy::Clamp)
+    ///     .read_with_io(&mut MockReader::new(&["99"]), &mut MockWriter::new());
+    /// assert_eq!(workers, Ok(16));
+    /// ```
+    pub fn policy(mut self, policy: RangePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// read the number from stdin
+    pub fn read(self) -> Result<T, InputError> {
+        self.read_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    pub fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<T, InputError> {
+        let mut prompt = String::new();
+        render_prompt_into(
+            &mut prompt,
+            &self.input.constraints.with_hint(&self.input.prompt),
+            self.input.default.as_deref(),
+            &self.input.theme.for_writer(writer),
+        );
+        let default = self.input.default.as_deref();
+        if let Some(default) = check_interactive(&prompt, default, reader)? {
+            return self
+                .check(default)
+                .map_err(|_| InputError::MissingDefault(self.input.prompt.clone()));
+        }
+
+        let mut retries =
+            Retries::with_max_attempts(self.input.max_attempts).theme(self.input.theme);
+        loop {
+            let answer = read_answer(&prompt, reader, writer)?.ok_or(InputError::Eof)?;
+            let answer = match (answer.is_empty(), default) {
+                (true, Some(default)) => default,
+                _ => &answer,
+            };
+            match self.check(answer) {
+                Ok(value) => return Ok(value),
+                Err(msg) => retries.reject(writer, &msg)?,
+            }
+        }
+    }
+
+    /// `answer` as a number in the range, or why not
+    fn check(&self, answer: &str) -> Result<T, String> {
+        let value = answer.parse::<T>().map_err(|_| {
+            format!(
+                "{} between {} and {}, got '{}'",
+                expected::<T>(),
+                self.min,
+                self.max,
+                answer
+            )
+        })?;
+        if value >= self.min && value <= self.max {
+            return Ok(value);
+        }
+        match (self.policy, value < self.min) {
+            (RangePolicy::Clamp, true) => Ok(self.min.clone()),
+            (RangePolicy::Clamp, false) => Ok(self.max.clone()),
+            (RangePolicy::Reject, _) => Err(format!(
+                "'{}' is not between {} and {}",
+                answer, self.min, self.max
+            )),
+        }
+    }
+}
+
+impl<T> Prompt for RangeInput<T>
+where
+    T: FromStr + PartialOrd + Display + Clone,
+{
+    type Output = T;
+
+    fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<T, InputError> {
+        RangeInput::read_with_io(self, reader, writer)
+    }
+}
@@ -0,0 +1,270 @@
+//! a small backtracking regex for the `pattern` of form files:
+//! literals, `.`, classes like `[a-z_]` and `[^0-9]`, `\d \w \s` and
+//! their negations, groups, `|`, the quantifiers `* + ? {n} {n,} {n,m}`
+//! and the anchors `^ $`. a match may start anywhere unless anchored
+
+use std::cell::Cell;
+
+/// a compiled pattern
+#[derive(Debug, Clone)]
+pub(crate) struct Regex {
+    alternatives: Vec<Vec<Piece>>,
+}
+
+#[derive(Debug, Clone)]
+struct Piece {
+    atom: Atom,
+    min: usize,
+    max: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+enum Atom {
+    Char(char),
+    Any,
+    /// ranges, negated
+    Class(Vec<(char, char)>, bool),
+    Group(Regex),
+    Start,
+    End,
+}
+
+/// matching gives up with an error after this many steps, so a pattern
+/// like `(a*)*b` can't hang a prompt
+const MAX_STEPS: usize = 100_000;
+
+impl Regex {
+    pub(crate) fn new(pattern: &str) -> Result<Self, String> {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut at = 0;
+        let regex = parse_alternatives(&chars, &mut at)?;
+        match chars.get(at) {
+            None => Ok(regex),
+            Some(c) => Err(format!("unexpected '{}' in pattern", c)),
+        }
+    }
+
+    /// whether the pattern matches somewhere in `text`. fails when
+    /// that takes more than `MAX_STEPS`, as the answer is unknown then
+    pub(crate) fn is_match(&self, text: &str) -> Result<bool, String> {
+        let chars: Vec<char> = text.chars().collect();
+        let steps = Cell::new(0);
+        let found = (0..=chars.len()).any(|start| {
+            let mut done = |_: usize| true;
+            self.match_at(&chars, start, &steps, &mut done)
+        });
+        match steps.get() > MAX_STEPS {
+            true => Err(format!(
+                "the pattern took too long to check '{}', it backtracks too much",
+                text
+            )),
+            false => Ok(found),
+        }
+    }
+
+    /// match at `at` and call `next` with every end until it accepts
+    fn match_at(
+        &self,
+        chars: &[char],
+        at: usize,
+        steps: &Cell<usize>,
+        next: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        self.alternatives
+            .iter()
+            .any(|pieces| match_pieces(pieces, chars, at, steps, next))
+    }
+}
+
+fn match_pieces(
+    pieces: &[Piece],
+    chars: &[char],
+    at: usize,
+    steps: &Cell<usize>,
+    next: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    let Some((piece, rest)) = pieces.split_first() else {
+        return next(at);
+    };
+    match_repeat(piece, 0, rest, chars, at, steps, next)
+}
+
+/// `piece` matched `count` times up to `at`, try once more first
+fn match_repeat(
+    piece: &Piece,
+    count: usize,
+    rest: &[Piece],
+    chars: &[char],
+    at: usize,
+    steps: &Cell<usize>,
+    next: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    steps.set(steps.get() + 1);
+    if steps.get() > MAX_STEPS {
+        return false;
+    }
+    if piece.max.is_none_or(|max| count < max) {
+        let mut more = |end: usize| {
+            // an empty repetition past the minimum would loop forever
+            (count < piece.min || end != at)
+                && match_repeat(piece, count + 1, rest, chars, end, steps, next)
+        };
+        if match_atom(&piece.atom, chars, at, steps, &mut more) {
+            return true;
+        }
+    }
+    count >= piece.min && match_pieces(rest, chars, at, steps, next)
+}
+
+fn match_atom(
+    atom: &Atom,
+    chars: &[char],
+    at: usize,
+    steps: &Cell<usize>,
+    next: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    let one = |test: &dyn Fn(char) -> bool| chars.get(at).is_some_and(|&c| test(c));
+    match atom {
+        Atom::Char(expected) => one(&|c| c == *expected) && next(at + 1),
+        Atom::Any => one(&|c| c != '\n') && next(at + 1),
+        Atom::Class(ranges, negated) => {
+            let test = |c: char| ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&c)) != *negated;
+            one(&test) && next(at + 1)
+        }
+        Atom::Group(regex) => regex.match_at(chars, at, steps, next),
+        Atom::Start => at == 0 && next(at),
+        Atom::End => at == chars.len() && next(at),
+    }
+}
+
+fn parse_alternatives(chars: &[char], at: &mut usize) -> Result<Regex, String> {
+    let mut alternatives = vec![parse_sequence(chars, at)?];
+    while chars.get(*at) == Some(&'|') {
+        *at += 1;
+        alternatives.push(parse_sequence(chars, at)?);
+    }
+    Ok(Regex { alternatives })
+}
+
+fn parse_sequence(chars: &[char], at: &mut usize) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    while let Some(&c) = chars.get(*at) {
+        if c == '|' || c == ')' {
+            break;
+        }
+        *at += 1;
+        let atom = match c {
+            '.' => Atom::Any,
+            '^' => Atom::Start,
+            '$' => Atom::End,
+            '(' => {
+                // `(?:` is the same as `(` here
+                if chars[*at..].starts_with(&['?', ':']) {
+                    *at += 2;
+                }
+                let group = parse_alternatives(chars, at)?;
+                if chars.get(*at) != Some(&')') {
+                    return Err("unclosed group in pattern".to_string());
+                }
+                *at += 1;
+                Atom::Group(group)
+            }
+            '[' => parse_class(chars, at)?,
+            '\\' => parse_escape(chars, at)?,
+            '*' | '+' | '?' | '{' => return Err(format!("nothing to repeat before '{}'", c)),
+            c => Atom::Char(c),
+        };
+        let (min, max) = parse_quantifier(chars, at)?;
+        pieces.push(Piece { atom, min, max });
+    }
+    Ok(pieces)
+}
+
+fn parse_quantifier(chars: &[char], at: &mut usize) -> Result<(usize, Option<usize>), String> {
+    let bounds = match chars.get(*at) {
+        Some('*') => (0, None),
+        Some('+') => (1, None),
+        Some('?') => (0, Some(1)),
+        Some('{') => {
+            let close = chars[*at..]
+                .iter()
+                .position(|&c| c == '}')
+                .ok_or("unclosed {} in pattern")?;
+            let inner: String = chars[*at + 1..*at + close].iter().collect();
+            let number = |s: &str| {
+                s.trim()
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid repetition {{{}}} in pattern", inner))
+            };
+            let bounds = match inner.split_once(',') {
+                None => (number(&inner)?, Some(number(&inner)?)),
+                Some((min, "")) => (number(min)?, None),
+                Some((min, max)) => (number(min)?, Some(number(max)?)),
+            };
+            *at += close;
+            bounds
+        }
+        _ => return Ok((1, Some(1))),
+    };
+    *at += 1;
+    Ok(bounds)
+}
+
+fn parse_class(chars: &[char], at: &mut usize) -> Result<Atom, String> {
+    let negated = chars.get(*at) == Some(&'^');
+    if negated {
+        *at += 1;
+    }
+    let mut ranges = Vec::new();
+    let mut first = true;
+    loop {
+        let c = *chars.get(*at).ok_or("unclosed [] in pattern")?;
+        *at += 1;
+        let lo = match c {
+            ']' if !first => return Ok(Atom::Class(ranges, negated)),
+            '\\' => match parse_escape(chars, at)? {
+                Atom::Char(c) => c,
+                Atom::Class(more, false) => {
+                    ranges.extend(more);
+                    first = false;
+                    continue;
+                }
+                _ => return Err("negated escape inside [] in pattern".to_string()),
+            },
+            c => c,
+        };
+        first = false;
+        match (chars.get(*at), chars.get(*at + 1)) {
+            (Some('-'), Some(&hi)) if hi != ']' => {
+                *at += 2;
+                if hi < lo {
+                    return Err(format!("invalid range {}-{} in pattern", lo, hi));
+                }
+                ranges.push((lo, hi));
+            }
+            _ => ranges.push((lo, lo)),
+        }
+    }
+}
+
+fn parse_escape(chars: &[char], at: &mut usize) -> Result<Atom, String> {
+    let c = *chars.get(*at).ok_or("pattern ends with \\")?;
+    *at += 1;
+    const DIGIT: &[(char, char)] = &[('0', '9')];
+    const WORD: &[(char, char)] = &[('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')];
+    const SPACE: &[(char, char)] = &[(' ', ' '), ('\t', '\r')];
+    Ok(match c {
+        'd' => Atom::Class(DIGIT.to_vec(), false),
+        'D' => Atom::Class(DIGIT.to_vec(), true),
+        'w' => Atom::Class(WORD.to_vec(), false),
+        'W' => Atom::Class(WORD.to_vec(), true),
+        's' => Atom::Class(SPACE.to_vec(), false),
+        'S' => Atom::Class(SPACE.to_vec(), true),
+        'n' => Atom::Char('\n'),
+        't' => Atom::Char('\t'),
+        c if c.is_ascii_alphanumeric() => {
+            return Err(format!("unknown escape \\{} in pattern", c));
+        }
+        c => Atom::Char(c),
+    })
+}
@@ -0,0 +1,88 @@
+//! # render
+//!
+//! the exact text each prompt writes before it reads, as a contract.
+//! downstream snapshot tests compare against `render::prompt` or use
+//! `assert_prompt_renders`, and the text only changes together with
+//! `FORMAT_VERSION`, in a major release. answers preset by the
+//! environment, like `INPUT_PY_ANSWER_<ID>`, skip the prompt and render
+//! nothing
+
+use crate::frontend::{self, TerminalFrontend};
+use crate::io::{MockReader, MockWriter};
+use crate::model::Question;
+use crate::prompter::Prompt;
+
+/// version of the prompt text. bumped with every change to what any
+/// prompt writes, which the changelog lists
+pub const FORMAT_VERSION: u32 = 1;
+
+/// # Rendered
+///
+/// what `assert_prompt_renders` takes: every `Prompt` and a `Question`
+pub trait Rendered {
+    /// the text written before the first answer is read
+    fn rendered(self) -> String;
+}
+
+impl<P: Prompt> Rendered for P {
+    fn rendered(self) -> String {
+        prompt(self)
+    }
+}
+
+impl Rendered for &Question {
+    fn rendered(self) -> String {
+        question(self)
+    }
+}
+
+/// the text `prompt` writes before its first answer, on an interactive
+/// reader
+///
+/// # usage
+/// ```
+/// use input_py::{render, Select};
+///
+/// assert_eq!(render::prompt(Select::new("region", &["eu", "us"])), "  1. eu\n  2. us\nregion:");
+/// ```
+pub fn prompt<P: Prompt>(prompt: P) -> String {
+    let mut writer = MockWriter::new();
+    let _ = prompt.read_with_io(&mut MockReader::new(&[]), &mut writer);
+    writer.output
+}
+
+/// the text `question` is asked with by `Input`, `Form` and
+/// `TerminalFrontend`
+///
+/// # usage
+/// ```
+/// use input_py::{render, Question};
+///
+/// assert_eq!(render::question(&Question::new("port", "port").default("5432")), "port [5432]:");
+/// ```
+pub fn question(question: &Question) -> String {
+    let mut terminal = TerminalFrontend::new(MockReader::new(&[]), MockWriter::new());
+    let _ = frontend::ask(&mut terminal, question);
+    terminal.into_inner().1.output
+}
+
+/// panic unless `question` renders as `expected`, naming the format
+/// version so a failure after an upgrade points at the changelog
+///
+/// # usage
+/// ```
+/// use input_py::render::assert_prompt_renders;
+/// use input_py::Input;
+///
+/// assert_prompt_renders(Input::new("deploy?").confirm().default(false), "deploy? [y/N]:");
+/// ```
+#[track_caller]
+pub fn assert_prompt_renders(question: impl Rendered, expected: &str) {
+    let rendered = question.rendered();
+    if rendered != expected {
+        panic!(
+            "the prompt rendered differently under format version {}\n  rendered: {:?}\n  expected: {:?}",
+            FORMAT_VERSION, rendered, expected
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! # screen
+//!
+//! the alternate screen of terminals. a long list or a text area asked
+//! there leaves the scrollback and the cursor as they were, so prompts
+//! fit into full screen apps
+
+use crate::error::InputError;
+use crate::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::prompter::Prompt;
+
+/// switches to the alternate screen, saving the cursor
+const ENTER: &str = "\u{1b}[?1049h\u{1b}[H";
+/// back to the normal screen, restoring the cursor
+const LEAVE: &str = "\u{1b}[?1049l";
+
+/// switch `writer` to the alternate screen with the cursor at the top.
+/// pair it with `leave`
+pub fn enter(writer: &mut dyn OutputWriter) -> Result<(), InputError> {
+    writer.write_str(ENTER)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// switch back to the normal screen and the cursor from before `enter`
+pub fn leave(writer: &mut dyn OutputWriter) -> Result<(), InputError> {
+    writer.write_str(LEAVE)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// # AltScreen
+///
+/// asks a prompt on the alternate screen and leaves it afterwards, also
+/// when the prompt fails. readers that aren't interactive, like pipes,
+/// are asked on the normal screen. with `summary` a line about the answer
+/// is written to the normal screen in place of the prompt
+///
+/// # usage
+/// ```
+/// use input_py::io::{MockReader, MockWriter};
+/// use input_py::screen::AltScreen;
+/// use input_py::Select;
+///
+/// let mut writer = MockWriter::new();
+/// let region = AltScreen::new(Select::new("region", &["eu", "us"]))
+///     .summary(|region| format!("region: {}", region))
+///     .read_with_io(&mut MockReader::new(&["2"]).interactive(true), &mut writer);
+/// assert_eq!(region, Ok("us".to_string()));
+/// assert!(writer.output.starts_with("\u{1b}[?1049h\u{1b}[H  1. eu\n"));
+/// assert!(writer.output.ends_with("region:\u{1b}[?1049lregion: us\n"));
+/// ```
+pub struct AltScreen<P: Prompt> {
+    prompt: P,
+    summary: Option<Summary<P::Output>>,
+}
+
+type Summary<T> = Box<dyn Fn(&T) -> String>;
+
+impl<P: Prompt> AltScreen<P> {
+    pub fn new(prompt: P) -> Self {
+        AltScreen {
+            prompt,
+            summary: None,
+        }
+    }
+
+    /// line written after leaving the alternate screen, from the answer
+    pub fn summary<F>(mut self, summary: F) -> Self
+    where
+        F: Fn(&P::Output) -> String + 'static,
+    {
+        self.summary = Some(Box::new(summary));
+        self
+    }
+
+    /// ask on stdin
+    pub fn read(self) -> Result<P::Output, InputError> {
+        self.read_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    pub fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<P::Output, InputError> {
+        if !reader.is_interactive() {
+            return self.prompt.read_with_io(reader, writer);
+        }
+        enter(writer)?;
+        let answer = self.prompt.read_with_io(reader, writer);
+        leave(writer)?;
+        let answer = answer?;
+        if let Some(summary) = &self.summary {
+            writer.write_all_lines(&[&summary(&answer)])?;
+            writer.flush()?;
+        }
+        Ok(answer)
+    }
+}
+
+impl<P: Prompt> Prompt for AltScreen<P> {
+    type Output = P::Output;
+
+    fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<P::Output, InputError> {
+        AltScreen::read_with_io(self, reader, writer)
+    }
+}
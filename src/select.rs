@@ -0,0 +1,655 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::{self, Theme};
+use crate::editor::{Key, KeyEvent, MouseKind};
+use crate::error::InputError;
+use crate::io::{GenericWriter, InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::model::{Answer, Provenance};
+use crate::prompter::Prompt;
+use crate::widget::{Event, Role, StyledWriter, Widget};
+use crate::{check_interactive, read_answer_redrawn, render_prompt_into, Retries};
+
+/// answer that fetches the items of `Select::items_with` again
+const REFRESH: &str = "r";
+
+/// fetches quicker than this show no spinner
+const SPINNER_DELAY: Duration = Duration::from_millis(150);
+
+/// time between spinner frames
+const SPINNER_TICK: Duration = Duration::from_millis(80);
+
+/// how often a running fetch is checked
+const FETCH_POLL: Duration = Duration::from_millis(5);
+
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+/// runs the provider of `items_with` for the prompt, with the spinner
+type Fetcher<T> = Box<dyn Fn(&str, &mut dyn OutputWriter) -> Result<Vec<T>, InputError>>;
+
+/// a line of the list
+enum Entry {
+    /// index into `values`
+    Item {
+        value: usize,
+        enabled: bool,
+    },
+    Header(String),
+    Separator,
+}
+
+/// # Select
+///
+/// choose one of several items. the items are listed with numbers and
+/// the answer is a number or an item itself. an empty answer picks the
+/// default.
+///
+/// long lists can be organized with `header` and `separator`. disabled
+/// items are shown dimmed without a number and can't be chosen.
+///
+/// `Select::new` chooses among strings, `Select::with_display` among any
+/// values, listed by a display closure, and returns the chosen value
+///
+/// # usage
+/// ```
+/// use input_py::Select;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut writer = MockWriter::new();
+/// let env = Select::new("env", &["dev", "staging", "prod"])
+///     .default("dev")
+///     .read_with_io(&mut MockReader::new(&["3"]), &mut writer);
+/// assert_eq!(env, Ok("prod".to_string()));
+/// assert_eq!(writer.output, "  1. dev\n  2. staging\n  3. prod\nenv [dev]:");
+/// ```
+pub struct Select<T = String> {
+    prompt: String,
+    values: Vec<T>,
+    entries: Vec<Entry>,
+    display: Box<dyn Fn(&T) -> String>,
+    default: Option<String>,
+    fetcher: Option<Fetcher<T>>,
+    theme: Theme,
+    #[cfg_attr(not(all(unix, feature = "line-editing")), allow(dead_code))]
+    mouse: bool,
+}
+
+impl Select<String> {
+    pub fn new<S: AsRef<str>>(prompt: &str, items: &[S]) -> Self {
+        let items = items.iter().map(|item| item.as_ref().to_string());
+        Select::with_display(prompt, items, String::clone)
+    }
+}
+
+impl<T> Select<T> {
+    /// choose among `items`, each listed as `display` shows it
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Select;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Region {
+    ///     id: u32,
+    ///     name: &'static str,
+    /// }
+    ///
+    /// let regions = [Region { id: 7, name: "eu-west" }, Region { id: 9, name: "us-east" }];
+    /// let mut writer = MockWriter::new();
+    /// let region = Select::with_display("region", regions, |r| format!("{} (#{})", r.name, r.id))
+    ///     .read_with_io(&mut MockReader::new(&["2"]), &mut writer);
+    /// assert_eq!(region, Ok(Region { id: 9, name: "us-east" }));
+    /// assert_eq!(writer.output, "  1. eu-west (#7)\n  2. us-east (#9)\nregion:");
+    /// ```
+    pub fn with_display<I, F>(prompt: &str, items: I, display: F) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        F: Fn(&T) -> String + 'static,
+    {
+        let mut select = Select {
+            prompt: prompt.to_string(),
+            values: Vec::new(),
+            entries: Vec::new(),
+            display: Box::new(display),
+            default: None,
+            fetcher: None,
+            theme: Theme::current(),
+            mouse: false,
+        };
+        for item in items {
+            select.push(item, true);
+        }
+        select
+    }
+
+    /// add an item after the ones added so far
+    pub fn item(mut self, value: impl Into<T>) -> Self {
+        self.push(value.into(), true);
+        self
+    }
+
+    /// add an item that is listed but can't be chosen
+    pub fn disabled(mut self, value: impl Into<T>) -> Self {
+        self.push(value.into(), false);
+        self
+    }
+
+    /// add a group title, the following items belong to it
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Select;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut writer = MockWriter::new();
+    /// let target = Select::new::<&str>("target", &[])
+    ///     .header("Cloud")
+    ///     .item("aws")
+    ///     .disabled("azure")
+    ///     .separator()
+    ///     .header("Local")
+    ///     .item("docker")
+    ///     .read_with_io(&mut MockReader::new(&["azure", "2"]), &mut writer);
+    /// assert_eq!(target, Ok("docker".to_string()));
+    /// assert_eq!(
+    ///     writer.output,
+    ///     "Cloud\n  1. aws\n     azure (unavailable)\n  ──────────\nLocal\n  2. docker\n\
+    ///      target:error: 'azure' is not available\ntarget:"
+    /// );
+    /// ```
+    pub fn header(mut self, title: &str) -> Self {
+        self.entries.push(Entry::Header(title.to_string()));
+        self
+    }
+
+    /// add a line between groups
+    pub fn separator(mut self) -> Self {
+        self.entries.push(Entry::Separator);
+        self
+    }
+
+    /// the text around the prompt and errors and the style of headers
+    /// and disabled items. default: the `config::theme`
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// the item shown as `label` is chosen by an empty answer
+    pub fn default(mut self, label: &str) -> Self {
+        self.default = Some(label.to_string());
+        self
+    }
+
+    /// choose with the arrows or a click on a terminal instead of
+    /// typing a number. only with the `line-editing` feature on a unix
+    /// terminal, the list is numbered as before otherwise, when reading
+    /// from other readers or when the terminal refuses raw mode. Esc
+    /// fails with `InputError::Aborted`. off by default, as catching the
+    /// mouse keeps the terminal from selecting text
+    pub fn mouse(mut self, mouse: bool) -> Self {
+        self.mouse = mouse;
+        self
+    }
+
+    /// choose from stdin
+    pub fn read(self) -> Result<T, InputError> {
+        #[cfg(all(unix, feature = "line-editing"))]
+        if self.mouse && StdinReader.is_interactive() && crate::line_editor::edits_stdin() {
+            if let Some(raw) = crate::line_editor::raw_mode() {
+                return crate::line_editor::run_widget(raw, self.into_widget()?);
+            }
+        }
+        self.read_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    pub fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<T, InputError> {
+        self.read_index_with_io(reader, writer)
+            .map(|(_, value)| value)
+    }
+
+    /// choose from stdin, with the position of the chosen item
+    pub fn read_index(self) -> Result<(usize, T), InputError> {
+        self.read_index_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    /// the chosen item and its position among the items, counted from 0
+    /// in the order they were given, disabled ones included
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Select;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let env = Select::new("Pick env", &["dev", "staging", "prod"])
+    ///     .read_index_with_io(&mut MockReader::new(&["staging"]), &mut MockWriter::new());
+    /// assert_eq!(env, Ok((1, "staging".to_string())));
+    /// ```
+    pub fn read_index_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<(usize, T), InputError> {
+        let id = self.prompt.clone();
+        self.ask(&id, reader, writer)
+            .map(|(index, value, _)| (index, value))
+    }
+
+    /// the chosen item as the `Answer` to question `id`: its label, the
+    /// default when an empty line chose it, and how many times it was
+    /// asked
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Select;
+    /// use input_py::io::{MockReader, MockWriter};
+    /// use input_py::model::Provenance;
+    ///
+    /// let mut reader = MockReader::new(&["9", ""]);
+    /// let env = Select::new("env", &["dev", "prod"])
+    ///     .default("prod")
+    ///     .read_answer_with_io("env", &mut reader, &mut MockWriter::new())
+    ///     .unwrap();
+    /// assert_eq!((env.value.as_str(), env.provenance, env.attempts), ("prod", Provenance::Default, 2));
+    /// ```
+    pub fn read_answer_with_io(
+        self,
+        id: &str,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Answer, InputError> {
+        self.ask(id, reader, writer).map(|(_, _, answer)| answer)
+    }
+
+    /// the position and value of the chosen item with its `Answer`
+    fn ask(
+        mut self,
+        id: &str,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<(usize, T, Answer), InputError> {
+        let mut prompt = String::new();
+        let theme = self.theme.for_writer(writer);
+        render_prompt_into(&mut prompt, &self.prompt, self.default.as_deref(), &theme);
+        let unattended = check_interactive(&prompt, self.default.as_deref(), reader)?.is_some();
+
+        self.fetch(writer)?;
+        if unattended {
+            return match self.choose("") {
+                Ok(value) => Ok(self.chosen(id, value, Provenance::Default, 1)),
+                Err(_) => Err(InputError::MissingDefault(self.prompt)),
+            };
+        }
+        self.write_list(writer)?;
+        let mut retries = Retries::new().theme(self.theme);
+        loop {
+            let answer = read_answer_redrawn(&prompt, reader, writer, &mut |writer| {
+                self.write_list(writer)
+            })?
+            .ok_or(InputError::Eof)?;
+            if self.refreshes(&answer) {
+                self.fetch(writer)?;
+                self.write_list(writer)?;
+                continue;
+            }
+            let provenance = match answer.is_empty() {
+                true => Provenance::Default,
+                false => Provenance::Entered,
+            };
+            match self.choose(&answer) {
+                Ok(value) => return Ok(self.chosen(id, value, provenance, retries.attempts())),
+                Err(msg) => retries.reject(writer, &msg)?,
+            }
+        }
+    }
+
+    /// `ask`'s result for the item at `value`
+    fn chosen(
+        self,
+        id: &str,
+        value: usize,
+        provenance: Provenance,
+        attempts: u32,
+    ) -> (usize, T, Answer) {
+        let label = (self.display)(&self.values[value]);
+        let answer = Answer::new(id, &label, provenance).attempts(attempts);
+        (value, self.take(value), answer)
+    }
+
+    /// this select as a widget, see `widget`. Up and Down or the wheel
+    /// move between the items, a digit jumps to that item and Enter or a
+    /// click on an item chooses. the items of `items_with` are fetched
+    /// here
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::editor::Key;
+    /// use input_py::widget::{Event, TextCanvas, Widget};
+    /// use input_py::Select;
+    ///
+    /// let mut env = Select::new("env", &["dev", "prod"]).into_widget().unwrap();
+    /// env.handle_key(Key::Down.into());
+    /// let mut canvas = TextCanvas::new();
+    /// env.render(&mut canvas);
+    /// assert_eq!(canvas.lines(), ["env", "  1. dev", "> 2. prod"]);
+    /// assert_eq!(env.handle_key(Key::Enter.into()), Event::Done("prod".to_string()));
+    /// ```
+    pub fn into_widget(mut self) -> Result<SelectWidget<T>, InputError> {
+        self.fetch(&mut GenericWriter::new(std::io::sink()))?;
+        let first = self.choices().next().map(|(value, _)| value);
+        let Some(first) = first else {
+            return Err(InputError::NoItems(self.prompt));
+        };
+        let active = match self.choose("") {
+            Ok(value) => value,
+            Err(_) if self.default.is_some() => {
+                return Err(InputError::MissingDefault(self.prompt));
+            }
+            Err(_) => first,
+        };
+        Ok(SelectWidget {
+            select: Some(self),
+            active,
+        })
+    }
+
+    fn push(&mut self, value: T, enabled: bool) {
+        self.entries.push(Entry::Item {
+            value: self.values.len(),
+            enabled,
+        });
+        self.values.push(value);
+    }
+
+    /// the chosen value, the select is done
+    fn take(mut self, value: usize) -> T {
+        self.values.swap_remove(value)
+    }
+
+    /// `(value, label)` of the items that can be chosen, in order
+    fn choices(&self) -> impl Iterator<Item = (usize, String)> + '_ {
+        self.entries.iter().filter_map(|entry| match entry {
+            Entry::Item {
+                value,
+                enabled: true,
+            } => Some((*value, (self.display)(&self.values[*value]))),
+            _ => None,
+        })
+    }
+
+    /// whether `answer` asks to fetch the items again
+    fn refreshes(&self, answer: &str) -> bool {
+        self.fetcher.is_some()
+            && answer == REFRESH
+            && !self.choices().any(|(_, label)| label == REFRESH)
+    }
+
+    /// replace the items with the provider's, if there is one
+    fn fetch(&mut self, writer: &mut dyn OutputWriter) -> Result<(), InputError> {
+        let Some(fetcher) = &self.fetcher else {
+            return Ok(());
+        };
+        let items = fetcher(&self.prompt, writer)?;
+        self.values.clear();
+        self.entries.clear();
+        for item in items {
+            self.push(item, true);
+        }
+        Ok(())
+    }
+
+    fn write_list(&self, writer: &mut dyn OutputWriter) -> Result<(), InputError> {
+        if self.choices().next().is_none() {
+            return Err(InputError::NoItems(self.prompt.clone()));
+        }
+        let theme = &self.theme;
+        let mut number = 0;
+        let mut list: Vec<String> = Vec::with_capacity(self.entries.len() + 1);
+        for entry in &self.entries {
+            list.push(match entry {
+                Entry::Item {
+                    value,
+                    enabled: true,
+                } => {
+                    number += 1;
+                    format!("  {}. {}", number, (self.display)(&self.values[*value]))
+                }
+                Entry::Item { value, .. } => format!(
+                    "     {}{} (unavailable){}",
+                    theme.disabled.0,
+                    (self.display)(&self.values[*value]),
+                    theme.disabled.1
+                ),
+                Entry::Header(title) => format!("{}{}{}", theme.bold.0, title, theme.bold.1),
+                Entry::Separator => format!("  {}", "─".repeat(10)),
+            });
+        }
+        if self.fetcher.is_some() {
+            list.push(format!("  ({} to refresh)", REFRESH));
+        }
+        let list: Vec<&str> = list.iter().map(String::as_str).collect();
+        writer.write_all_lines(&list)?;
+        Ok(())
+    }
+
+    /// the value `answer` picks, by label or number
+    fn choose(&self, answer: &str) -> Result<usize, String> {
+        let answer = match (answer.is_empty(), &self.default) {
+            (true, Some(default)) => default.as_str(),
+            (true, None) => return Err("please choose an item".to_string()),
+            (false, _) => answer,
+        };
+        for entry in &self.entries {
+            if let Entry::Item { value, enabled } = entry {
+                if (self.display)(&self.values[*value]) == answer {
+                    return match enabled {
+                        true => Ok(*value),
+                        false => Err(format!("'{}' is not available", answer)),
+                    };
+                }
+            }
+        }
+        let count = self.choices().count();
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=count).contains(&n) => match self.choices().nth(n - 1) {
+                Some((value, _)) => Ok(value),
+                None => Err(format!("'{}' is not an item", answer)),
+            },
+            _ => Err(format!(
+                "'{}' is not an item or a number between 1 and {}",
+                answer, count
+            )),
+        }
+    }
+}
+
+/// a `Select` driven by key events, made by `Select::into_widget`
+pub struct SelectWidget<T> {
+    /// none once chosen
+    select: Option<Select<T>>,
+    /// the highlighted value
+    active: usize,
+}
+
+impl<T> SelectWidget<T> {
+    /// the value next to the highlighted one that can be chosen, before
+    /// it when `back`
+    fn step(&self, select: &Select<T>, back: bool) -> usize {
+        let choices: Vec<usize> = select.choices().map(|(value, _)| value).collect();
+        let at = choices.iter().position(|v| *v == self.active).unwrap_or(0);
+        match back {
+            true => choices[at.saturating_sub(1)],
+            false => choices[(at + 1).min(choices.len() - 1)],
+        }
+    }
+}
+
+impl<T> Widget for SelectWidget<T> {
+    type Output = T;
+
+    fn handle_key(&mut self, key: KeyEvent) -> Event<T> {
+        let Some(select) = &self.select else {
+            return Event::Continue;
+        };
+        match (key.key, key.ctrl) {
+            (Key::Char('c'), true) | (Key::Esc, _) => return Event::Cancelled,
+            (Key::Enter, _) => {
+                if let Some(select) = self.select.take() {
+                    return Event::Done(select.take(self.active));
+                }
+            }
+            (Key::Up, _) | (Key::Char('p'), true) => self.active = self.step(select, true),
+            (Key::Down, _) | (Key::Char('n'), true) => self.active = self.step(select, false),
+            (Key::Home, _) => self.active = select.choices().next().map_or(0, |(v, _)| v),
+            (Key::End, _) => self.active = select.choices().last().map_or(0, |(v, _)| v),
+            (Key::Mouse(mouse), _) => match mouse.kind {
+                MouseKind::ScrollUp => self.active = self.step(select, true),
+                MouseKind::ScrollDown => self.active = self.step(select, false),
+                // the prompt is on the first line, the entries below it
+                MouseKind::Click => {
+                    let entry = (mouse.row as usize)
+                        .checked_sub(1)
+                        .and_then(|at| select.entries.get(at));
+                    if let Some(Entry::Item {
+                        value,
+                        enabled: true,
+                    }) = entry
+                    {
+                        let value = *value;
+                        if let Some(select) = self.select.take() {
+                            return Event::Done(select.take(value));
+                        }
+                    }
+                }
+            },
+            (Key::Char(c), false) => {
+                let digit = c.to_digit(10).filter(|n| *n > 0);
+                if let Some((value, _)) = digit.and_then(|n| select.choices().nth(n as usize - 1)) {
+                    self.active = value;
+                }
+            }
+            _ => {}
+        }
+        Event::Continue
+    }
+
+    fn render(&self, out: &mut dyn StyledWriter) {
+        let Some(select) = &self.select else {
+            return;
+        };
+        out.write(&select.prompt, Role::Prompt);
+        let mut number = 0;
+        for entry in &select.entries {
+            out.end_line();
+            match entry {
+                Entry::Item {
+                    value,
+                    enabled: true,
+                } => {
+                    number += 1;
+                    let label = (select.display)(&select.values[*value]);
+                    match *value == self.active {
+                        true => out.write(&format!("> {}. {}", number, label), Role::Active),
+                        false => out.write(&format!("  {}. {}", number, label), Role::Text),
+                    }
+                }
+                Entry::Item { value, .. } => {
+                    let label = (select.display)(&select.values[*value]);
+                    out.write(&format!("     {} (unavailable)", label), Role::Disabled);
+                }
+                Entry::Header(title) => out.write(title, Role::Header),
+                Entry::Separator => out.write(&format!("  {}", "─".repeat(10)), Role::Hint),
+            }
+        }
+    }
+}
+
+impl<T: Send> Select<T> {
+    /// fetch the items with `provider` when the prompt is reached instead
+    /// of using the given ones, for choices that come from an API. a slow
+    /// fetch shows a spinner, answering `r` fetches the items again
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::Select;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let region = Select::new::<&str>("region", &[])
+    ///     .items_with(|| vec!["eu-west".to_string(), "us-east".to_string()])
+    ///     .read_with_io(&mut MockReader::new(&["r", "us-east"]), &mut MockWriter::new());
+    /// assert_eq!(region, Ok("us-east".to_string()));
+    /// ```
+    pub fn items_with<F>(mut self, provider: F) -> Self
+    where
+        F: Fn() -> Vec<T> + Send + Sync + 'static,
+    {
+        self.fetcher = Some(Box::new(move |prompt, writer| {
+            fetch_with_spinner(&provider, prompt, writer)
+        }));
+        self
+    }
+}
+
+/// run `provider` on another thread and spin while it takes long. only a
+/// terminal gets the animation, other writers get a single `loading` line
+/// and deterministic runs nothing, as when the fetch shows up is timing
+fn fetch_with_spinner<T, F>(
+    provider: &F,
+    prompt: &str,
+    writer: &mut dyn OutputWriter,
+) -> Result<Vec<T>, InputError>
+where
+    T: Send,
+    F: Fn() -> Vec<T> + Sync,
+{
+    let animate = writer.is_terminal();
+    let quiet = config::is_deterministic();
+    let label = format!("loading {} ", prompt);
+    let mut spun = false;
+    let items = thread::scope(|scope| {
+        let fetching = scope.spawn(provider);
+        let started = Instant::now();
+        let mut frames = SPINNER_FRAMES.iter().cycle();
+        let mut next_frame = SPINNER_DELAY;
+        while !fetching.is_finished() {
+            if !quiet && started.elapsed() >= next_frame {
+                if animate {
+                    let frame = frames.next().unwrap_or(&' ');
+                    writer.write_str(&format!("\r{}{}", label, frame))?;
+                    spun = true;
+                    next_frame += SPINNER_TICK;
+                } else {
+                    writer.write_str(&format!("loading {}…\n", prompt))?;
+                    next_frame = Duration::MAX;
+                }
+                writer.flush()?;
+            }
+            thread::sleep(FETCH_POLL);
+        }
+        fetching
+            .join()
+            .map_err(|_| InputError::Io(format!("fetching the items of '{}' failed", prompt)))
+    });
+    if spun {
+        let width = label.chars().count() + 1;
+        writer.write_str(&format!("\r{}\r", " ".repeat(width)))?;
+    }
+    items
+}
+
+impl<T> Prompt for Select<T> {
+    type Output = T;
+
+    fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<T, InputError> {
+        Select::read_with_io(self, reader, writer)
+    }
+}
@@ -0,0 +1,67 @@
+//! # serialize
+//!
+//! `Answers` as a map of id to value, with the `serde` feature. an
+//! `Answer` with its provenance serializes as a struct
+
+use std::fmt;
+
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::form::Answers;
+
+/// in asking order, like `Answers::iter`
+///
+/// # usage
+/// ```
+/// use input_py::form::Answers;
+///
+/// let mut answers = Answers::new();
+/// answers.insert("host", "db.local");
+/// answers.insert("port", "5432");
+/// let json = serde_json::to_string(&answers).unwrap();
+/// assert_eq!(json, r#"{"host":"db.local","port":"5432"}"#);
+/// ```
+impl Serialize for Answers {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (id, value) in self.iter() {
+            map.serialize_entry(id, value)?;
+        }
+        map.end()
+    }
+}
+
+/// every value counts as entered
+///
+/// # usage
+/// ```
+/// use input_py::form::Answers;
+///
+/// let answers: Answers = serde_json::from_str(r#"{"host":"db.local"}"#).unwrap();
+/// assert_eq!(answers.get("host"), Some("db.local"));
+/// ```
+impl<'de> Deserialize<'de> for Answers {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(AnswersVisitor)
+    }
+}
+
+struct AnswersVisitor;
+
+impl<'de> Visitor<'de> for AnswersVisitor {
+    type Value = Answers;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of answers by id")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Answers, A::Error> {
+        let mut answers = Answers::new();
+        while let Some((id, value)) = map.next_entry::<String, String>()? {
+            answers.insert(&id, &value);
+        }
+        Ok(answers)
+    }
+}
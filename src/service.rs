@@ -0,0 +1,67 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+use crate::error::InputError;
+use crate::frontend::{self, Frontend, TerminalFrontend};
+use crate::io::{StdinReader, StdoutWriter};
+use crate::model::{Answer, Question};
+
+type Job = (Question, Sender<Result<Answer, InputError>>);
+
+/// # PromptService
+///
+/// runs all prompting on one dedicated thread, so async and multi
+/// threaded applications can request answers without blocking their own
+/// threads and without interleaving prompts. questions are asked in the
+/// order they are submitted
+///
+/// # usage
+/// ```
+/// use input_py::frontend::{Question, TerminalFrontend};
+/// use input_py::io::{MockReader, MockWriter};
+/// use input_py::service::PromptService;
+///
+/// let frontend = TerminalFrontend::new(MockReader::new(&["db"]), MockWriter::new());
+/// let service = PromptService::start(frontend);
+/// let host = service.ask(Question::new("host", "host"));
+/// assert_eq!(host.recv().unwrap().unwrap().value, "db");
+/// ```
+pub struct PromptService {
+    jobs: Sender<Job>,
+    worker: JoinHandle<()>,
+}
+
+impl PromptService {
+    /// start the prompt thread on `frontend`
+    pub fn start<F: Frontend + Send + 'static>(mut frontend: F) -> Self {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let worker = thread::spawn(move || {
+            for (question, reply) in queue {
+                let answer = frontend::ask(&mut frontend, &question);
+                // the requester may have stopped waiting
+                let _ = reply.send(answer);
+            }
+        });
+        PromptService { jobs, worker }
+    }
+
+    /// start the prompt thread on stdin and stdout
+    pub fn stdio() -> Self {
+        PromptService::start(TerminalFrontend::new(StdinReader, StdoutWriter))
+    }
+
+    /// queue `question`, the answer arrives on the returned receiver
+    pub fn ask(&self, question: Question) -> Receiver<Result<Answer, InputError>> {
+        let (reply, answer) = mpsc::channel();
+        if let Err(mpsc::SendError((_, reply))) = self.jobs.send((question, reply)) {
+            let _ = reply.send(Err(InputError::Io("prompt service stopped".to_string())));
+        }
+        answer
+    }
+
+    /// finish the queued questions and stop the thread
+    pub fn shutdown(self) {
+        drop(self.jobs);
+        let _ = self.worker.join();
+    }
+}
@@ -0,0 +1,287 @@
+//! # storage
+//!
+//! where answers outlive a run: the `History`, answers kept with
+//! `Input::remember`, `Form::profile` and `Form::checkpoint_in`. a
+//! `FileStorage` keeps one file per key in a directory, a
+//! `MemoryStorage` keeps nothing past the process. apps with their own
+//! config store or database implement `Storage` for it
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::error::InputError;
+
+/// # Storage
+///
+/// text values by key, like `history` or `profile.prod`. shared between
+/// prompts as an `Arc<dyn Storage>`, so the methods take `&self`
+///
+/// # usage
+/// ```
+/// use input_py::storage::{MemoryStorage, Storage};
+///
+/// let storage = MemoryStorage::new();
+/// storage.put("profile.prod", "host = \"db\"\n").unwrap();
+/// assert_eq!(storage.get("profile.prod").unwrap().as_deref(), Some("host = \"db\"\n"));
+/// assert_eq!(storage.list().unwrap(), ["profile.prod"]);
+/// storage.delete("profile.prod").unwrap();
+/// assert_eq!(storage.get("profile.prod").unwrap(), None);
+/// ```
+pub trait Storage: Send + Sync {
+    /// the value under `key`, none if there is none
+    fn get(&self, key: &str) -> Result<Option<String>, InputError>;
+
+    /// keep `value` under `key`, replacing what was there
+    fn put(&self, key: &str, value: &str) -> Result<(), InputError>;
+
+    /// every key, sorted
+    fn list(&self) -> Result<Vec<String>, InputError>;
+
+    /// remove `key`, nothing happens if it isn't there
+    fn delete(&self, key: &str) -> Result<(), InputError>;
+}
+
+/// # FileStorage
+///
+/// one file per key in a directory, created at the first `put`. a value
+/// is written to a temporary file first, so a crash never leaves half
+/// of it. keys are file names with characters other than letters,
+/// digits, `_`, `-` and `.` written as `%XX`
+///
+/// # usage
+/// ```
+/// use input_py::storage::{FileStorage, Storage};
+///
+/// let dir = std::env::temp_dir().join("input_py_doc_storage");
+/// let storage = FileStorage::new(&dir);
+/// storage.put("answer/db host", "db1").unwrap();
+/// assert!(dir.join("answer%2Fdb%20host").exists());
+/// assert_eq!(FileStorage::new(&dir).get("answer/db host").unwrap().as_deref(), Some("db1"));
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        FileStorage {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, InputError> {
+        if key.is_empty() {
+            return Err(InputError::Io("storage keys can't be empty".to_string()));
+        }
+        Ok(self.dir.join(encode(key)))
+    }
+}
+
+impl Storage for FileStorage {
+    fn get(&self, key: &str) -> Result<Option<String>, InputError> {
+        match fs::read_to_string(self.path(key)?) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn put(&self, key: &str, value: &str) -> Result<(), InputError> {
+        let path = self.path(key)?;
+        fs::create_dir_all(&self.dir)?;
+        // `~` is encoded in keys, so the temporary file is never one
+        write_replacing(&path, "~", value)
+    }
+
+    fn list(&self) -> Result<Vec<String>, InputError> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut keys = Vec::new();
+        for entry in entries {
+            let name = entry?.file_name();
+            // temporary files of `put` and whatever else lives there
+            if let Some(key) = name.to_str().and_then(decode) {
+                keys.push(key);
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), InputError> {
+        match fs::remove_file(self.path(key)?) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// # MemoryStorage
+///
+/// values kept in memory, for tests and runs that shouldn't leave files
+#[derive(Debug, Default)]
+pub struct MemoryStorage(Mutex<BTreeMap<String, String>>);
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        MemoryStorage::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, String>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn get(&self, key: &str) -> Result<Option<String>, InputError> {
+        Ok(self.lock().get(key).cloned())
+    }
+
+    fn put(&self, key: &str, value: &str) -> Result<(), InputError> {
+        self.lock().insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<String>, InputError> {
+        Ok(self.lock().keys().cloned().collect())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), InputError> {
+        self.lock().remove(key);
+        Ok(())
+    }
+}
+
+/// a shared storage, so one can be wrapped and still used directly
+impl<S: Storage + ?Sized> Storage for Arc<S> {
+    fn get(&self, key: &str) -> Result<Option<String>, InputError> {
+        (**self).get(key)
+    }
+
+    fn put(&self, key: &str, value: &str) -> Result<(), InputError> {
+        (**self).put(key, value)
+    }
+
+    fn list(&self) -> Result<Vec<String>, InputError> {
+        (**self).list()
+    }
+
+    fn delete(&self, key: &str) -> Result<(), InputError> {
+        (**self).delete(key)
+    }
+}
+
+/// one value kept in a file of its own or under a key of a `Storage`
+#[derive(Clone)]
+pub(crate) enum Slot {
+    File(PathBuf),
+    Key(Arc<dyn Storage>, String),
+}
+
+impl Slot {
+    /// the value, none if nothing was written yet
+    pub(crate) fn read(&self) -> Result<Option<String>, InputError> {
+        match self {
+            Slot::File(path) => match fs::read_to_string(path) {
+                Ok(text) => Ok(Some(text)),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            },
+            Slot::Key(storage, key) => storage.get(key),
+        }
+    }
+
+    /// replace the value whole
+    pub(crate) fn write(&self, text: &str) -> Result<(), InputError> {
+        match self {
+            Slot::File(path) => write_replacing(path, ".tmp", text),
+            Slot::Key(storage, key) => storage.put(key, text),
+        }
+    }
+
+    pub(crate) fn delete(&self) -> Result<(), InputError> {
+        match self {
+            Slot::File(path) => match fs::remove_file(path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            },
+            Slot::Key(storage, key) => storage.delete(key),
+        }
+    }
+}
+
+/// the path or the key, for error messages
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Slot::File(path) => write!(f, "{}", path.display()),
+            Slot::Key(_, key) => write!(f, "{}", key),
+        }
+    }
+}
+
+impl fmt::Debug for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Slot::File(path) => f.debug_tuple("File").field(path).finish(),
+            Slot::Key(_, key) => f.debug_tuple("Key").field(key).finish(),
+        }
+    }
+}
+
+/// write `text` to `path` through a temporary file next to it, named
+/// like `path` with `suffix`
+fn write_replacing(path: &Path, suffix: &str, text: &str) -> Result<(), InputError> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(suffix);
+    fs::write(&tmp, text)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// `key` as a file name. a leading `.` is encoded too, so no key is
+/// `.`, `..` or hidden
+fn encode(key: &str) -> String {
+    let mut name = String::with_capacity(key.len());
+    for (i, byte) in key.bytes().enumerate() {
+        match byte {
+            b'.' if i == 0 => name.push_str("%2E"),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_' | b'-' | b'.' => name.push(byte as char),
+            _ => name.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    name
+}
+
+/// the key of a file name made by `encode`, none for other files
+fn decode(name: &str) -> Option<String> {
+    if name.is_empty() || name.starts_with('.') {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(name.len());
+    let mut rest = name.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        match byte {
+            b'%' => {
+                let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    let key = String::from_utf8(bytes).ok()?;
+    (encode(&key) == name).then_some(key)
+}
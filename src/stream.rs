@@ -0,0 +1,273 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "tokio")]
+use std::sync::OnceLock;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::async_io::{AsyncInputReader, AsyncOutputWriter};
+#[cfg(feature = "tokio")]
+use crate::config::Style;
+use crate::config::Theme;
+use crate::error::InputError;
+use crate::form::Answers;
+use crate::input::Input;
+use crate::io::{InputReader, OutputWriter};
+use crate::read_answer;
+
+#[derive(Default)]
+struct Shared {
+    answers: VecDeque<Result<String, InputError>>,
+    finished: bool,
+    waker: Option<Waker>,
+}
+
+/// # AnswerStream
+///
+/// answers of an `Input` asked again and again on a background thread,
+/// created by `Input::into_stream`. ends on EOF or after the first error.
+/// works with any async runtime
+///
+/// # usage
+/// ```no_run
+/// use input_py::Input;
+///
+/// async fn sum() -> i64 {
+///     let mut stream = Input::new("number").into_stream();
+///     let mut sum = 0;
+///     while let Some(line) = stream.next().await {
+///         sum += line.unwrap().parse::<i64>().unwrap_or(0);
+///     }
+///     sum
+/// }
+/// ```
+pub struct AnswerStream {
+    shared: Arc<Mutex<Shared>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl AnswerStream {
+    pub(crate) fn spawn<R, W>(input: Input, mut reader: R, mut writer: W) -> Self
+    where
+        R: InputReader + Send + 'static,
+        W: OutputWriter + Send + 'static,
+    {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let stream = AnswerStream {
+            shared: Arc::clone(&shared),
+            cancelled: Arc::clone(&cancelled),
+        };
+
+        let (prompt, default) = prompt_of(&input, &input.theme.for_writer(&writer));
+        thread::spawn(move || {
+            while !cancelled.load(Ordering::Relaxed) {
+                let answer = match ask(&prompt, &default, &mut reader, &mut writer) {
+                    Ok(Some(answer)) => Ok(answer),
+                    Ok(None) => break,
+                    Err(err) => Err(err),
+                };
+                let failed = answer.is_err();
+                let mut state = shared.lock().unwrap_or_else(|e| e.into_inner());
+                state.answers.push_back(answer);
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+                if failed {
+                    break;
+                }
+            }
+            let mut state = shared.lock().unwrap_or_else(|e| e.into_inner());
+            state.finished = true;
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        stream
+    }
+
+    /// the next answer, `None` once the stream ended
+    // named like `StreamExt::next` so loops read the same as with futures
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Next<'_> {
+        Next { stream: self }
+    }
+}
+
+impl Drop for AnswerStream {
+    /// stops asking once the read in progress returns
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// the prompt of `input` rendered in `theme` and its default
+pub(crate) fn prompt_of(input: &Input, theme: &Theme) -> (String, Option<String>) {
+    let default = input.default_with(&Answers::new());
+    let mut prompt = String::new();
+    crate::render_prompt_into(&mut prompt, &input.prompt, default.as_deref(), theme);
+    (prompt, default)
+}
+
+/// one processed answer, the default when it is empty. `None` on EOF
+fn ask(
+    prompt: &str,
+    default: &Option<String>,
+    reader: &mut dyn InputReader,
+    writer: &mut dyn OutputWriter,
+) -> Result<Option<String>, InputError> {
+    Ok(
+        read_answer(prompt, reader, writer)?.map(|answer| match default {
+            Some(default) if answer.is_empty() => default.clone(),
+            _ => answer,
+        }),
+    )
+}
+
+/// `ask` on an async reader and writer, EOF is an error
+pub(crate) async fn ask_async(
+    prompt: String,
+    default: Option<String>,
+    reader: &mut dyn AsyncInputReader,
+    writer: &mut dyn AsyncOutputWriter,
+) -> Result<String, InputError> {
+    writer.write_str(&prompt).await?;
+    writer.flush().await?;
+    let mut buf = String::new();
+    if reader.read_line(&mut buf).await? == 0 {
+        return Err(InputError::Eof);
+    }
+    crate::process_input_in_place(&mut buf);
+    match default {
+        Some(default) if buf.is_empty() => Ok(default),
+        _ => Ok(buf),
+    }
+}
+
+#[derive(Default)]
+struct Slot {
+    answer: Option<Result<String, InputError>>,
+    waker: Option<Waker>,
+}
+
+/// # AnswerFuture
+///
+/// a single answer of an `Input`, created by `Input::read_async`. it is
+/// read on a background thread, so the executor is never blocked and it
+/// works with tokio, async-std or any other runtime. with the `tokio`
+/// feature `read_async` reads stdin with tokio's `AsyncBufReadExt`
+/// instead, which needs a tokio runtime. like `AnswerStream` it uses the
+/// prompt and default of the `Input` and leaves out its checks
+///
+/// # usage
+/// ```no_run
+/// use input_py::Input;
+///
+/// async fn name() -> String {
+///     Input::new("name").default("guest").read_async().await.unwrap()
+/// }
+/// ```
+pub struct AnswerFuture {
+    pending: Pending,
+}
+
+enum Pending {
+    Thread(Arc<Mutex<Slot>>),
+    #[cfg(feature = "tokio")]
+    Tokio(Pin<Box<dyn Future<Output = Result<String, InputError>> + Send>>),
+}
+
+impl AnswerFuture {
+    pub(crate) fn spawn<R, W>(input: &Input, mut reader: R, mut writer: W) -> Self
+    where
+        R: InputReader + Send + 'static,
+        W: OutputWriter + Send + 'static,
+    {
+        let slot = Arc::new(Mutex::new(Slot::default()));
+        let future = AnswerFuture {
+            pending: Pending::Thread(Arc::clone(&slot)),
+        };
+        let (prompt, default) = prompt_of(input, &input.theme.for_writer(&writer));
+        thread::spawn(move || {
+            let answer = ask(&prompt, &default, &mut reader, &mut writer)
+                .and_then(|answer| answer.ok_or(InputError::Eof));
+            let mut slot = slot.lock().unwrap_or_else(|e| e.into_inner());
+            slot.answer = Some(answer);
+            if let Some(waker) = slot.waker.take() {
+                waker.wake();
+            }
+        });
+        future
+    }
+
+    /// reads the answer from tokio's stdin on the runtime
+    #[cfg(feature = "tokio")]
+    pub(crate) fn stdin(input: Input) -> Self {
+        use crate::async_io::{TokioReader, TokioWriter};
+        use tokio::io::{BufReader, Stdin};
+
+        // lines read ahead into the buffer belong to the next prompts
+        static STDIN: OnceLock<tokio::sync::Mutex<TokioReader<BufReader<Stdin>>>> = OnceLock::new();
+        // async writers can't tell whether they are a terminal
+        let theme = Theme {
+            colors: Style::PLAIN,
+            ..input.theme
+        };
+        let (prompt, default) = prompt_of(&input, &theme);
+        let answer = async move {
+            let stdin = STDIN.get_or_init(|| {
+                tokio::sync::Mutex::new(TokioReader::new(BufReader::new(tokio::io::stdin())))
+            });
+            let mut reader = stdin.lock().await;
+            let mut writer = TokioWriter::new(tokio::io::stdout());
+            ask_async(prompt, default, &mut *reader, &mut writer).await
+        };
+        AnswerFuture {
+            pending: Pending::Tokio(Box::pin(answer)),
+        }
+    }
+}
+
+impl Future for AnswerFuture {
+    type Output = Result<String, InputError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let slot = match &mut self.get_mut().pending {
+            Pending::Thread(slot) => slot,
+            #[cfg(feature = "tokio")]
+            Pending::Tokio(answer) => return answer.as_mut().poll(cx),
+        };
+        let mut slot = slot.lock().unwrap_or_else(|e| e.into_inner());
+        match slot.answer.take() {
+            Some(answer) => Poll::Ready(answer),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// future returned by `AnswerStream::next`
+pub struct Next<'a> {
+    stream: &'a mut AnswerStream,
+}
+
+impl Future for Next<'_> {
+    type Output = Option<Result<String, InputError>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.stream.shared.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(answer) = state.answers.pop_front() {
+            return Poll::Ready(Some(answer));
+        }
+        if state.finished {
+            return Poll::Ready(None);
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
@@ -0,0 +1,242 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::config::Theme;
+use crate::error::InputError;
+use crate::io::{InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::{read_answer, render_prompt_into, Retries};
+
+/// # Row
+///
+/// one entered row of a `TablePrompt`, every value already checked
+/// against its column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    values: Vec<(String, String)>,
+}
+
+impl Row {
+    /// raw value of the column `name`
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(column, _)| column == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// value of the column `name` parsed as `T`
+    pub fn parse<T>(&self, name: &str) -> Result<T, String>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self
+            .get(name)
+            .ok_or_else(|| format!("unknown column '{}'", name))?;
+        value
+            .parse::<T>()
+            .map_err(|e| format!("invalid {} '{}': {}", name, value, e))
+    }
+}
+
+/// # FromRow
+///
+/// maps an entered `Row` into the caller's row type
+///
+/// # usage
+/// ```
+/// use input_py::table::{FromRow, Row};
+///
+/// struct Backend {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// impl FromRow for Backend {
+///     fn from_row(row: &Row) -> Result<Self, String> {
+///         Ok(Backend {
+///             host: row.parse("host")?,
+///             port: row.parse("port")?,
+///         })
+///     }
+/// }
+/// ```
+pub trait FromRow: Sized {
+    fn from_row(row: &Row) -> Result<Self, String>;
+}
+
+type ColumnCheck = Box<dyn Fn(&str) -> Result<(), String>>;
+
+struct Column {
+    name: String,
+    check: ColumnCheck,
+}
+
+/// # TablePrompt
+///
+/// asks for rows of named columns, one column after the other, until an
+/// empty answer is given for the first column of a row
+///
+/// # usage
+/// ```
+/// use input_py::table::{FromRow, Row, TablePrompt};
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// struct Backend {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// impl FromRow for Backend {
+///     fn from_row(row: &Row) -> Result<Self, String> {
+///         Ok(Backend {
+///             host: row.parse("host")?,
+///             port: row.parse("port")?,
+///         })
+///     }
+/// }
+///
+/// let mut reader = MockReader::new(&["db1", "5432", ""]);
+/// let mut writer = MockWriter::new();
+/// let backends: Vec<Backend> = TablePrompt::new("backend")
+///     .column::<String>("host")
+///     .column::<u16>("port")
+///     .read_with_io(&mut reader, &mut writer)
+///     .unwrap();
+/// assert_eq!(backends[0].port, 5432);
+/// ```
+pub struct TablePrompt {
+    title: String,
+    columns: Vec<Column>,
+    min_rows: usize,
+    max_rows: Option<usize>,
+}
+
+impl TablePrompt {
+    pub fn new(title: &str) -> Self {
+        TablePrompt {
+            title: title.to_string(),
+            columns: Vec::new(),
+            min_rows: 0,
+            max_rows: None,
+        }
+    }
+
+    /// add a column whose value must parse as `T`
+    pub fn column<T>(self, name: &str) -> Self
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.column_with::<T, _>(name, |_| Ok(()))
+    }
+
+    /// add a column whose value must parse as `T` and pass `validator`
+    pub fn column_with<T, F>(mut self, name: &str, validator: F) -> Self
+    where
+        T: FromStr,
+        T::Err: Display,
+        F: Fn(&T) -> Result<(), String> + 'static,
+    {
+        let check = move |raw: &str| {
+            let value = raw.parse::<T>().map_err(|e| e.to_string())?;
+            validator(&value)
+        };
+        self.columns.push(Column {
+            name: name.to_string(),
+            check: Box::new(check),
+        });
+        self
+    }
+
+    /// minimum number of rows
+    pub fn min_rows(mut self, min: usize) -> Self {
+        self.min_rows = min;
+        self
+    }
+
+    /// stop asking after `max` rows
+    pub fn max_rows(mut self, max: usize) -> Self {
+        self.max_rows = Some(max);
+        self
+    }
+
+    /// read the rows from stdin
+    pub fn read<R: FromRow>(self) -> Result<Vec<R>, InputError> {
+        self.read_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    /// # Panics
+    /// when no column was added, the table could never be finished
+    pub fn read_with_io<R: FromRow>(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Vec<R>, InputError> {
+        assert!(
+            !self.columns.is_empty(),
+            "TablePrompt '{}' has no columns",
+            self.title
+        );
+        let mut rows = Vec::new();
+        let mut retries = Retries::new();
+        while self.max_rows.is_none_or(|max| rows.len() < max) {
+            let can_finish = rows.len() >= self.min_rows;
+            let index = rows.len() + 1;
+            let row = match self.read_row(index, can_finish, &mut retries, reader, writer)? {
+                Some(row) => row,
+                None if can_finish => break,
+                None => {
+                    let msg = format!("at least {} rows required", self.min_rows);
+                    retries.reject(writer, &msg)?;
+                    continue;
+                }
+            };
+            match R::from_row(&row) {
+                Ok(row) => rows.push(row),
+                Err(msg) => retries.reject(writer, &msg)?,
+            }
+        }
+        Ok(rows)
+    }
+
+    /// `None` when the user finished the table with an empty answer,
+    /// or with EOF if `can_finish` is set
+    fn read_row(
+        &self,
+        index: usize,
+        can_finish: bool,
+        retries: &mut Retries,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<Option<Row>, InputError> {
+        let mut values = Vec::with_capacity(self.columns.len());
+        let mut column_index = 0;
+        let theme = Theme::current().for_writer(writer);
+        while let Some(column) = self.columns.get(column_index) {
+            let mut prompt = String::new();
+            let text = format!("{} #{} {}", self.title, index, column.name);
+            render_prompt_into(&mut prompt, &text, None, &theme);
+            let value = match read_answer(&prompt, reader, writer)? {
+                Some(value) => value,
+                None if column_index == 0 && can_finish => return Ok(None),
+                None => return Err(InputError::Eof),
+            };
+            if value.is_empty() && column_index == 0 {
+                return Ok(None);
+            }
+            match (column.check)(&value) {
+                Ok(()) => {
+                    retries.accepted();
+                    values.push((column.name.clone(), value));
+                    column_index += 1;
+                }
+                Err(msg) => retries.reject(
+                    writer,
+                    &format!("invalid {} '{}': {}", column.name, value, msg),
+                )?,
+            }
+        }
+        Ok(Some(Row { values }))
+    }
+}
@@ -0,0 +1,98 @@
+//! # template
+//!
+//! placeholders in prompt text, like `Deploy {app} to {env}?`, so prompt
+//! catalogs and translation files can carry them. `{{` and `}}` stand for
+//! literal braces
+
+use crate::error::InputError;
+
+/// # render
+///
+/// `template` with every `{key}` replaced by its value. a key without a
+/// value fails with `InputError::Template`
+///
+/// # usage
+/// ```
+/// use input_py::template;
+///
+/// let prompt = template::render("Deploy {app} to {env}?", &[("app", "web"), ("env", "prod")]);
+/// assert_eq!(prompt, Ok("Deploy web to prod?".to_string()));
+/// assert!(template::render("Deploy {app}?", &[]).is_err());
+/// ```
+pub fn render(template: &str, values: &[(&str, &str)]) -> Result<String, InputError> {
+    let mut out = String::with_capacity(template.len());
+    for part in parse(template)? {
+        match part {
+            Part::Text(text) => out.push_str(text),
+            Part::Key(key) => match values.iter().find(|(k, _)| *k == key) {
+                Some((_, value)) => out.push_str(value),
+                None => {
+                    return Err(InputError::Template(format!(
+                        "no value for '{{{}}}' in '{}'",
+                        key, template
+                    )))
+                }
+            },
+        }
+    }
+    Ok(out)
+}
+
+/// # placeholders
+///
+/// the keys used in `template` in order, for checking a catalog up front
+///
+/// # usage
+/// ```
+/// use input_py::template;
+///
+/// assert_eq!(template::placeholders("{app} to {env}, {{literal}}"), Ok(vec!["app", "env"]));
+/// ```
+pub fn placeholders(template: &str) -> Result<Vec<&str>, InputError> {
+    Ok(parse(template)?
+        .into_iter()
+        .filter_map(|part| match part {
+            Part::Key(key) => Some(key),
+            Part::Text(_) => None,
+        })
+        .collect())
+}
+
+enum Part<'a> {
+    Text(&'a str),
+    Key(&'a str),
+}
+
+fn parse(template: &str) -> Result<Vec<Part<'_>>, InputError> {
+    let invalid = |msg: &str| InputError::Template(format!("{} in '{}'", msg, template));
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(at) = rest.find(['{', '}']) {
+        if at > 0 {
+            parts.push(Part::Text(&rest[..at]));
+        }
+        let brace = &rest[at..at + 1];
+        if rest[at + 1..].starts_with(brace) {
+            parts.push(Part::Text(brace));
+            rest = &rest[at + 2..];
+            continue;
+        }
+        if brace == "}" {
+            return Err(invalid("unmatched '}'"));
+        }
+        let end = rest[at..]
+            .find('}')
+            .ok_or_else(|| invalid("unclosed '{'"))?;
+        let key = &rest[at + 1..at + end];
+        let valid = |c: char| c.is_alphanumeric() || c == '_' || c == '-' || c == '.';
+        if key.is_empty() || !key.chars().all(valid) {
+            return Err(invalid(&format!("invalid placeholder '{{{}}}'", key)));
+        }
+        parts.push(Part::Key(key));
+        rest = &rest[at + end + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest));
+    }
+    Ok(parts)
+}
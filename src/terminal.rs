@@ -0,0 +1,455 @@
+//! # terminal
+//!
+//! the unix terminal behind `StdinReader` and the line editor, with the
+//! `terminal` feature: echo, raw mode, signals and the window size
+
+use std::cell::UnsafeCell;
+use std::io;
+use std::os::raw::{c_int, c_short, c_ulong};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const TCIFLUSH: c_int = 0;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const TCIFLUSH: c_int = 1;
+
+const POLLIN: c_short = 1;
+const TCSANOW: c_int = 0;
+const SIGWINCH: c_int = 28;
+const SIGINT: c_int = 2;
+const SIG_DFL: usize = 0;
+const SIG_IGN: usize = 1;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SIGSTOP: c_int = 19;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const SIGSTOP: c_int = 17;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SIGTSTP: c_int = 20;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const SIGTSTP: c_int = 18;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SIGCONT: c_int = 18;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const SIGCONT: c_int = 19;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const TIOCGWINSZ: c_ulong = 0x5413;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const TIOCGWINSZ: c_ulong = 0x4008_7468;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+type Nfds = std::os::raw::c_ulong;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+type Nfds = std::os::raw::c_uint;
+
+#[repr(C)]
+struct PollFd {
+    fd: c_int,
+    events: c_short,
+    revents: c_short,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct WinSize {
+    rows: u16,
+    columns: u16,
+    x_pixels: u16,
+    y_pixels: u16,
+}
+
+/// `ECHO` of `c_lflag`, the same on every unix
+const ECHO: u32 = 0o10;
+
+/// a `struct termios`, kept as bytes because its layout differs
+/// between platforms. large enough for all of them
+#[repr(C, align(8))]
+#[derive(Clone, Copy)]
+struct Termios([u8; 256]);
+
+impl Termios {
+    /// the modes of the terminal on stdin
+    fn get() -> io::Result<Self> {
+        let mut modes = Termios([0; 256]);
+        // SAFETY: `modes` is larger than any `struct termios`
+        match unsafe { tcgetattr(0, &mut modes) } {
+            0 => Ok(modes),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    /// make them the modes of the terminal on stdin
+    fn set(&self) -> io::Result<()> {
+        // SAFETY: `self` was filled in by `tcgetattr`
+        match unsafe { tcsetattr(0, TCSANOW, self) } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    /// the same modes with the echo off
+    fn without_echo(mut self) -> Self {
+        // `c_lflag` is the fourth `tcflag_t`, which is a `c_ulong` on
+        // apple platforms and 32 bits elsewhere
+        #[cfg(target_vendor = "apple")]
+        {
+            let mut lflag = [0; 8];
+            lflag.copy_from_slice(&self.0[24..32]);
+            let lflag = u64::from_ne_bytes(lflag) & !u64::from(ECHO);
+            self.0[24..32].copy_from_slice(&lflag.to_ne_bytes());
+        }
+        #[cfg(not(target_vendor = "apple"))]
+        {
+            let mut lflag = [0; 4];
+            lflag.copy_from_slice(&self.0[12..16]);
+            let lflag = u32::from_ne_bytes(lflag) & !ECHO;
+            self.0[12..16].copy_from_slice(&lflag.to_ne_bytes());
+        }
+        self
+    }
+
+    /// the same modes in raw mode, which has the echo off too
+    #[cfg(feature = "line-editing")]
+    fn raw(mut self) -> Self {
+        // SAFETY: `self` was filled in by `tcgetattr`
+        unsafe { cfmakeraw(&mut self) };
+        self
+    }
+}
+
+/// a `struct sigaction`, kept as bytes like `Termios`. only saved and
+/// put back, handlers are installed with `signal`
+#[repr(C, align(8))]
+struct SigAction([u8; 256]);
+
+/// the terminal modes around a hidden read, for the Ctrl+Z handler
+struct Modes {
+    cooked: UnsafeCell<Termios>,
+    hidden: UnsafeCell<Termios>,
+}
+
+// SAFETY: written only while `HIDDEN` is false, read only while true
+unsafe impl Sync for Modes {}
+
+extern "C" {
+    fn tcflush(fd: c_int, queue: c_int) -> c_int;
+    fn tcgetattr(fd: c_int, termios: *mut Termios) -> c_int;
+    fn tcsetattr(fd: c_int, when: c_int, termios: *const Termios) -> c_int;
+    #[cfg(feature = "line-editing")]
+    fn cfmakeraw(termios: *mut Termios);
+    fn poll(fds: *mut PollFd, nfds: Nfds, timeout: c_int) -> c_int;
+    fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    fn signal(signum: c_int, handler: usize) -> usize;
+    fn sigaction(signum: c_int, action: *const SigAction, previous: *mut SigAction) -> c_int;
+    fn raise(signum: c_int) -> c_int;
+}
+
+/// set by the SIGWINCH and SIGCONT handler, cleared by `take_redraw`
+static REDRAW: AtomicBool = AtomicBool::new(false);
+/// set by the SIGINT handler while catching, cleared by `Interrupts`
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+/// whether the SIGINT handler notes Ctrl+C instead of ending the process
+static CATCHING: AtomicBool = AtomicBool::new(false);
+/// whether an `EchoOff` is active and `MODES` holds its modes
+static HIDDEN: AtomicBool = AtomicBool::new(false);
+static MODES: Modes = Modes {
+    cooked: UnsafeCell::new(Termios([0; 256])),
+    hidden: UnsafeCell::new(Termios([0; 256])),
+};
+/// the `Signals` guards alive and what their handlers replaced
+static WATCH: Mutex<Watch> = Mutex::new(Watch {
+    guards: 0,
+    saved: Vec::new(),
+});
+
+struct Watch {
+    guards: usize,
+    saved: Vec<Saved>,
+}
+
+/// the disposition of a signal before a handler replaced it
+struct Saved {
+    signum: c_int,
+    action: SigAction,
+}
+
+impl Saved {
+    /// note the disposition of `signum`, none where it can't be read
+    fn new(signum: c_int) -> Option<Self> {
+        let mut action = SigAction([0; 256]);
+        // SAFETY: `action` is larger than any `struct sigaction`
+        match unsafe { sigaction(signum, ptr::null(), &mut action) } {
+            0 => Some(Saved { signum, action }),
+            _ => None,
+        }
+    }
+
+    /// put the disposition back, flags and mask included
+    fn restore(&self) {
+        // SAFETY: `action` was filled in by `sigaction` itself
+        unsafe { sigaction(self.signum, &self.action, ptr::null_mut()) };
+    }
+}
+
+extern "C" fn on_redraw(_: c_int) {
+    REDRAW.store(true, Ordering::Relaxed);
+}
+
+/// stops like the default SIGTSTP action, but hands the shell its
+/// terminal with echo on and turns it off again on resume
+extern "C" fn on_stop(_: c_int) {
+    let hidden = HIDDEN.load(Ordering::SeqCst);
+    // SAFETY: only async-signal-safe calls. SIGSTOP can't be blocked
+    // while SIGTSTP is, so the process stops right here until SIGCONT
+    unsafe {
+        if hidden {
+            tcsetattr(0, TCSANOW, MODES.cooked.get());
+        }
+        raise(SIGSTOP);
+        if hidden {
+            tcsetattr(0, TCSANOW, MODES.hidden.get());
+        }
+    }
+}
+
+/// notes Ctrl+C for `Interrupts::take`, or ends the process like the
+/// default SIGINT action with the echo back on
+extern "C" fn on_interrupt(_: c_int) {
+    if CATCHING.load(Ordering::SeqCst) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+        return;
+    }
+    // SAFETY: only async-signal-safe calls. SIGINT is blocked in the
+    // handler, the raised one ends the process once it returns
+    unsafe {
+        if HIDDEN.load(Ordering::SeqCst) {
+            tcsetattr(0, TCSANOW, MODES.cooked.get());
+        }
+        signal(SIGINT, SIG_DFL);
+        raise(SIGINT);
+    }
+}
+
+/// handles SIGWINCH, SIGCONT and SIGTSTP while alive. the first guard
+/// installs the handlers, the last one dropped puts back what the app
+/// had before
+pub(crate) struct Signals;
+
+impl Signals {
+    pub(crate) fn watch() -> Self {
+        let mut watch = WATCH.lock().unwrap_or_else(PoisonError::into_inner);
+        if watch.guards == 0 {
+            let handlers = [
+                (SIGWINCH, on_redraw as extern "C" fn(c_int) as usize),
+                (SIGCONT, on_redraw as extern "C" fn(c_int) as usize),
+                (SIGTSTP, on_stop as extern "C" fn(c_int) as usize),
+            ];
+            for (signum, handler) in handlers {
+                let Some(saved) = Saved::new(signum) else {
+                    continue;
+                };
+                // SAFETY: the handlers only touch atomics and the terminal modes
+                let previous = unsafe { signal(signum, handler) };
+                // started with Ctrl+Z ignored, like under nohup: keep it so
+                if signum == SIGTSTP && previous == SIG_IGN {
+                    saved.restore();
+                    continue;
+                }
+                watch.saved.push(saved);
+            }
+        }
+        watch.guards += 1;
+        Signals
+    }
+}
+
+impl Drop for Signals {
+    fn drop(&mut self) {
+        let mut watch = WATCH.lock().unwrap_or_else(PoisonError::into_inner);
+        watch.guards -= 1;
+        if watch.guards == 0 {
+            for saved in watch.saved.drain(..) {
+                saved.restore();
+            }
+        }
+    }
+}
+
+/// whether the terminal was resized or the process continued since
+/// the last call, while a `Signals` guard was watching
+pub(crate) fn take_redraw() -> bool {
+    REDRAW.swap(false, Ordering::Relaxed)
+}
+
+/// columns of the terminal on stdout
+pub(crate) fn columns() -> Option<usize> {
+    let mut size = WinSize::default();
+    // SAFETY: TIOCGWINSZ fills in a `WinSize`, which outlives the call
+    match unsafe { ioctl(1, TIOCGWINSZ, &mut size as *mut WinSize) } {
+        0 if size.columns > 0 => Some(usize::from(size.columns)),
+        _ => None,
+    }
+}
+
+/// discard what was typed on stdin but not read yet
+pub(crate) fn flush_input() -> io::Result<()> {
+    // SAFETY: tcflush only takes plain integers, fd 0 is stdin
+    match unsafe { tcflush(0, TCIFLUSH) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+/// whether stdin becomes readable within `timeout`. a signal ends
+/// the wait early with false, resizes and Ctrl+Z are handled meanwhile
+pub(crate) fn poll_input(timeout: Duration) -> io::Result<bool> {
+    let _watching = Signals::watch();
+    let mut fd = PollFd {
+        fd: 0,
+        events: POLLIN,
+        revents: 0,
+    };
+    let millis = timeout.as_millis().min(c_int::MAX as u128) as c_int;
+    // SAFETY: `fd` is a valid pollfd for the duration of the call
+    match unsafe { poll(&mut fd, 1, millis) } {
+        -1 => match io::Error::last_os_error() {
+            err if err.kind() == io::ErrorKind::Interrupted => Ok(false),
+            err => Err(err),
+        },
+        ready => Ok(ready > 0),
+    }
+}
+
+/// keeps the terminal from echoing typed input until dropped
+pub(crate) struct EchoOff {
+    _watching: Signals,
+}
+
+impl EchoOff {
+    pub(crate) fn new() -> io::Result<Self> {
+        let watching = Signals::watch();
+        let cooked = Termios::get()?;
+        hide(cooked, cooked.without_echo())?;
+        Ok(EchoOff {
+            _watching: watching,
+        })
+    }
+}
+
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+/// switch stdin from the `cooked` modes to `hidden` ones, which the
+/// Ctrl+Z handler switches between until `restore`
+fn hide(cooked: Termios, hidden: Termios) -> io::Result<()> {
+    // SAFETY: `HIDDEN` is false, no handler reads `MODES`
+    unsafe {
+        *MODES.cooked.get() = cooked;
+        *MODES.hidden.get() = hidden;
+    }
+    hidden.set()?;
+    HIDDEN.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// put back the modes `hide` replaced
+fn restore() {
+    HIDDEN.store(false, Ordering::SeqCst);
+    // SAFETY: `HIDDEN` is false, no handler writes `MODES`
+    let _ = unsafe { &*MODES.cooked.get() }.set();
+}
+
+/// handles SIGINT during a canonical read until dropped. when `catch`
+/// is set Ctrl+C is noted for `take`, otherwise it still ends the
+/// process but turns the echo back on first. a handler of the app is
+/// kept unless catching
+pub(crate) struct Interrupts {
+    previous: Option<Saved>,
+}
+
+impl Interrupts {
+    pub(crate) fn new(catch: bool) -> Self {
+        INTERRUPTED.store(false, Ordering::SeqCst);
+        CATCHING.store(catch, Ordering::SeqCst);
+        let Some(saved) = Saved::new(SIGINT) else {
+            return Interrupts { previous: None };
+        };
+        // SAFETY: the handler only touches atomics and the terminal modes
+        let previous = unsafe { signal(SIGINT, on_interrupt as extern "C" fn(c_int) as usize) };
+        if !catch && previous != SIG_DFL {
+            saved.restore();
+            return Interrupts { previous: None };
+        }
+        Interrupts {
+            previous: Some(saved),
+        }
+    }
+
+    /// whether Ctrl+C was pressed since the last call
+    pub(crate) fn take(&self) -> bool {
+        INTERRUPTED.swap(false, Ordering::SeqCst)
+    }
+}
+
+impl Drop for Interrupts {
+    fn drop(&mut self) {
+        if let Some(previous) = &self.previous {
+            previous.restore();
+        }
+        CATCHING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// raw mode without echo until dropped, for the line editor. Ctrl+Z
+/// restores the terminal like under `EchoOff`
+#[cfg(feature = "line-editing")]
+pub(crate) struct RawMode {
+    _watching: Signals,
+}
+
+#[cfg(feature = "line-editing")]
+impl RawMode {
+    pub(crate) fn new() -> io::Result<Self> {
+        let watching = Signals::watch();
+        let cooked = Termios::get()?;
+        hide(cooked, cooked.raw())?;
+        Ok(RawMode {
+            _watching: watching,
+        })
+    }
+}
+
+#[cfg(feature = "line-editing")]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+/// stop like Ctrl+Z in canonical mode, `on_stop` restores the
+/// terminal meanwhile
+#[cfg(feature = "line-editing")]
+pub(crate) fn suspend() {
+    // SAFETY: raise only takes a signal number
+    unsafe {
+        raise(SIGTSTP);
+    }
+}
+
+/// Ctrl+C as canonical mode would send it
+#[cfg(feature = "line-editing")]
+pub(crate) fn interrupt() {
+    // SAFETY: raise only takes a signal number
+    unsafe {
+        raise(SIGINT);
+    }
+}
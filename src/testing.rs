@@ -0,0 +1,120 @@
+//! # testing
+//!
+//! helpers for testing prompt code against a real terminal
+
+use std::fs::File;
+use std::io::{self, BufReader, IsTerminal, Read, Write};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+
+use crate::io::{BufReaderInput, GenericWriter};
+use crate::pty::PtyPair;
+
+/// terminal side of a `Pty`, handed to the closure run by `Pty::run`
+pub struct PtyTerminal {
+    pub reader: BufReaderInput<BufReader<File>>,
+    pub writer: GenericWriter<File>,
+    tty: File,
+}
+
+impl PtyTerminal {
+    /// the terminal device, for isatty checks or terminal mode changes
+    pub fn file(&self) -> &File {
+        &self.tty
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        self.tty.is_terminal()
+    }
+}
+
+/// what ran on a `Pty` returned and printed
+#[derive(Debug)]
+pub struct PtyOutput<T> {
+    pub value: T,
+    /// everything shown on the terminal, including the echoed input.
+    /// the terminal turns `\n` into `\r\n`
+    pub output: String,
+}
+
+/// # Pty
+///
+/// runs code or a program with a real pseudo terminal attached, so
+/// hidden input, terminal modes and isatty dependent behavior can be
+/// tested. `input` is typed into the terminal before anything runs
+///
+/// # usage
+/// ```
+/// use input_py::read_input_with_io;
+/// use input_py::testing::Pty;
+///
+/// let run = Pty::run("alice\n", |terminal| {
+///     assert!(terminal.is_terminal());
+///     read_input_with_io("name", None, &mut terminal.reader, &mut terminal.writer)
+/// })
+/// .unwrap();
+/// assert_eq!(run.value, Ok("alice".to_string()));
+/// assert!(run.output.contains("name:"));
+/// ```
+pub struct Pty;
+
+impl Pty {
+    /// run `f` with a terminal whose input is `input`
+    pub fn run<T, F>(input: &str, f: F) -> io::Result<PtyOutput<T>>
+    where
+        F: FnOnce(&mut PtyTerminal) -> T,
+    {
+        let pair = PtyPair::open()?;
+        let mut terminal = PtyTerminal {
+            reader: BufReaderInput::new(BufReader::new(pair.slave.try_clone()?)),
+            writer: GenericWriter::new(pair.slave.try_clone()?),
+            tty: pair.slave,
+        };
+        let capture = capture(pair.master.try_clone()?);
+        let mut master = pair.master;
+        master.write_all(input.as_bytes())?;
+
+        let value = f(&mut terminal);
+        drop(terminal);
+        let output = capture.join().unwrap_or_default();
+        Ok(PtyOutput { value, output })
+    }
+
+    /// run `command` with the terminal as stdin, stdout and stderr
+    pub fn run_command(command: &mut Command, input: &str) -> io::Result<PtyOutput<ExitStatus>> {
+        let pair = PtyPair::open()?;
+        let mut child = command
+            .stdin(Stdio::from(pair.slave.try_clone()?))
+            .stdout(Stdio::from(pair.slave.try_clone()?))
+            .stderr(Stdio::from(pair.slave))
+            .spawn()?;
+        // close the parent's copies so reading the master ends with the program
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        let capture = capture(pair.master.try_clone()?);
+        let mut master = pair.master;
+        master.write_all(input.as_bytes())?;
+
+        let value = child.wait()?;
+        let output = capture.join().unwrap_or_default();
+        Ok(PtyOutput { value, output })
+    }
+}
+
+/// reads the master until every terminal side handle is closed
+fn capture(mut master: File) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        let mut buf = [0u8; 4096];
+        // the master reports an error instead of EOF once the terminal side is closed
+        while let Ok(n) = master.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            output.extend_from_slice(&buf[..n]);
+        }
+        String::from_utf8_lossy(&output).into_owned()
+    })
+}
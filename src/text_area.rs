@@ -0,0 +1,260 @@
+use crate::config::Theme;
+use crate::error::InputError;
+use crate::io::{self, InputReader, OutputWriter, StdinReader, StdoutWriter};
+use crate::prompter::Prompt;
+use crate::{preset_answer, render_prompt_into};
+
+/// finishes a boxed text area when entered on its own line
+const ESC: &str = "\u{1b}";
+
+/// width used when neither `width` nor `COLUMNS` is set
+const DEFAULT_WIDTH: usize = 80;
+
+/// spaces before a line from the text above it and the line itself, ``
+/// while it is still to be typed
+type IndentHint = Box<dyn Fn(&str, &str) -> usize>;
+
+/// # TextArea
+///
+/// prompt for multi-line text, returned with `\n` between the lines.
+///
+/// in the default boxed mode every line is entered after a `│ ` margin
+/// and Ctrl+D (EOF) or a line with just Esc finishes. the text is then
+/// shown in a box, wrapped at the width of the terminal at that point.
+/// plain mode works like a heredoc: lines are read until the terminator
+/// line or EOF, nothing is drawn
+///
+/// # usage
+/// ```
+/// use input_py::TextArea;
+/// use input_py::io::{MockReader, MockWriter};
+///
+/// let mut reader = MockReader::new(&["first line", "  indented", "\u{1b}"]);
+/// let mut writer = MockWriter::new();
+/// let text = TextArea::new("notes").width(20).read_with_io(&mut reader, &mut writer);
+/// assert_eq!(text, Ok("first line\n  indented".to_string()));
+/// assert!(writer.output.ends_with("│   indented       │\n└──────────────────┘\n"));
+/// ```
+pub struct TextArea {
+    prompt: String,
+    width: Option<usize>,
+    plain: bool,
+    terminator: String,
+    indent: Option<IndentHint>,
+}
+
+impl TextArea {
+    pub fn new(prompt: &str) -> Self {
+        TextArea {
+            prompt: prompt.to_string(),
+            width: None,
+            plain: false,
+            terminator: "EOF".to_string(),
+            indent: None,
+        }
+    }
+
+    /// width of the box in columns. default: `COLUMNS`, the terminal's
+    /// width when the text is done, or 80
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// heredoc input without box and margin, for pipes and scripts.
+    /// default: false
+    pub fn plain(mut self, plain: bool) -> Self {
+        self.plain = plain;
+        self
+    }
+
+    /// line that ends the text in plain mode, an empty one ends it at the
+    /// first empty line. default: `EOF`
+    pub fn terminator(mut self, terminator: &str) -> Self {
+        self.terminator = terminator.to_string();
+        self
+    }
+
+    /// indent every line by `width` spaces per bracket left open above
+    /// it, a line starting with closing brackets is moved back. brackets
+    /// within strings count too
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::TextArea;
+    /// use input_py::io::{MockReader, MockWriter};
+    ///
+    /// let mut reader = MockReader::new(&["fn main() {", "if ok {", "run();", "}", "}", ""]);
+    /// let code = TextArea::new("code")
+    ///     .plain(true)
+    ///     .terminator("")
+    ///     .auto_indent(4)
+    ///     .read_with_io(&mut reader, &mut MockWriter::new());
+    /// assert_eq!(code, Ok("fn main() {\n    if ok {\n        run();\n    }\n}".to_string()));
+    /// ```
+    pub fn auto_indent(self, width: usize) -> Self {
+        self.indent_hint(move |above, line| {
+            let closing = line.chars().take_while(|c| is_closing(*c)).count();
+            bracket_depth(above).saturating_sub(closing) * width
+        })
+    }
+
+    /// indent lines by the spaces `hint` gives for the text above and the
+    /// line. it is asked with an empty line for the indent written before
+    /// the line is typed, then with the typed line, so keywords like
+    /// `else` can move it back. what the user types goes after the indent
+    pub fn indent_hint<F>(mut self, hint: F) -> Self
+    where
+        F: Fn(&str, &str) -> usize + 'static,
+    {
+        self.indent = Some(Box::new(hint));
+        self
+    }
+
+    /// read the text from stdin
+    pub fn read(self) -> Result<String, InputError> {
+        self.read_with_io(&mut StdinReader, &mut StdoutWriter)
+    }
+
+    pub fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<String, InputError> {
+        if let Some(answer) = preset_answer(&self.prompt, &self.prompt, None, reader)? {
+            return Ok(answer.to_string());
+        }
+
+        let (hint, margin, end) = match self.plain {
+            true if self.terminator.is_empty() => ("end with an empty line".to_string(), "", ""),
+            true => (
+                format!("end with {}", self.terminator),
+                "",
+                self.terminator.as_str(),
+            ),
+            false => ("finish with Ctrl+D or Esc".to_string(), "│ ", ESC),
+        };
+        let mut prompt = String::new();
+        let theme = Theme::current().for_writer(writer);
+        render_prompt_into(
+            &mut prompt,
+            &format!("{} ({})", self.prompt, hint),
+            None,
+            &theme,
+        );
+        prompt.push('\n');
+        writer.write_str(&prompt)?;
+
+        let mut lines: Vec<String> = Vec::new();
+        let mut buf = String::new();
+        let mut indent = 0;
+        loop {
+            if let Some(hint) = &self.indent {
+                indent = hint(&lines.join("\n"), "");
+            }
+            writer.write_str(margin)?;
+            writer.write_str(&" ".repeat(indent))?;
+            writer.flush()?;
+            buf.clear();
+            if reader.read_line(&mut buf)? == 0 {
+                if lines.is_empty() {
+                    return Err(InputError::Eof);
+                }
+                // Ctrl+D leaves the cursor after the margin
+                writer.write_str("\n")?;
+                break;
+            }
+            let line = buf.trim_end_matches(['\n', '\r']);
+            if line == end {
+                break;
+            }
+            match &self.indent {
+                Some(hint) => {
+                    let indent = hint(&lines.join("\n"), line.trim_start());
+                    lines.push(format!("{}{}", " ".repeat(indent), line));
+                }
+                None => lines.push(line.to_string()),
+            }
+        }
+
+        let text = lines.join("\n");
+        if !self.plain {
+            let width = self.width.unwrap_or_else(terminal_width);
+            let rows = render_box(&self.prompt, &text, width);
+            let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+            writer.write_all_lines(&rows)?;
+            writer.flush()?;
+        }
+        Ok(text)
+    }
+}
+
+impl Prompt for TextArea {
+    type Output = String;
+
+    fn read_with_io(
+        self,
+        reader: &mut dyn InputReader,
+        writer: &mut dyn OutputWriter,
+    ) -> Result<String, InputError> {
+        TextArea::read_with_io(self, reader, writer)
+    }
+}
+
+/// brackets opened and not closed in `text`
+fn bracket_depth(text: &str) -> usize {
+    text.chars().fold(0, |depth, c| match c {
+        '(' | '[' | '{' => depth + 1,
+        c if is_closing(c) => depth.saturating_sub(1),
+        _ => depth,
+    })
+}
+
+fn is_closing(c: char) -> bool {
+    matches!(c, ')' | ']' | '}')
+}
+
+/// columns of the terminal from `COLUMNS` or the terminal itself, which
+/// follows resizes while the text was typed
+fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .or_else(io::terminal_columns)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// `text` in a box `width` columns wide with `title` in the top border,
+/// lines are wrapped at the inner width
+fn render_box(title: &str, text: &str, width: usize) -> Vec<String> {
+    let inner = width.saturating_sub(4).max(1);
+    let title: String = title.chars().take(inner.saturating_sub(1)).collect();
+    let fill = (inner + 1).saturating_sub(title.chars().count() + 1);
+    let mut rows = vec![format!("┌─{} {}┐", title, "─".repeat(fill))];
+    for line in text.split('\n') {
+        for row in wrap(line, inner) {
+            let pad = inner - row.chars().count();
+            rows.push(format!("│ {}{} │", row, " ".repeat(pad)));
+        }
+    }
+    rows.push(format!("└{}┘", "─".repeat(inner + 2)));
+    rows
+}
+
+/// `line` split into rows of at most `width` characters, at spaces
+/// where possible
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let mut rows = Vec::new();
+    let mut rest: Vec<char> = line.chars().collect();
+    while rest.len() > width {
+        let cut = match rest[..=width].iter().rposition(|c| *c == ' ') {
+            Some(space) if space > 0 => space,
+            _ => width,
+        };
+        rows.push(rest[..cut].iter().collect());
+        let skip = if rest[cut] == ' ' { cut + 1 } else { cut };
+        rest.drain(..skip);
+    }
+    rows.push(rest.into_iter().collect());
+    rows
+}
@@ -0,0 +1,247 @@
+//! minimal TOML support for answers files and form definitions:
+//! `key = value` pairs and `[table]` headers, flattened to dotted keys
+//! like `db.port`. `parse_values` also reads arrays on one line and
+//! reports the table headers, inline tables and multi-line strings are
+//! not supported
+
+/// a value of `parse_values`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Value {
+    Scalar(String),
+    Array(Vec<String>),
+    /// a `[table]` header, under the table's name
+    Table,
+}
+
+/// parse `text` into `(key, value)` pairs in file order. strings are
+/// unquoted, other values keep their source text
+pub(crate) fn parse(text: &str) -> Result<Vec<(String, String)>, String> {
+    let entries = parse_with(text, false)?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|(key, value)| match value {
+            Value::Scalar(value) => Some((key, value)),
+            Value::Array(_) | Value::Table => None,
+        })
+        .collect())
+}
+
+/// `parse` also reading arrays like `["dev", "prod"]` and headers, for
+/// form files
+pub(crate) fn parse_values(text: &str) -> Result<Vec<(String, Value)>, String> {
+    parse_with(text, true)
+}
+
+fn parse_with(text: &str, forms: bool) -> Result<Vec<(String, Value)>, String> {
+    let mut entries = Vec::new();
+    let mut table = String::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let at = |msg: &str| format!("line {}: {}", i + 1, msg);
+
+        if let Some(header) = line.strip_prefix('[') {
+            let end = unquoted(header, |c| c == ']').ok_or_else(|| at("unclosed table header"))?;
+            let (name, rest) = (&header[..end], &header[end + 1..]);
+            if !is_comment(rest) {
+                return Err(at("unexpected text after table header"));
+            }
+            table = parse_key(name).map_err(|e| at(&e))?;
+            if forms {
+                entries.push((table.clone(), Value::Table));
+            }
+            continue;
+        }
+
+        // quoted keys may hold a `=`
+        let end = unquoted(line, |c| c == '=').ok_or_else(|| at("expected key = value"))?;
+        let (key, value) = (&line[..end], &line[end + 1..]);
+        let key = parse_key(key).map_err(|e| at(&e))?;
+        let value = match value.trim() {
+            array if forms && array.starts_with('[') => {
+                Value::Array(parse_array(array).map_err(|e| at(&e))?)
+            }
+            value => Value::Scalar(parse_value(value).map_err(|e| at(&e))?),
+        };
+        let key = if table.is_empty() {
+            key
+        } else {
+            format!("{}.{}", table, key)
+        };
+        if entries.iter().any(|(k, _)| *k == key) {
+            return Err(at(&format!("duplicate key '{}'", key)));
+        }
+        entries.push((key, value));
+    }
+    Ok(entries)
+}
+
+fn is_comment(rest: &str) -> bool {
+    let rest = rest.trim();
+    rest.is_empty() || rest.starts_with('#')
+}
+
+/// dotted key of bare or quoted parts. quoted parts are read like
+/// string values and may hold dots, `=` and escapes
+fn parse_key(key: &str) -> Result<String, String> {
+    let invalid = || format!("invalid key '{}'", key.trim());
+    let mut parts = Vec::new();
+    let mut rest = key;
+    loop {
+        let end = unquoted(rest, |c| c == '.').unwrap_or(rest.len());
+        let part = rest[..end].trim();
+        let name = match part.starts_with(['"', '\'']) {
+            true => parse_value(part).map_err(|_| invalid())?,
+            false if is_bare(part) => part.to_string(),
+            false => return Err(invalid()),
+        };
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        parts.push(name);
+        match rest.get(end + 1..) {
+            Some(after) => rest = after,
+            None => return Ok(parts.join(".")),
+        }
+    }
+}
+
+fn is_bare(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// `["a", 'b', 3]` on one line, the items as `parse_value` reads them
+fn parse_array(array: &str) -> Result<Vec<String>, String> {
+    let mut rest = array[1..].trim_start();
+    let mut items = Vec::new();
+    loop {
+        if let Some(after) = rest.strip_prefix(']') {
+            return match is_comment(after) {
+                true => Ok(items),
+                false => Err("unexpected text after value".to_string()),
+            };
+        }
+        let end = unquoted(rest, |c| c == ',' || c == ']').ok_or("unclosed array")?;
+        items.push(parse_value(rest[..end].trim())?);
+        rest = rest[end..].trim_start();
+        if let Some(after) = rest.strip_prefix(',') {
+            rest = after.trim_start();
+        } else if !rest.starts_with(']') {
+            return Err("expected , or ] in array".to_string());
+        }
+    }
+}
+
+/// the first char of `rest` outside of quotes that `stop` accepts, like
+/// the `,` or `]` ending an array item or the `=` after a key
+fn unquoted(rest: &str, stop: impl Fn(char) -> bool) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in rest.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, c) if stop(c) => return Some(i),
+            _ => {}
+        }
+        escaped = false;
+    }
+    None
+}
+
+fn parse_value(value: &str) -> Result<String, String> {
+    if let Some(rest) = value.strip_prefix('"') {
+        let mut out = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    return match is_comment(chars.as_str()) {
+                        true => Ok(out),
+                        false => Err("unexpected text after value".to_string()),
+                    }
+                }
+                '\\' => out.push(unescape(&mut chars)?),
+                c => out.push(c),
+            }
+        }
+        return Err("unclosed string".to_string());
+    }
+    if let Some(rest) = value.strip_prefix('\'') {
+        let (literal, after) = rest.split_once('\'').ok_or("unclosed string")?;
+        return match is_comment(after) {
+            true => Ok(literal.to_string()),
+            false => Err("unexpected text after value".to_string()),
+        };
+    }
+    let bare = value.split('#').next().unwrap_or("").trim();
+    if bare.is_empty() || bare.contains(char::is_whitespace) {
+        return Err(format!("invalid value '{}'", value));
+    }
+    Ok(bare.to_string())
+}
+
+fn unescape(chars: &mut std::str::Chars) -> Result<char, String> {
+    match chars.next() {
+        Some('"') => Ok('"'),
+        Some('\\') => Ok('\\'),
+        Some('n') => Ok('\n'),
+        Some('t') => Ok('\t'),
+        Some('r') => Ok('\r'),
+        Some('u') => {
+            let hex: String = chars.by_ref().take(4).collect();
+            u32::from_str_radix(&hex, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or_else(|| format!("invalid escape '\\u{}'", hex))
+        }
+        Some(c) => Err(format!("invalid escape '\\{}'", c)),
+        None => Err("unclosed string".to_string()),
+    }
+}
+
+/// `entries` as `key = "value"` lines that `parse` reads back
+pub(crate) fn write(entries: &[(&str, &str)]) -> String {
+    let mut out = String::new();
+    for (key, value) in entries {
+        for (i, part) in key.split('.').enumerate() {
+            if i > 0 {
+                out.push('.');
+            }
+            match is_bare(part) {
+                true => out.push_str(part),
+                false => push_quoted(&mut out, part),
+            }
+        }
+        out.push_str(" = ");
+        push_quoted(&mut out, value);
+        out.push('\n');
+    }
+    out
+}
+
+/// `text` as a basic string, escaped so `parse_value` reads it back
+fn push_quoted(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
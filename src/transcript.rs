@@ -0,0 +1,225 @@
+use std::time::{Duration, Instant};
+
+use crate::config;
+use crate::error::InputError;
+use crate::frontend::Frontend;
+use crate::model::Question;
+
+/// what happened in one transcript entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryKind {
+    /// a question was shown
+    Asked(Box<Question>),
+    /// the answer as entered, an empty one selects the default
+    Answered { id: String, value: String },
+    /// an answer was rejected
+    Rejected { id: String, message: String },
+}
+
+/// # Entry
+///
+/// one recorded event, `at` is the time since the transcript started.
+/// always zero in `config::deterministic` mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub at: Duration,
+    pub kind: EntryKind,
+}
+
+/// # QuestionStats
+///
+/// how one question went, from `Transcript::stats`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuestionStats {
+    pub id: String,
+    /// time from showing the question to the answer, summed over the
+    /// times it was asked
+    pub duration: Duration,
+    /// how many answers were rejected
+    pub retries: u32,
+}
+
+/// # Transcript
+///
+/// records the prompt and answer exchanges of a session. wrap the
+/// frontend with `Transcript::record` to fill it
+///
+/// # usage
+/// ```
+/// use input_py::frontend::TerminalFrontend;
+/// use input_py::io::{MockReader, MockWriter};
+/// use input_py::transcript::Transcript;
+/// use input_py::{Form, Input};
+///
+/// let mut terminal = TerminalFrontend::new(MockReader::new(&["db", ""]), MockWriter::new());
+/// let mut transcript = Transcript::new();
+/// Form::new()
+///     .field("host", Input::new("host"))
+///     .field("port", Input::new("port").default("5432"))
+///     .run_with_frontend(&mut transcript.record(&mut terminal))
+///     .unwrap();
+/// assert_eq!(
+///     transcript.render_plain(),
+///     "001 ? host\n001 > db\n002 ? port [5432]\n002 > 5432 (default)\n"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    started: Instant,
+    entries: Vec<Entry>,
+}
+
+impl Default for Transcript {
+    fn default() -> Self {
+        Transcript::new()
+    }
+}
+
+impl Transcript {
+    pub fn new() -> Self {
+        Transcript {
+            started: Instant::now(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn push(&mut self, kind: EntryKind) {
+        let at = if config::is_deterministic() {
+            Duration::ZERO
+        } else {
+            self.started.elapsed()
+        };
+        self.entries.push(Entry { at, kind });
+    }
+
+    /// how long every question took and how often its answers were
+    /// rejected, in the order the questions were first asked. slow
+    /// questions with many retries are the confusing ones
+    ///
+    /// # usage
+    /// ```
+    /// use input_py::frontend::{self, Question, TerminalFrontend};
+    /// use input_py::io::{MockReader, MockWriter};
+    /// use input_py::transcript::Transcript;
+    ///
+    /// let mut question = Question::new("size", "size");
+    /// question.constraints.choices = vec!["s".to_string(), "m".to_string()];
+    /// let reader = MockReader::new(&["xl", "m"]);
+    /// let mut terminal = TerminalFrontend::new(reader, MockWriter::new());
+    /// let mut transcript = Transcript::new();
+    /// frontend::ask(&mut transcript.record(&mut terminal), &question).unwrap();
+    /// let stats = transcript.stats();
+    /// assert_eq!((stats[0].id.as_str(), stats[0].retries), ("size", 1));
+    /// ```
+    pub fn stats(&self) -> Vec<QuestionStats> {
+        let mut stats: Vec<QuestionStats> = Vec::new();
+        let mut asked: Option<(&str, Duration)> = None;
+        for entry in &self.entries {
+            let id = match &entry.kind {
+                EntryKind::Asked(question) => &question.id,
+                EntryKind::Answered { id, .. } | EntryKind::Rejected { id, .. } => id,
+            };
+            let index = match stats.iter().position(|s| &s.id == id) {
+                Some(index) => index,
+                None => {
+                    stats.push(QuestionStats {
+                        id: id.clone(),
+                        duration: Duration::ZERO,
+                        retries: 0,
+                    });
+                    stats.len() - 1
+                }
+            };
+            match &entry.kind {
+                EntryKind::Asked(_) => asked = Some((id, entry.at)),
+                EntryKind::Answered { .. } => {
+                    if let Some((_, at)) = asked.take().filter(|(asked, _)| asked == id) {
+                        stats[index].duration += entry.at.saturating_sub(at);
+                    }
+                }
+                EntryKind::Rejected { .. } => stats[index].retries += 1,
+            }
+        }
+        stats
+    }
+
+    /// a frontend that asks on `frontend` and records into this transcript
+    pub fn record<'a>(&'a mut self, frontend: &'a mut dyn Frontend) -> RecordingFrontend<'a> {
+        RecordingFrontend {
+            inner: frontend,
+            transcript: self,
+        }
+    }
+
+    /// stable text rendering for snapshot tests. every line starts with the
+    /// number of the question instead of a timestamp, `?` marks questions,
+    /// `>` answers and `!` rejections. secret answers are redacted and
+    /// masked ones shown as `Question::display` does
+    pub fn render_plain(&self) -> String {
+        let mut out = String::new();
+        let mut number = 0;
+        let mut current: Option<&Question> = None;
+        for entry in &self.entries {
+            match &entry.kind {
+                EntryKind::Asked(question) => {
+                    number += 1;
+                    current = Some(question);
+                    out.push_str(&format!("{:03} ? {}", number, question.prompt));
+                    if let Some(default) = &question.default {
+                        out.push_str(&format!(" [{}]", question.display(default)));
+                    }
+                    out.push('\n');
+                }
+                EntryKind::Answered { id, value } => {
+                    let shown = match current.filter(|q| &q.id == id) {
+                        Some(question) => match &question.default {
+                            Some(default) if value.is_empty() => {
+                                format!("{} (default)", question.display(default))
+                            }
+                            _ => question.display(value),
+                        },
+                        None => value.clone(),
+                    };
+                    out.push_str(&format!("{:03} > {}\n", number, shown));
+                }
+                EntryKind::Rejected { message, .. } => {
+                    out.push_str(&format!("{:03} ! {}\n", number, message));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// # RecordingFrontend
+///
+/// created by `Transcript::record`
+pub struct RecordingFrontend<'a> {
+    inner: &'a mut dyn Frontend,
+    transcript: &'a mut Transcript,
+}
+
+impl Frontend for RecordingFrontend<'_> {
+    fn ask(&mut self, question: &Question) -> Result<String, InputError> {
+        self.transcript
+            .push(EntryKind::Asked(Box::new(question.clone())));
+        let value = self.inner.ask(question)?;
+        self.transcript.push(EntryKind::Answered {
+            id: question.id.clone(),
+            value: value.clone(),
+        });
+        Ok(value)
+    }
+
+    fn report_error(&mut self, question: &Question, message: &str) -> Result<(), InputError> {
+        self.transcript.push(EntryKind::Rejected {
+            id: question.id.clone(),
+            message: message.to_string(),
+        });
+        self.inner.report_error(question, message)
+    }
+}
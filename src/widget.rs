@@ -0,0 +1,174 @@
+//! # widget
+//!
+//! prompts as widgets for TUI frameworks. a widget gets key events from
+//! the application and draws itself through a `StyledWriter` with roles
+//! instead of escape codes, so ratatui, cursive or any other library
+//! maps them to its own styles. `Select::into_widget`,
+//! `MultiSelect::into_widget`, `Confirm::into_widget` and `Editor` are
+//! widgets
+
+use crate::editor::{Editor, KeyEvent, Outcome};
+use crate::highlight::Style;
+
+/// what a piece of a widget is, for the application to style it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Text,
+    /// the question
+    Prompt,
+    /// keys and defaults, like `[Y/n]`
+    Hint,
+    /// the highlighted item of a list
+    Active,
+    /// an item that can't be chosen
+    Disabled,
+    /// a group title in a list
+    Header,
+    /// spans of a `Highlighter`, with its style
+    Styled(Style),
+}
+
+/// # StyledWriter
+///
+/// where widgets draw, line by line
+pub trait StyledWriter {
+    fn write(&mut self, text: &str, role: Role);
+    /// start a new line
+    fn end_line(&mut self);
+    /// the cursor goes right after what was written so far
+    fn set_cursor(&mut self) {}
+}
+
+/// what a key did to a widget
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event<T> {
+    /// still asking, draw it again
+    Continue,
+    /// answered, the widget is spent
+    Done(T),
+    /// the user backed out with Esc or Ctrl+C
+    Cancelled,
+}
+
+/// # Widget
+///
+/// a prompt driven by the application's key events
+pub trait Widget {
+    type Output;
+
+    fn handle_key(&mut self, key: KeyEvent) -> Event<Self::Output>;
+    fn render(&self, out: &mut dyn StyledWriter);
+}
+
+/// # TextCanvas
+///
+/// a `StyledWriter` keeping plain text lines and the cursor, for tests
+/// and simple frontends
+///
+/// # usage
+/// ```
+/// use input_py::editor::{Key, KeyEvent};
+/// use input_py::widget::{Event, TextCanvas, Widget};
+/// use input_py::Input;
+///
+/// let mut confirm = Input::new("deploy?").confirm().default(false).into_widget().unwrap();
+/// let mut canvas = TextCanvas::new();
+/// confirm.render(&mut canvas);
+/// assert_eq!(canvas.lines(), ["deploy? [y/N]: "]);
+/// assert_eq!(canvas.cursor(), Some((0, 15)));
+/// assert_eq!(confirm.handle_key(Key::Char('y').into()), Event::Done(true));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextCanvas {
+    lines: Vec<String>,
+    /// row and column in chars
+    cursor: Option<(usize, usize)>,
+}
+
+impl TextCanvas {
+    pub fn new() -> Self {
+        TextCanvas {
+            lines: vec![String::new()],
+            cursor: None,
+        }
+    }
+
+    /// the lines written, without a last empty one
+    pub fn lines(&self) -> &[String] {
+        match self.lines.last() {
+            Some(last) if last.is_empty() => &self.lines[..self.lines.len() - 1],
+            _ => &self.lines,
+        }
+    }
+
+    /// row and column of the cursor, in chars
+    pub fn cursor(&self) -> Option<(usize, usize)> {
+        self.cursor
+    }
+}
+
+impl StyledWriter for TextCanvas {
+    fn write(&mut self, text: &str, _role: Role) {
+        if let Some(line) = self.lines.last_mut() {
+            line.push_str(text);
+        }
+    }
+
+    fn end_line(&mut self) {
+        self.lines.push(String::new());
+    }
+
+    fn set_cursor(&mut self) {
+        let row = self.lines.len() - 1;
+        self.cursor = Some((row, self.lines[row].chars().count()));
+    }
+}
+
+/// the editor's line with the vi mode indicator or the search prompt in
+/// front. `Done` carries an accepted line, Ctrl+C and Ctrl+D on an empty
+/// line cancel
+impl Widget for Editor {
+    type Output = String;
+
+    fn handle_key(&mut self, key: KeyEvent) -> Event<String> {
+        match Editor::handle_key(self, key) {
+            Outcome::Accept(line) => Event::Done(line),
+            Outcome::Eof | Outcome::Interrupted => Event::Cancelled,
+            Outcome::Continue | Outcome::ClearScreen | Outcome::Suspend => Event::Continue,
+        }
+    }
+
+    fn render(&self, out: &mut dyn StyledWriter) {
+        if !self.indicator().is_empty() {
+            out.write(self.indicator(), Role::Hint);
+            out.write(" ", Role::Text);
+        }
+        if let Some(prompt) = self.search_prompt() {
+            out.write(&prompt, Role::Prompt);
+        }
+        let mut at = 0;
+        for (text, style) in self.styled_line().spans() {
+            let role = match style {
+                ("", "") => Role::Text,
+                style => Role::Styled(*style),
+            };
+            let end = at + text.len();
+            if (at..end).contains(&self.cursor()) {
+                let (before, after) = text.split_at(self.cursor() - at);
+                out.write(before, role);
+                out.set_cursor();
+                out.write(after, role);
+            } else {
+                out.write(text, role);
+            }
+            at = end;
+        }
+        if self.cursor() == self.line().len() {
+            out.set_cursor();
+        }
+        if let Some(max_len) = self.limit() {
+            let len = self.line().chars().count();
+            out.write(&format!(" {}/{}", len, max_len), Role::Hint);
+        }
+    }
+}
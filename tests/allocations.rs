@@ -0,0 +1,47 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::{self, Cursor};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use input_py::io::{BufReaderInput, GenericWriter};
+use input_py::Prompter;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+// the only test in this binary, so no other thread allocates meanwhile
+#[test]
+fn test_read_fast_does_not_allocate_after_warm_up() {
+    let input = "  first line  \r\n".repeat(100);
+    let reader = BufReaderInput::new(Cursor::new(input.into_bytes()));
+    let mut prompter = Prompter::new(reader, GenericWriter::new(io::sink()));
+    let mut answer = String::new();
+    assert!(prompter.read_fast("value", &mut answer).unwrap());
+    assert_eq!(answer, "first line");
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..99 {
+        assert!(prompter.read_fast("value", &mut answer).unwrap());
+    }
+    assert_eq!(ALLOCATIONS.load(Ordering::Relaxed), before);
+    assert!(!prompter.read_fast("value", &mut answer).unwrap());
+}
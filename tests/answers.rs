@@ -0,0 +1,86 @@
+use input_py::answers::{self, AnswerSource};
+use input_py::io::{MockReader, MockWriter};
+use input_py::{Form, Input, InputError, Select};
+
+#[test]
+fn test_load_by_extension() {
+    let dir = std::env::temp_dir();
+    let id = std::process::id();
+    let files = [
+        ("toml", "[db]\nport = 6543\n"),
+        ("json", r#"{"db": {"port": "6543"}}"#),
+        ("env", "db.port=6543\n"),
+    ];
+    for (ext, text) in files {
+        let path = dir.join(format!("input_py_answers_{}.{}", id, ext));
+        std::fs::write(&path, text).unwrap();
+        let answers = AnswerSource::load(&path).unwrap();
+        assert_eq!(answers.get("db.port"), Some("6543"), "{}", ext);
+        std::fs::remove_file(&path).unwrap();
+    }
+    let missing = AnswerSource::load(dir.join(format!("input_py_answers_{}.none", id)));
+    assert!(matches!(missing, Err(InputError::Io(_))));
+}
+
+#[test]
+fn test_invalid_answers() {
+    let line = AnswerSource::from_key_values("a=1\nnot an answer");
+    assert_eq!(
+        line,
+        Err(InputError::Io(
+            "answers: line 2: expected key=value".to_string()
+        ))
+    );
+    let array = AnswerSource::from_json(r#"{"tags": ["a"]}"#);
+    assert_eq!(
+        array,
+        Err(InputError::Io(
+            "answers: 'tags' is not a string, number or bool".to_string()
+        ))
+    );
+    assert!(AnswerSource::from_json("[1]").is_err());
+}
+
+// the playbook is process wide, so this is the only test playing one
+#[test]
+fn test_playbook_answers_every_prompt() {
+    let playbook = AnswerSource::from_toml("[db]\nhost = \"db1\"\n").unwrap();
+    let playing = answers::play(playbook);
+    assert!(answers::is_playing());
+
+    let mut reader = MockReader::new(&[]);
+    let mut writer = MockWriter::new();
+    let answers = Form::new()
+        .field("db.host", Input::new("host"))
+        .field("db.port", Input::new("port").default("5432"))
+        .run_with_io(&mut reader, &mut writer)
+        .unwrap();
+    assert_eq!(answers.get("db.host"), Some("db1"));
+    assert_eq!(answers.get("db.port"), Some("5432"));
+
+    // prompts without an id fail with their prompt line
+    let env = Select::new("env", &["dev", "prod"]).read_with_io(&mut reader, &mut writer);
+    assert_eq!(env, Err(InputError::MissingAnswer("env:".to_string())));
+    assert_eq!(writer.output, "");
+
+    // a second playbook replaces the first until its guard is dropped
+    let replaced = answers::play(AnswerSource::new().answer("db.host", "db2"));
+    let host = Input::new("host")
+        .id("db.host")
+        .read_with_io(&mut reader, &mut writer);
+    assert_eq!(host, Ok("db2".to_string()));
+    drop(replaced);
+    let host = Input::new("host")
+        .id("db.host")
+        .read_with_io(&mut reader, &mut writer);
+    assert_eq!(host, Ok("db1".to_string()));
+
+    // without a playbook prompts read again and MissingAnswer is off
+    drop(playing);
+    assert!(!answers::is_playing());
+    let mut reader = MockReader::new(&["db3"]);
+    let host = Input::new("host")
+        .id("db.host")
+        .read_with_io(&mut reader, &mut writer);
+    assert_eq!(host, Ok("db3".to_string()));
+}
@@ -0,0 +1,113 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+
+use serde_json::Value;
+
+/// crates that open network connections
+const NETWORK_CRATES: &[&str] = &[
+    "curl",
+    "h2",
+    "hyper",
+    "mio",
+    "native-tls",
+    "openssl",
+    "reqwest",
+    "rustls",
+    "socket2",
+    "tungstenite",
+    "ureq",
+];
+
+/// the crates input_py builds with every feature on, not the dev ones,
+/// and the features each is built with
+fn dependency_graph() -> HashMap<String, Vec<String>> {
+    let output = Command::new(env!("CARGO"))
+        .args(["metadata", "--format-version", "1", "--all-features"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let metadata: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: HashMap<&str, &str> = metadata["packages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|package| {
+            (
+                package["id"].as_str().unwrap(),
+                package["name"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    let nodes: HashMap<&str, &Value> = metadata["resolve"]["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| (node["id"].as_str().unwrap(), node))
+        .collect();
+
+    let mut graph = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut todo = vec![metadata["resolve"]["root"].as_str().unwrap()];
+    while let Some(id) = todo.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        let node = nodes[id];
+        let features = node["features"].as_array().unwrap();
+        let features = features.iter().map(|f| f.as_str().unwrap().to_string());
+        graph.insert(names[id].to_string(), features.collect());
+        for dep in node["deps"].as_array().unwrap() {
+            let kinds = dep["dep_kinds"].as_array().unwrap();
+            if kinds.iter().any(|kind| kind["kind"].is_null()) {
+                todo.push(dep["pkg"].as_str().unwrap());
+            }
+        }
+    }
+    graph
+}
+
+#[test]
+fn test_no_network_in_dependencies() {
+    let graph = dependency_graph();
+    assert!(graph.contains_key("input_py"));
+    for name in NETWORK_CRATES {
+        assert!(!graph.contains_key(*name), "input_py depends on {}", name);
+    }
+    if let Some(features) = graph.get("tokio") {
+        assert!(
+            !features.iter().any(|f| f == "net"),
+            "tokio is built with net"
+        );
+    }
+}
+
+fn sources(dir: &Path, files: &mut Vec<String>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            sources(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(std::fs::read_to_string(path).unwrap());
+        }
+    }
+}
+
+#[test]
+fn test_no_network_in_sources() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut files = Vec::new();
+    sources(&root.join("src"), &mut files);
+    sources(&root.join("derive/src"), &mut files);
+    for file in &files {
+        for api in ["TcpListener", "TcpStream", "UdpSocket", "ToSocketAddrs"] {
+            assert!(!file.contains(api), "input_py uses {}", api);
+        }
+    }
+    assert!(!input_py::capabilities::reach().network);
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "color")]
+
+use anstyle::{Ansi256Color, AnsiColor};
+use input_py::config::{Color, Style};
+use input_py::io::{MockReader, MockWriter};
+use input_py::Input;
+
+#[test]
+fn test_colors_round_trip() {
+    let colors = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+    ];
+    for color in colors {
+        assert_eq!(Color::try_from(AnsiColor::from(color)), Ok(color));
+        assert_eq!(Color::try_from(anstyle::Color::from(color)), Ok(color));
+    }
+    let other = anstyle::Color::Ansi256(Ansi256Color(208));
+    assert_eq!(Color::try_from(other), Err(other));
+}
+
+#[test]
+fn test_prompt_in_clap_style() {
+    // like the `valid` style of clap's help
+    let valid = anstyle::Style::new().fg_color(Some(AnsiColor::Green.into()));
+    let style = Style {
+        prompt_color: valid.get_fg_color().and_then(|fg| fg.try_into().ok()),
+        ..Style::PLAIN
+    };
+    assert_eq!(style.prompt_color, Some(Color::Green));
+    let mut writer = MockWriter::new().terminal(true);
+    Input::new("name")
+        .style(style)
+        .read_with_io(&mut MockReader::new(&["ann"]), &mut writer)
+        .unwrap();
+    // green, unless NO_COLOR is set
+    assert!(writer.output == "\x1b[32mname\x1b[39m:" || writer.output == "name:");
+}
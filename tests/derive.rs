@@ -0,0 +1,55 @@
+#![cfg(feature = "derive")]
+
+use input_py::derive::FromForm;
+use input_py::form::Answers;
+use input_py::io::{MockReader, MockWriter};
+use input_py::InputError;
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Deploy {
+    service_name: String,
+    #[input(default = "3")]
+    replicas: u8,
+    #[input(prompt = "canary?", default = "false")]
+    canary: bool,
+}
+
+#[test]
+fn test_derived_form() {
+    let mut reader = MockReader::new(&["api", "many", "", "true"]);
+    let mut writer = MockWriter::new();
+    let deploy = Deploy::ask_with_io(&mut reader, &mut writer);
+    assert_eq!(
+        deploy,
+        Ok(Deploy {
+            service_name: "api".to_string(),
+            replicas: 3,
+            canary: true,
+        })
+    );
+    assert_eq!(
+        writer.output,
+        "service name:replicas [3]:error: invalid value 'many': expected integer\n\
+         replicas [3]:canary? [false]:"
+    );
+}
+
+#[test]
+fn test_derived_from_answers() {
+    let mut answers = Answers::new();
+    answers.insert("service_name", "api");
+    answers.insert("replicas", "300");
+    answers.insert("canary", "false");
+    assert_eq!(
+        Deploy::from_answers(&answers),
+        Err(InputError::InvalidAnswer {
+            answer: "300".to_string(),
+            msg: "expected integer".to_string(),
+        })
+    );
+    answers.remove("replicas");
+    assert_eq!(
+        Deploy::from_answers(&answers),
+        Err(InputError::MissingAnswer("replicas".to_string()))
+    );
+}
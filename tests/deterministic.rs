@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+use input_py::config;
+use input_py::frontend::{self, Question, TerminalFrontend};
+use input_py::io::{MockReader, MockWriter};
+use input_py::transcript::Transcript;
+use input_py::Select;
+
+// the setting is process wide, so this lives in its own test binary
+#[test]
+fn test_deterministic_output_is_stable() {
+    config::deterministic(true);
+    let mut terminal = TerminalFrontend::new(MockReader::new(&["db"]), MockWriter::new());
+    let mut transcript = Transcript::new();
+    std::thread::sleep(Duration::from_millis(5));
+    frontend::ask(
+        &mut transcript.record(&mut terminal),
+        &Question::new("host", "host"),
+    )
+    .unwrap();
+    assert!(transcript.entries().iter().all(|e| e.at == Duration::ZERO));
+
+    let mut writer = MockWriter::new().terminal(true);
+    let env = Select::new::<&str>("env", &[])
+        .items_with(|| {
+            std::thread::sleep(Duration::from_millis(250));
+            vec!["slow".to_string()]
+        })
+        .read_with_io(&mut MockReader::new(&["1"]), &mut writer);
+    assert_eq!(env, Ok("slow".to_string()));
+    assert!(writer.output.starts_with("  1. slow\n"));
+    config::deterministic(false);
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "encodings")]
+
+use input_py::encodings::{DecodingReader, Encoding, EncodingWriter};
+use input_py::io::{MockReader, MockWriter};
+use input_py::{read_input_with_io, Input, InputError};
+
+#[test]
+fn test_decoding_reader() {
+    let shift_jis = Encoding::for_label(b"shift_jis").unwrap();
+    let mut reader = DecodingReader::new(&b"\x93\x8c\x8b\x9e\r\n\xff\n"[..], shift_jis);
+    let mut writer = MockWriter::new();
+    let city = read_input_with_io("city", None, &mut reader, &mut writer);
+    assert_eq!(city, Ok("東京".to_string()));
+    // invalid bytes aren't an error
+    let next = read_input_with_io("next", None, &mut reader, &mut writer);
+    assert_eq!(next, Ok("\u{fffd}".to_string()));
+    let end = Input::new("end").read_with_io(&mut reader, &mut writer);
+    assert_eq!(end, Err(InputError::Eof));
+}
+
+#[test]
+#[should_panic(expected = "UTF-16LE is not ASCII compatible")]
+fn test_decoding_reader_utf16() {
+    let utf16 = Encoding::for_label(b"utf-16le").unwrap();
+    DecodingReader::new(&b""[..], utf16);
+}
+
+#[test]
+fn test_encoding_writer() {
+    let latin1 = Encoding::for_label(b"windows-1252").unwrap();
+    let mut writer = EncodingWriter::new(Vec::new(), latin1);
+    let price = Input::new("Preis in €")
+        .default("½")
+        .read_with_io(&mut MockReader::new(&[""]), &mut writer);
+    assert_eq!(price, Ok("½".to_string()));
+    assert_eq!(writer.into_inner(), b"Preis in \x80 [\xbd]:");
+
+    // characters the encoding lacks
+    let latin2 = Encoding::for_label(b"iso-8859-2").unwrap();
+    let mut writer = EncodingWriter::new(Vec::new(), latin2);
+    read_input_with_io("→", None, &mut MockReader::new(&["x"]), &mut writer).unwrap();
+    assert_eq!(writer.into_inner(), b"&#8594;:");
+}
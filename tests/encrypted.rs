@@ -0,0 +1,73 @@
+#![cfg(feature = "encryption")]
+
+use std::sync::Arc;
+
+use input_py::encrypted::EncryptedStorage;
+use input_py::io::{MockReader, MockWriter};
+use input_py::model::MaskStyle;
+use input_py::storage::{MemoryStorage, Storage};
+use input_py::{Input, InputError};
+
+#[test]
+fn test_passphrase_opens_storage_again() {
+    let inner = Arc::new(MemoryStorage::new());
+    let storage = EncryptedStorage::with_passphrase(inner.clone(), "hunter2").unwrap();
+    storage.put("token", "tok_abcdef").unwrap();
+    let sealed = inner.get("token").unwrap().unwrap();
+    assert!(sealed.starts_with("enc1:"));
+    assert!(!sealed.contains("tok_"));
+
+    let again = EncryptedStorage::with_passphrase(inner.clone(), "hunter2").unwrap();
+    assert_eq!(again.get("token").unwrap().as_deref(), Some("tok_abcdef"));
+    assert_eq!(again.list().unwrap(), ["token"]);
+
+    let wrong = EncryptedStorage::with_passphrase(inner.clone(), "hunter3").unwrap();
+    assert!(
+        matches!(wrong.get("token"), Err(InputError::Io(msg)) if msg.contains("wrong passphrase"))
+    );
+
+    // a value moved to another key doesn't decrypt
+    inner.put("other", &sealed).unwrap();
+    assert!(again.get("other").is_err());
+}
+
+#[test]
+fn test_remembered_answer_is_encrypted() {
+    let inner = Arc::new(MemoryStorage::new());
+    let storage: Arc<dyn Storage> = Arc::new(EncryptedStorage::with_key(inner.clone(), [7; 32]));
+    let token = || Input::new("token").remember(storage.clone(), "token");
+    token()
+        .read_with_io(
+            &mut MockReader::new(&["tok_abcdef"]),
+            &mut MockWriter::new(),
+        )
+        .unwrap();
+    assert!(!inner.get("token").unwrap().unwrap().contains("tok_"));
+
+    let mut writer = MockWriter::new();
+    let answer = token().read_with_io(&mut MockReader::new(&[""]), &mut writer);
+    assert_eq!(answer, Ok("tok_abcdef".to_string()));
+}
+
+#[test]
+fn test_remembered_secret_is_masked_in_the_prompt() {
+    let inner = Arc::new(MemoryStorage::new());
+    let storage: Arc<dyn Storage> = Arc::new(EncryptedStorage::with_key(inner, [7; 32]));
+    let token = || {
+        Input::new("token")
+            .mask_display(MaskStyle::Last(4))
+            .remember(storage.clone(), "token")
+    };
+    token()
+        .read_with_io(
+            &mut MockReader::new(&["tok_abcdef"]),
+            &mut MockWriter::new(),
+        )
+        .unwrap();
+
+    let mut writer = MockWriter::new();
+    let answer = token().read_with_io(&mut MockReader::new(&[""]), &mut writer);
+    assert_eq!(answer, Ok("tok_abcdef".to_string()));
+    assert_eq!(writer.output, "token [******cdef]:");
+    assert!(!writer.output.contains("tok_ab"));
+}
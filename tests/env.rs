@@ -0,0 +1,104 @@
+use std::fs;
+
+use input_py::answers::Recorder;
+use input_py::frontend::{self, Question, TerminalFrontend};
+use input_py::history::History;
+use input_py::io::{MockReader, MockWriter, StdinReader};
+use input_py::{config, read_input_with_io, Form, Input, InputError};
+
+// the variables are read once per process, so this lives in its own test binary
+#[test]
+fn test_env_controls_prompting() {
+    let path = std::env::temp_dir().join(format!("input_py_answers_{}.toml", std::process::id()));
+    fs::write(
+        &path,
+        "# answers for the deploy wizard\nhost = \"db.internal\"\n\n[db]\nport = 6543 # not the default\n\"user name\" = 'ops'\n",
+    )
+    .unwrap();
+    std::env::set_var(config::ENV_ANSWERS, &path);
+    std::env::set_var(config::ENV_DEFAULTS, "accept");
+    std::env::set_var(config::ENV_NONINTERACTIVE, "1");
+    std::env::set_var("INPUT_PY_ANSWER_APP_LOG_LEVEL", "debug");
+    std::env::set_var("INPUT_PY_ANSWER_HOST", "db.override");
+    std::env::set_var("INPUT_PY_ANSWER_DB_NAME", "orders");
+    std::env::set_var("INPUT_PY_ANSWER_REGION", "mars");
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        // unrelated variables that aren't UTF-8 must not break prompting
+        std::env::set_var("INPUT_PY_TEST_BYTES", OsStr::from_bytes(b"\xff"));
+        std::env::set_var("INPUT_PY_ANSWER_TOKEN", OsStr::from_bytes(b"ab\xff"));
+    }
+
+    let mut writer = MockWriter::new();
+    let host = read_input_with_io(
+        "host",
+        Some("localhost"),
+        &mut MockReader::new(&[]),
+        &mut writer,
+    );
+    assert_eq!(host, Ok("db.override".to_string()));
+    let port = Input::new("port")
+        .id("db.port")
+        .read_with_io(&mut StdinReader, &mut writer);
+    assert_eq!(port, Ok("6543".to_string()));
+    let level = Input::new("log level")
+        .id("app.log-level")
+        .read_with_io(&mut StdinReader, &mut writer);
+    assert_eq!(level, Ok("debug".to_string()));
+    let recording = std::env::temp_dir().join(format!("input_py_env_{}.toml", std::process::id()));
+    let recorder = Recorder::new(&recording);
+    let mut history = History::new();
+    let name = Input::new("Database name")
+        .key("db_name")
+        .recorder(recorder.clone())
+        .history(&mut history)
+        .read_with_io(&mut StdinReader, &mut writer);
+    assert_eq!(name, Ok("orders".to_string()));
+    assert_eq!(
+        recorder.entries(),
+        [("db_name".to_string(), "orders".to_string())]
+    );
+    assert_eq!(history.entries(), ["orders"]);
+    fs::remove_file(recording).unwrap();
+    #[cfg(unix)]
+    {
+        let token = Input::new("token").read_with_io(&mut StdinReader, &mut writer);
+        let err = InputError::Io("INPUT_PY_ANSWER_TOKEN is not valid UTF-8".to_string());
+        assert_eq!(token, Err(err));
+    }
+    let region = Input::new("region")
+        .choices(&["eu", "us"])
+        .read_with_io(&mut StdinReader, &mut writer);
+    let err = region.unwrap_err();
+    assert!(matches!(&err, InputError::InvalidAnswer { answer, .. } if answer == "mars"));
+    assert!(err.to_string().starts_with("invalid answer 'mars': "));
+    let timeout = read_input_with_io(
+        "timeout",
+        Some("30"),
+        &mut MockReader::new(&[]),
+        &mut writer,
+    );
+    assert_eq!(timeout, Ok("30".to_string()));
+    let name = read_input_with_io("name", None, &mut StdinReader, &mut writer);
+    assert_eq!(name, Err(InputError::NotInteractive("name".to_string())));
+    assert!(config::is_strict_interactive());
+
+    let answers = Form::new()
+        .skip_sections(true)
+        .section("Database")
+        .field("db.port", Input::new("port").default("5432"))
+        .field("db.user name", Input::new("user").default("postgres"))
+        .run_with_io(&mut StdinReader, &mut writer)
+        .unwrap();
+    assert_eq!(answers.get("db.port"), Some("6543"));
+    assert_eq!(answers.get("db.user name"), Some("ops"));
+    assert_eq!(writer.output, "— Database —\n");
+
+    let mut terminal = TerminalFrontend::new(MockReader::new(&[]), MockWriter::new());
+    let port = frontend::ask(&mut terminal, &Question::new("db.port", "port")).unwrap();
+    assert_eq!(port.value, "6543");
+    fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,11 @@
+#![cfg(feature = "fuzz")]
+
+use input_py::fuzz;
+
+#[test]
+fn test_fuzz_generated_inputs_keep_invariants() {
+    for raw in fuzz::InputGen::new(7).take(10_000) {
+        fuzz::check_invariants(&raw).unwrap();
+        let _ = fuzz::parse_checked::<f64>(&raw);
+    }
+}
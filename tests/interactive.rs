@@ -0,0 +1,109 @@
+use std::io;
+
+use input_py::config;
+use input_py::frontend::{self, Question, TerminalFrontend};
+use input_py::input::NonInteractive;
+use input_py::io::{InputReader, MockReader, MockWriter};
+use input_py::{read_input_with_io, Input, InputError, Prompter};
+
+/// stands in for stdin redirected from /dev/null in CI
+struct Detached;
+
+impl InputReader for Detached {
+    fn read_line(&mut self, _buf: &mut String) -> io::Result<usize> {
+        panic!("a non interactive reader must not be read in strict mode");
+    }
+}
+
+// the setting is process wide, so this lives in its own test binary
+#[test]
+fn test_strict_interactive_fails_fast() {
+    config::strict_interactive(true);
+    let mut writer = MockWriter::new();
+
+    let port = read_input_with_io("port", Some("5432"), &mut Detached, &mut writer);
+    assert_eq!(port, Ok("5432".to_string()));
+    let host = read_input_with_io("host", None, &mut Detached, &mut writer);
+    assert_eq!(host, Err(InputError::NotInteractive("host".to_string())));
+    assert!(host.unwrap_err().to_string().contains("not a terminal"));
+
+    let list = Input::new("tags")
+        .list::<String>()
+        .read_with_io(&mut Detached, &mut writer);
+    assert_eq!(list, Err(InputError::NotInteractive("tags:".to_string())));
+
+    let mut prompter = Prompter::new(Detached, MockWriter::new());
+    assert_eq!(prompter.ask("user", Some("admin")), Ok("admin".to_string()));
+    assert!(matches!(
+        prompter.ask("user", None),
+        Err(InputError::NotInteractive(_))
+    ));
+
+    let mut terminal = TerminalFrontend::new(Detached, MockWriter::new());
+    let answer = frontend::ask(
+        &mut terminal,
+        &Question::new("port", "port").default("5432"),
+    );
+    assert_eq!(answer.unwrap().value, "5432");
+    assert_eq!(writer.output, "");
+
+    // readers are only interactive when they say so
+    let mut reader = MockReader::new(&["db"]);
+    assert!(matches!(
+        read_input_with_io("host", None, &mut reader, &mut writer),
+        Err(InputError::NotInteractive(_))
+    ));
+    let mut reader = MockReader::new(&["db"]).interactive(true);
+    assert_eq!(
+        read_input_with_io("host", None, &mut reader, &mut writer),
+        Ok("db".to_string())
+    );
+    config::strict_interactive(false);
+}
+
+#[test]
+fn test_non_interactive_behavior() {
+    let mut writer = MockWriter::new();
+    let port = Input::new("port")
+        .non_interactive_behavior(NonInteractive::UseDefault)
+        .read_with_io(&mut Detached, &mut writer);
+    assert_eq!(port, Err(InputError::NotInteractive("port".to_string())));
+    let port = Input::new("port")
+        .default("5432")
+        .non_interactive_behavior(NonInteractive::Error)
+        .read_with_io(&mut Detached, &mut writer);
+    assert_eq!(port, Err(InputError::NotInteractive("port".to_string())));
+
+    // stdin piped from a file
+    let mut reader = MockReader::new(&["", "70000", "8080"]);
+    let silent = || {
+        Input::new("port")
+            .default("80")
+            .non_interactive_behavior(NonInteractive::ReadSilently)
+    };
+    assert_eq!(
+        silent().parse_with_io::<u16>(&mut reader, &mut writer),
+        Ok(80)
+    );
+    assert_eq!(
+        silent().parse_with_io::<u16>(&mut reader, &mut writer),
+        Err(InputError::ParseError("70000".to_string()))
+    );
+    assert_eq!(
+        silent().read_with_io(&mut reader, &mut writer),
+        Ok("8080".to_string())
+    );
+    assert_eq!(
+        silent().read_with_io(&mut reader, &mut writer),
+        Ok("80".to_string())
+    );
+    assert_eq!(writer.output, "");
+
+    // a terminal is asked as usual
+    let mut reader = MockReader::new(&["db"]).interactive(true);
+    let host = Input::new("host")
+        .non_interactive_behavior(NonInteractive::Error)
+        .read_with_io(&mut reader, &mut writer);
+    assert_eq!(host, Ok("db".to_string()));
+    assert_eq!(writer.output, "host:");
+}
@@ -0,0 +1,170 @@
+#![cfg(feature = "line-editing")]
+
+use input_py::editor::{EditMode, Editor, Key, KeyEvent, Mouse, MouseKind, Outcome};
+use input_py::Select;
+
+#[test]
+fn test_line_editor_key_decoder() {
+    use input_py::line_editor::KeyDecoder;
+
+    let mut decoder = KeyDecoder::new();
+    let ctrl = |key: Key| KeyEvent {
+        ctrl: true,
+        ..key.into()
+    };
+    assert_eq!(
+        decoder.feed(b"\x1b[1;5C\x1b[3~\x1bOH\x1b[4~\x1b\x7f\x1bb\r"),
+        [
+            ctrl(Key::Right),
+            Key::Delete.into(),
+            Key::Home.into(),
+            Key::End.into(),
+            KeyEvent {
+                alt: true,
+                ..Key::Backspace.into()
+            },
+            KeyEvent::alt('b'),
+            Key::Enter.into()
+        ]
+    );
+    // split sequences and chars wait for their rest
+    assert_eq!(decoder.feed(b"\x1b["), []);
+    assert_eq!(decoder.feed(b"A\xc3"), [Key::Up.into()]);
+    assert_eq!(
+        decoder.feed(b"\xa9\x1b[15~\x1b[9z\x17"),
+        [Key::Char('é').into(), Key::F(5).into(), KeyEvent::ctrl('w')]
+    );
+    assert!(!decoder.is_pending());
+    // SGR mouse reports, releases and other buttons are skipped
+    let mouse = |kind, row, column| Key::Mouse(Mouse { kind, row, column }).into();
+    assert_eq!(
+        decoder.feed(b"\x1b[<0;5;12M\x1b[<0;5;12m\x1b[<2;1;1M\x1b[<64;1;3M\x1b[<69;1;3M"),
+        [
+            mouse(MouseKind::Click, 11, 4),
+            mouse(MouseKind::ScrollUp, 2, 0),
+            mouse(MouseKind::ScrollDown, 2, 0)
+        ]
+    );
+}
+
+#[test]
+fn test_widget_session_mouse() {
+    use input_py::line_editor::WidgetSession;
+    use input_py::widget::Event;
+
+    let select = Select::new::<&str>("env", &[])
+        .item("dev")
+        .disabled("qa")
+        .item("prod")
+        .into_widget()
+        .unwrap();
+    let mut session = WidgetSession::new(select);
+    let mut out = String::new();
+    session.begin(true, &mut out);
+    assert_eq!(
+        out,
+        "\r\x1b[Jenv\r\n> 1. dev\r\n     qa (unavailable)\r\n  2. prod\x1b[6n"
+    );
+    out.clear();
+    // a click before the cursor report can't be placed
+    assert_eq!(session.feed(b"\x1b[<0;5;4M", &mut out), None);
+    // the report split across reads, with a key after it
+    assert_eq!(session.feed(b"\x1b[8;", &mut out), None);
+    assert!(session.is_pending());
+    assert_eq!(session.feed(b"9R\x1b[B", &mut out), None);
+    assert_eq!(
+        out,
+        "\x1b[3A\r\x1b[Jenv\r\n  1. dev\r\n     qa (unavailable)\r\n> 2. prod"
+    );
+    // the widget starts on row 4 counted from 0, qa is on row 6
+    assert_eq!(session.feed(b"\x1b[<0;5;7M", &mut out), None);
+    assert_eq!(session.feed(b"\x1b[<65;5;7M", &mut out), None);
+    assert_eq!(
+        session.feed(b"\x1b[<0;5;6M", &mut out),
+        Some(Event::Done("dev".to_string()))
+    );
+
+    let mut session = WidgetSession::new(Select::new("env", &["dev"]).into_widget().unwrap());
+    session.begin(false, &mut out);
+    assert_eq!(session.feed(b"\x1b", &mut out), None);
+    assert_eq!(session.flush_escape(&mut out), Some(Event::Cancelled));
+    assert!(!session.interrupted());
+    let mut session = WidgetSession::new(Select::new("env", &["dev"]).into_widget().unwrap());
+    session.begin(false, &mut out);
+    assert_eq!(session.feed(b"\x03", &mut out), Some(Event::Cancelled));
+    assert!(session.interrupted());
+}
+
+#[test]
+fn test_line_editor_max_len() {
+    use input_py::line_editor::LineSession;
+
+    let mut session = LineSession::new(Editor::new().mode(EditMode::Emacs).max_len(4));
+    let mut out = String::new();
+    session.begin("pin: ", &mut out);
+    assert_eq!(out, "\rpin:  0/4\u{1b}[K\u{1b}[4D");
+    out.clear();
+    assert_eq!(session.feed(b"12345", &mut out), None);
+    assert_eq!(out, "\rpin: 1234 4/4\u{1b}[K\u{1b}[4D");
+    assert_eq!(
+        session.feed(b"\x7f5\r", &mut out),
+        Some(Outcome::Accept("1235".to_string()))
+    );
+}
+
+#[test]
+fn test_line_editor_session() {
+    use input_py::line_editor::LineSession;
+
+    let mut session = LineSession::new(Editor::new().mode(EditMode::Emacs));
+    let mut out = String::new();
+    assert_eq!(session.begin("cmd: ", &mut out), None);
+    assert_eq!(out, "");
+    assert_eq!(
+        session.feed(b"git push origin\x1b\x7f\x1b[H", &mut out),
+        None
+    );
+    assert_eq!(out, "\rcmd: git push \u{1b}[K\u{1b}[9D");
+    // a pasted second line waits for the next prompt
+    assert_eq!(
+        session.feed(b"\x1b[F\r ls\r", &mut out),
+        Some(Outcome::Accept("git push ".to_string()))
+    );
+    out.clear();
+    // typed at the end of the line, it is echoed as is
+    assert_eq!(
+        session.begin("cmd: ", &mut out),
+        Some(Outcome::Accept(" ls".to_string()))
+    );
+    assert_eq!(out, " ls");
+    // the history is kept, Ctrl+Z is left to the backend
+    out.clear();
+    assert_eq!(session.begin("cmd: ", &mut out), None);
+    assert_eq!(session.feed(b"\x12git", &mut out), None);
+    assert!(
+        out.ends_with("(reverse-i-search)`git': git push \u{1b}[K\u{1b}[9D"),
+        "{:?}",
+        out
+    );
+    assert_eq!(session.feed(b"\x1a", &mut out), Some(Outcome::Suspend));
+    assert_eq!(session.feed(b"\x03", &mut out), Some(Outcome::Interrupted));
+    assert_eq!(session.feed(b"\x1b", &mut out), None);
+    assert!(session.is_pending());
+    assert_eq!(session.flush_escape(&mut out), None);
+    assert_eq!(session.feed(b"\x04", &mut out), Some(Outcome::Eof));
+}
+
+#[test]
+fn test_line_editor_lists_completions() {
+    use input_py::line_editor::LineSession;
+
+    let words = vec!["status".to_string(), "stash".to_string()];
+    let mut session = LineSession::new(Editor::new().mode(EditMode::Emacs).completer(words));
+    let mut out = String::new();
+    session.begin("git ", &mut out);
+    assert_eq!(session.feed(b"s\t", &mut out), None);
+    assert_eq!(out, "sta");
+    out.clear();
+    session.feed(b"\t", &mut out);
+    assert_eq!(out, "\r\nstatus  stash\r\n\rgit sta\u{1b}[K");
+}
@@ -0,0 +1,40 @@
+use input_py::config;
+use input_py::diff::ChangeSummary;
+use input_py::io::{MockReader, MockWriter};
+use input_py::locale::Locale;
+use input_py::model::Question;
+use input_py::{Answers, Input};
+
+// the locale is global, so this lives in its own test binary
+#[test]
+fn test_locale_formats_and_parses() {
+    config::locale(Locale::DE);
+
+    let n = Input::new("n")
+        .parse_with_io::<f64>(&mut MockReader::new(&["1.234,5"]), &mut MockWriter::new());
+    assert_eq!(n, Ok(1234.5));
+    let n = Input::new("n")
+        .parse_with_io::<u32>(&mut MockReader::new(&["12.345"]), &mut MockWriter::new());
+    assert_eq!(n, Ok(12345));
+
+    let mut answers = Answers::new();
+    answers.insert("budget", "2500.5");
+    answers.insert("start", "2024-03-01");
+    answers.insert("name", "web-1");
+    assert_eq!(answers.parse::<f64>("budget"), Ok(2500.5));
+    let summary = ChangeSummary::new([("budget", "2000")], &answers);
+    assert_eq!(
+        summary.render(),
+        "~ budget = 2.000 → 2.500,5\n+ start = 01.03.2024\n+ name = web-1\n"
+    );
+    assert_eq!(
+        Question::new("budget", "budget").display("2500.5"),
+        "2.500,5"
+    );
+
+    config::locale(Locale::C);
+    assert_eq!(
+        Question::new("budget", "budget").display("2500.5"),
+        "2500.5"
+    );
+}
@@ -0,0 +1,23 @@
+use input_py::config::{self, Color, Style};
+use input_py::io::{MockReader, MockWriter};
+use input_py::Input;
+
+// the variables are read once per process, so this lives in its own test binary
+#[test]
+fn test_no_color_keeps_prompts_plain() {
+    std::env::set_var(config::ENV_NO_COLOR, "1");
+    let style = Style {
+        prompt_color: Some(Color::Cyan),
+        error_color: Some(Color::Red),
+        ..Style::PLAIN
+    };
+    let mut writer = MockWriter::new().terminal(true);
+    let n = Input::new("n")
+        .style(style)
+        .parse_with_io::<u8>(&mut MockReader::new(&["x", "1"]), &mut writer);
+    assert_eq!(n, Ok(1));
+    assert_eq!(
+        writer.output,
+        "n:error: invalid value 'x': expected integer\nn:"
+    );
+}
@@ -0,0 +1,18 @@
+#![cfg(feature = "proptest")]
+
+use input_py::fuzz::{self, number_input, raw_input};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn test_processed_input_keeps_invariants(raw in raw_input()) {
+        prop_assert_eq!(fuzz::check_invariants(&raw), Ok(()));
+        let _ = fuzz::parse_checked::<f64>(&raw);
+    }
+
+    #[test]
+    fn test_padded_numbers_parse(input in number_input()) {
+        let (number, raw) = input;
+        prop_assert_eq!(fuzz::parse_checked::<i64>(&raw), Some(number));
+    }
+}
@@ -0,0 +1,65 @@
+#![cfg(all(feature = "pty", unix))]
+
+use input_py::driver::Driver;
+use input_py::read_input_with_io;
+
+#[test]
+fn test_driver_with_pty_echoes_answers() {
+    let run = Driver::new(env!("CARGO_BIN_EXE_input-demo"))
+        .arg("text")
+        .arg("--prompt")
+        .arg("test")
+        .pty(true)
+        .expect("test:", "hello")
+        .run()
+        .unwrap();
+    assert!(run.status.success());
+    assert!(run.transcript.starts_with("test:hello"));
+}
+
+#[test]
+fn test_pty_terminal() {
+    use input_py::testing::Pty;
+
+    let run = Pty::run("alice\n", |terminal| {
+        let interactive = terminal.is_terminal();
+        let name = read_input_with_io("name", None, &mut terminal.reader, &mut terminal.writer);
+        (interactive, name)
+    })
+    .unwrap();
+    assert_eq!(run.value, (true, Ok("alice".to_string())));
+    assert!(run.output.contains("alice"));
+    assert!(run.output.contains("name:"));
+
+    let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_input-demo"));
+    command.args(["text", "--prompt", "test"]);
+    let run = Pty::run_command(&mut command, "hello\n").unwrap();
+    assert!(run.value.success());
+    assert!(run.output.contains("test:"));
+}
+
+#[test]
+fn test_pty_hidden_input_without_stty() {
+    use input_py::testing::Pty;
+
+    // the echo goes off without starting programs, so no `stty` is needed
+    let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_input-demo"));
+    command.arg("password").env("PATH", "");
+    let run = Pty::run_command(&mut command, "hunter2\nhunter2\n").unwrap();
+    assert!(run.value.success());
+    assert!(!run.output.contains("warning"));
+    assert!(run.output.contains("password set, 7 characters"));
+}
+
+#[test]
+fn test_pty_demo_secret_is_hidden() {
+    use input_py::testing::Pty;
+
+    let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_input-demo"));
+    command.arg("secret");
+    let run = Pty::run_command(&mut command, "tok_abcdef\n").unwrap();
+    assert!(run.value.success());
+    // the input is typed before the echo goes off, only the summary counts
+    let (_, summary) = run.output.split_once("entered ").unwrap();
+    assert_eq!(summary.trim_end(), "********ef");
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "regex")]
+
+use input_py::io::{MockReader, MockWriter};
+use input_py::Input;
+use regex::Regex;
+
+#[test]
+fn test_input_regex() {
+    // unanchored, a match anywhere will do
+    let mut writer = MockWriter::new();
+    let tag = Input::new("tag")
+        .regex("a tag with a digit", Regex::new(r"\d").unwrap())
+        .read_with_io(&mut MockReader::new(&["beta", "beta2"]), &mut writer);
+    assert_eq!(tag, Ok("beta2".to_string()));
+    assert_eq!(
+        writer.output,
+        "tag [a tag with a digit]:error: 'beta' is not a tag with a digit\ntag [a tag with a digit]:"
+    );
+
+    let zip = Input::new("zip")
+        .regex("5 digits", Regex::new(r"^\d{5}$").unwrap())
+        .default("10115")
+        .read_with_io(
+            &mut MockReader::new(&["123456", ""]),
+            &mut MockWriter::new(),
+        );
+    assert_eq!(zip, Ok("10115".to_string()));
+}
@@ -0,0 +1,52 @@
+use std::io;
+
+use input_py::io::{InputReader, MockReader, MockWriter};
+use input_py::{config, Form, Input, InputError, ListMode};
+
+/// like `yes x | tool`, never runs out of invalid answers
+struct Endless(&'static str);
+
+impl InputReader for Endless {
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        buf.push_str(self.0);
+        buf.push('\n');
+        Ok(self.0.len() + 1)
+    }
+}
+
+// the limit is process wide, so this lives in its own test binary
+#[test]
+fn test_reprompt_loops_give_up() {
+    config::max_consecutive_errors(3);
+    let mut writer = MockWriter::new();
+
+    let numbers = Input::new("n")
+        .list::<u32>()
+        .read_with_io(&mut Endless("x"), &mut writer);
+    assert_eq!(numbers, Err(InputError::TooManyErrors(3)));
+    assert_eq!(writer.output.matches("error: ").count(), 3);
+
+    let form = Form::new()
+        .accept_defaults_prompt(true)
+        .field("a", Input::new("a"));
+    let answers = form.run_with_io(&mut Endless("maybe"), &mut writer);
+    assert_eq!(answers.unwrap_err(), InputError::TooManyErrors(3));
+
+    // accepted answers start a new row of errors
+    let mut reader = MockReader::new(&["x", "x", "1", "x", "x", "2", ""]);
+    let numbers = Input::new("n")
+        .list::<u32>()
+        .mode(ListMode::Repeated)
+        .read_with_io(&mut reader, &mut writer);
+    assert_eq!(numbers, Ok(vec![1, 2]));
+
+    config::max_consecutive_errors(0);
+    let mut reader = MockReader::new(&["x"; 60]);
+    assert_eq!(
+        Input::new("n")
+            .list::<u32>()
+            .read_with_io(&mut reader, &mut writer),
+        Err(InputError::Eof)
+    );
+    config::max_consecutive_errors(50);
+}
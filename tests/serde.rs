@@ -0,0 +1,32 @@
+#![cfg(feature = "serde")]
+
+use input_py::form::Answers;
+use input_py::io::{MockReader, MockWriter};
+use input_py::model::{Answer, Provenance};
+use input_py::{Form, Input};
+
+#[test]
+fn test_form_answers_to_json() {
+    let answers = Form::new()
+        .field("host", Input::new("host"))
+        .field("port", Input::new("port").default("5432"))
+        .run_with_io(&mut MockReader::new(&["db", ""]), &mut MockWriter::new())
+        .unwrap();
+    let json = serde_json::to_string(&answers).unwrap();
+    assert_eq!(json, r#"{"host":"db","port":"5432"}"#);
+    let again: Answers = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        again.iter().collect::<Vec<_>>(),
+        answers.iter().collect::<Vec<_>>()
+    );
+
+    let port = Answer::new("port", "5432", Provenance::Default);
+    let json = serde_json::to_string(&port).unwrap();
+    assert_eq!(
+        json,
+        r#"{"id":"port","value":"5432","provenance":"default","attempts":1}"#
+    );
+    assert_eq!(serde_json::from_str::<Answer>(&json).unwrap(), port);
+
+    assert!(serde_json::from_str::<Answers>(r#"{"port":5432}"#).is_err());
+}
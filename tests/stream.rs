@@ -0,0 +1,103 @@
+#![cfg(feature = "async")]
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use input_py::io::{MockReader, MockWriter};
+use input_py::{Input, InputError};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+fn test_answer_stream_until_eof() {
+    let reader = MockReader::new(&["1", "", "3"]);
+    let mut stream = Input::new("n")
+        .default("2")
+        .into_stream_with_io(reader, MockWriter::new());
+    let answers = block_on(async {
+        let mut answers = Vec::new();
+        while let Some(line) = stream.next().await {
+            answers.push(line.unwrap());
+        }
+        answers
+    });
+    assert_eq!(answers, vec!["1", "2", "3"]);
+}
+
+#[test]
+fn test_read_async() {
+    let name = Input::new("name")
+        .default("guest")
+        .read_async_with_io(MockReader::new(&["  ann "]), MockWriter::new());
+    assert_eq!(block_on(name), Ok("ann".to_string()));
+    let name = Input::new("name")
+        .default("guest")
+        .read_async_with_io(MockReader::new(&[""]), MockWriter::new());
+    assert_eq!(block_on(name), Ok("guest".to_string()));
+    let name = Input::new("name").read_async_with_io(MockReader::new(&[]), MockWriter::new());
+    assert_eq!(block_on(name), Err(InputError::Eof));
+}
+
+#[test]
+fn test_async_reader_and_writer() {
+    use input_py::async_io::{
+        AsyncBufReaderInput, AsyncGenericWriter, AsyncInputReader, AsyncOutputWriter,
+    };
+
+    let mut reader = MockReader::new(&[" ann "]);
+    let mut writer = MockWriter::new();
+    let name = block_on(Input::new("name").read_with_async_io(&mut reader, &mut writer));
+    assert_eq!(name, Ok("ann".to_string()));
+    assert_eq!((writer.output.as_str(), writer.flushes), ("name:", 1));
+    let name = block_on(
+        Input::new("name")
+            .default("guest")
+            .read_with_async_io(&mut MockReader::new(&[""]), &mut writer),
+    );
+    assert_eq!(name, Ok("guest".to_string()));
+    let name = block_on(Input::new("name").read_with_async_io(&mut reader, &mut writer));
+    assert_eq!(name, Err(InputError::Eof));
+
+    let mut reader = AsyncBufReaderInput::new("one\ntwo".as_bytes());
+    let mut writer = AsyncGenericWriter::new(Vec::new());
+    let lines = block_on(async {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        while reader.read_line(&mut line).await.unwrap() > 0 {
+            writer.write_str(&line.to_uppercase()).await.unwrap();
+            lines.push(std::mem::take(&mut line));
+        }
+        writer.flush().await.unwrap();
+        lines
+    });
+    assert_eq!(lines, vec!["one\n", "two"]);
+    assert_eq!(writer.into_inner(), Some(b"ONE\nTWO".to_vec()));
+
+    // trait objects, like the sync traits
+    let mut reader = AsyncBufReaderInput::new("x\n".as_bytes());
+    let reader: &mut dyn AsyncInputReader = &mut reader;
+    let mut writer = MockWriter::new();
+    let answer = block_on(Input::new("x").read_with_async_io(reader, &mut writer));
+    assert_eq!(answer, Ok("x".to_string()));
+}
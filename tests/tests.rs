@@ -1,7 +1,31 @@
+#![allow(clippy::empty_line_after_outer_attr)]
+
 #[cfg(test)]
 
 mod tests{
-    use input_py::input;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use input_py::{capabilities, fuzz, input};
+    use input_py::answers::{AnswerSource, Recorder};
+    use input_py::diff::{ChangeKind, ChangeSummary};
+    use input_py::editor::{Action, EditMode, Editor, Key, KeyEvent, KeymapId, KillRing, Mouse, MouseKind, Outcome};
+    use input_py::highlight::{Highlighter, StyledLine};
+    use input_py::driver::{Driver, DriverError};
+    use input_py::frontend::{self, Frontend, JsonFrontend, Question, TerminalFrontend};
+    use input_py::io::{BlockingBridgeReader, EchoReader, FlushPolicy, FlushPolicyWriter, MockReader, MockWriter, InputReader, OutputWriter, TeeReader, TeeWriter};
+    use input_py::map_editor::MapEditor;
+    use input_py::menu::Menu;
+    use input_py::{markdown, template};
+    use input_py::model::{Answer, MaskStyle, Provenance, QuestionKind};
+    use input_py::service::PromptService;
+    use input_py::storage::{FileStorage, MemoryStorage, Storage};
+    use input_py::table::{FromRow, Row, TablePrompt};
+    use input_py::range::RangePolicy;
+    use input_py::transcript::{EntryKind, Transcript};
+    use input_py::config::{Color, Style, Theme};
+    use input_py::{input_or_default_after_with_io, read_input_parse_with_io, read_input_opt_with_io, read_multiline_with_io, read_lines_with_io, read_input_with_io, render_prompt, Confirm3, Confirmation, MultiSelect, Select, DeadlinePolicy, Prompter, TextArea, Dedup, Form, Input, InputError, ListMode};
 
     #[test]
     fn test_input() {
@@ -9,4 +33,3108 @@ mod tests{
         let input_data = input(text);
         assert_eq!(input_data, Ok("input_text".to_string()));
     }
+
+    #[test]
+    fn test_read_input_with_io() {
+        let mut reader = MockReader::new(&["  input_text  "]);
+        let mut writer = MockWriter::new();
+        let input_data = read_input_with_io("test", None, &mut reader, &mut writer);
+        assert_eq!(input_data, Ok("input_text".to_string()));
+        assert_eq!(writer.output, "test:");
+    }
+
+    #[test]
+    fn test_render_prompt_matches_terminal_output() {
+        let mut writer = MockWriter::new();
+        read_input_with_io(
+            "port",
+            Some("5432"),
+            &mut MockReader::new(&[""]),
+            &mut writer,
+        )
+        .unwrap();
+        let question = Question::new("port", "port").default("5432");
+        assert_eq!(render_prompt(&question, &Theme::DEFAULT), writer.output);
+        assert_eq!(
+            render_prompt(&Question::new("host", "host"), &Theme::default()),
+            "host:"
+        );
+        let theme = Theme {
+            default_open: " (",
+            default_close: ")",
+            ..Theme::DEFAULT
+        };
+        assert_eq!(render_prompt(&question, &theme), "port (5432):");
+    }
+
+    #[test]
+    fn test_list_delimited_reprompts_invalid_item() {
+        let mut reader = MockReader::new(&["1, x, 3", "1, 2, 2, 3"]);
+        let mut writer = MockWriter::new();
+        let values = Input::new("numbers")
+            .list::<u32>()
+            .dedup(Dedup::Remove)
+            .validate_item(|n| {
+                if *n > 0 {
+                    Ok(())
+                } else {
+                    Err("must be positive".to_string())
+                }
+            })
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(values, Ok(vec![1, 2, 3]));
+        assert!(writer.output.contains("error: invalid value 'x'"));
+    }
+
+    #[test]
+    fn test_list_repeated_enforces_min_and_max() {
+        let mut reader = MockReader::new(&["a", "a", "", "b", "c"]);
+        let mut writer = MockWriter::new();
+        let values = Input::new("name")
+            .list::<String>()
+            .mode(ListMode::Repeated)
+            .dedup(Dedup::Reject)
+            .min(2)
+            .max(2)
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(values, Ok(vec!["a".to_string(), "b".to_string()]));
+        assert!(writer.output.contains("error: duplicate value 'a'"));
+        assert!(writer.output.contains("error: at least 2 values required"));
+    }
+
+    #[test]
+    fn test_list_eof_without_enough_values() {
+        let mut reader = MockReader::new(&[]);
+        let mut writer = MockWriter::new();
+        let values = Input::new("numbers")
+            .list::<u32>()
+            .min(1)
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(values, Err(InputError::Eof));
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Backend {
+        host: String,
+        port: u16,
+        weight: u8,
+    }
+
+    impl FromRow for Backend {
+        fn from_row(row: &Row) -> Result<Self, String> {
+            Ok(Backend {
+                host: row.parse("host")?,
+                port: row.parse("port")?,
+                weight: row.parse("weight")?,
+            })
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "TablePrompt 'backend' has no columns")]
+    fn test_table_prompt_without_columns() {
+        let mut reader = MockReader::new(&[]);
+        let _ = TablePrompt::new("backend")
+            .read_with_io::<Backend>(&mut reader, &mut MockWriter::new());
+    }
+
+    #[test]
+    fn test_table_prompt_rows() {
+        let mut reader = MockReader::new(&["db1", "99999", "5432", "10", "db2", "5433", "0", ""]);
+        let mut writer = MockWriter::new();
+        let backends: Result<Vec<Backend>, InputError> = TablePrompt::new("backend")
+            .column::<String>("host")
+            .column::<u16>("port")
+            .column_with::<u8, _>("weight", |w| {
+                if *w > 0 {
+                    Ok(())
+                } else {
+                    Err("must be positive".to_string())
+                }
+            })
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(backends, Err(InputError::Eof));
+        assert!(writer.output.contains("backend #1 port:"));
+        assert!(writer.output.contains("error: invalid port '99999'"));
+        assert!(writer
+            .output
+            .contains("error: invalid weight '0': must be positive"));
+
+        let mut reader = MockReader::new(&["db1", "5432", "10", ""]);
+        let backends: Vec<Backend> = TablePrompt::new("backend")
+            .column::<String>("host")
+            .column::<u16>("port")
+            .column::<u8>("weight")
+            .min_rows(1)
+            .read_with_io(&mut reader, &mut writer)
+            .unwrap();
+        assert_eq!(
+            backends,
+            vec![Backend {
+                host: "db1".to_string(),
+                port: 5432,
+                weight: 10
+            }]
+        );
+    }
+
+    #[test]
+    fn test_map_editor_commands() {
+        let mut settings = HashMap::new();
+        settings.insert("host".to_string(), "localhost".to_string());
+        let mut reader = MockReader::new(&[
+            "set port http",
+            "set port 8080",
+            "del host",
+            "del missing",
+            "bogus",
+            "done",
+        ]);
+        let mut writer = MockWriter::new();
+        MapEditor::new("settings")
+            .validate("port", |v| {
+                v.parse::<u16>().map(|_| ()).map_err(|e| e.to_string())
+            })
+            .edit_with_io(&mut settings, &mut reader, &mut writer)
+            .unwrap();
+        assert_eq!(settings.len(), 1);
+        assert_eq!(settings["port"], "8080");
+        assert!(writer.output.starts_with("  host = localhost\nsettings:"));
+        assert!(writer.output.contains("error: invalid port 'http'"));
+        assert!(writer.output.contains("error: no entry 'missing'"));
+        assert!(writer
+            .output
+            .contains("error: commands: set <key> <value>, del <key>, done"));
+    }
+
+    #[test]
+    fn test_map_editor_eof_keeps_map() {
+        let mut settings = HashMap::new();
+        let mut reader = MockReader::new(&["set port 8080"]);
+        let mut writer = MockWriter::new();
+        let result =
+            MapEditor::new("settings").edit_with_io(&mut settings, &mut reader, &mut writer);
+        assert_eq!(result, Err(InputError::Eof));
+        assert!(settings.is_empty());
+    }
+
+    #[test]
+    fn test_form_sections_with_skip() {
+        let mut reader = MockReader::new(&["myapp", "maybe", "", "", "secret"]);
+        let mut writer = MockWriter::new();
+        let answers = Form::new()
+            .skip_sections(true)
+            .field("name", Input::new("name"))
+            .section("Network")
+            .field("host", Input::new("host").default("localhost"))
+            .field("port", Input::new("port").default("5432"))
+            .section("Auth")
+            .field("user", Input::new("user").default("admin"))
+            .field("password", Input::new("password"))
+            .run_with_io(&mut reader, &mut writer)
+            .unwrap();
+        assert_eq!(answers.get("name"), Some("myapp"));
+        assert_eq!(answers.parse::<u16>("port"), Ok(5432));
+        assert_eq!(answers.get("user"), Some("admin"));
+        assert_eq!(answers.get("password"), Some("secret"));
+        assert!(writer
+            .output
+            .contains("— Network —\naccept all defaults in this section? [Y/n]:"));
+        assert!(writer.output.contains("error: 'maybe' is not y or n"));
+        assert!(writer.output.contains("— Auth —\nuser [admin]:"));
+    }
+
+    #[test]
+    fn test_form_accept_defaults_prompt() {
+        let form = || {
+            Form::new()
+                .accept_defaults_prompt(true)
+                .field("host", Input::new("host").default("localhost"))
+                .field("port", Input::new("port").default("5432"))
+        };
+
+        let mut reader = MockReader::new(&["y"]);
+        let mut writer = MockWriter::new();
+        let answers = form().run_with_io(&mut reader, &mut writer).unwrap();
+        assert_eq!(answers.get("host"), Some("localhost"));
+        assert_eq!(writer.output, "Use defaults for everything? [Y/n]:");
+
+        let mut reader = MockReader::new(&["n", "db", ""]);
+        let answers = form().run_with_io(&mut reader, &mut writer).unwrap();
+        assert_eq!(answers.get("host"), Some("db"));
+        assert_eq!(answers.get("port"), Some("5432"));
+
+        let mut reader = MockReader::new(&[""]);
+        let result = form()
+            .field("user", Input::new("user"))
+            .run_with_io(&mut reader, &mut writer);
+        assert_eq!(result, Err(InputError::MissingDefault("user".to_string())));
+    }
+
+    #[test]
+    fn test_form_checkpoint_resumes() {
+        let path =
+            std::env::temp_dir().join(format!("input_py_checkpoint_{}.toml", std::process::id()));
+        let form = || {
+            Form::new()
+                .field("db.name", Input::new("name"))
+                .field("token", Input::new("token").mask_display(MaskStyle::Full))
+                .field("my note", Input::new("note"))
+                .checkpoint(&path)
+        };
+        // fails once the lines run out, like a run stopped with Ctrl+C
+        struct Interrupted(MockReader);
+        impl InputReader for Interrupted {
+            fn read_line(&mut self, buf: &mut String) -> std::io::Result<usize> {
+                match self.0.read_line(buf)? {
+                    0 => Err(std::io::ErrorKind::Interrupted.into()),
+                    n => Ok(n),
+                }
+            }
+        }
+        let mut reader = Interrupted(MockReader::new(&["say \"hi\" \\o/", "s3cret"]));
+        let first = form().run_with_io(&mut reader, &mut MockWriter::new());
+        assert_eq!(first.unwrap_err(), InputError::Interrupted);
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(saved, "db.name = \"say \\\"hi\\\" \\\\o/\"\n");
+
+        let mut writer = MockWriter::new();
+        let answers = form()
+            .run_with_io(&mut MockReader::new(&["again", "done"]), &mut writer)
+            .unwrap();
+        assert_eq!(answers.get("db.name"), Some("say \"hi\" \\o/"));
+        assert_eq!(answers.get("token"), Some("again"));
+        assert_eq!(answers.get("my note"), Some("done"));
+        assert_eq!(writer.output, "token:note:");
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_interrupted_prompt() {
+        // like StdinReader under config::catch_interrupts
+        struct CtrlC(MockReader, bool);
+        impl InputReader for CtrlC {
+            fn read_line(&mut self, buf: &mut String) -> std::io::Result<usize> {
+                match std::mem::take(&mut self.1) {
+                    true => Err(std::io::ErrorKind::Interrupted.into()),
+                    false => self.0.read_line(buf),
+                }
+            }
+        }
+
+        let mut reader = CtrlC(MockReader::new(&["admin"]), true);
+        let mut writer = MockWriter::new();
+        let name = loop {
+            match read_input_with_io("user", Some("guest"), &mut reader, &mut writer) {
+                Err(InputError::Interrupted) => continue,
+                answer => break answer,
+            }
+        };
+        assert_eq!(name, Ok("admin".to_string()));
+        assert_eq!(writer.output, "user [guest]:user [guest]:");
+
+        let mut reader = CtrlC(MockReader::new(&["y"]), true);
+        let confirmed = Input::new("deploy")
+            .confirm()
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(confirmed, Err(InputError::Interrupted));
+        assert_eq!(InputError::Interrupted.to_string(), "interrupted");
+    }
+
+    #[test]
+    fn test_form_checkpoint_in_storage() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let form = || {
+            Form::new()
+                .field("name", Input::new("name"))
+                .field("env", Input::new("env").choices(&["prod"]).max_attempts(1))
+                .checkpoint_in(storage.clone(), "checkpoint.setup")
+        };
+        let mut reader = MockReader::new(&["db", "dev"]);
+        let first = form().run_with_io(&mut reader, &mut MockWriter::new());
+        assert_eq!(first, Err(InputError::AttemptsExceeded { attempts: 1 }));
+        assert_eq!(
+            storage.get("checkpoint.setup").unwrap().as_deref(),
+            Some("name = \"db\"\n")
+        );
+
+        let mut writer = MockWriter::new();
+        let answers = form()
+            .run_with_io(&mut MockReader::new(&["prod"]), &mut writer)
+            .unwrap();
+        assert_eq!(answers.get("name"), Some("db"));
+        assert_eq!(writer.output, "env [prod]:");
+        assert_eq!(storage.list().unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_file_storage_keys() {
+        let dir = std::env::temp_dir().join(format!("input_py_storage_{}", std::process::id()));
+        let storage = FileStorage::new(&dir);
+        assert_eq!(storage.list().unwrap(), Vec::<String>::new());
+        storage.put(".hidden", "1").unwrap();
+        storage.put("a/b", "2").unwrap();
+        storage.put("a/b", "3").unwrap();
+        std::fs::write(dir.join("notes.txt~"), "not a key").unwrap();
+        assert_eq!(storage.list().unwrap(), [".hidden", "a/b"]);
+        assert_eq!(storage.get("a/b").unwrap().as_deref(), Some("3"));
+        storage.delete("a/b").unwrap();
+        storage.delete("a/b").unwrap();
+        assert_eq!(storage.get("a/b").unwrap(), None);
+        assert!(matches!(storage.put("", "x"), Err(InputError::Io(_))));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_form_undo() {
+        let mut reader = MockReader::new(&[
+            ":undo", "db", "5433", ":undo", ":undo", "db.local", "5432", "app",
+        ]);
+        let mut writer = MockWriter::new();
+        let answers = Form::new()
+            .field("host", Input::new("host"))
+            .field("port", Input::new("port"))
+            .section("App")
+            .field("name", Input::new("name"))
+            .run_with_io(&mut reader, &mut writer)
+            .unwrap();
+        assert_eq!(answers.get("host"), Some("db.local"));
+        assert_eq!(answers.get("port"), Some("5432"));
+        assert_eq!(answers.get("name"), Some("app"));
+        assert_eq!(
+            writer.output,
+            "host:error: nothing to undo\nhost:port:— App —\nname:port:host:port:name:"
+        );
+    }
+
+    #[test]
+    fn test_form_undo_constrained() {
+        let path = std::env::temp_dir().join(format!("input_py_undo_{}.toml", std::process::id()));
+        let recorder = Recorder::new(&path);
+        let mut history = input_py::history::History::new();
+        let mut reader = MockReader::new(&["eu", ":undo", "us", "5432"]);
+        let mut writer = MockWriter::new();
+        let answers = Form::new()
+            .field("region", Input::new("region").choices(&["eu", "us"]))
+            .field(
+                "port",
+                Input::new("port")
+                    .max_len(5)
+                    .history(&mut history)
+                    .pattern("a number", |answer| {
+                        answer.chars().all(|c| c.is_ascii_digit())
+                    }),
+            )
+            .recorder(recorder.clone())
+            .run_with_io(&mut reader, &mut writer)
+            .unwrap();
+        assert_eq!(answers.get("region"), Some("us"));
+        assert_eq!(answers.get("port"), Some("5432"));
+        assert!(!writer.output.contains("error"));
+        assert_eq!(history.entries(), ["5432"]);
+        assert!(recorder
+            .entries()
+            .iter()
+            .all(|(_, answer)| answer != ":undo"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_prompter_undo_history() {
+        let mut prompter = Prompter::new(MockReader::new(&["a", "", "c"]), MockWriter::new());
+        assert_eq!(prompter.undo(), Ok(None));
+        assert_eq!(prompter.ask("first", None), Ok("a".to_string()));
+        assert_eq!(prompter.ask("second", Some("b")), Ok("b".to_string()));
+        assert_eq!(prompter.undo(), Ok(Some("c".to_string())));
+        assert_eq!(prompter.undo(), Err(InputError::Eof));
+        assert_eq!(prompter.history().collect::<Vec<_>>(), [("first", "a")]);
+        let (_, writer) = prompter.into_inner();
+        assert_eq!(writer.output, "first:second [b]:second [b]:second [b]:");
+
+        let mut prompter =
+            Prompter::new(MockReader::new(&["a"]), MockWriter::new()).pipe_mode(true);
+        prompter.ask("first", None).unwrap();
+        assert_eq!(prompter.undo(), Ok(None));
+    }
+
+    #[test]
+    fn test_change_summary() {
+        let mut answers = input_py::Answers::new();
+        answers.insert("host", "db");
+        answers.insert("port", "5433");
+        answers.insert("name", "app");
+        let existing: HashMap<String, String> =
+            [("host", "db"), ("port", "5432"), ("user", "admin")]
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+
+        let summary = ChangeSummary::new(&existing, &answers).theme(Theme::ANSI);
+        let kinds: Vec<ChangeKind> = summary.changes().iter().map(|c| c.kind()).collect();
+        assert_eq!(
+            kinds,
+            [
+                ChangeKind::Unchanged,
+                ChangeKind::Changed,
+                ChangeKind::Added
+            ]
+        );
+        assert_eq!(
+            summary.render(),
+            "  host = db\n\x1b[33m~ port = 5432 → 5433\x1b[39m\n\x1b[32m+ name = app\x1b[39m\n"
+        );
+        assert_eq!(
+            summary.confirm_with_io(
+                &mut MockReader::new(&["maybe", "n"]),
+                &mut MockWriter::new()
+            ),
+            Ok(false)
+        );
+
+        let mut writer = MockWriter::new();
+        let mut unchanged = input_py::Answers::new();
+        unchanged.insert("host", "db");
+        let apply = ChangeSummary::new(&existing, &unchanged)
+            .confirm_with_io(&mut MockReader::new(&[]), &mut writer);
+        assert_eq!(apply, Ok(false));
+        assert_eq!(writer.output, "  host = db\nno changes\n");
+    }
+
+    #[test]
+    fn test_form_review_mode() {
+        let mut reader = MockReader::new(&["2, 9", "2", "6543", "secret"]);
+        let mut writer = MockWriter::new();
+        let answers = Form::new()
+            .review(true)
+            .field("host", Input::new("host").default("localhost"))
+            .field("port", Input::new("port").default("5432"))
+            .field("password", Input::new("password"))
+            .run_with_io(&mut reader, &mut writer)
+            .unwrap();
+        assert_eq!(answers.get("host"), Some("localhost"));
+        assert_eq!(answers.get("port"), Some("6543"));
+        assert_eq!(answers.get("password"), Some("secret"));
+        assert_eq!(
+            answers.answer("host").unwrap().provenance,
+            Provenance::Default
+        );
+        assert_eq!(
+            answers.answer("port").unwrap().provenance,
+            Provenance::Entered
+        );
+        assert_eq!(answers.answer("port").unwrap().attempts, 1);
+        assert!(writer.output.starts_with(
+            "  1. host [localhost]\n  2. port [5432]\n  3. password [(no default)]\n"
+        ));
+        assert!(writer
+            .output
+            .contains("error: '9' is not a number between 1 and 3"));
+    }
+
+    #[test]
+    fn test_form_default_from_earlier_answers() {
+        let form = || {
+            Form::new()
+                .field("host", Input::new("host").default("localhost"))
+                .section("Connection")
+                .field(
+                    "url",
+                    Input::new("url")
+                        .default_from(|a| a.get("host").map(|h| format!("{}:5432", h))),
+                )
+                .field(
+                    "name",
+                    Input::new("name").default("app").default_from(|_| None),
+                )
+        };
+        let mut writer = MockWriter::new();
+        let answers = form()
+            .run_with_io(&mut MockReader::new(&["db", "", ""]), &mut writer)
+            .unwrap();
+        assert_eq!(answers.get("url"), Some("db:5432"));
+        assert_eq!(answers.get("name"), Some("app"));
+        assert!(writer.output.contains("url [db:5432]:"));
+
+        let mut writer = MockWriter::new();
+        let answers = form()
+            .skip_sections(true)
+            .run_with_io(&mut MockReader::new(&["n", "db", "y"]), &mut writer)
+            .unwrap();
+        assert_eq!(answers.get("url"), Some("db:5432"));
+
+        let mut writer = MockWriter::new();
+        let answers = form()
+            .review(true)
+            .run_with_io(&mut MockReader::new(&["1", "db"]), &mut writer)
+            .unwrap();
+        assert!(writer
+            .output
+            .starts_with("  1. host [localhost]\n  2. url [localhost:5432]\n"));
+        assert_eq!(answers.get("url"), Some("db:5432"));
+    }
+
+    #[test]
+    fn test_form_over_json_frontend() {
+        let reader = MockReader::new(&[
+            r#"{"id":"host","value":"db \"1\""}"#,
+            r#"{"id":"host","value":"x"}"#,
+            "not json",
+            r#"{"id":"port","value":null}"#,
+        ]);
+        let mut frontend = JsonFrontend::new(reader, MockWriter::new());
+        let answers = Form::new()
+            .field("host", Input::new("host"))
+            .field("port", Input::new("port").default("5432"))
+            .run_with_frontend(&mut frontend)
+            .unwrap();
+        assert_eq!(answers.get("host"), Some("db \"1\""));
+        assert_eq!(answers.get("port"), Some("5432"));
+
+        let (_, writer) = frontend.into_inner();
+        let lines: Vec<&str> = writer.output.lines().collect();
+        assert_eq!(
+            lines[0],
+            r#"{"type":"question","id":"host","kind":"text","prompt":"host","default":null}"#
+        );
+        assert_eq!(
+            lines[1],
+            r#"{"type":"question","id":"port","kind":"text","prompt":"port","default":"5432"}"#
+        );
+        assert_eq!(
+            lines[2],
+            r#"{"type":"error","id":"port","message":"expected an answer for 'port'"}"#
+        );
+        assert!(lines[3].starts_with(r#"{"type":"error","id":"port","message":"invalid JSON: "#));
+    }
+
+    #[test]
+    fn test_custom_frontend() {
+        struct Scripted {
+            answers: Vec<&'static str>,
+            asked: Vec<String>,
+        }
+
+        impl Frontend for Scripted {
+            fn ask(&mut self, question: &Question) -> Result<String, InputError> {
+                self.asked.push(question.id.clone());
+                Ok(self.answers.remove(0).to_string())
+            }
+
+            fn report_error(
+                &mut self,
+                _question: &Question,
+                _message: &str,
+            ) -> Result<(), InputError> {
+                Ok(())
+            }
+        }
+
+        let mut scripted = Scripted {
+            answers: vec!["db", ""],
+            asked: Vec::new(),
+        };
+        let answers = Form::new()
+            .field("host", Input::new("host"))
+            .field("port", Input::new("port").default("5432"))
+            .run_with_frontend(&mut scripted)
+            .unwrap();
+        assert_eq!(answers.get("host"), Some("db"));
+        assert_eq!(answers.get("port"), Some("5432"));
+        assert_eq!(scripted.asked, vec!["host", "port"]);
+
+        let mut terminal = TerminalFrontend::new(MockReader::new(&[""]), MockWriter::new());
+        let name = Input::new("name")
+            .default("guest")
+            .read_with_frontend(&mut terminal);
+        assert_eq!(name, Ok("guest".to_string()));
+        assert_eq!(terminal.into_inner().1.output, "name [guest]:");
+    }
+
+    #[test]
+    fn test_driver_answers_prompts() {
+        let run = Driver::new(env!("CARGO_BIN_EXE_input-demo"))
+            .arg("text")
+            .arg("--prompt")
+            .arg("test")
+            .expect("test:", "hello")
+            .timeout(Duration::from_secs(10))
+            .run()
+            .unwrap();
+        assert!(run.status.success());
+        assert_eq!(run.transcript, "test:hello\nhello\n");
+
+        let err = Driver::new(env!("CARGO_BIN_EXE_input-demo"))
+            .arg("text")
+            .arg("--prompt")
+            .arg("test")
+            .expect("test:", "")
+            .expect("never printed", "")
+            .run()
+            .unwrap_err();
+        assert!(matches!(err, DriverError::Exited { .. }));
+
+        let run = Driver::new(env!("CARGO_BIN_EXE_input-demo"))
+            .arg("text")
+            .arg("--locale")
+            .arg("de")
+            .expect("text:", "1234.5")
+            .timeout(Duration::from_secs(10))
+            .run()
+            .unwrap();
+        assert_eq!(run.transcript, "text:1234.5\n1.234,5\n");
+    }
+
+    #[test]
+    fn test_transcript_stats() {
+        struct Slow(MockReader);
+        impl InputReader for Slow {
+            fn read_line(&mut self, buf: &mut String) -> std::io::Result<usize> {
+                std::thread::sleep(Duration::from_millis(20));
+                self.0.read_line(buf)
+            }
+        }
+
+        let reader = Slow(MockReader::new(&["db", "9", "x", "2"]));
+        let mut terminal = TerminalFrontend::new(reader, MockWriter::new());
+        let mut transcript = Transcript::new();
+        Form::new()
+            .field("host", Input::new("host"))
+            .field("replicas", Input::new("replicas").choices(&["1", "2", "3"]))
+            .run_with_frontend(&mut transcript.record(&mut terminal))
+            .unwrap();
+        let stats = transcript.stats();
+        let ids: Vec<(&str, u32)> = stats.iter().map(|s| (s.id.as_str(), s.retries)).collect();
+        assert_eq!(ids, vec![("host", 0), ("replicas", 2)]);
+        assert!(stats[0].duration >= Duration::from_millis(20));
+        assert!(stats[1].duration >= Duration::from_millis(60));
+    }
+
+    #[test]
+    fn test_transcript_render_plain() {
+        let mut terminal =
+            TerminalFrontend::new(MockReader::new(&["hunter2", ""]), MockWriter::new());
+        let mut transcript = Transcript::new();
+        {
+            let mut recording = transcript.record(&mut terminal);
+            let password = Question::new("password", "password").kind(QuestionKind::Secret);
+            assert_eq!(
+                frontend::ask(&mut recording, &password).unwrap().value,
+                "hunter2"
+            );
+            recording.report_error(&password, "too short").unwrap();
+            let user = Question::new("user", "user").default("admin");
+            let answer = frontend::ask(&mut recording, &user).unwrap();
+            assert_eq!(answer, Answer::new("user", "admin", Provenance::Default));
+        }
+        assert_eq!(
+            transcript.render_plain(),
+            "001 ? password\n001 > ********\n001 ! too short\n002 ? user [admin]\n002 > admin (default)\n"
+        );
+        assert_eq!(transcript.entries().len(), 5);
+    }
+
+    #[test]
+    fn test_mask_display_in_transcript_and_review() {
+        assert_eq!(MaskStyle::Last(4).apply("abc"), "***");
+        assert_eq!(MaskStyle::First(2).apply("ab-1234"), "ab*****");
+
+        let mut terminal =
+            TerminalFrontend::new(MockReader::new(&["tok_12345678"]), MockWriter::new());
+        let mut transcript = Transcript::new();
+        let token = Input::new("token").mask_display(MaskStyle::Last(4));
+        let token = token.read_with_frontend(&mut transcript.record(&mut terminal));
+        assert_eq!(token, Ok("tok_12345678".to_string()));
+        assert_eq!(
+            transcript.render_plain(),
+            "001 ? token\n001 > ********5678\n"
+        );
+
+        // masked and hidden inputs are secrets, read with the echo off
+        let reader = TeeReader::new(MockReader::new(&["tok_1", "tok_2"]), MockWriter::new());
+        let mut terminal = TerminalFrontend::new(reader, MockWriter::new());
+        let token = Input::new("token").mask_display(MaskStyle::Last(4));
+        assert_eq!(token.question("token").kind, QuestionKind::Secret);
+        token.read_with_frontend(&mut terminal).unwrap();
+        Input::new("token")
+            .hidden(true)
+            .read_with_frontend(&mut terminal)
+            .unwrap();
+        let (reader, _) = terminal.into_inner();
+        let (_, audit) = reader.into_inner();
+        assert_eq!(audit.output, "(hidden)\n(hidden)\n");
+
+        let mut writer = MockWriter::new();
+        Form::new()
+            .review(true)
+            .field(
+                "token",
+                Input::new("token")
+                    .default("tok_12345678")
+                    .mask_display(MaskStyle::Last(4)),
+            )
+            .run_with_io(&mut MockReader::new(&[""]), &mut writer)
+            .unwrap();
+        assert!(writer.output.starts_with("  1. token [********5678]\n"));
+    }
+
+    #[test]
+    fn test_rejected_secrets_are_masked() {
+        let mut writer = MockWriter::new();
+        let token = Input::new("token")
+            .mask_display(MaskStyle::Last(2))
+            .max_len(4)
+            .read_with_io(&mut MockReader::new(&["abcdef", "abcd"]), &mut writer);
+        assert_eq!(token, Ok("abcd".to_string()));
+        assert_eq!(
+            writer.output,
+            "token [max 4 chars]:error: '****ef' is longer than 4 characters\ntoken [max 4 chars]:"
+        );
+
+        let mut terminal =
+            TerminalFrontend::new(MockReader::new(&["hunter2", "red"]), MockWriter::new());
+        let mut transcript = Transcript::new();
+        let mut color = Question::new("color", "color").kind(QuestionKind::Secret);
+        color.constraints.choices = vec!["red".to_string()];
+        frontend::ask(&mut transcript.record(&mut terminal), &color).unwrap();
+        assert!(transcript
+            .render_plain()
+            .contains("001 ! '********' is not one of red\n"));
+        let (_, writer) = terminal.into_inner();
+        assert!(!writer.output.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_text_area_box_and_heredoc() {
+        let mut writer = MockWriter::new();
+        let text = TextArea::new("msg").width(16).read_with_io(
+            &mut MockReader::new(&["a long line of text", ""]),
+            &mut writer,
+        );
+        assert_eq!(text, Ok("a long line of text\n".to_string()));
+        let expected = "msg (finish with Ctrl+D or Esc):\n│ │ │ \n┌─msg ─────────┐\n│ a long line  │\n│ of text      │\n│              │\n└──────────────┘\n";
+        assert_eq!(writer.output, expected);
+
+        let mut writer = MockWriter::new();
+        let mut reader = MockReader::new(&["line 1", "", ".", "after"]);
+        let text = TextArea::new("body")
+            .plain(true)
+            .terminator(".")
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(text, Ok("line 1\n".to_string()));
+        assert_eq!(writer.output, "body (end with .):\n");
+
+        let empty = TextArea::new("body")
+            .plain(true)
+            .read_with_io(&mut MockReader::new(&[]), &mut writer);
+        assert_eq!(empty, Err(InputError::Eof));
+    }
+
+    #[test]
+    fn test_text_area_indent() {
+        let mut writer = MockWriter::new();
+        let mut reader = MockReader::new(&["items = [", "1,", "(2, 3)]", "x", "EOF"]);
+        let text = TextArea::new("py")
+            .plain(true)
+            .auto_indent(2)
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(text, Ok("items = [\n  1,\n  (2, 3)]\nx".to_string()));
+        // the indent of each line follows the margin
+        assert_eq!(writer.output, "py (end with EOF):\n    ");
+
+        let mut writer = MockWriter::new();
+        let mut reader = MockReader::new(&["{", "  }", "\u{1b}"]);
+        let text = TextArea::new("json")
+            .width(12)
+            .auto_indent(4)
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(text, Ok("{\n  }".to_string()));
+        assert!(writer
+            .output
+            .starts_with("json (finish with Ctrl+D or Esc):\n│ │     │ ┌"));
+
+        // python like blocks: a colon opens one, else moves back
+        let blocks = |above: &str, line: &str| {
+            let last = above.lines().last().unwrap_or("");
+            let indent = last.len() - last.trim_start().len();
+            match (last.ends_with(':'), line.starts_with("else")) {
+                (true, false) => indent + 4,
+                (false, true) => indent.saturating_sub(4),
+                _ => indent,
+            }
+        };
+        let mut reader = MockReader::new(&["if ok:", "run()", "else:", "stop()", ""]);
+        let text = TextArea::new("code")
+            .plain(true)
+            .terminator("")
+            .indent_hint(blocks)
+            .read_with_io(&mut reader, &mut MockWriter::new());
+        assert_eq!(text, Ok("if ok:\n    run()\nelse:\n    stop()".to_string()));
+    }
+
+    #[test]
+    fn test_alt_screen() {
+        use input_py::screen::{self, AltScreen};
+
+        let mut writer = MockWriter::new();
+        let mut reader = MockReader::new(&["dear team,", "\u{1b}"]).interactive(true);
+        let text =
+            AltScreen::new(TextArea::new("mail").width(16)).read_with_io(&mut reader, &mut writer);
+        assert_eq!(text, Ok("dear team,".to_string()));
+        assert!(writer.output.starts_with("\x1b[?1049h\x1b[Hmail (finish"));
+        assert!(writer.output.ends_with("└──────────────┘\n\x1b[?1049l"));
+
+        // the screen is left when the prompt fails
+        let mut writer = MockWriter::new();
+        let region = AltScreen::new(Select::new("region", &["eu", "us"]))
+            .summary(|region| format!("region: {}", region))
+            .read_with_io(&mut MockReader::new(&[]).interactive(true), &mut writer);
+        assert_eq!(region, Err(InputError::Eof));
+        assert!(writer.output.ends_with("region:\x1b[?1049l"));
+
+        let mut writer = MockWriter::new();
+        let region = AltScreen::new(Select::new("region", &["eu", "us"]))
+            .summary(|region| format!("region: {}", region))
+            .read_with_io(&mut MockReader::new(&["eu"]), &mut writer);
+        assert_eq!(region, Ok("eu".to_string()));
+        assert_eq!(writer.output, "  1. eu\n  2. us\nregion:");
+
+        let mut writer = MockWriter::new();
+        screen::enter(&mut writer).unwrap();
+        screen::leave(&mut writer).unwrap();
+        assert_eq!(writer.output, "\x1b[?1049h\x1b[H\x1b[?1049l");
+    }
+
+    #[test]
+    fn test_read_multiline() {
+        let mut reader =
+            MockReader::new(&["Fixes the parser.", "", "  Details follow.", "EOF", "next"]);
+        let mut writer = MockWriter::new();
+        let text = read_multiline_with_io("description", Some("EOF"), &mut reader, &mut writer);
+        assert_eq!(
+            text,
+            Ok("Fixes the parser.\n\n  Details follow.".to_string())
+        );
+        assert_eq!(writer.output, "description (end with EOF):\n");
+        assert_eq!(
+            read_input_with_io("then", None, &mut reader, &mut writer),
+            Ok("next".to_string())
+        );
+
+        let text = read_multiline_with_io(
+            "notes",
+            None,
+            &mut MockReader::new(&["only line"]),
+            &mut MockWriter::new(),
+        );
+        assert_eq!(text, Ok("only line".to_string()));
+        let text = read_multiline_with_io(
+            "notes",
+            None,
+            &mut MockReader::new(&[""]),
+            &mut MockWriter::new(),
+        );
+        assert_eq!(text, Ok(String::new()));
+    }
+
+    #[test]
+    fn test_read_all_lines() {
+        let mut writer = MockWriter::new();
+        let lines = read_lines_with_io(
+            "urls",
+            &mut MockReader::new(&["  https://a.example ", "", "https://b.example"]),
+            &mut writer,
+        );
+        assert_eq!(
+            lines,
+            Ok(vec![
+                "https://a.example".to_string(),
+                String::new(),
+                "https://b.example".to_string()
+            ])
+        );
+        assert_eq!(writer.output, "urls:");
+        assert_eq!(
+            read_lines_with_io("urls", &mut MockReader::new(&[]), &mut MockWriter::new()),
+            Ok(vec![])
+        );
+
+        let mut writer = MockWriter::new();
+        let ports = Input::new("ports for {host}")
+            .vars(&[("host", "web1")])
+            .default("80")
+            .read_all_lines_with_io(&mut MockReader::new(&["443", " ", "8080"]), &mut writer);
+        assert_eq!(
+            ports,
+            Ok(vec![
+                "443".to_string(),
+                "80".to_string(),
+                "8080".to_string()
+            ])
+        );
+        assert_eq!(writer.output, "ports for web1 [80]:");
+    }
+
+    #[test]
+    fn test_markdown_body() {
+        let body = "# Setup\nUse **bold** and `code`, not *single* or **open\n  * nested item\n1. numbered";
+        assert_eq!(
+            markdown::render(body, &Theme::DEFAULT),
+            "Setup\nUse bold and code, not *single* or **open\n  • nested item\n1. numbered\n"
+        );
+        assert_eq!(
+            markdown::render("**a** `b`", &Theme::ANSI),
+            "\x1b[1ma\x1b[22m \x1b[36mb\x1b[39m\n"
+        );
+
+        let mut writer = MockWriter::new();
+        let form = Form::new().field(
+            "region",
+            Input::new("region")
+                .body("Choose a **region**")
+                .theme(Theme::ANSI),
+        );
+        form.run_with_io(&mut MockReader::new(&["eu"]), &mut writer)
+            .unwrap();
+        assert_eq!(writer.output, "Choose a \x1b[1mregion\x1b[22m\nregion:");
+
+        let mut terminal = TerminalFrontend::new(MockReader::new(&["eu"]), MockWriter::new());
+        Input::new("region")
+            .body("- eu")
+            .read_with_frontend(&mut terminal)
+            .unwrap();
+        assert_eq!(terminal.into_inner().1.output, "• eu\nregion:");
+    }
+
+    #[test]
+    fn test_prompt_template_values() {
+        assert_eq!(
+            template::render("{{{a}}}", &[("a", "x")]),
+            Ok("{x}".to_string())
+        );
+        assert!(matches!(
+            template::render("{a", &[]),
+            Err(InputError::Template(_))
+        ));
+        assert!(matches!(
+            template::placeholders("a}"),
+            Err(InputError::Template(_))
+        ));
+        assert!(matches!(
+            template::placeholders("{a b}"),
+            Err(InputError::Template(_))
+        ));
+
+        let mut writer = MockWriter::new();
+        let deploy = Input::new("Deploy {app} to {env}?").vars(&[("app", "web")]);
+        let missing = deploy.read_with_io(&mut MockReader::new(&["y"]), &mut writer);
+        assert_eq!(
+            missing,
+            Err(InputError::Template(
+                "no value for '{env}' in 'Deploy {app} to {env}?'".to_string()
+            ))
+        );
+        assert_eq!(writer.output, "");
+
+        let mut terminal = TerminalFrontend::new(MockReader::new(&["y"]), MockWriter::new());
+        let answer = Input::new("Deploy {app}?")
+            .vars(&[("app", "web")])
+            .body("Ships `{app}`")
+            .read_with_frontend(&mut terminal);
+        assert_eq!(answer, Ok("y".to_string()));
+        assert_eq!(terminal.into_inner().1.output, "Ships web\nDeploy web?:");
+        assert_eq!(
+            Input::new("Deploy {app}?").vars(&[]).question("q").prompt,
+            "Deploy {app}?"
+        );
+    }
+
+    #[test]
+    fn test_flush_pending_discards_type_ahead() {
+        let mut writer = MockWriter::new();
+        let mut reader = MockReader::new(&["no", "yes"]).typed_ahead(&[""]);
+        let keep = Input::new("continue?").read_with_io(&mut reader, &mut writer);
+        assert_eq!(keep, Ok("".to_string()));
+        let confirm = Input::new("delete?")
+            .flush_pending(true)
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(confirm, Ok("no".to_string()));
+
+        let (mut reader, sender) = BlockingBridgeReader::channel();
+        sender.send("stray");
+        sender.send("");
+        let feeder = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            sender.send("yes")
+        });
+        let confirm = Input::new("delete?")
+            .flush_pending(true)
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(confirm, Ok("yes".to_string()));
+        feeder.join().unwrap();
+    }
+
+    #[test]
+    fn test_form_type_ahead_toggle() {
+        let form = || {
+            Form::new()
+                .field("host", Input::new("host"))
+                .field("port", Input::new("port").default("5432"))
+        };
+        let mut reader = MockReader::new(&[]).typed_ahead(&["db", "6543"]);
+        let answers = form()
+            .run_with_io(&mut reader, &mut MockWriter::new())
+            .unwrap();
+        assert_eq!(
+            (answers.get("host"), answers.get("port")),
+            (Some("db"), Some("6543"))
+        );
+
+        let mut reader = MockReader::new(&["db", ""]).typed_ahead(&["stray", "6543"]);
+        let answers = form()
+            .type_ahead(false)
+            .run_with_io(&mut reader, &mut MockWriter::new())
+            .unwrap();
+        assert_eq!(
+            (answers.get("host"), answers.get("port")),
+            (Some("db"), Some("5432"))
+        );
+    }
+
+    #[test]
+    fn test_input_id_keys_transcript() {
+        let mut terminal = TerminalFrontend::new(MockReader::new(&["6543"]), MockWriter::new());
+        let mut transcript = Transcript::new();
+        let port = Input::new("port")
+            .id("db.port")
+            .read_with_frontend(&mut transcript.record(&mut terminal));
+        assert_eq!(port, Ok("6543".to_string()));
+        assert!(matches!(&transcript.entries()[0].kind, EntryKind::Asked(q) if q.id == "db.port"));
+        assert!(
+            matches!(&transcript.entries()[1].kind, EntryKind::Answered { id, .. } if id == "db.port")
+        );
+    }
+
+    #[test]
+    fn test_fuzz_entry_points() {
+        assert_eq!(fuzz::process_input_checked(b"  42\r\n"), "42");
+        assert_eq!(fuzz::process_input_checked(b"\xffabc\n"), "\u{fffd}abc");
+        assert_eq!(fuzz::parse_checked::<i32>(b"-7\r\n"), Some(-7));
+        assert_eq!(fuzz::parse_checked::<i32>(b"\xff"), None);
+        for raw in [
+            &b""[..],
+            b"\r\n",
+            "\u{3000}x\u{a0}\n".as_bytes(),
+            b"\xe6\x97",
+        ] {
+            fuzz::check_invariants(raw).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_echo_reader_line_discipline() {
+        let (mut reader, mut writer) = EchoReader::new(
+            MockReader::new(&["ab\u{8}c\rsecond", ""]),
+            MockWriter::new(),
+        );
+        assert_eq!(
+            read_input_with_io("first", None, &mut reader, &mut writer),
+            Ok("ac".to_string())
+        );
+        assert_eq!(
+            read_input_with_io("second", None, &mut reader, &mut writer),
+            Ok("second".to_string())
+        );
+        assert_eq!(
+            read_input_with_io("third", Some("x"), &mut reader, &mut writer),
+            Ok("x".to_string())
+        );
+        let mut buf = String::new();
+        assert_eq!(reader.read_line(&mut buf).unwrap(), 0);
+        drop(reader);
+        assert_eq!(
+            writer.into_inner().output,
+            "first:ac\nsecond:second\nthird [x]:\n"
+        );
+    }
+
+    #[test]
+    fn test_prompter_session_deadline() {
+        let past = std::time::Instant::now();
+        let mut prompter = Prompter::new(MockReader::new(&["never read"]), MockWriter::new())
+            .session_deadline(past);
+        assert_eq!(
+            prompter.ask("host", Some("localhost")),
+            Err(InputError::Timeout)
+        );
+        let mut answer = String::new();
+        assert_eq!(
+            prompter.read_fast("n", &mut answer),
+            Err(InputError::Timeout)
+        );
+
+        let mut prompter = prompter.deadline_policy(DeadlinePolicy::UseDefault);
+        assert_eq!(
+            prompter.ask("host", Some("localhost")),
+            Ok("localhost".to_string())
+        );
+        assert_eq!(prompter.ask("user", None), Err(InputError::Timeout));
+        assert_eq!(prompter.into_inner().1.output, "");
+
+        let later = std::time::Instant::now() + Duration::from_secs(3600);
+        let mut prompter =
+            Prompter::new(MockReader::new(&["db"]), MockWriter::new()).session_deadline(later);
+        assert_eq!(prompter.ask("host", None), Ok("db".to_string()));
+    }
+
+    #[test]
+    fn test_prompter_pipe_mode() {
+        let mut prompter =
+            Prompter::new(MockReader::new(&["a", "", "c"]), MockWriter::new()).pipe_mode(true);
+        let mut lines = Vec::new();
+        while let Some(line) = prompter.try_ask("line", Some("-")).unwrap() {
+            lines.push(line);
+        }
+        assert_eq!(lines, ["a", "-", "c"]);
+        assert_eq!(prompter.ask("more", None), Err(InputError::Eof));
+        let (_, writer) = prompter.into_inner();
+        assert_eq!((writer.output.as_str(), writer.flushes), ("", 0));
+    }
+
+    #[test]
+    fn test_prompter_parse_error_has_line() {
+        let long = "x".repeat(50);
+        let lines = ["1", "", "2.5", long.as_str()];
+        let mut prompter =
+            Prompter::new(MockReader::new(&lines), MockWriter::new()).pipe_mode(true);
+        assert_eq!(prompter.parse::<u8>("n"), Ok(Some(1)));
+        assert_eq!(prompter.ask("skip", Some("-")), Ok("-".to_string()));
+        // the default was not read, so it has no line
+        assert_eq!(
+            prompter.parse_error("expected a number"),
+            InputError::Parse {
+                line: None,
+                text: "-".to_string(),
+                msg: "expected a number".to_string(),
+            }
+        );
+        assert_eq!(
+            prompter.parse::<u8>("n"),
+            Err(InputError::Parse {
+                line: Some(3),
+                text: "2.5".to_string(),
+                msg: "expected integer".to_string(),
+            })
+        );
+        let err = prompter.parse::<bool>("flag").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "parse error: expected true or false at line 4: '{}…'",
+                "x".repeat(40)
+            )
+        );
+        assert_eq!(prompter.parse::<u8>("n"), Ok(None));
+        assert_eq!(prompter.line(), 4);
+    }
+
+    #[test]
+    fn test_input_or_default_after() {
+        let (mut reader, sender) = BlockingBridgeReader::channel();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(30));
+            sender.send(" safe ");
+        });
+        let mut writer = MockWriter::new();
+        let mode = input_or_default_after_with_io(
+            "boot mode",
+            "normal",
+            Duration::from_secs(5),
+            &mut reader,
+            &mut writer,
+        );
+        assert_eq!(mode, Ok("safe".to_string()));
+        assert_eq!(writer.output, "boot mode [normal] (5s):");
+
+        // the input ended before the countdown
+        let (mut reader, sender) = BlockingBridgeReader::channel();
+        drop(sender);
+        let mode = input_or_default_after_with_io(
+            "boot mode",
+            "normal",
+            Duration::from_secs(5),
+            &mut reader,
+            &mut MockWriter::new(),
+        );
+        assert_eq!(mode, Ok("normal".to_string()));
+
+        let mode = input_or_default_after_with_io(
+            "boot mode",
+            "normal",
+            Duration::ZERO,
+            &mut MockReader::new(&["safe"]),
+            &mut MockWriter::new(),
+        );
+        assert_eq!(mode, Ok("normal".to_string()));
+        let mode = input_or_default_after_with_io(
+            "boot mode",
+            "normal",
+            Duration::from_secs(5),
+            &mut MockReader::new(&["safe"]),
+            &mut MockWriter::new(),
+        );
+        assert_eq!(mode, Ok("safe".to_string()));
+    }
+
+    #[test]
+    fn test_input_or_default_after_typed_halfway() {
+        // the user typed part of a line, which the reader only gets later
+        struct Halfway {
+            discarded: bool,
+        }
+        impl InputReader for Halfway {
+            fn read_line(&mut self, _buf: &mut String) -> std::io::Result<usize> {
+                unreachable!("the line was never finished")
+            }
+            fn discard_pending(&mut self) -> std::io::Result<()> {
+                self.discarded = true;
+                Ok(())
+            }
+            fn wait_readable(&mut self, timeout: Duration) -> std::io::Result<bool> {
+                std::thread::sleep(timeout);
+                Ok(false)
+            }
+        }
+
+        let mut reader = Halfway { discarded: false };
+        let mut writer = MockWriter::new().terminal(true);
+        let mode = input_or_default_after_with_io(
+            "boot mode",
+            "normal",
+            Duration::from_millis(1050),
+            &mut reader,
+            &mut writer,
+        );
+        assert_eq!(mode, Ok("normal".to_string()));
+        assert!(reader.discarded);
+        // the countdown is redrawn in place and the cursor put back
+        let (first, redraw) = writer.output.split_once("\x1b7\r").unwrap();
+        assert!(first.contains("(2s)"));
+        assert!(redraw.contains("(1s)"));
+        assert!(redraw.ends_with("\x1b8\n"));
+    }
+
+    #[test]
+    fn test_input_timeout() {
+        let wait = Duration::from_millis(30);
+        let (mut reader, _sender) = BlockingBridgeReader::channel();
+        let mut writer = MockWriter::new();
+        let answer = Input::new("retry")
+            .default("no")
+            .timeout(wait)
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(answer, Ok("no".to_string()));
+        assert_eq!(writer.output, "retry [no]:\n");
+        // a line typed halfway doesn't reach the next prompt
+        struct Halfway(bool);
+        impl InputReader for Halfway {
+            fn read_line(&mut self, _buf: &mut String) -> std::io::Result<usize> {
+                unreachable!("the line was never finished")
+            }
+            fn discard_pending(&mut self) -> std::io::Result<()> {
+                self.0 = true;
+                Ok(())
+            }
+            fn wait_readable(&mut self, timeout: Duration) -> std::io::Result<bool> {
+                std::thread::sleep(timeout);
+                Ok(false)
+            }
+        }
+        let mut reader = Halfway(false);
+        let answer = Input::new("retry")
+            .default("no")
+            .timeout(wait)
+            .read_with_io(&mut reader, &mut MockWriter::new());
+        assert_eq!(answer, Ok("no".to_string()));
+        assert!(reader.0);
+
+        let (mut reader, _sender) = BlockingBridgeReader::channel();
+        let port = Input::new("port")
+            .timeout(wait)
+            .parse_with_io::<u16>(&mut reader, &mut MockWriter::new());
+        assert_eq!(port, Err(InputError::TimedOut));
+        assert_eq!(InputError::TimedOut.to_string(), "no answer in time");
+        let (mut reader, _sender) = BlockingBridgeReader::channel();
+        let port = Input::new("port")
+            .default("80")
+            .timeout(wait)
+            .parse_with_io::<u16>(&mut reader, &mut MockWriter::new());
+        assert_eq!(port, Ok(80));
+        // a default the checks reject doesn't count as an answer
+        let (mut reader, _sender) = BlockingBridgeReader::channel();
+        let port = Input::new("port")
+            .default("http")
+            .timeout(wait)
+            .parse_with_io::<u16>(&mut reader, &mut MockWriter::new());
+        assert_eq!(port, Err(InputError::TimedOut));
+
+        // the time covers the answers asked again
+        let (mut reader, sender) = BlockingBridgeReader::channel();
+        sender.send("x");
+        let mut writer = MockWriter::new();
+        let port = Input::new("port")
+            .timeout(wait)
+            .parse_with_io::<u16>(&mut reader, &mut writer);
+        assert_eq!(port, Err(InputError::TimedOut));
+        assert_eq!(
+            writer.output,
+            "port:error: invalid value 'x': expected integer\nport:\n"
+        );
+
+        let (mut reader, sender) = BlockingBridgeReader::channel();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            sender.send(" 8080 ");
+        });
+        let port = Input::new("port")
+            .timeout(Duration::from_secs(5))
+            .parse_with_io::<u16>(&mut reader, &mut MockWriter::new());
+        assert_eq!(port, Ok(8080));
+        let answer = Input::new("name")
+            .timeout(Duration::ZERO)
+            .read_with_io(&mut MockReader::new(&["piped"]), &mut MockWriter::new());
+        assert_eq!(answer, Ok("piped".to_string()));
+    }
+
+    #[test]
+    fn test_select() {
+        let mut writer = MockWriter::new();
+        let env = Select::new("env", &["dev", "prod"]).read_with_io(
+            &mut MockReader::new(&["", "3", "stage", "prod"]),
+            &mut writer,
+        );
+        assert_eq!(env, Ok("prod".to_string()));
+        assert_eq!(
+            writer.output,
+            "  1. dev\n  2. prod\nenv:error: please choose an item\n\
+             env:error: '3' is not an item or a number between 1 and 2\n\
+             env:error: 'stage' is not an item or a number between 1 and 2\nenv:"
+        );
+        let env = Select::new("env", &["dev", "prod"])
+            .default("prod")
+            .read_with_io(&mut MockReader::new(&[""]), &mut MockWriter::new());
+        assert_eq!(env, Ok("prod".to_string()));
+        let none: [&str; 0] = [];
+        let env = Select::new("env", &none)
+            .read_with_io(&mut MockReader::new(&["1"]), &mut MockWriter::new());
+        assert_eq!(env, Err(InputError::NoItems("env".to_string())));
+        let env = Select::new("env", &["dev"])
+            .read_with_io(&mut MockReader::new(&[]), &mut MockWriter::new());
+        assert_eq!(env, Err(InputError::Eof));
+
+        let env = Select::new("env", &["dev"])
+            .disabled("stage")
+            .item("prod")
+            .read_index_with_io(&mut MockReader::new(&["2"]), &mut MockWriter::new());
+        assert_eq!(env, Ok((2, "prod".to_string())));
+        let env = Select::new("env", &["dev", "prod"])
+            .default("dev")
+            .read_index_with_io(&mut MockReader::new(&[""]), &mut MockWriter::new());
+        assert_eq!(env, Ok((0, "dev".to_string())));
+    }
+
+    #[test]
+    fn test_resize_redraws_the_prompt() {
+        // the terminal is resized once before the answer is typed
+        struct Resized(MockReader, u32);
+        impl InputReader for Resized {
+            fn read_line(&mut self, buf: &mut String) -> std::io::Result<usize> {
+                self.0.read_line(buf)
+            }
+            fn wait_readable(&mut self, _timeout: Duration) -> std::io::Result<bool> {
+                Ok(self.1 == 0)
+            }
+            fn needs_redraw(&mut self) -> bool {
+                self.1 = self.1.saturating_sub(1);
+                self.1 == 0
+            }
+        }
+
+        let mut writer = MockWriter::new();
+        let env = Select::new("env", &["dev", "prod"])
+            .read_with_io(&mut Resized(MockReader::new(&["2"]), 2), &mut writer);
+        assert_eq!(env, Ok("prod".to_string()));
+        assert_eq!(
+            writer.output,
+            "  1. dev\n  2. prod\nenv:\n  1. dev\n  2. prod\nenv:"
+        );
+
+        let mut writer = MockWriter::new();
+        let name = read_input_with_io(
+            "name",
+            None,
+            &mut Resized(MockReader::new(&["ada"]), 2),
+            &mut writer,
+        );
+        assert_eq!(name, Ok("ada".to_string()));
+        assert_eq!(writer.output, "name:\nname:");
+    }
+
+    #[test]
+    fn test_menu() {
+        let menu = || {
+            Menu::new()
+                .prompt("action")
+                .item("Build", || 1)
+                .item("Test", || 2)
+                .quit("Exit")
+        };
+        let ran = menu().run_with_io(&mut MockReader::new(&["Test"]), &mut MockWriter::new());
+        assert_eq!(ran, Ok(Some(2)));
+        let ran = menu().run_with_io(&mut MockReader::new(&["q"]), &mut MockWriter::new());
+        assert_eq!(ran, Ok(None));
+        let ran = menu().run_with_io(&mut MockReader::new(&["", "Exit"]), &mut MockWriter::new());
+        assert_eq!(ran, Ok(None));
+        let ran = menu().run_with_io(&mut MockReader::new(&[]), &mut MockWriter::new());
+        assert_eq!(ran, Err(InputError::Eof));
+
+        let mut writer = MockWriter::new();
+        let ran = Menu::new()
+            .item("Only", || ())
+            .run_with_io(&mut MockReader::new(&["q", "1"]), &mut writer);
+        assert_eq!(ran, Ok(Some(())));
+        assert_eq!(
+            writer.output,
+            "  1. Only\nchoose:error: 'q' is not an option or a number between 1 and 1\nchoose:"
+        );
+        let ran =
+            Menu::<()>::new().run_with_io(&mut MockReader::new(&["1"]), &mut MockWriter::new());
+        assert_eq!(ran, Err(InputError::NoItems("choose".to_string())));
+    }
+
+    #[test]
+    fn test_multi_select() {
+        let mut writer = MockWriter::new();
+        let chosen = MultiSelect::new("tags", &["a", "b", "c"]).read_indices_with_io(
+            &mut MockReader::new(&["1 x", "2,,1 2", "2,,1"]).interactive(true),
+            &mut writer,
+        );
+        assert_eq!(chosen, Ok(vec![0, 1]));
+        assert_eq!(
+            writer.output,
+            "  1. a\n  2. b\n  3. c\ntags:error: 'x' is not a number between 1 and 3\n  1 x\n    ^\n\
+             tags:error: duplicate value '2'\n  2,,1 2\n       ^\ntags:"
+        );
+
+        let chosen = MultiSelect::new("tags", &["a", "b"])
+            .read_with_io(&mut MockReader::new(&[""]), &mut MockWriter::new());
+        assert_eq!(chosen, Ok(vec![]));
+        let mut writer = MockWriter::new();
+        let chosen = MultiSelect::with_display("ports", [80, 443, 8080], |p| p.to_string())
+            .defaults(&["8080", "80"])
+            .read_with_io(&mut MockReader::new(&[""]), &mut writer);
+        assert_eq!(chosen, Ok(vec![80, 8080]));
+        assert!(writer.output.ends_with("ports [1,3]:"));
+        let chosen = MultiSelect::new("tags", &["a"])
+            .defaults(&["z"])
+            .read_with_io(&mut MockReader::new(&[""]), &mut MockWriter::new());
+        assert_eq!(chosen, Err(InputError::MissingDefault("tags".to_string())));
+    }
+
+    #[test]
+    fn test_select_groups_and_disabled_items() {
+        let mut writer = MockWriter::new();
+        let target = Select::new("target", &["aws"])
+            .disabled("gcp")
+            .header("Local")
+            .item("docker")
+            .default("gcp")
+            .theme(Theme::ANSI)
+            .read_with_io(&mut MockReader::new(&["", "3", "2"]), &mut writer);
+        assert_eq!(target, Ok("docker".to_string()));
+        assert_eq!(
+            writer.output,
+            "  1. aws\n     \x1b[2mgcp (unavailable)\x1b[22m\n\x1b[1mLocal\x1b[22m\n  2. docker\n\
+             target [gcp]:error: 'gcp' is not available\n\
+             target [gcp]:error: '3' is not an item or a number between 1 and 2\ntarget [gcp]:"
+        );
+
+        let target = Select::new::<&str>("target", &[])
+            .disabled("gcp")
+            .read_with_io(&mut MockReader::new(&["1"]), &mut MockWriter::new());
+        assert_eq!(target, Err(InputError::NoItems("target".to_string())));
+    }
+
+    #[test]
+    fn test_select_rich_values() {
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        enum Tier {
+            Free,
+            Pro,
+            Enterprise,
+        }
+        let label = |tier: &Tier| format!("{:?}", tier).to_lowercase();
+        let mut writer = MockWriter::new();
+        let tier = Select::with_display("tier", [Tier::Free, Tier::Pro], label)
+            .disabled(Tier::Enterprise)
+            .default("pro")
+            .read_with_io(&mut MockReader::new(&["enterprise", ""]), &mut writer);
+        assert_eq!(tier, Ok(Tier::Pro));
+        assert_eq!(
+            writer.output,
+            "  1. free\n  2. pro\n     enterprise (unavailable)\ntier [pro]:error: 'enterprise' is not available\ntier [pro]:"
+        );
+
+        let ids = Select::with_display("id", Vec::<u32>::new(), u32::to_string)
+            .items_with(|| vec![10, 20])
+            .read_with_io(&mut MockReader::new(&["20"]), &mut MockWriter::new());
+        assert_eq!(ids, Ok(20));
+    }
+
+    #[test]
+    fn test_eof_is_not_an_empty_answer() {
+        let mut reader = MockReader::new(&["", "db1"]);
+        let mut writer = MockWriter::new();
+        let mut hosts = Vec::new();
+        while let Some(host) =
+            read_input_opt_with_io("host", Some("localhost"), &mut reader, &mut writer).unwrap()
+        {
+            hosts.push(host);
+        }
+        assert_eq!(hosts, ["localhost", "db1"]);
+
+        let name = read_input_with_io("name", Some("guest"), &mut reader, &mut writer);
+        assert_eq!(name, Err(InputError::Eof));
+        let name = Input::new("name").read_with_io(&mut reader, &mut writer);
+        assert_eq!(name, Err(InputError::Eof));
+        let empty = Input::new("name").read_with_io(&mut MockReader::new(&[""]), &mut writer);
+        assert_eq!(empty, Ok(String::new()));
+    }
+
+    #[test]
+    fn test_read_input_parse_with_io() {
+        use std::net::IpAddr;
+
+        let mut reader = MockReader::new(&[" 42 ", "-1.5", "10.0.0.1", "", "ten"]);
+        let mut writer = MockWriter::new();
+        assert_eq!(
+            read_input_parse_with_io::<i64>("n", None, &mut reader, &mut writer),
+            Ok(42)
+        );
+        assert_eq!(
+            read_input_parse_with_io::<f32>("x", None, &mut reader, &mut writer),
+            Ok(-1.5)
+        );
+        let addr = read_input_parse_with_io::<IpAddr>("addr", None, &mut reader, &mut writer);
+        assert_eq!(addr, Ok("10.0.0.1".parse().unwrap()));
+        assert_eq!(
+            read_input_parse_with_io::<u8>("n", Some("7"), &mut reader, &mut writer),
+            Ok(7)
+        );
+        let err = read_input_parse_with_io::<u8>("n", None, &mut reader, &mut writer).unwrap_err();
+        assert_eq!(err, InputError::ParseError("ten".to_string()));
+        assert_eq!(err.to_string(), "could not parse 'ten'");
+    }
+
+    #[test]
+    fn test_input_validate() {
+        let even = |n: &str| match n.parse::<u32>().map(|n| n % 2) {
+            Ok(0) => Ok(()),
+            _ => Err(format!("'{}' is not even", n)),
+        };
+        let mut writer = MockWriter::new();
+        let n = Input::new("n")
+            .validate(even)
+            .parse_with_io::<u32>(&mut MockReader::new(&["3", "4"]), &mut writer);
+        assert_eq!(n, Ok(4));
+        assert_eq!(writer.output, "n:error: '3' is not even\nn:");
+
+        let mut writer = MockWriter::new();
+        let answers = Form::new()
+            .field(
+                "slug",
+                Input::new("slug").validate(|slug| match slug.contains(' ') {
+                    true => Err("no spaces please".to_string()),
+                    false => Ok(()),
+                }),
+            )
+            .run_with_io(&mut MockReader::new(&["my app", "my-app"]), &mut writer)
+            .unwrap();
+        assert_eq!(answers.get("slug"), Some("my-app"));
+        assert!(writer.output.contains("error: no spaces please\n"));
+    }
+
+    #[test]
+    fn test_max_attempts() {
+        let mut writer = MockWriter::new();
+        let color = Input::new("color")
+            .choices(&["red", "blue"])
+            .max_attempts(3)
+            .read_with_io(&mut MockReader::new(&["a", "b", "c", "red"]), &mut writer);
+        assert_eq!(color, Err(InputError::AttemptsExceeded { attempts: 3 }));
+        assert_eq!(writer.output.matches("error: ").count(), 3);
+
+        let ports = Input::new("ports")
+            .max_attempts(1)
+            .list::<u16>()
+            .read_with_io(&mut MockReader::new(&["x", "80"]), &mut MockWriter::new());
+        assert_eq!(ports, Err(InputError::AttemptsExceeded { attempts: 1 }));
+
+        let level = Input::new("level")
+            .max_attempts(2)
+            .range(1..=3)
+            .read_with_io(&mut MockReader::new(&["9", "2"]), &mut MockWriter::new());
+        assert_eq!(level, Ok(2));
+        assert_eq!(
+            InputError::AttemptsExceeded { attempts: 2 }.to_string(),
+            "no valid answer after 2 attempts"
+        );
+    }
+
+    #[test]
+    fn test_input_parse_reprompts() {
+        let mut writer = MockWriter::new();
+        let ratio = Input::new("ratio")
+            .default("0.25")
+            .parse_with_io::<f64>(&mut MockReader::new(&["half", ""]), &mut writer);
+        assert_eq!(ratio, Ok(0.25));
+        assert_eq!(
+            writer.output,
+            "ratio [0.25]:error: invalid value 'half': expected number\nratio [0.25]:"
+        );
+
+        let level = Input::new("level").max_len(1).parse_with_io::<u8>(
+            &mut MockReader::new(&["10", "x", "3"]),
+            &mut MockWriter::new(),
+        );
+        assert_eq!(level, Ok(3));
+
+        let eof = Input::new("n")
+            .parse_with_io::<u8>(&mut MockReader::new(&["x"]), &mut MockWriter::new());
+        assert_eq!(eof, Err(InputError::Eof));
+    }
+
+    #[test]
+    fn test_range_input() {
+        let mut writer = MockWriter::new();
+        let threads = Input::new("Threads")
+            .default("8")
+            .range(1..=64)
+            .read_with_io(&mut MockReader::new(&["many", "0", "12"]), &mut writer);
+        assert_eq!(threads, Ok(12));
+        assert_eq!(
+            writer.output,
+            "Threads [1-64] [8]:error: expected integer between 1 and 64, got 'many'\n\
+             Threads [1-64] [8]:error: '0' is not between 1 and 64\n\
+             Threads [1-64] [8]:"
+        );
+
+        let ratio = Input::new("ratio")
+            .range(0.0..=1.0)
+            .policy(RangePolicy::Clamp)
+            .read_with_io(&mut MockReader::new(&["-2.5"]), &mut MockWriter::new());
+        assert_eq!(ratio, Ok(0.0));
+
+        let mut prompter = Prompter::new(MockReader::new(&["3", "x"]), MockWriter::new());
+        assert_eq!(prompter.parse_in("level", 1..=5), Ok(Some(3u8)));
+        let err = prompter.parse_in::<u8>("level", 1..=5).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parse error: expected integer between 1 and 5 at line 2: 'x'"
+        );
+    }
+
+    #[test]
+    fn test_constraint_hints() {
+        let size = Input::new("size").choices(&["s", "m", "l"]).default("m");
+        assert_eq!(
+            render_prompt(&size.question("size"), &Theme::DEFAULT),
+            "size [s/m/l] [m]:"
+        );
+
+        let mut writer = MockWriter::new();
+        Input::new("tags")
+            .list::<String>()
+            .min(1)
+            .max(3)
+            .read_with_io(&mut MockReader::new(&["a, b"]), &mut writer)
+            .unwrap();
+        assert_eq!(writer.output, "tags [1-3 values]:");
+
+        let reader = MockReader::new(&["xl", ""]);
+        let mut frontend = TerminalFrontend::new(reader, MockWriter::new());
+        let answer = frontend::ask(&mut frontend, &size.question("size")).unwrap();
+        assert_eq!(answer.value, "m");
+        let (_, writer) = frontend.into_inner();
+        assert_eq!(
+            writer.output,
+            "size [s/m/l] [m]:error: 'xl' is not one of s, m, l\nsize [s/m/l] [m]:"
+        );
+    }
+
+    #[test]
+    fn test_errors_point_at_the_bad_value() {
+        let mut writer = MockWriter::new();
+        let ports = Input::new("ports").list::<u16>().read_with_io(
+            &mut MockReader::new(&["80, http, 443", "80"]).interactive(true),
+            &mut writer,
+        );
+        assert_eq!(ports, Ok(vec![80]));
+        assert!(writer.output.contains("'http'"));
+        assert!(writer
+            .output
+            .contains("\n  80, http, 443\n      ^^^^\nports:"));
+
+        let mut writer = MockWriter::new();
+        let name = Input::new("name").max_len(4).read_with_io(
+            &mut MockReader::new(&["grüner", "grün"]).interactive(true),
+            &mut writer,
+        );
+        assert_eq!(name, Ok("grün".to_string()));
+        assert_eq!(
+            writer.output,
+            "name [max 4 chars]:error: 'grüner' is longer than 4 characters\n  grüner\n      ^^ 6/4\nname [max 4 chars]:"
+        );
+
+        let mut writer = MockWriter::new();
+        Input::new("title")
+            .max_len(2)
+            .theme(Theme::ANSI)
+            .read_with_io(
+                &mut MockReader::new(&["abc", "ab"]).interactive(true),
+                &mut writer,
+            )
+            .unwrap();
+        assert!(writer.output.contains("\n    ^ \x1b[33m3/2\x1b[39m\n"));
+
+        // piped input gets the message alone
+        let mut writer = MockWriter::new();
+        let mut reader = MockReader::new(&["1, x", "1"]);
+        Input::new("ids")
+            .list::<u8>()
+            .read_with_io(&mut reader, &mut writer)
+            .unwrap();
+        assert!(!writer.output.contains('^'));
+    }
+
+    #[test]
+    fn test_password_confirm() {
+        use input_py::password::PasswordConfirm;
+
+        let mut reader = MockReader::new(&["a", "b", " c ", "d"]);
+        let mut writer = MockWriter::new();
+        let password = PasswordConfirm::new("password", "again")
+            .max_mismatches(2)
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(password, Err(InputError::MismatchError { attempts: 2 }));
+        assert_eq!(
+            writer
+                .output
+                .matches("error: the entries don't match\n")
+                .count(),
+            2
+        );
+
+        let (mut reader, mut writer) =
+            EchoReader::new(MockReader::new(&[" s3cret", " s3cret"]), MockWriter::new());
+        let password =
+            PasswordConfirm::new("password", "again").read_with_io(&mut reader, &mut writer);
+        assert_eq!(password, Ok(" s3cret".to_string()));
+        drop(reader);
+        assert_eq!(writer.into_inner().output, "password:\nagain:\n");
+    }
+
+    #[test]
+    fn test_confirm3() {
+        let mut writer = MockWriter::new();
+        let answer = Confirm3::new("deploy?")
+            .default(Confirmation::Yes)
+            .read_with_io(&mut MockReader::new(&["maybe", ""]), &mut writer);
+        assert_eq!(answer, Ok(Confirmation::Yes));
+        assert_eq!(
+            writer.output,
+            "deploy? [Y/n/a]:error: 'maybe' is not y, n or a\ndeploy? [Y/n/a]:"
+        );
+
+        let answer = Confirm3::new("deploy?")
+            .read_with_io(&mut MockReader::new(&["", "No"]), &mut MockWriter::new());
+        assert_eq!(answer, Ok(Confirmation::No));
+        assert_eq!(answer.unwrap().or_abort(), Ok(false));
+
+        let answer = Confirm3::new("deploy?")
+            .read_with_io(&mut MockReader::new(&["\u{1b}"]), &mut MockWriter::new());
+        assert_eq!(answer.unwrap().or_abort(), Err(InputError::Aborted));
+    }
+
+    fn type_text(editor: &mut Editor, text: &str) {
+        for c in text.chars() {
+            assert_eq!(editor.handle_key(Key::Char(c).into()), Outcome::Continue);
+        }
+    }
+
+    #[test]
+    fn test_editor_emacs_keys() {
+        let mut editor = Editor::new().mode(EditMode::Emacs);
+        type_text(&mut editor, "grüße an alle");
+        editor.handle_key(KeyEvent::alt('b'));
+        editor.handle_key(KeyEvent::alt('b'));
+        editor.handle_key(KeyEvent::ctrl('k'));
+        assert_eq!(editor.line(), "grüße ");
+        editor.handle_key(Key::Left.into());
+        editor.handle_key(Key::Backspace.into());
+        editor.handle_key(Key::Backspace.into());
+        assert_eq!((editor.line(), editor.cursor()), ("grü ", 4));
+        editor.handle_key(KeyEvent::ctrl('e'));
+        type_text(&mut editor, "x y");
+        editor.handle_key(KeyEvent::ctrl('w'));
+        assert_eq!(editor.line(), "grü x ");
+        editor.handle_key(KeyEvent::ctrl('u'));
+        assert_eq!(editor.handle_key(KeyEvent::ctrl('d')), Outcome::Eof);
+        assert_eq!(editor.indicator(), "");
+
+        editor
+            .keymap_mut(KeymapId::Insert)
+            .bind(Key::F(2).into(), Action::MoveHome);
+        type_text(&mut editor, "b");
+        editor.handle_key(Key::F(2).into());
+        type_text(&mut editor, "a");
+        assert_eq!(
+            editor.handle_key(Key::Enter.into()),
+            Outcome::Accept("ab".to_string())
+        );
+        assert_eq!(editor.line(), "");
+    }
+
+    #[test]
+    fn test_editor_custom_bindings() {
+        let today = Action::custom(|editor| {
+            editor.insert_str("2024-05-01");
+            Outcome::Continue
+        });
+        let mut editor = Editor::new()
+            .mode(EditMode::Vi)
+            .bind(Key::F(2).into(), today.clone())
+            .bind(
+                KeyEvent::ctrl('o'),
+                Action::custom(|editor| editor.run(Action::Accept)),
+            );
+        assert_eq!(
+            editor.keymap(KeymapId::Command).get(Key::F(2).into()),
+            Some(&today)
+        );
+        type_text(&mut editor, "on ");
+        editor.handle_key(Key::F(2).into());
+        editor.handle_key(Key::Esc.into());
+        type_text(&mut editor, "0");
+        editor.handle_key(Key::F(2).into());
+        assert_eq!(editor.line(), "2024-05-01on 2024-05-01");
+        assert_eq!(
+            editor.handle_key(KeyEvent::ctrl('o')),
+            Outcome::Accept("2024-05-01on 2024-05-01".to_string())
+        );
+
+        let mut editor = Editor::new().mode(EditMode::Emacs);
+        type_text(&mut editor, "ls");
+        assert_eq!(editor.handle_key(KeyEvent::ctrl('l')), Outcome::ClearScreen);
+        assert_eq!(editor.line(), "ls");
+    }
+
+    #[test]
+    fn test_editor_kill_ring() {
+        let prompter = Prompter::new(MockReader::new(&[]), MockWriter::new());
+        let mut first = prompter.editor().mode(EditMode::Emacs);
+        type_text(&mut first, "one two three");
+        first.handle_key(KeyEvent::alt('b'));
+        first.handle_key(KeyEvent::ctrl('k'));
+        first.handle_key(KeyEvent::ctrl('w'));
+        assert_eq!(first.line(), "one ");
+        first.handle_key(Key::Backspace.into());
+        first.handle_key(KeyEvent::ctrl('u'));
+        assert_eq!(
+            first.handle_key(Key::Enter.into()),
+            Outcome::Accept(String::new())
+        );
+
+        // the kills joined until the backspace, a new prompt yanks them
+        let mut second = prompter.editor().mode(EditMode::Emacs);
+        type_text(&mut second, ">");
+        second.handle_key(KeyEvent::ctrl('y'));
+        assert_eq!(second.line(), ">one");
+        second.handle_key(KeyEvent::alt('y'));
+        assert_eq!(second.line(), ">two three");
+        second.handle_key(KeyEvent::alt('y'));
+        assert_eq!(second.line(), ">one");
+        type_text(&mut second, "!");
+        second.handle_key(KeyEvent::alt('y'));
+        assert_eq!(second.line(), ">one!");
+
+        let ring = KillRing::new();
+        for n in 0..12 {
+            ring.push(&n.to_string());
+        }
+        assert_eq!(ring.entries().len(), 10);
+        assert_eq!(ring.yank(), Some("11".to_string()));
+        let mut vi = Editor::new().mode(EditMode::Vi).kill_ring(ring);
+        vi.handle_key(Key::Esc.into());
+        type_text(&mut vi, "P");
+        assert_eq!(vi.line(), "11");
+    }
+
+    #[test]
+    fn test_editor_reverse_search() {
+        let mut editor = Editor::new().mode(EditMode::Emacs).history([
+            "git status",
+            "git commit -m 'fix'",
+            "ls",
+        ]);
+        type_text(&mut editor, "draft");
+        editor.handle_key(KeyEvent::ctrl('r'));
+        assert_eq!(
+            editor.search_prompt().as_deref(),
+            Some("(reverse-i-search)`': ")
+        );
+        type_text(&mut editor, "git");
+        assert_eq!((editor.line(), editor.cursor()), ("git commit -m 'fix'", 0));
+        editor.handle_key(KeyEvent::ctrl('r'));
+        assert_eq!(editor.line(), "git status");
+        editor.handle_key(KeyEvent::ctrl('r'));
+        assert_eq!(
+            editor.search_prompt().as_deref(),
+            Some("(failed reverse-i-search)`git': ")
+        );
+        assert_eq!(editor.line(), "git status");
+        type_text(&mut editor, "x");
+        assert_eq!(
+            editor.search_prompt().as_deref(),
+            Some("(failed reverse-i-search)`gitx': ")
+        );
+        editor.handle_key(Key::Backspace.into());
+        assert_eq!(editor.line(), "git commit -m 'fix'");
+        // Ctrl+G goes back to what was typed
+        editor.handle_key(KeyEvent::ctrl('g'));
+        assert_eq!((editor.line(), editor.search_prompt()), ("draft", None));
+
+        // other keys end the search and act on the entry found
+        editor.handle_key(KeyEvent::ctrl('r'));
+        type_text(&mut editor, "stat");
+        editor.handle_key(KeyEvent::ctrl('e'));
+        type_text(&mut editor, " -s");
+        assert_eq!(
+            editor.handle_key(Key::Enter.into()),
+            Outcome::Accept("git status -s".to_string())
+        );
+        editor.handle_key(KeyEvent::ctrl('r'));
+        type_text(&mut editor, "-s");
+        assert_eq!(editor.line(), "git status -s");
+
+        let mut prompter = Prompter::new(MockReader::new(&["deploy prod", "x"]), MockWriter::new());
+        prompter.ask("command", None).unwrap();
+        prompter.ask("again", None).unwrap();
+        let mut editor = prompter.editor();
+        editor.handle_key(KeyEvent::ctrl('r'));
+        type_text(&mut editor, "prod");
+        assert_eq!(
+            editor.handle_key(Key::Enter.into()),
+            Outcome::Accept("deploy prod".to_string())
+        );
+    }
+
+    struct Quotes;
+
+    impl Highlighter for Quotes {
+        fn highlight(&self, line: &str) -> StyledLine {
+            let mut styled = StyledLine::new();
+            for (i, part) in line.split('"').enumerate() {
+                if i > 0 {
+                    styled.push("\"", ("<q>", "</q>"));
+                }
+                match i % 2 {
+                    1 => styled.push(part, ("<q>", "</q>")),
+                    _ => styled.plain(part),
+                }
+            }
+            styled
+        }
+    }
+
+    #[test]
+    fn test_editor_highlighter() {
+        let mut editor = Editor::new().mode(EditMode::Emacs);
+        type_text(&mut editor, "say \"hi");
+        assert_eq!(editor.styled_line(), StyledLine::from("say \"hi"));
+
+        let mut editor = Editor::new().mode(EditMode::Emacs).highlighter(Quotes);
+        type_text(&mut editor, "say \"hi");
+        assert_eq!(editor.styled_line().render(), "say <q>\"</q><q>hi</q>");
+        type_text(&mut editor, "\" now");
+        let styled = editor.clone().styled_line();
+        assert_eq!(styled.text(), "say \"hi\" now");
+        assert_eq!(styled.spans().len(), 5);
+        assert_eq!(styled.render(), "say <q>\"</q><q>hi</q><q>\"</q> now");
+
+        // spans that change the text are not drawn
+        let mut editor =
+            Editor::new().highlighter(|line: &str| StyledLine::from(line.to_uppercase().as_str()));
+        type_text(&mut editor, "ls");
+        assert_eq!(editor.styled_line().render(), "ls");
+    }
+
+    #[test]
+    fn test_editor_matching_bracket() {
+        let mut editor = Editor::new().mode(EditMode::Emacs);
+        type_text(&mut editor, "f(x, [a(b)], \"ü\")");
+        assert_eq!(editor.matching_bracket(), Some(1));
+        editor.handle_key(KeyEvent::ctrl('b'));
+        assert_eq!(editor.matching_bracket(), None);
+        editor.handle_key(KeyEvent::ctrl('a'));
+        for _ in 0..2 {
+            editor.handle_key(KeyEvent::ctrl('f'));
+        }
+        assert_eq!(editor.matching_bracket(), Some(17));
+        editor.set_line("a)");
+        assert_eq!(editor.matching_bracket(), None);
+        editor.set_line("");
+        assert_eq!(editor.matching_bracket(), None);
+    }
+
+    #[test]
+    fn test_widgets() {
+        use input_py::widget::{Event, Role, StyledWriter, TextCanvas, Widget};
+
+        fn draw(widget: &dyn Widget<Output = impl Sized>) -> TextCanvas {
+            let mut canvas = TextCanvas::new();
+            widget.render(&mut canvas);
+            canvas
+        }
+
+        let mut target = Select::new::<&str>("target", &[])
+            .header("Cloud")
+            .item("aws")
+            .disabled("azure")
+            .item("gcp")
+            .separator()
+            .item("docker")
+            .default("gcp")
+            .into_widget()
+            .unwrap();
+        assert_eq!(
+            draw(&target).lines(),
+            [
+                "target",
+                "Cloud",
+                "  1. aws",
+                "     azure (unavailable)",
+                "> 2. gcp",
+                "  ──────────",
+                "  3. docker"
+            ]
+        );
+        assert_eq!(target.handle_key(Key::Up.into()), Event::Continue);
+        assert_eq!(draw(&target).lines()[2], "> 1. aws");
+        target.handle_key(Key::Up.into());
+        target.handle_key(Key::End.into());
+        target.handle_key(Key::Down.into());
+        assert_eq!(draw(&target).lines()[6], "> 3. docker");
+        target.handle_key(Key::Char('2').into());
+        target.handle_key(Key::Char('9').into());
+        assert_eq!(
+            target.handle_key(Key::Enter.into()),
+            Event::Done("gcp".to_string())
+        );
+        assert_eq!(target.handle_key(Key::Enter.into()), Event::Continue);
+        assert!(draw(&target).lines().is_empty());
+
+        // rows count from the prompt, headers and disabled items ignore clicks
+        let click = |row| {
+            Key::Mouse(Mouse {
+                kind: MouseKind::Click,
+                row,
+                column: 4,
+            })
+        };
+        let scroll = |kind| {
+            Key::Mouse(Mouse {
+                kind,
+                row: 0,
+                column: 0,
+            })
+        };
+        let mut target = Select::new::<&str>("target", &[])
+            .header("Cloud")
+            .item("aws")
+            .disabled("azure")
+            .item("gcp")
+            .into_widget()
+            .unwrap();
+        for row in [0, 1, 3, 9] {
+            assert_eq!(target.handle_key(click(row).into()), Event::Continue);
+        }
+        target.handle_key(scroll(MouseKind::ScrollDown).into());
+        assert_eq!(draw(&target).lines()[4], "> 2. gcp");
+        target.handle_key(scroll(MouseKind::ScrollUp).into());
+        assert_eq!(draw(&target).lines()[2], "> 1. aws");
+        assert_eq!(
+            target.handle_key(click(4).into()),
+            Event::Done("gcp".to_string())
+        );
+
+        let mut features = MultiSelect::new("features", &["tls", "metrics", "tracing"])
+            .defaults(&["metrics"])
+            .into_widget()
+            .unwrap();
+        features.handle_key(click(1).into());
+        features.handle_key(click(2).into());
+        features.handle_key(click(7).into());
+        features.handle_key(scroll(MouseKind::ScrollDown).into());
+        features.handle_key(Key::Char('3').into());
+        assert_eq!(
+            draw(&features).lines(),
+            [
+                "features",
+                "  [x] 1. tls",
+                "  [ ] 2. metrics",
+                "> [x] 3. tracing"
+            ]
+        );
+        assert_eq!(features.handle_key(Key::Home.into()), Event::Continue);
+        assert_eq!(
+            features.handle_key(Key::Enter.into()),
+            Event::Done(vec!["tls".to_string(), "tracing".to_string()])
+        );
+        assert!(matches!(
+            MultiSelect::new("features", &["tls"])
+                .defaults(&["dns"])
+                .into_widget(),
+            Err(InputError::MissingDefault(_))
+        ));
+
+        let mut env = Select::new("env", &["dev"]).into_widget().unwrap();
+        assert_eq!(env.handle_key(KeyEvent::ctrl('c')), Event::Cancelled);
+        assert!(matches!(
+            Select::new::<&str>("env", &[]).into_widget(),
+            Err(InputError::NoItems(_))
+        ));
+        assert!(matches!(
+            Select::new("env", &["dev"]).default("qa").into_widget(),
+            Err(InputError::MissingDefault(_))
+        ));
+
+        let mut confirm = Input::new("deploy?").confirm().into_widget().unwrap();
+        assert_eq!(draw(&confirm).lines(), ["deploy? [y/n]: "]);
+        assert_eq!(confirm.handle_key(Key::Enter.into()), Event::Continue);
+        assert_eq!(confirm.handle_key(Key::Char('x').into()), Event::Continue);
+        assert_eq!(
+            confirm.handle_key(Key::Char('N').into()),
+            Event::Done(false)
+        );
+        let mut confirm = Input::new("deploy?")
+            .confirm()
+            .default(true)
+            .into_widget()
+            .unwrap();
+        assert_eq!(confirm.handle_key(Key::Enter.into()), Event::Done(true));
+        assert_eq!(confirm.handle_key(Key::Esc.into()), Event::Cancelled);
+        assert!(Input::new("deploy?")
+            .default("later")
+            .confirm()
+            .into_widget()
+            .is_err());
+
+        let mut editor = Editor::new().mode(EditMode::Vi).highlighter(Quotes);
+        type_text(&mut editor, "say \"hi\"");
+        editor.handle_key(Key::Esc.into());
+        let canvas = draw(&editor);
+        assert_eq!(canvas.lines(), ["(cmd) say \"hi\""]);
+        assert_eq!(canvas.cursor(), Some((0, 13)));
+
+        #[derive(Default)]
+        struct Recorder(Vec<(String, Role)>);
+        impl StyledWriter for Recorder {
+            fn write(&mut self, text: &str, role: Role) {
+                self.0.push((text.to_string(), role));
+            }
+            fn end_line(&mut self) {}
+            fn set_cursor(&mut self) {
+                self.0.push(("|".to_string(), Role::Text));
+            }
+        }
+        let mut recorder = Recorder::default();
+        Widget::render(&editor, &mut recorder);
+        let quote = Role::Styled(("<q>", "</q>"));
+        assert_eq!(
+            recorder.0[..2],
+            [
+                ("(cmd)".to_string(), Role::Hint),
+                (" ".to_string(), Role::Text)
+            ]
+        );
+        assert_eq!(
+            recorder.0[recorder.0.len() - 3..],
+            [
+                (String::new(), quote),
+                ("|".to_string(), Role::Text),
+                ("\"".to_string(), quote)
+            ]
+        );
+
+        let mut editor = Editor::new().mode(EditMode::Emacs);
+        type_text(&mut editor, "ls");
+        assert_eq!(draw(&editor).cursor(), Some((0, 2)));
+        assert_eq!(
+            Widget::handle_key(&mut editor, KeyEvent::ctrl('l')),
+            Event::Continue
+        );
+        assert_eq!(
+            Widget::handle_key(&mut editor, Key::Enter.into()),
+            Event::Done("ls".to_string())
+        );
+        assert_eq!(
+            Widget::handle_key(&mut editor, KeyEvent::ctrl('d')),
+            Event::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_editor_vi_mode() {
+        let mut editor = Editor::new().mode(EditMode::Vi);
+        assert_eq!(editor.indicator(), "(ins)");
+        type_text(&mut editor, "one two three");
+        editor.handle_key(Key::Esc.into());
+        assert_eq!((editor.indicator(), editor.cursor()), ("(cmd)", 12));
+        // typed chars are commands now, unbound ones do nothing
+        type_text(&mut editor, "0wxq");
+        assert_eq!((editor.line(), editor.cursor()), ("one wo three", 4));
+        type_text(&mut editor, "D");
+        assert_eq!((editor.line(), editor.cursor()), ("one ", 3));
+        type_text(&mut editor, "A");
+        type_text(&mut editor, "four");
+        assert_eq!(editor.active_keymap(), KeymapId::Insert);
+        editor.handle_key(Key::Esc.into());
+        assert_eq!(
+            editor.handle_key(Key::Enter.into()),
+            Outcome::Accept("one four".to_string())
+        );
+        assert_eq!(editor.indicator(), "(ins)");
+        assert_eq!(editor.handle_key(KeyEvent::ctrl('c')), Outcome::Interrupted);
+    }
+
+    #[test]
+    fn test_confirm() {
+        let mut writer = MockWriter::new();
+        let answer = Input::new("deploy?")
+            .confirm()
+            .read_with_io(&mut MockReader::new(&["", "Yes"]), &mut writer);
+        assert_eq!(answer, Ok(true));
+        assert_eq!(
+            writer.output,
+            "deploy? [y/n]:error: '' is not y or n\ndeploy? [y/n]:"
+        );
+
+        let mut writer = MockWriter::new();
+        let answer = Input::new("deploy?")
+            .default("n")
+            .confirm()
+            .read_with_io(&mut MockReader::new(&[""]), &mut writer);
+        assert_eq!(answer, Ok(false));
+        assert_eq!(writer.output, "deploy? [y/N]:");
+
+        let answer = Input::new("deploy?")
+            .default("later")
+            .confirm()
+            .read_with_io(&mut MockReader::new(&["y"]), &mut MockWriter::new());
+        assert_eq!(
+            answer,
+            Err(InputError::MissingDefault("deploy?".to_string()))
+        );
+        let answer = Input::new("deploy?")
+            .max_attempts(1)
+            .confirm()
+            .read_with_io(&mut MockReader::new(&["x"]), &mut MockWriter::new());
+        assert_eq!(answer, Err(InputError::AttemptsExceeded { attempts: 1 }));
+        let answer = Input::new("deploy?")
+            .confirm()
+            .read_with_io(&mut MockReader::new(&[]), &mut MockWriter::new());
+        assert_eq!(answer, Err(InputError::Eof));
+    }
+
+    #[test]
+    fn test_select_items_with_refresh() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let counter = fetches.clone();
+        let mut writer = MockWriter::new();
+        let env = Select::new("env", &["unused"])
+            .items_with(move || {
+                let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                (1..=n).map(|i| format!("env{}", i)).collect()
+            })
+            .read_with_io(&mut MockReader::new(&["r", "2"]), &mut writer);
+        assert_eq!(env, Ok("env2".to_string()));
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            writer.output,
+            "  1. env1\n  (r to refresh)\nenv:  1. env1\n  2. env2\n  (r to refresh)\nenv:"
+        );
+
+        let mut writer = MockWriter::new().terminal(true);
+        let env = Select::new::<&str>("env", &[])
+            .items_with(|| {
+                std::thread::sleep(Duration::from_millis(250));
+                vec!["slow".to_string()]
+            })
+            .read_with_io(&mut MockReader::new(&["1"]), &mut writer);
+        assert_eq!(env, Ok("slow".to_string()));
+        assert!(writer.output.starts_with("\rloading env |"));
+        assert!(writer
+            .output
+            .contains(&format!("\r{}\r  1. slow\n", " ".repeat(13))));
+
+        let mut writer = MockWriter::new();
+        let env = Select::new::<&str>("env", &[])
+            .items_with(|| {
+                std::thread::sleep(Duration::from_millis(400));
+                vec!["slow".to_string()]
+            })
+            .read_with_io(&mut MockReader::new(&["1"]), &mut writer);
+        assert_eq!(env, Ok("slow".to_string()));
+        assert!(writer.output.starts_with("loading env…\n  1. slow\n"));
+
+        let env = Select::new::<&str>("env", &[])
+            .items_with(Vec::new)
+            .read_with_io(&mut MockReader::new(&["1"]), &mut MockWriter::new());
+        assert_eq!(env, Err(InputError::NoItems("env".to_string())));
+    }
+
+    #[test]
+    fn test_prompter_metrics() {
+        use input_py::metrics::{self, Metrics};
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Recorded(Arc<Mutex<Vec<(String, f64)>>>);
+        impl Metrics for Recorded {
+            fn counter(&mut self, name: &str, value: u64) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((name.to_string(), value as f64));
+            }
+
+            fn histogram(&mut self, name: &str, value: f64) {
+                self.0.lock().unwrap().push((name.to_string(), value));
+            }
+        }
+        let count = |recorded: &Recorded, name: &str| -> f64 {
+            let recorded = recorded.0.lock().unwrap();
+            recorded
+                .iter()
+                .filter(|(n, _)| n == name)
+                .map(|(_, v)| v)
+                .sum()
+        };
+
+        let recorded = Recorded::default();
+        let reader = MockReader::new(&["x", "9", "2", "dev"]);
+        let mut prompter = Prompter::new(reader, MockWriter::new()).metrics(recorded.clone());
+        prompter.prompt(Select::new("size", &["s", "m"])).unwrap();
+        prompter.ask("env", None).unwrap();
+        assert_eq!(count(&recorded, metrics::PROMPTS), 2.0);
+        assert_eq!(count(&recorded, metrics::RETRIES), 2.0);
+
+        // counted where the answers are rejected, not from the output
+        let recorded_retries = Recorded::default();
+        let reader = MockReader::new(&["x", "s", "k1"]);
+        let mut quiet = Prompter::new(reader, MockWriter::new()).metrics(recorded_retries.clone());
+        let plain = Theme {
+            error_prefix: "",
+            ..Theme::DEFAULT
+        };
+        let size = Input::new("size").choices(&["s", "m"]).theme(plain);
+        quiet.prompt(size).unwrap();
+        quiet
+            .prompt(Input::new("key").body("error: codes look like k1"))
+            .unwrap();
+        assert_eq!(count(&recorded_retries, metrics::RETRIES), 1.0);
+        let timings = recorded
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(n, _)| n == metrics::ANSWER_SECONDS)
+            .count();
+        assert_eq!(timings, 2);
+
+        let recorded = Recorded::default();
+        let mut prompter = Prompter::new(MockReader::new(&[]), MockWriter::new())
+            .session_deadline(std::time::Instant::now())
+            .metrics(recorded.clone());
+        assert_eq!(prompter.ask("late", None), Err(InputError::Timeout));
+        assert_eq!(count(&recorded, metrics::TIMEOUTS), 1.0);
+        assert_eq!(count(&recorded, metrics::PROMPTS), 0.0);
+    }
+
+    #[test]
+    fn test_prompter_chain() {
+        let mut prompter =
+            Prompter::new(MockReader::new(&["blog", "", "80, 443"]), MockWriter::new());
+        let answers = prompter
+            .chain(Select::new("project", &["blog", "shop"]))
+            .pipe(|project| Input::new("branch").default(&format!("{}-main", project)))
+            .pipe(|_| Input::new("ports").list::<u16>())
+            .finish();
+        assert_eq!(
+            answers,
+            Ok((("blog".to_string(), "blog-main".to_string()), vec![80, 443]))
+        );
+
+        let mut prompter = Prompter::new(MockReader::new(&[]), MockWriter::new());
+        let mut reached = false;
+        let answers = prompter
+            .chain(Select::new("project", &["blog"]))
+            .pipe(|_| {
+                reached = true;
+                Input::new("branch")
+            })
+            .finish();
+        assert_eq!(answers, Err(InputError::Eof));
+        assert!(!reached);
+    }
+
+    #[test]
+    fn test_write_all_lines_is_one_write() {
+        struct CountingWriter {
+            writes: usize,
+            output: String,
+        }
+
+        impl OutputWriter for CountingWriter {
+            fn write_str(&mut self, s: &str) -> std::io::Result<()> {
+                self.writes += 1;
+                self.output.push_str(s);
+                Ok(())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut writer = CountingWriter {
+            writes: 0,
+            output: String::new(),
+        };
+        writer.write_all_lines(&["a", "b", "c"]).unwrap();
+        assert_eq!(writer.writes, 1);
+        assert_eq!(writer.output, "a\nb\nc\n");
+    }
+
+    #[test]
+    fn test_flush_policy() {
+        let ask_twice = |policy| {
+            let mut reader = MockReader::new(&["a", "b"]);
+            let mut writer = FlushPolicyWriter::new(MockWriter::new(), policy);
+            read_input_with_io("x", None, &mut reader, &mut writer).unwrap();
+            writer.write_str("note\n").unwrap();
+            read_input_with_io("y", None, &mut reader, &mut writer).unwrap();
+            let writer = writer.into_inner();
+            assert_eq!(writer.output, "x:note\ny:");
+            writer.flushes
+        };
+        assert_eq!(ask_twice(FlushPolicy::OnPromptEnd), 2);
+        assert_eq!(ask_twice(FlushPolicy::Always), 5);
+        assert_eq!(ask_twice(FlushPolicy::Never), 0);
+    }
+
+    #[test]
+    fn test_tee_writer() {
+        struct Broken;
+        impl OutputWriter for Broken {
+            fn write_str(&mut self, _s: &str) -> std::io::Result<()> {
+                Err(std::io::ErrorKind::StorageFull.into())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut terminal = MockWriter::new().terminal(true);
+        let mut log = MockWriter::new();
+        let mut transcript = MockWriter::new();
+        let mut writer = TeeWriter::new()
+            .sink(&mut terminal)
+            .sink(Broken)
+            .sink_with(&mut log, FlushPolicy::Always)
+            .sink_with(&mut transcript, FlushPolicy::Never);
+        assert!(writer.is_terminal());
+        let err = writer.write_str("x:").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::StorageFull);
+        writer.flush().unwrap();
+        drop(writer);
+        for sink in [&terminal, &log, &transcript] {
+            assert_eq!(sink.output, "x:");
+        }
+        assert_eq!(
+            (terminal.flushes, log.flushes, transcript.flushes),
+            (1, 2, 0)
+        );
+        assert!(!TeeWriter::new().is_terminal());
+    }
+
+    #[test]
+    fn test_tee_reader() {
+        use input_py::io::BufReaderInput;
+
+        let raw = "caf\u{e9} \r\n\u{feff}db1\n  ".as_bytes();
+        let mut reader = TeeReader::new(BufReaderInput::new(raw), MockWriter::new());
+        let mut writer = MockWriter::new();
+        let lines = read_lines_with_io("hosts", &mut reader, &mut writer).unwrap();
+        assert_eq!(lines, ["caf\u{e9}", "\u{feff}db1", ""]);
+        let (_, audit) = reader.into_inner();
+        assert_eq!(audit.output, "caf\u{e9} \r\n\u{feff}db1\n  ");
+        assert_eq!(audit.flushes, 3);
+    }
+
+    #[test]
+    fn test_blocking_bridge_reader() {
+        let (mut reader, sender) = BlockingBridgeReader::channel();
+        let feeder = std::thread::spawn(move || {
+            assert!(sender.send("1, 2"));
+            assert!(sender.send("3\n"));
+        });
+        let mut writer = MockWriter::new();
+        let first = Input::new("numbers")
+            .list::<u32>()
+            .read_with_io(&mut reader, &mut writer);
+        assert_eq!(first, Ok(vec![1, 2]));
+        feeder.join().unwrap();
+        assert_eq!(
+            read_input_with_io("n", None, &mut reader, &mut writer),
+            Ok("3".to_string())
+        );
+        let mut buf = String::new();
+        assert_eq!(reader.read_line(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_prompt_service_from_threads() {
+        let frontend = TerminalFrontend::new(MockReader::new(&["db", ""]), MockWriter::new());
+        let service = std::sync::Arc::new(PromptService::start(frontend));
+        let host = service.ask(Question::new("host", "host"));
+        let worker = {
+            let service = std::sync::Arc::clone(&service);
+            std::thread::spawn(move || {
+                service
+                    .ask(Question::new("port", "port").default("5432"))
+                    .recv()
+                    .unwrap()
+            })
+        };
+        assert_eq!(
+            host.recv().unwrap(),
+            Ok(Answer::new("host", "db", Provenance::Entered))
+        );
+        assert_eq!(
+            worker.join().unwrap(),
+            Ok(Answer::new("port", "5432", Provenance::Default))
+        );
+        let eof = service.ask(Question::new("user", "user"));
+        assert_eq!(eof.recv().unwrap(), Err(InputError::Eof));
+    }
+
+    #[test]
+    fn test_answer_counts_attempts() {
+        let mut terminal =
+            TerminalFrontend::new(MockReader::new(&["blue", "red"]), MockWriter::new());
+        let mut color = Question::new("color", "color");
+        color.constraints.choices = vec!["red".to_string(), "green".to_string()];
+        let answer = frontend::ask(&mut terminal, &color).unwrap();
+        assert_eq!(answer.value, "red");
+        assert_eq!(answer.attempts, 2);
+        let answers = Form::new()
+            .field(
+                "port",
+                Input::new("port")
+                    .validate(|value| value.parse::<u16>().map(|_| ()).map_err(|e| e.to_string())),
+            )
+            .run_with_io(
+                &mut MockReader::new(&["x", "y", "80"]),
+                &mut MockWriter::new(),
+            )
+            .unwrap();
+        assert_eq!(answers.answer("port").unwrap().attempts, 3);
+    }
+
+    #[test]
+    fn test_history() {
+        use input_py::history::History;
+
+        let path = std::env::temp_dir().join(format!("input_py_history_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut history = History::load(&path).unwrap().limit(3);
+        assert!(history.is_empty());
+        let mut reader = MockReader::new(&["a\\b", "", "x", "x", "y"]);
+        for _ in 0..4 {
+            Input::new("q")
+                .default("d")
+                .history(&mut history)
+                .read_with_io(&mut reader, &mut MockWriter::new())
+                .unwrap();
+        }
+        history.push("two\nlines");
+        assert_eq!(history.entries(), ["d", "x", "two\nlines"]);
+        history.save().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "d\nx\ntwo\\nlines\n"
+        );
+        assert_eq!(History::load(&path).unwrap().entries(), history.entries());
+        std::fs::remove_file(&path).unwrap();
+        assert!(History::new().save().is_err());
+
+        // Up and Down browse, the draft comes back after the newest entry
+        let mut editor = Editor::new()
+            .mode(EditMode::Emacs)
+            .shared_history(history.clone());
+        type_text(&mut editor, "dr");
+        editor.handle_key(Key::Up.into());
+        assert_eq!(editor.line(), "two\nlines");
+        editor.handle_key(KeyEvent::ctrl('p'));
+        editor.handle_key(KeyEvent::ctrl('p'));
+        editor.handle_key(Key::Up.into());
+        assert_eq!(editor.line(), "d");
+        editor.handle_key(Key::Down.into());
+        editor.handle_key(Key::Down.into());
+        editor.handle_key(Key::Down.into());
+        assert_eq!(editor.line(), "dr");
+        assert_eq!(editor.cursor(), 2);
+        editor.handle_key(Key::Up.into());
+        editor.handle_key(Key::Up.into());
+        assert_eq!(
+            editor.handle_key(Key::Enter.into()),
+            Outcome::Accept("x".to_string())
+        );
+        assert_eq!(history.entries(), ["x", "two\nlines", "x"]);
+    }
+
+    #[test]
+    fn test_render_golden() {
+        use input_py::model::Constraints;
+        use input_py::render::{self, assert_prompt_renders};
+
+        assert_eq!(render::FORMAT_VERSION, 1);
+        assert_prompt_renders(Input::new("name").default("x"), "name [x]:");
+        assert_prompt_renders(Input::new("age").range(1..=9), "age [1-9]:");
+        assert_prompt_renders(Input::new("code").max_len(3), "code [max 3 chars]:");
+        assert_prompt_renders(Input::new("ports").list::<u16>(), "ports:");
+        assert_prompt_renders(Input::new("go?").confirm().default(true), "go? [Y/n]:");
+        assert_prompt_renders(MultiSelect::new("r", &["a", "b"]), "  1. a\n  2. b\nr:");
+        assert_prompt_renders(
+            TextArea::new("body"),
+            "body (finish with Ctrl+D or Esc):\n│ ",
+        );
+        let question = Question {
+            constraints: Constraints {
+                choices: vec!["a".to_string(), "b".to_string()],
+                ..Constraints::default()
+            },
+            ..Question::new("pick", "pick")
+        };
+        assert_prompt_renders(&question, "pick [a/b]:");
+        let mismatch =
+            std::panic::catch_unwind(|| assert_prompt_renders(Input::new("name"), "name: "));
+        let message = *mismatch.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("format version 1"), "{}", message);
+    }
+
+    #[test]
+    fn test_editor_completion() {
+        let commands = |partial: &str| -> Vec<String> {
+            ["checkout", "cherry-pick", "commit", "clone"]
+                .iter()
+                .filter(|c| c.starts_with(partial))
+                .map(|c| c.to_string())
+                .collect()
+        };
+        let mut editor = Editor::new().mode(EditMode::Emacs).completer(commands);
+        type_text(&mut editor, "ch");
+        editor.handle_key(KeyEvent::ctrl('b'));
+        // only the text before the cursor is completed
+        editor.handle_key(Key::Tab.into());
+        assert_eq!(editor.line(), "ch");
+        assert_eq!(
+            editor.completions(),
+            ["checkout", "cherry-pick", "commit", "clone"]
+        );
+        editor.handle_key(Key::End.into());
+        assert!(editor.completions().is_empty());
+        editor.handle_key(Key::Tab.into());
+        assert_eq!((editor.line(), editor.cursor()), ("che", 3));
+        type_text(&mut editor, "r");
+        editor.handle_key(Key::Tab.into());
+        assert_eq!(editor.line(), "cherry-pick");
+        assert!(editor.completions().is_empty());
+
+        // without a completer Tab does nothing
+        let mut plain = Editor::new().mode(EditMode::Emacs);
+        type_text(&mut plain, "x");
+        plain.handle_key(Key::Tab.into());
+        assert_eq!(plain.line(), "x");
+    }
+
+    #[test]
+    fn test_form_from_toml() {
+        let path = std::env::temp_dir().join(format!("input_py_form_{}.toml", std::process::id()));
+        let text = r#"
+[name]
+prompt = "app name"
+pattern = '^[a-z][a-z0-9-]{1,15}$'
+pattern_hint = "lowercase name"
+
+[tls]
+type = "bool"
+default = "n"
+section = "Network"
+
+[cert]
+condition = "answers.tls == true"
+section = "Network"
+
+[port]
+type = "integer"
+default = "8080"
+choices = ["80", "8080"]
+section = "Network"
+"#;
+        std::fs::write(&path, text).unwrap();
+        let mut reader = MockReader::new(&["Web", "web-1", "maybe", "", "81", ""]);
+        let mut writer = MockWriter::new();
+        let answers = Form::from_toml(&path)
+            .unwrap()
+            .run_with_io(&mut reader, &mut writer)
+            .unwrap();
+        assert_eq!(answers.get("name"), Some("web-1"));
+        assert_eq!(answers.get("tls"), Some("n"));
+        assert_eq!(answers.get("cert"), None);
+        assert_eq!(answers.get("port"), Some("8080"));
+        assert!(writer
+            .output
+            .starts_with("app name [lowercase name]:error: 'Web' is not lowercase name\n"));
+        assert!(writer.output.contains("— Network —\n"));
+        assert!(writer.output.contains("error: 'maybe' is not y or n\n"));
+
+        let mut reader = MockReader::new(&["web", "yes", "cert.pem", "80"]);
+        let answers = Form::from_toml(&path)
+            .unwrap()
+            .run_with_io(&mut reader, &mut MockWriter::new())
+            .unwrap();
+        assert_eq!(answers.get("cert"), Some("cert.pem"));
+
+        for (text, error) in [
+            ("[a]\ncolor = 1\n", "question 'a': unknown key 'color'"),
+            (
+                "[a]\ntype = \"date\"\n",
+                "question 'a': unknown type 'date'",
+            ),
+            (
+                "[a]\npattern = \"(a\"\n",
+                "question 'a': unclosed group in pattern",
+            ),
+            (
+                "[a]\ncondition = \"a\"\n",
+                "question 'a': condition: unknown name 'a'",
+            ),
+            ("[a]\nchoices = [\"x\"\n", "line 2: unclosed array"),
+            (
+                "prompt = \"x\"\n",
+                "'prompt' is outside of a [question] table",
+            ),
+        ] {
+            std::fs::write(&path, text).unwrap();
+            let Err(InputError::Io(msg)) = Form::from_toml(&path) else {
+                panic!("{} was accepted", text);
+            };
+            assert!(msg.contains(error), "{}", msg);
+        }
+        std::fs::remove_file(&path).unwrap();
+        assert!(Form::from_toml(&path).is_err());
+    }
+
+    #[test]
+    fn test_form_from_toml_secret() {
+        let path =
+            std::env::temp_dir().join(format!("input_py_form_secret_{}.toml", std::process::id()));
+        std::fs::write(&path, "[user]\n[token]\ntype = \"secret\"\n").unwrap();
+        let form = Form::from_toml(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut reader =
+            TeeReader::new(MockReader::new(&["admin", "tok_abcdef"]), MockWriter::new());
+        let answers = form
+            .run_with_io(&mut reader, &mut MockWriter::new())
+            .unwrap();
+        assert_eq!(answers.get("token"), Some("tok_abcdef"));
+        // the secret is read with the echo off
+        let (_, audit) = reader.into_inner();
+        assert_eq!(audit.output, "admin\n(hidden)\n");
+    }
+
+    #[test]
+    fn test_form_from_toml_pattern_step_limit() {
+        let path =
+            std::env::temp_dir().join(format!("input_py_form_steps_{}.toml", std::process::id()));
+        std::fs::write(&path, "[a]\npattern = \"(a*)*b\"\n").unwrap();
+        let form = Form::from_toml(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut writer = MockWriter::new();
+        let answers = form
+            .run_with_io(
+                &mut MockReader::new(&["aaaaaaaaaaaaaaaaaaaaaaaaaaaaac", "b"]),
+                &mut writer,
+            )
+            .unwrap();
+        assert_eq!(answers.get("a"), Some("b"));
+        assert!(writer.output.contains("took too long"));
+    }
+
+    #[test]
+    fn test_form_expressions() {
+        let path =
+            std::env::temp_dir().join(format!("input_py_form_expr_{}.toml", std::process::id()));
+        let text = r#"
+[env]
+[tls]
+type = "bool"
+[cert]
+condition = 'answers.tls == true && answers.env != "dev"'
+[replicas]
+default_from = 'answers.env == "prod" ? 3 : answers.env + "-1"'
+[name]
+validate = 'len(value) <= (answers.env == "prod" ? 4 : 8) && !matches(value, "^-")'
+validate_error = "too long"
+[port]
+default_from = "answers.replicas + 8080"
+validate = "value > 1024 && value < 65536"
+"#;
+        std::fs::write(&path, text).unwrap();
+        let run = |lines: &[&str]| {
+            let mut writer = MockWriter::new();
+            let answers = Form::from_toml(&path)
+                .unwrap()
+                .run_with_io(&mut MockReader::new(lines), &mut writer)
+                .unwrap();
+            (answers, writer.output)
+        };
+
+        let (answers, output) = run(&["prod", "y", "cert.pem", "", "webapp", "web", "80", ""]);
+        assert_eq!(answers.get("cert"), Some("cert.pem"));
+        assert_eq!(answers.get("replicas"), Some("3"));
+        assert_eq!(answers.get("port"), Some("8083"));
+        assert!(output.contains("replicas [3]:"));
+        assert!(output.contains("name:error: too long\n"));
+        assert!(output.contains("error: '80' fails value > 1024 && value < 65536\n"));
+
+        let (answers, _) = run(&["dev", "yes", "", "webapp", "1025"]);
+        assert_eq!(answers.get("cert"), None);
+        assert_eq!(answers.get("replicas"), Some("dev-1"));
+        assert_eq!(answers.get("port"), Some("1025"));
+
+        let nested = format!(
+            "[a]\ncondition = '{}1{}'\n",
+            "(".repeat(100),
+            ")".repeat(100)
+        );
+        for (text, error) in [
+            (
+                "[a]\ncondition = 'answers.b == 1'\n",
+                "reads answers.b, which isn't a question",
+            ),
+            (
+                "[a]\ncondition = 'answers.a == 1'\n",
+                "reads its own answer",
+            ),
+            (
+                "[a]\ndefault_from = 'value'\n",
+                "reads value, which only validate knows",
+            ),
+            (
+                "[a]\nvalidate = '1 < value < 3'\n",
+                "comparisons can't be chained",
+            ),
+            (
+                "[a]\nvalidate = 'matches(value, \"(\")'\n",
+                "unclosed group in pattern",
+            ),
+            ("[a]\ncondition = 'true ?'\n", "expression ends too early"),
+            (
+                "[a]\ncondition = 'std.process.exit(1)'\n",
+                "unknown name 'std.process.exit'",
+            ),
+            (nested.as_str(), "nested too deep"),
+        ] {
+            std::fs::write(&path, text).unwrap();
+            let Err(InputError::Io(msg)) = Form::from_toml(&path) else {
+                panic!("{} was accepted", text);
+            };
+            assert!(msg.contains(error), "{}", msg);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_input_style() {
+        let style = Style {
+            prompt_color: Some(Color::Cyan),
+            default_color: Some(Color::Green),
+            error_color: Some(Color::Red),
+        };
+        let ask = |writer: &mut MockWriter| {
+            Input::new("port")
+                .default("80")
+                .style(style)
+                .parse_with_io::<u16>(&mut MockReader::new(&["http", "443"]), writer)
+        };
+
+        let mut writer = MockWriter::new();
+        assert_eq!(ask(&mut writer), Ok(443));
+        assert_eq!(
+            writer.output,
+            "port [80]:error: invalid value 'http': expected integer\nport [80]:"
+        );
+
+        // NO_COLOR is read once per process, tests/no_color.rs covers it
+        if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return;
+        }
+        let mut writer = MockWriter::new().terminal(true);
+        assert_eq!(ask(&mut writer), Ok(443));
+        let prompt = "\x1b[36mport\x1b[39m\x1b[32m [80]\x1b[39m:";
+        assert_eq!(
+            writer.output,
+            format!(
+                "{}\x1b[31merror: invalid value 'http': expected integer\x1b[39m\n{}",
+                prompt, prompt
+            )
+        );
+
+        let mut writer =
+            FlushPolicyWriter::new(MockWriter::new().terminal(true), FlushPolicy::Always);
+        Input::new("name")
+            .style(Style::PLAIN)
+            .read_with_io(&mut MockReader::new(&["x"]), &mut writer)
+            .unwrap();
+        assert_eq!(writer.into_inner().output, "name:");
+    }
+    #[test]
+    fn test_locale_numbers_and_dates() {
+        use input_py::locale::Locale;
+
+        assert_eq!(Locale::C.display("1234567.5"), "1234567.5");
+        assert_eq!(Locale::EN_US.display("-1234567.25"), "-1,234,567.25");
+        assert_eq!(Locale::DE.display("999"), "999");
+        assert_eq!(Locale::EN_GB.display("2024-03-01"), "01/03/2024");
+        assert_eq!(Locale::DE.display("2024-02-30"), "2024-02-30");
+        assert_eq!(Locale::DE.display("1.2.3"), "1.2.3");
+
+        assert_eq!(
+            Locale::DE.parse_number("-1.234.567,5"),
+            Some("-1234567.5".to_string())
+        );
+        assert_eq!(Locale::DE.parse_number("12.34"), None);
+        assert_eq!(
+            Locale::EN_US.parse_number("1,000"),
+            Some("1000".to_string())
+        );
+        assert_eq!(
+            Locale::EN_US.parse_date("3/1/2024"),
+            Some("2024-03-01".to_string())
+        );
+        assert_eq!(Locale::DE.parse_date("29.02.2023"), None);
+        assert_eq!(
+            Locale::DE.parse_date("2024-03-01"),
+            Some("2024-03-01".to_string())
+        );
+    }
+
+    #[test]
+    fn test_input_theme_prefixes() {
+        let theme = Theme {
+            prompt_prefix: "? ",
+            error_prefix: "! ",
+            ..Theme::DEFAULT
+        };
+        let mut writer = MockWriter::new();
+        let n = Input::new("n")
+            .theme(theme)
+            .parse_with_io::<u8>(&mut MockReader::new(&["x", "2"]), &mut writer);
+        assert_eq!(n, Ok(2));
+        assert_eq!(
+            writer.output,
+            "? n:! invalid value 'x': expected integer\n? n:"
+        );
+    }
+
+    #[test]
+    fn test_capabilities_report() {
+        let report = capabilities::capabilities();
+        assert!(report.iter().all(|subsystem| subsystem.enabled));
+        assert!(report.iter().all(|subsystem| !subsystem.reaches.network));
+        let names: Vec<&str> = report.iter().map(|subsystem| subsystem.name).collect();
+        assert!(names.contains(&"driver"));
+        assert_eq!(names.contains(&"terminal"), cfg!(feature = "terminal"));
+        let terminal = report.iter().find(|subsystem| subsystem.name == "terminal");
+        assert!(terminal.is_none_or(|terminal| !terminal.reaches.processes));
+        assert_eq!(names.contains(&"encrypted"), cfg!(feature = "encryption"));
+        assert!(capabilities::reach().files);
+        assert!(!capabilities::reach().network);
+    }
+
+    #[test]
+    fn test_recorder_replays() {
+        let path =
+            std::env::temp_dir().join(format!("input_py_recording_{}.toml", std::process::id()));
+        let recorder = Recorder::new(&path);
+        let reader = MockReader::new(&["dev", "prod", "multi\\line"]);
+        let mut prompter = Prompter::new(reader, MockWriter::new()).recorder(recorder.clone());
+        prompter.ask("env", None).unwrap();
+        prompter.undo().unwrap();
+        prompter.ask("note", None).unwrap();
+        Form::new()
+            .field("token", Input::new("token").mask_display(MaskStyle::Full))
+            .field("user", Input::new("user").default("admin"))
+            .recorder(recorder)
+            .run_with_io(
+                &mut MockReader::new(&["s3cret", ""]),
+                &mut MockWriter::new(),
+            )
+            .unwrap();
+
+        let replay = AnswerSource::load(&path).unwrap();
+        assert_eq!(replay.get("env"), Some("prod"));
+        assert_eq!(replay.get("note"), Some("multi\\line"));
+        assert_eq!(replay.get("user"), Some("admin"));
+        assert_eq!(replay.get("token"), None);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_recorded_keys_round_trip() {
+        let path = std::env::temp_dir().join(format!("input_py_keys_{}.toml", std::process::id()));
+        let recorder = Recorder::new(&path);
+        let ids = ["host.name", "a=b", "say \"hi\"", "x = y.z", "[section]"];
+        for (i, id) in ids.iter().enumerate() {
+            recorder.record(id, &i.to_string()).unwrap();
+        }
+        let replay = AnswerSource::load(&path).unwrap();
+        for (i, id) in ids.iter().enumerate() {
+            assert_eq!(replay.get(id), Some(i.to_string().as_str()), "{}", id);
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        let parsed = AnswerSource::from_toml("\"a=b\" = \"1\"\n[\"db]\"]\n'x.y' = 2\n").unwrap();
+        assert_eq!(parsed.get("a=b"), Some("1"));
+        assert_eq!(parsed.get("db].x.y"), Some("2"));
+        assert!(AnswerSource::from_toml("a. = 1\n").is_err());
+    }
 }
@@ -0,0 +1,58 @@
+use input_py::config::{self, Theme};
+use input_py::io::{MockReader, MockWriter};
+use input_py::select::Select;
+use input_py::{Form, Input};
+
+// the theme is global, so this lives in its own test binary
+#[test]
+fn test_global_theme_reaches_every_prompt() {
+    config::theme(Theme {
+        prompt_prefix: "? ",
+        prompt_suffix: " ›",
+        default_open: " (",
+        default_close: ")",
+        error_prefix: "✗ ",
+        section: ("## ", ""),
+        ..Theme::DEFAULT
+    });
+
+    let mut writer = MockWriter::new();
+    let port = Input::new("port")
+        .default("80")
+        .parse_with_io::<u16>(&mut MockReader::new(&["x", ""]), &mut writer);
+    assert_eq!(port, Ok(80));
+    assert_eq!(
+        writer.output,
+        "? port (80) ›✗ invalid value 'x': expected integer\n? port (80) ›"
+    );
+
+    let mut writer = MockWriter::new();
+    let sure = Input::new("sure")
+        .confirm()
+        .read_with_io(&mut MockReader::new(&["y"]), &mut writer);
+    assert_eq!(sure, Ok(true));
+    assert_eq!(writer.output, "? sure (y/n) ›");
+
+    let mut writer = MockWriter::new();
+    let env = Select::new("env", &["dev", "prod"])
+        .read_with_io(&mut MockReader::new(&["3", "2"]), &mut writer);
+    assert_eq!(env, Ok("prod".to_string()));
+    assert!(writer.output.contains("? env ›✗ "));
+
+    let mut writer = MockWriter::new();
+    let answers = Form::new()
+        .section("Network")
+        .field("host", Input::new("host"))
+        .run_with_io(&mut MockReader::new(&["db"]), &mut writer);
+    assert!(answers.is_ok());
+    assert_eq!(writer.output, "## Network\n? host ›");
+
+    // prompts keep the theme they were created with
+    let input = Input::new("name");
+    config::theme(Theme::DEFAULT);
+    let mut writer = MockWriter::new();
+    input
+        .read_with_io(&mut MockReader::new(&["ann"]), &mut writer)
+        .unwrap();
+    assert_eq!(writer.output, "? name ›");
+}
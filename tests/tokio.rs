@@ -0,0 +1,107 @@
+#![cfg(feature = "tokio")]
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use input_py::async_io::{TokioReader, TokioWriter};
+use input_py::io::{BlockingBridgeReader, MockWriter};
+use input_py::{read_input_with_io, Input, InputError};
+use tokio::io::{AsyncBufRead, AsyncRead, ReadBuf};
+use tokio::runtime::{Builder, Handle};
+
+/// an async stream that fails after its lines
+struct Broken(&'static [u8]);
+
+impl AsyncRead for Broken {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        unreachable!("read through AsyncBufRead")
+    }
+}
+
+impl AsyncBufRead for Broken {
+    fn poll_fill_buf(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        match self.get_mut().0 {
+            [] => Poll::Ready(Err(io::Error::other("connection reset"))),
+            rest => Poll::Ready(Ok(rest)),
+        }
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.0 = &this.0[amt..];
+    }
+}
+
+#[test]
+fn test_bridge_from_async() {
+    let runtime = Builder::new_current_thread().build().unwrap();
+    let answers = runtime.block_on(async {
+        let source: &'static [u8] = b" db \n5432\n";
+        let mut reader = BlockingBridgeReader::from_async(source, Handle::current());
+        tokio::task::spawn_blocking(move || {
+            let mut writer = MockWriter::new();
+            let host = read_input_with_io("host", None, &mut reader, &mut writer);
+            let port = Input::new("port").parse_with_io::<u16>(&mut reader, &mut writer);
+            // the stream ended
+            let more = read_input_with_io("more", None, &mut reader, &mut writer);
+            (host, port, more, writer.output)
+        })
+        .await
+        .unwrap()
+    });
+    assert_eq!(answers.0, Ok("db".to_string()));
+    assert_eq!(answers.1, Ok(5432));
+    assert_eq!(answers.2, Err(InputError::Eof));
+    assert_eq!(answers.3, "host:port:more:");
+}
+
+#[test]
+fn test_bridge_from_async_error() {
+    let runtime = Builder::new_current_thread().build().unwrap();
+    let answers = runtime.block_on(async {
+        let mut reader = BlockingBridgeReader::from_async(Broken(b"db\n"), Handle::current());
+        tokio::task::spawn_blocking(move || {
+            let mut writer = MockWriter::new();
+            let host = read_input_with_io("host", None, &mut reader, &mut writer);
+            let port = read_input_with_io("port", None, &mut reader, &mut writer);
+            (host, port)
+        })
+        .await
+        .unwrap()
+    });
+    assert_eq!(answers.0, Ok("db".to_string()));
+    assert_eq!(
+        answers.1,
+        Err(InputError::Io("connection reset".to_string()))
+    );
+}
+
+#[test]
+fn test_tokio_reader_and_writer() {
+    let runtime = Builder::new_current_thread().build().unwrap();
+    let (answers, output) = runtime.block_on(async {
+        let source: &'static [u8] = b" ann \n\n";
+        let mut reader = TokioReader::new(source);
+        let mut writer = TokioWriter::new(Vec::new());
+        let name = Input::new("name")
+            .read_with_async_io(&mut reader, &mut writer)
+            .await;
+        let role = Input::new("role")
+            .default("guest")
+            .read_with_async_io(&mut reader, &mut writer)
+            .await;
+        let more = Input::new("more")
+            .read_with_async_io(&mut reader, &mut writer)
+            .await;
+        ((name, role, more), writer.into_inner())
+    });
+    assert_eq!(answers.0, Ok("ann".to_string()));
+    assert_eq!(answers.1, Ok("guest".to_string()));
+    assert_eq!(answers.2, Err(InputError::Eof));
+    assert_eq!(output, b"name:role [guest]:more:");
+}